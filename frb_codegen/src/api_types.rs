@@ -1,16 +1,27 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
 
+use anyhow::Result;
 use convert_case::{Case, Casing};
+use serde::{Deserialize, Serialize};
 
 use ApiType::*;
 
 pub type ApiStructPool = HashMap<String, ApiStruct>;
+pub type ApiEnumPool = HashMap<String, ApiEnum>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiFile {
     pub funcs: Vec<ApiFunc>,
     pub struct_pool: ApiStructPool,
+    pub enum_pool: ApiEnumPool,
     pub has_executor: bool,
+    /// Set by the transformer unless `--no-dedup`: structs whose Dart class is a `typedef` of
+    /// another struct with the same fields, e.g. `Vector2D` to `Point2D`
+    #[serde(skip)]
+    pub struct_dart_aliases: HashMap<String, String>,
 }
 
 impl ApiFile {
@@ -26,9 +37,32 @@ impl ApiFile {
                 for field in &func.inputs {
                     field.ty.visit_types(f, self);
                 }
+                // the setters of structs marked `#[frb(on_change)]` send their fields to Rust
+                if let StructRef(s) = &func.output {
+                    let s = s.get(self);
+                    if s.notify_on_change {
+                        for field in &s.fields {
+                            field.ty.visit_types(f, self);
+                        }
+                    }
+                }
             }
             if include_func_output {
                 func.output.visit_types(f, self);
+                if let Some(ApiFuncError::Custom(ty)) = &func.error_output {
+                    ty.visit_types(f, self);
+                }
+                if let Some(ty) = &func.stream_error_output {
+                    ty.visit_types(f, self);
+                }
+                // the arguments of callbacks are sent to Dart like outputs
+                for field in &func.inputs {
+                    if let Callback(callback) = &field.ty {
+                        for arg in &callback.args {
+                            arg.visit_types(f, self);
+                        }
+                    }
+                }
             }
         }
     }
@@ -59,24 +93,239 @@ impl ApiFile {
 
         ans
     }
+
+    /// Combine the [ApiFile]s parsed from different input files, each given with its path
+    pub fn merge(files: Vec<(String, ApiFile)>) -> Result<ApiFile, MergeError> {
+        let mut files = files.into_iter();
+        let (first_path, mut merged) = files.next().expect("no input files to merge");
+        let mut func_paths = HashMap::new();
+        let mut struct_paths = HashMap::new();
+        let mut enum_paths = HashMap::new();
+        record_paths(
+            &mut func_paths,
+            merged.funcs.iter().map(|f| &f.name),
+            &first_path,
+        );
+        record_paths(&mut struct_paths, merged.struct_pool.keys(), &first_path);
+        record_paths(&mut enum_paths, merged.enum_pool.keys(), &first_path);
+
+        for (path, other) in files {
+            if let Some((name, first_path)) =
+                record_paths(&mut func_paths, other.funcs.iter().map(|f| &f.name), &path)
+            {
+                return Err(MergeError::DuplicateFunction {
+                    name,
+                    first_path,
+                    second_path: path,
+                });
+            }
+            if let Some((name, first_path)) =
+                record_paths(&mut struct_paths, other.struct_pool.keys(), &path)
+            {
+                return Err(MergeError::DuplicateStruct {
+                    name,
+                    first_path,
+                    second_path: path,
+                });
+            }
+            if let Some((name, first_path)) =
+                record_paths(&mut enum_paths, other.enum_pool.keys(), &path)
+            {
+                return Err(MergeError::DuplicateEnum {
+                    name,
+                    first_path,
+                    second_path: path,
+                });
+            }
+
+            merged.funcs.extend(other.funcs);
+            merged.struct_pool.extend(other.struct_pool);
+            merged.enum_pool.extend(other.enum_pool);
+            merged.has_executor |= other.has_executor;
+            merged.struct_dart_aliases.extend(other.struct_dart_aliases);
+        }
+        Ok(merged)
+    }
+}
+
+/// Record that the items named [names] are defined in [path]. Returns the first name which was
+/// already recorded, together with the path it was recorded for.
+fn record_paths<'a>(
+    paths: &mut HashMap<String, String>,
+    names: impl Iterator<Item = &'a String>,
+    path: &str,
+) -> Option<(String, String)> {
+    for name in names {
+        if let Some(first_path) = paths.insert(name.clone(), path.to_string()) {
+            return Some((name.clone(), first_path));
+        }
+    }
+    None
 }
 
-#[derive(Debug, Clone)]
+/// An item defined in two input files, with the paths of both
+#[derive(Debug, PartialEq, Eq)]
+pub enum MergeError {
+    DuplicateFunction {
+        name: String,
+        first_path: String,
+        second_path: String,
+    },
+    DuplicateStruct {
+        name: String,
+        first_path: String,
+        second_path: String,
+    },
+    DuplicateEnum {
+        name: String,
+        first_path: String,
+        second_path: String,
+    },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, name, first_path, second_path) = match self {
+            MergeError::DuplicateFunction {
+                name,
+                first_path,
+                second_path,
+            } => ("function", name, first_path, second_path),
+            MergeError::DuplicateStruct {
+                name,
+                first_path,
+                second_path,
+            } => ("struct", name, first_path, second_path),
+            MergeError::DuplicateEnum {
+                name,
+                first_path,
+                second_path,
+            } => ("enum", name, first_path, second_path),
+        };
+        write!(
+            f,
+            "{} `{}` is defined in both {} and {}",
+            kind, name, first_path, second_path
+        )
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Store the [ApiFile] as json, e.g. as a build artifact to be inspected or modified by external tools
+pub fn save_api_file(file: &ApiFile, path: &Path) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+/// Load an [ApiFile] previously stored by [save_api_file]
+pub fn load_api_file(path: &Path) -> Result<ApiFile> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiFunc {
     pub name: String,
     pub inputs: Vec<ApiField>,
     pub output: ApiType,
+    /// The `E` of `Result<T, E>`, [None] for `anyhow::Error`
+    pub error_output: Option<ApiFuncError>,
+    /// The `E` of `StreamSink<Result<T, E>>`, whose `Err` events become errors of the Dart stream
+    pub stream_error_output: Option<ApiType>,
     pub mode: ApiFuncMode,
     pub comments: Vec<Comment>,
+    /// The message of `#[deprecated]`, see [ApiStruct::deprecated]
+    pub deprecated: Option<String>,
+    /// `#[frb(test)]`: emit a Dart test stub calling this function
+    pub is_test: bool,
+    /// `#[frb(rate_limit = N)]`: drop stream events exceeding N events per second
+    pub rate_limit_events_per_second: Option<u32>,
+    /// `#[frb(stream_buffer = N)]`: block the stream producer when N events are waiting
+    pub stream_buffer_size: Option<usize>,
+    /// `#[frb(name = "...")]`, or `#[frb(dart_name = "...")]`
+    pub dart_name_override: Option<String>,
+    /// `#[frb(throw_on_null_output)]`: throw in Dart instead of returning null for `None` outputs
+    pub throw_on_null: bool,
+    /// Set if defined in an `impl` block of a struct, whose name is then prefixed to [Self::name]
+    pub method: Option<ApiMethod>,
+    /// The inline module defining this function, e.g. `geometry` of
+    /// `pub mod geometry { pub fn area(..) }`
+    pub rust_module: Option<String>,
+    /// `file:line:column` of the name of the function, e.g. `src/api.rs:42:8`, where the file is
+    /// only known after parsing, see [crate::config::RawOpts::source_locations]
+    pub source_location: String,
 }
 
 impl ApiFunc {
     pub fn wire_func_name(&self) -> String {
         format!("wire_{}", self.name)
     }
+
+    /// e.g. `Point::new` for methods
+    pub fn rust_call_path(&self) -> String {
+        match &self.method {
+            Some(method) => format!("{}::{}", method.struct_name, method.method_name),
+            None => match &self.rust_module {
+                Some(module) => format!("{}::{}", module, self.name),
+                None => self.name.clone(),
+            },
+        }
+    }
+
+    pub fn dart_name(&self) -> String {
+        self.dart_name_override
+            .clone()
+            .unwrap_or_else(|| ApiIdent::to_dart_camel_case(&self.name))
+    }
+}
+
+/// A `pub fn` in an `impl` block of a struct. Methods taking `self` receive it as the first input
+/// named [ApiMethod::RECEIVER_NAME].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiMethod {
+    pub struct_name: String,
+    pub method_name: String,
+    /// [None] for associated functions such as `new`
+    pub receiver: Option<ApiMethodReceiver>,
+}
+
+impl ApiMethod {
+    pub const RECEIVER_NAME: &'static str = "that";
+
+    /// Dart keywords such as `new` cannot be method names, so they get the struct name appended
+    pub fn dart_method_name(&self) -> String {
+        let name = ApiIdent::to_dart_camel_case(&self.method_name);
+        if DART_RESERVED_WORDS.contains(&name.as_str()) {
+            format!("{}{}", name, self.struct_name)
+        } else {
+            name
+        }
+    }
+}
+
+/// e.g. `self` is [ApiMethodReceiver::Value] and `&self` is [ApiMethodReceiver::Ref]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ApiMethodReceiver {
+    Value,
+    Ref,
 }
 
-#[derive(Debug, Clone, PartialOrd, PartialEq)]
+const DART_RESERVED_WORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+    "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+    "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+    "with",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ApiFuncError {
+    /// Reported to Dart as a `FfiException` with the string as its message, like `anyhow::Error`
+    String,
+    /// A user-defined enum, which is thrown in Dart as is
+    Custom(ApiType),
+}
+
+#[derive(Debug, Clone, PartialOrd, PartialEq, Serialize, Deserialize)]
 pub enum ApiFuncMode {
     Normal,
     Sync,
@@ -105,7 +354,7 @@ impl ApiFuncMode {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiIdent {
     pub raw: String,
 }
@@ -120,19 +369,60 @@ impl ApiIdent {
     }
 
     pub fn dart_style(&self) -> String {
-        self.raw.to_case(Case::Camel)
+        Self::to_dart_camel_case(&self.raw)
+    }
+
+    /// e.g. `getHttpResponseUrl` of `get_http_response_url`, `parseXmlDocument` of
+    /// `parseXMLDocument`, or `readIO` of `read_io`, see [DART_UPPER_CASE_ACRONYMS]
+    pub fn to_dart_camel_case(raw: &str) -> String {
+        to_dart_case(raw, true)
     }
+
+    /// e.g. `HttpClient` of `HTTPClient`, or `IOSink` of `io_sink`
+    pub fn to_dart_pascal_case(raw: &str) -> String {
+        to_dart_case(raw, false)
+    }
+}
+
+/// Dart keeps two-letter acronyms upper case, unless they start a lowerCamelCase name, while longer
+/// ones such as `HTTP`, `URL`, `XML`, `JSON`, `UUID` or `API` and abbreviations such as `ID` are
+/// capitalized like words, see
+/// https://dart.dev/guides/language/effective-dart/style#do-capitalize-acronyms-and-abbreviations-longer-than-two-letters-like-words
+const DART_UPPER_CASE_ACRONYMS: &[&str] = &["db", "io", "ip", "os", "ui"];
+
+fn to_dart_case(raw: &str, is_lower_camel: bool) -> String {
+    raw.to_case(Case::Snake)
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(idx, word)| {
+            if idx == 0 && is_lower_camel {
+                word.to_string()
+            } else if DART_UPPER_CASE_ACRONYMS.contains(&word) {
+                word.to_uppercase()
+            } else {
+                word[..1].to_uppercase() + &word[1..]
+            }
+        })
+        .collect()
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ApiType {
     Primitive(ApiTypePrimitive),
     Delegate(ApiTypeDelegate),
     PrimitiveList(ApiTypePrimitiveList),
     Optional(ApiTypeOptional),
     GeneralList(Box<ApiTypeGeneralList>),
+    AssocList(Box<ApiTypeAssocList>),
+    Map(Box<ApiTypeMap>),
+    Tuple(Box<ApiTypeTuple>),
     StructRef(ApiTypeStructRef),
+    EnumRef(ApiTypeEnumRef),
     Boxed(Box<ApiTypeBoxed>),
+    OpaqueWeak(ApiTypeOpaqueWeak),
+    Opaque(ApiTypeOpaque),
+    Callback(ApiTypeCallback),
 }
 
 macro_rules! api_type_call_child {
@@ -143,8 +433,15 @@ macro_rules! api_type_call_child {
                 Delegate(inner) => inner.$func(),
                 PrimitiveList(inner) => inner.$func(),
                 GeneralList(inner) => inner.$func(),
+                AssocList(inner) => inner.$func(),
+                Map(inner) => inner.$func(),
+                Tuple(inner) => inner.$func(),
                 StructRef(inner) => inner.$func(),
+                EnumRef(inner) => inner.$func(),
                 Boxed(inner) => inner.$func(),
+                OpaqueWeak(inner) => inner.$func(),
+                Opaque(inner) => inner.$func(),
+                Callback(inner) => inner.$func(),
                 Optional(inner) => inner.$func(),
             }
         }
@@ -162,15 +459,40 @@ impl ApiType {
                 f(&ApiType::Primitive(inner.primitive.clone()));
             }
             GeneralList(inner) => inner.inner.visit_types(f, api_file),
+            AssocList(inner) => {
+                inner.key.visit_types(f, api_file);
+                inner.value.visit_types(f, api_file);
+            }
+            Map(inner) => {
+                inner.key.visit_types(f, api_file);
+                inner.value.visit_types(f, api_file);
+            }
+            Tuple(inner) => {
+                for element in &inner.elements {
+                    element.visit_types(f, api_file);
+                }
+            }
             StructRef(struct_ref) => {
                 for field in &struct_ref.get(api_file).fields {
                     field.ty.visit_types(f, api_file);
                 }
             }
             Boxed(inner) => inner.inner.visit_types(f, api_file),
+            // the upgraded value is sent to Dart, so the struct is needed as well
+            OpaqueWeak(weak) => StructRef(weak.inner.clone()).visit_types(f, api_file),
             Delegate(d) => d.get_delegate().visit_types(f, api_file),
             Optional(inner) => inner.inner.visit_types(f, api_file),
-            Primitive(_) => {}
+            EnumRef(enum_ref) => {
+                for variant in &enum_ref.get(api_file).variants {
+                    if let ApiVariantKind::Struct(st) = &variant.kind {
+                        for field in &st.fields {
+                            field.ty.visit_types(f, api_file);
+                        }
+                    }
+                }
+            }
+            // the arguments are sent to Dart, see [ApiFile::visit_types]
+            Primitive(_) | Opaque(_) | Callback(_) => {}
         }
     }
 
@@ -186,6 +508,7 @@ impl ApiType {
     pub fn required_modifier(&self) -> &'static str {
         match self {
             Optional(_) => "",
+            Callback(callback) if callback.is_optional => "",
             _ => "required ",
         }
     }
@@ -232,7 +555,7 @@ pub trait ApiTypeChild {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ApiTypePrimitive {
     U8,
     I8,
@@ -242,6 +565,10 @@ pub enum ApiTypePrimitive {
     I32,
     U64,
     I64,
+    /// Passed as `u64`, whatever the pointer width of the target
+    Usize,
+    /// Passed as `i64`, whatever the pointer width of the target
+    Isize,
     F32,
     F64,
     Bool,
@@ -261,7 +588,9 @@ impl ApiTypeChild for ApiTypePrimitive {
             | ApiTypePrimitive::U32
             | ApiTypePrimitive::I32
             | ApiTypePrimitive::U64
-            | ApiTypePrimitive::I64 => "int",
+            | ApiTypePrimitive::I64
+            | ApiTypePrimitive::Usize
+            | ApiTypePrimitive::Isize => "int",
             ApiTypePrimitive::F32 | ApiTypePrimitive::F64 => "double",
             ApiTypePrimitive::Bool => "bool",
         }
@@ -273,7 +602,11 @@ impl ApiTypeChild for ApiTypePrimitive {
     }
 
     fn rust_api_type(&self) -> String {
-        self.rust_wire_type()
+        match self {
+            ApiTypePrimitive::Usize => "usize".to_string(),
+            ApiTypePrimitive::Isize => "isize".to_string(),
+            _ => self.rust_wire_type(),
+        }
     }
 
     fn rust_wire_type(&self) -> String {
@@ -284,8 +617,8 @@ impl ApiTypeChild for ApiTypePrimitive {
             ApiTypePrimitive::I16 => "i16",
             ApiTypePrimitive::U32 => "u32",
             ApiTypePrimitive::I32 => "i32",
-            ApiTypePrimitive::U64 => "u64",
-            ApiTypePrimitive::I64 => "i64",
+            ApiTypePrimitive::U64 | ApiTypePrimitive::Usize => "u64",
+            ApiTypePrimitive::I64 | ApiTypePrimitive::Isize => "i64",
             ApiTypePrimitive::F32 => "f32",
             ApiTypePrimitive::F64 => "f64",
             ApiTypePrimitive::Bool => "bool",
@@ -306,12 +639,29 @@ impl ApiTypePrimitive {
             ApiTypePrimitive::I16 => "ffi.Int16",
             ApiTypePrimitive::U32 => "ffi.Uint32",
             ApiTypePrimitive::I32 => "ffi.Int32",
-            ApiTypePrimitive::U64 => "ffi.Uint64",
-            ApiTypePrimitive::I64 => "ffi.Int64",
+            ApiTypePrimitive::U64 | ApiTypePrimitive::Usize => "ffi.Uint64",
+            ApiTypePrimitive::I64 | ApiTypePrimitive::Isize => "ffi.Int64",
             ApiTypePrimitive::F32 => "ffi.Float",
             ApiTypePrimitive::F64 => "ffi.Double",
         }
     }
+
+    /// e.g. `Int32` of `ByteData.getInt32`, which reads the primitive from bytes
+    pub fn byte_data_type(&self) -> &'static str {
+        match self {
+            ApiTypePrimitive::U8 | ApiTypePrimitive::Bool => "Uint8",
+            ApiTypePrimitive::I8 => "Int8",
+            ApiTypePrimitive::U16 => "Uint16",
+            ApiTypePrimitive::I16 => "Int16",
+            ApiTypePrimitive::U32 => "Uint32",
+            ApiTypePrimitive::I32 => "Int32",
+            ApiTypePrimitive::U64 | ApiTypePrimitive::Usize => "Uint64",
+            ApiTypePrimitive::I64 | ApiTypePrimitive::Isize => "Int64",
+            ApiTypePrimitive::F32 => "Float32",
+            ApiTypePrimitive::F64 => "Float64",
+        }
+    }
+
     pub fn try_from_rust_str(s: &str) -> Option<Self> {
         match s {
             "u8" => Some(ApiTypePrimitive::U8),
@@ -322,6 +672,8 @@ impl ApiTypePrimitive {
             "i32" => Some(ApiTypePrimitive::I32),
             "u64" => Some(ApiTypePrimitive::U64),
             "i64" => Some(ApiTypePrimitive::I64),
+            "usize" => Some(ApiTypePrimitive::Usize),
+            "isize" => Some(ApiTypePrimitive::Isize),
             "f32" => Some(ApiTypePrimitive::F32),
             "f64" => Some(ApiTypePrimitive::F64),
             "bool" => Some(ApiTypePrimitive::Bool),
@@ -331,11 +683,84 @@ impl ApiTypePrimitive {
 }
 
 // types that delegate to another type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ApiTypeDelegate {
     String,
     SyncReturnVecU8,
-    ZeroCopyBufferVecPrimitive(ApiTypePrimitive),
+    /// `SyncReturn<String>`, returned like `SyncReturn<Vec<u8>>` of the UTF-8 bytes
+    SyncReturnString,
+    /// `ZeroCopyBuffer<Vec<T>>`, where `T` is a primitive, or a `Vec` of a primitive for e.g. rows
+    /// of pixels. The latter is sent as the frame lengths and the concatenated frames.
+    ZeroCopyBufferVec(Box<ApiType>),
+    /// `ZeroCopyBuffer<Vec<String>>`, sent like `ZeroCopyBuffer<Vec<Vec<u8>>>` of the UTF-8 bytes
+    ZeroCopyBufferVecString,
+    /// `NonNull<T>` passed as an integer address, only allowed with `#[frb(unsafe_ptr)]`.
+    /// Holds the (stringified) type of `T`.
+    NonNull(String),
+    /// `Box<dyn Error>` passed as its message. Holds the trait object bounds, e.g. `+Send+Sync`.
+    DynError(String),
+    /// `Range<T>` (or `RangeInclusive<T>` if the bool is true) passed as `[start, end]`
+    Range(ApiTypePrimitive, bool),
+    /// `Cow<'_, [u8]>`, passed like `ZeroCopyBuffer<Vec<u8>>`
+    CowBytes,
+    /// `Cow<'_, str>`, passed like `String`
+    CowStr,
+    /// `&str` parameter, received like `String` and lent to the function
+    StringRef,
+    /// `&[u8]` parameter, received like `Vec<u8>` and lent to the function
+    SliceU8,
+    /// `[T; N]`, passed like `Vec<T>` and checked to have exactly `N` elements. Arrays of numbers
+    /// are sent to Dart as typed data without a copy.
+    Array(Box<ApiTypeArray>),
+    /// `HashSet<T>` or `BTreeSet<T>`, passed like `Vec<T>` in no particular order and received in
+    /// Dart as a `Set<T>`
+    Set(Box<ApiTypeSet>),
+    /// Date and time types of `chrono`, passed as milliseconds since the Unix epoch.
+    /// Needs the `chrono` feature of `flutter_rust_bridge`.
+    Chrono(ApiTypeChrono),
+    /// `uuid::Uuid` passed as its 16 bytes, received in Dart as a `String` if the bool is true,
+    /// or otherwise as a `UuidValue` of the `uuid` package.
+    /// Needs the `uuid` feature of `flutter_rust_bridge`.
+    Uuid(bool),
+    /// `i128`, passed as its high and low 64-bit halves, since Dart integers are 64-bit
+    I128,
+    /// `u128`, passed like `i128`
+    U128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ApiTypeChrono {
+    Utc,
+    Local,
+    /// Received in Dart as a UTC `DateTime` at midnight
+    NaiveDate,
+    /// Received in Dart as a UTC `DateTime`
+    NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeArray {
+    pub element: ApiType,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeSet {
+    pub element: ApiType,
+    /// `BTreeSet` instead of `HashSet`
+    pub is_ordered: bool,
+}
+
+/// The `Vec` of [element], which arrays and sets are passed as
+fn list_of(element: &ApiType) -> ApiType {
+    match element {
+        ApiType::Primitive(primitive) => ApiType::PrimitiveList(ApiTypePrimitiveList {
+            primitive: primitive.clone(),
+        }),
+        element => ApiType::GeneralList(Box::new(ApiTypeGeneralList {
+            inner: element.clone(),
+        })),
+    }
 }
 
 impl ApiTypeDelegate {
@@ -344,16 +769,53 @@ impl ApiTypeDelegate {
             ApiTypeDelegate::String => ApiType::PrimitiveList(ApiTypePrimitiveList {
                 primitive: ApiTypePrimitive::U8,
             }),
-            ApiTypeDelegate::SyncReturnVecU8 => ApiType::PrimitiveList(ApiTypePrimitiveList {
+            ApiTypeDelegate::SyncReturnVecU8 | ApiTypeDelegate::SyncReturnString => {
+                ApiType::PrimitiveList(ApiTypePrimitiveList {
+                    primitive: ApiTypePrimitive::U8,
+                })
+            }
+            ApiTypeDelegate::ZeroCopyBufferVec(inner) => list_of(inner),
+            ApiTypeDelegate::ZeroCopyBufferVecString => {
+                list_of(&ApiType::Delegate(ApiTypeDelegate::String))
+            }
+            ApiTypeDelegate::NonNull(_) => ApiType::Primitive(ApiTypePrimitive::I64),
+            ApiTypeDelegate::DynError(_) => ApiType::PrimitiveList(ApiTypePrimitiveList {
+                primitive: ApiTypePrimitive::U8,
+            }),
+            ApiTypeDelegate::Range(primitive, _) => ApiType::PrimitiveList(ApiTypePrimitiveList {
+                primitive: primitive.clone(),
+            }),
+            ApiTypeDelegate::CowBytes | ApiTypeDelegate::CowStr => {
+                ApiType::PrimitiveList(ApiTypePrimitiveList {
+                    primitive: ApiTypePrimitive::U8,
+                })
+            }
+            ApiTypeDelegate::StringRef => ApiType::Delegate(ApiTypeDelegate::String),
+            ApiTypeDelegate::SliceU8 => ApiType::PrimitiveList(ApiTypePrimitiveList {
                 primitive: ApiTypePrimitive::U8,
             }),
-            ApiTypeDelegate::ZeroCopyBufferVecPrimitive(primitive) => {
+            ApiTypeDelegate::Chrono(_) => ApiType::Primitive(ApiTypePrimitive::I64),
+            ApiTypeDelegate::Uuid(_) => {
+                ApiType::Delegate(ApiTypeDelegate::Array(Box::new(ApiTypeArray {
+                    element: ApiType::Primitive(ApiTypePrimitive::U8),
+                    length: 16,
+                })))
+            }
+            ApiTypeDelegate::Array(array) => list_of(&array.element),
+            ApiTypeDelegate::Set(set) => list_of(&set.element),
+            ApiTypeDelegate::I128 | ApiTypeDelegate::U128 => {
                 ApiType::PrimitiveList(ApiTypePrimitiveList {
-                    primitive: primitive.clone(),
+                    primitive: ApiTypePrimitive::I64,
                 })
             }
         }
     }
+
+    /// Borrowed types are only supported as function parameters, which own the delegate value
+    /// and pass a reference to it
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ApiTypeDelegate::StringRef | ApiTypeDelegate::SliceU8)
+    }
 }
 
 impl ApiTypeChild for ApiTypeDelegate {
@@ -361,18 +823,75 @@ impl ApiTypeChild for ApiTypeDelegate {
         match self {
             ApiTypeDelegate::String => "String".to_string(),
             ApiTypeDelegate::SyncReturnVecU8 => "SyncReturnVecU8".to_string(),
-            ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
+            ApiTypeDelegate::SyncReturnString => "SyncReturnString".to_string(),
+            ApiTypeDelegate::ZeroCopyBufferVec(inner)
+                if matches!(**inner, ApiType::Primitive(_)) =>
+            {
                 "ZeroCopyBuffer_".to_owned() + &self.get_delegate().dart_api_type()
             }
+            ApiTypeDelegate::ZeroCopyBufferVec(_) | ApiTypeDelegate::ZeroCopyBufferVecString => {
+                "ZeroCopyBuffer_".to_owned() + &self.get_delegate().safe_ident()
+            }
+            ApiTypeDelegate::NonNull(inner) => format!(
+                "NonNull_{}",
+                inner.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+            ),
+            ApiTypeDelegate::DynError(_) => "DynError".to_string(),
+            ApiTypeDelegate::Range(primitive, inclusive) => format!(
+                "{}_{}",
+                if *inclusive {
+                    "RangeInclusive"
+                } else {
+                    "Range"
+                },
+                primitive.rust_api_type()
+            ),
+            ApiTypeDelegate::CowBytes => "CowBytes".to_string(),
+            ApiTypeDelegate::CowStr => "CowStr".to_string(),
+            ApiTypeDelegate::StringRef => "StringRef".to_string(),
+            ApiTypeDelegate::SliceU8 => "SliceU8".to_string(),
+            ApiTypeDelegate::Array(array) => {
+                format!("array_{}_{}", array.element.safe_ident(), array.length)
+            }
+            ApiTypeDelegate::Set(set) => format!(
+                "{}_{}",
+                if set.is_ordered {
+                    "b_tree_set"
+                } else {
+                    "hash_set"
+                },
+                set.element.safe_ident()
+            ),
+            ApiTypeDelegate::Chrono(chrono) => format!("Chrono_{:?}", chrono),
+            ApiTypeDelegate::Uuid(_) => "Uuid".to_string(),
+            ApiTypeDelegate::I128 => "i128".to_string(),
+            ApiTypeDelegate::U128 => "u128".to_string(),
         }
     }
 
     fn dart_api_type(&self) -> String {
         match self {
-            ApiTypeDelegate::String => "String".to_string(),
-            ApiTypeDelegate::SyncReturnVecU8 | ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
-                self.get_delegate().dart_api_type()
+            ApiTypeDelegate::String
+            | ApiTypeDelegate::DynError(_)
+            | ApiTypeDelegate::CowStr
+            | ApiTypeDelegate::StringRef
+            | ApiTypeDelegate::SyncReturnString => "String".to_string(),
+            ApiTypeDelegate::SyncReturnVecU8
+            | ApiTypeDelegate::ZeroCopyBufferVec(_)
+            | ApiTypeDelegate::ZeroCopyBufferVecString
+            | ApiTypeDelegate::NonNull(_)
+            | ApiTypeDelegate::CowBytes
+            | ApiTypeDelegate::SliceU8
+            | ApiTypeDelegate::Array(_) => self.get_delegate().dart_api_type(),
+            ApiTypeDelegate::Range(primitive, _) => {
+                format!("FrbRange<{}>", primitive.dart_api_type())
             }
+            ApiTypeDelegate::Set(set) => format!("Set<{}>", set.element.dart_api_type()),
+            ApiTypeDelegate::Chrono(_) => "DateTime".to_string(),
+            ApiTypeDelegate::Uuid(true) => "String".to_string(),
+            ApiTypeDelegate::Uuid(false) => "UuidValue".to_string(),
+            ApiTypeDelegate::I128 => "Int128".to_string(),
+            ApiTypeDelegate::U128 => "Uint128".to_string(),
         }
     }
 
@@ -384,9 +903,49 @@ impl ApiTypeChild for ApiTypeDelegate {
         match self {
             ApiTypeDelegate::String => "String".to_owned(),
             ApiTypeDelegate::SyncReturnVecU8 => "SyncReturn<Vec<u8>>".to_string(),
-            ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
+            ApiTypeDelegate::SyncReturnString => "SyncReturn<String>".to_string(),
+            ApiTypeDelegate::ZeroCopyBufferVec(_) | ApiTypeDelegate::ZeroCopyBufferVecString => {
                 format!("ZeroCopyBuffer<{}>", self.get_delegate().rust_api_type())
             }
+            ApiTypeDelegate::NonNull(inner) => format!("std::ptr::NonNull<{}>", inner),
+            ApiTypeDelegate::DynError(bounds) => {
+                format!("Box<dyn std::error::Error{}>", bounds.replace('+', " + "))
+            }
+            ApiTypeDelegate::Range(primitive, inclusive) => format!(
+                "std::ops::{}<{}>",
+                if *inclusive {
+                    "RangeInclusive"
+                } else {
+                    "Range"
+                },
+                primitive.rust_api_type()
+            ),
+            ApiTypeDelegate::CowBytes => "std::borrow::Cow<'static, [u8]>".to_string(),
+            ApiTypeDelegate::CowStr => "std::borrow::Cow<'static, str>".to_string(),
+            ApiTypeDelegate::StringRef => "&str".to_string(),
+            ApiTypeDelegate::SliceU8 => "&[u8]".to_string(),
+            ApiTypeDelegate::Array(array) => {
+                format!("[{}; {}]", array.element.rust_api_type(), array.length)
+            }
+            ApiTypeDelegate::Set(set) => format!(
+                "std::collections::{}<{}>",
+                if set.is_ordered {
+                    "BTreeSet"
+                } else {
+                    "HashSet"
+                },
+                set.element.rust_api_type()
+            ),
+            ApiTypeDelegate::Chrono(chrono) => match chrono {
+                ApiTypeChrono::Utc => "chrono::DateTime<chrono::Utc>",
+                ApiTypeChrono::Local => "chrono::DateTime<chrono::Local>",
+                ApiTypeChrono::NaiveDate => "chrono::NaiveDate",
+                ApiTypeChrono::NaiveDateTime => "chrono::NaiveDateTime",
+            }
+            .to_string(),
+            ApiTypeDelegate::Uuid(_) => "uuid::Uuid".to_string(),
+            ApiTypeDelegate::I128 => "i128".to_string(),
+            ApiTypeDelegate::U128 => "u128".to_string(),
         }
     }
 
@@ -399,7 +958,7 @@ impl ApiTypeChild for ApiTypeDelegate {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTypePrimitiveList {
     pub primitive: ApiTypePrimitive,
 }
@@ -443,7 +1002,7 @@ impl ApiTypeChild for ApiTypePrimitiveList {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTypeGeneralList {
     pub inner: ApiType,
 }
@@ -474,9 +1033,176 @@ impl ApiTypeChild for ApiTypeGeneralList {
     }
 }
 
-#[derive(Debug, Clone)]
+/// `Vec<(K, V)>`, a `Map<K, V>` in Dart. Outputs are sent as alternating keys and values, and
+/// inputs are passed like `HashMap<K, V>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeAssocList {
+    pub key: ApiType,
+    pub value: ApiType,
+}
+
+impl ApiTypeChild for ApiTypeAssocList {
+    fn safe_ident(&self) -> String {
+        format!(
+            "assoc_list_{}_{}",
+            self.key.safe_ident(),
+            self.value.safe_ident()
+        )
+    }
+
+    fn dart_api_type(&self) -> String {
+        format!(
+            "Map<{}, {}>",
+            self.key.dart_api_type(),
+            self.value.dart_api_type()
+        )
+    }
+
+    fn dart_wire_type(&self) -> String {
+        format!("ffi.Pointer<wire_{}>", self.safe_ident())
+    }
+
+    fn rust_api_type(&self) -> String {
+        format!(
+            "Vec<({}, {})>",
+            self.key.rust_api_type(),
+            self.value.rust_api_type()
+        )
+    }
+
+    fn rust_wire_type(&self) -> String {
+        format!("wire_{}", self.safe_ident())
+    }
+
+    fn rust_wire_is_pointer(&self) -> bool {
+        true
+    }
+}
+
+/// `HashMap<K, V>` or `BTreeMap<K, V>`, received in Dart as a `Map<K, V>`. Dart passes the keys
+/// and values as two arrays of the same length, while Rust passes them alternately in one list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeMap {
+    pub key: ApiType,
+    pub value: ApiType,
+    /// `BTreeMap` instead of `HashMap`
+    pub is_ordered: bool,
+}
+
+impl ApiTypeChild for ApiTypeMap {
+    fn safe_ident(&self) -> String {
+        format!(
+            "{}_{}_{}",
+            if self.is_ordered {
+                "b_tree_map"
+            } else {
+                "hash_map"
+            },
+            self.key.safe_ident(),
+            self.value.safe_ident()
+        )
+    }
+
+    fn dart_api_type(&self) -> String {
+        format!(
+            "Map<{}, {}>",
+            self.key.dart_api_type(),
+            self.value.dart_api_type()
+        )
+    }
+
+    fn dart_wire_type(&self) -> String {
+        format!("ffi.Pointer<wire_{}>", self.safe_ident())
+    }
+
+    fn rust_api_type(&self) -> String {
+        format!(
+            "std::collections::{}<{}, {}>",
+            if self.is_ordered {
+                "BTreeMap"
+            } else {
+                "HashMap"
+            },
+            self.key.rust_api_type(),
+            self.value.rust_api_type()
+        )
+    }
+
+    fn rust_wire_type(&self) -> String {
+        format!("wire_{}", self.safe_ident())
+    }
+
+    fn rust_wire_is_pointer(&self) -> bool {
+        true
+    }
+}
+
+/// `(A, B)` or `(A, B, C)`, received in Dart as a `FrbTuple2<A, B>` or `FrbTuple3<A, B, C>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeTuple {
+    pub elements: Vec<ApiType>,
+}
+
+impl ApiTypeTuple {
+    /// Field names of the wire struct, which are also the field names of the Dart class
+    pub fn field_names(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.elements.len()).map(|idx| format!("field{}", idx))
+    }
+}
+
+impl ApiTypeChild for ApiTypeTuple {
+    fn safe_ident(&self) -> String {
+        format!(
+            "tuple_{}",
+            self.elements
+                .iter()
+                .map(ApiType::safe_ident)
+                .collect::<Vec<_>>()
+                .join("_")
+        )
+    }
+
+    fn dart_api_type(&self) -> String {
+        format!(
+            "FrbTuple{}<{}>",
+            self.elements.len(),
+            self.elements
+                .iter()
+                .map(ApiType::dart_api_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    fn dart_wire_type(&self) -> String {
+        format!("ffi.Pointer<wire_{}>", self.safe_ident())
+    }
+
+    fn rust_api_type(&self) -> String {
+        format!(
+            "({})",
+            self.elements
+                .iter()
+                .map(ApiType::rust_api_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    fn rust_wire_type(&self) -> String {
+        format!("wire_{}", self.safe_ident())
+    }
+
+    fn rust_wire_is_pointer(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTypeStructRef {
+    /// e.g. `ApiResponseString` of a generic struct, see [ApiStruct::generic_rust_type]
     pub name: String,
+    pub generic_rust_type: Option<String>,
 }
 
 impl ApiTypeStructRef {
@@ -498,7 +1224,10 @@ impl ApiTypeChild for ApiTypeStructRef {
     }
 
     fn rust_api_type(&self) -> String {
-        self.name.to_string()
+        self.generic_rust_type
+            .as_ref()
+            .unwrap_or(&self.name)
+            .to_string()
     }
 
     fn rust_wire_type(&self) -> String {
@@ -506,22 +1235,335 @@ impl ApiTypeChild for ApiTypeStructRef {
     }
 }
 
-#[derive(Debug, Clone)]
+/// `std::sync::Weak<T>`, passed to Dart as the address of a leaked box and received as a
+/// `WeakRef<T>`, which can be upgraded to a copy of the value while it is still alive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeOpaqueWeak {
+    pub inner: ApiTypeStructRef,
+}
+
+impl ApiTypeOpaqueWeak {
+    pub fn upgrade_func_name(&self) -> String {
+        format!("upgrade_{}", self.safe_ident())
+    }
+
+    pub fn drop_func_name(&self) -> String {
+        format!("drop_{}", self.safe_ident())
+    }
+}
+
+impl ApiTypeChild for ApiTypeOpaqueWeak {
+    fn safe_ident(&self) -> String {
+        format!("weak_{}", self.inner.safe_ident())
+    }
+
+    fn dart_api_type(&self) -> String {
+        format!("WeakRef<{}>", self.inner.dart_api_type())
+    }
+
+    fn dart_wire_type(&self) -> String {
+        "int".to_string()
+    }
+
+    fn rust_api_type(&self) -> String {
+        format!("std::sync::Weak<{}>", self.inner.rust_api_type())
+    }
+
+    fn rust_wire_type(&self) -> String {
+        "i64".to_string()
+    }
+}
+
+/// A trait object such as `Box<dyn Fn(i32) -> i32 + Send>` or a struct marked `#[frb(opaque)]`,
+/// kept in a slab of the Rust output and passed to Dart as its index, where it is an opaque handle
+/// class named [name]. Passing the handle back to Rust moves the object out of the slab.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeOpaque {
+    /// The type alias of the trait object if any, e.g. `Adder` of
+    /// `type Adder = Box<dyn Fn(i32) -> i32 + Send>`, otherwise derived from the trait object, or
+    /// the name of the struct
+    pub name: String,
+    pub rust_type: String,
+}
+
+impl ApiTypeOpaque {
+    pub fn drop_func_name(&self) -> String {
+        format!("drop_{}", self.safe_ident())
+    }
+
+    /// Called by the `NativeFinalizer` of the Dart handle, with the handle as its token
+    pub fn dispose_func_name(&self) -> String {
+        format!("frb_dispose_{}", self.safe_ident())
+    }
+
+    /// The static slab holding the objects, see `OpaqueSlab` of `flutter_rust_bridge`
+    pub fn slab_name(&self) -> String {
+        self.safe_ident().to_uppercase()
+    }
+}
+
+impl ApiTypeChild for ApiTypeOpaque {
+    fn safe_ident(&self) -> String {
+        format!("opaque_{}", self.name.to_case(Case::Snake))
+    }
+
+    fn dart_api_type(&self) -> String {
+        self.name.clone()
+    }
+
+    fn dart_wire_type(&self) -> String {
+        "int".to_string()
+    }
+
+    fn rust_api_type(&self) -> String {
+        self.rust_type.clone()
+    }
+
+    fn rust_wire_type(&self) -> String {
+        "i64".to_string()
+    }
+}
+
+/// A Dart function passed to a Rust function as a closure, e.g. `impl Fn(u32)` or
+/// `Option<Box<dyn Fn(f64) -> bool + Send>>`. Dart passes the native port of a `ReceivePort`
+/// listening to the calls, see `DartCallback` of `flutter_rust_bridge`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeCallback {
+    pub args: Vec<ApiType>,
+    /// [None] for `()`
+    pub ret: Option<ApiTypePrimitive>,
+    /// The closure returns `Result<ret>`, which is the error if the Dart function throws, while
+    /// otherwise it panics
+    pub ret_is_result: bool,
+    /// `Option<Box<dyn Fn(..)>>`, where Dart passes 0 for `null`
+    pub is_optional: bool,
+    /// The boxed closure, e.g. `Box<dyn Fn(u32) + Send + Sync>` of `impl Fn(u32)`
+    pub rust_box_type: String,
+}
+
+impl ApiTypeCallback {
+    pub const MAX_ARGS: usize = 3;
+    pub const REJECT_FUNC_NAME: &'static str = "wire_reject_callback";
+
+    /// e.g. `wire_reply_callback_i32` of callbacks returning `i32`
+    pub fn reply_func_name(&self) -> Option<String> {
+        self.ret
+            .as_ref()
+            .map(|ret| format!("wire_reply_callback_{}", ret.safe_ident()))
+    }
+}
+
+impl ApiTypeChild for ApiTypeCallback {
+    fn safe_ident(&self) -> String {
+        let words = self
+            .rust_api_type()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        format!("callback_{}", words.to_case(Case::Snake))
+    }
+
+    fn dart_api_type(&self) -> String {
+        format!(
+            "{} Function({}){}",
+            self.ret
+                .as_ref()
+                .map_or("void".to_string(), |ret| ret.dart_api_type()),
+            self.args
+                .iter()
+                .map(ApiType::dart_api_type)
+                .collect::<Vec<_>>()
+                .join(", "),
+            if self.is_optional { "?" } else { "" }
+        )
+    }
+
+    fn dart_wire_type(&self) -> String {
+        "int".to_string()
+    }
+
+    fn rust_api_type(&self) -> String {
+        if self.is_optional {
+            format!("Option<{}>", self.rust_box_type)
+        } else {
+            self.rust_box_type.clone()
+        }
+    }
+
+    fn rust_wire_type(&self) -> String {
+        "i64".to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTypeEnumRef {
+    pub name: String,
+}
+
+impl ApiTypeEnumRef {
+    pub fn get<'a>(&self, f: &'a ApiFile) -> &'a ApiEnum {
+        &f.enum_pool[&self.name]
+    }
+}
+
+impl ApiTypeChild for ApiTypeEnumRef {
+    fn safe_ident(&self) -> String {
+        self.dart_api_type().to_case(Case::Snake)
+    }
+    fn dart_api_type(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn dart_wire_type(&self) -> String {
+        "int".to_string()
+    }
+
+    fn rust_api_type(&self) -> String {
+        self.name.to_string()
+    }
+
+    /// passed as the index of the variant
+    fn rust_wire_type(&self) -> String {
+        "i32".to_string()
+    }
+}
+
+/// An enum, passed as the index of the variant if no variant carries data. Otherwise it is passed
+/// as a list of the index followed by the fields of the variant, which is only supported as output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiEnum {
+    pub name: String,
+    pub variants: Vec<ApiVariant>,
+    pub comments: Vec<Comment>,
+    /// `#[frb(enum_class)]`: emit a Dart enum with methods, which requires Dart 2.17
+    pub is_enum_class: bool,
+    /// `#[non_exhaustive]`: the Dart enum gets an extra `unknown` variant for unrecognised values
+    pub is_non_exhaustive: bool,
+}
+
+impl ApiEnum {
+    pub const UNKNOWN_VARIANT_DART_NAME: &'static str = "unknown";
+
+    /// Whether any variant carries data, i.e. the enum is generated as a Dart class hierarchy
+    pub fn is_struct(&self) -> bool {
+        self.variants
+            .iter()
+            .any(|variant| matches!(variant.kind, ApiVariantKind::Struct(_)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiVariant {
+    pub name: ApiIdent,
+    pub comments: Vec<Comment>,
+    pub kind: ApiVariantKind,
+}
+
+impl ApiVariant {
+    /// e.g. `MyError_NotFound`
+    pub fn dart_class_name(&self, enum_name: &str) -> String {
+        format!("{}_{}", enum_name, self.name.rust_style())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ApiVariantKind {
+    Value,
+    /// A tuple or struct variant, whose fields are stored as those of a struct named after the variant
+    Struct(Box<ApiStruct>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiStruct {
     pub name: String,
     pub fields: Vec<ApiField>,
     pub is_fields_named: bool,
     pub comments: Vec<Comment>,
+    /// The message of `#[deprecated]`, e.g. `since 1.2: use Bar instead`, empty if it has neither
+    /// `since` nor `note`. Emitted as `@Deprecated(...)` in Dart.
+    pub deprecated: Option<String>,
+    /// `#[frb(on_change)]`: emit a Dart class extending Flutter's `ChangeNotifier`, whose setters also
+    /// update the Rust copy of an object received from Rust and notify listeners
+    pub notify_on_change: bool,
+    /// `#[frb(on_change = "...")]`: the name of a Dart callback field that setters also call
+    pub on_change_callback: Option<String>,
+    pub associated_consts: Vec<ApiConst>,
+    /// non-`pub` fields are not bridged
+    pub num_private_fields: usize,
+    /// `#[derive(Default)]` or `impl Default`, which fills the private fields when constructing
+    /// from Dart
+    pub implements_default: bool,
+    /// `#[frb(mirror = "...")]`: a type of another crate with the same fields, e.g.
+    /// `image::Rgb<u8>`, which the generated Rust code converts from and into this struct
+    pub mirror: Option<String>,
+    /// The Rust type if it differs from [name], which is the name in Dart and of the wire struct:
+    /// a generic struct is bridged once per type argument, e.g. `ApiResponse<String>` as
+    /// `ApiResponseString`, and one in an inline module with the module path prefixed, e.g.
+    /// `geometry::Point` as `GeometryPoint`
+    pub generic_rust_type: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+impl ApiStruct {
+    pub fn rust_type(&self) -> &str {
+        self.generic_rust_type.as_ref().unwrap_or(&self.name)
+    }
+
+    pub fn has_private_fields(&self) -> bool {
+        self.num_private_fields > 0
+    }
+
+    /// The slab keeping the Rust copies of the objects marked `#[frb(on_change)]` sent to Dart
+    pub fn on_change_slab_name(&self) -> String {
+        format!("ON_CHANGE_{}", self.name.to_case(Case::UpperSnake))
+    }
+
+    pub fn on_change_setter_func_name(&self, field: &ApiField) -> String {
+        format!(
+            "wire_set_{}_{}",
+            self.name.to_case(Case::Snake),
+            field.name.rust_style()
+        )
+    }
+
+    pub fn on_change_drop_func_name(&self) -> String {
+        format!("drop_on_change_{}", self.name.to_case(Case::Snake))
+    }
+}
+
+/// An associated constant such as `impl MyStruct { pub const MAX_SIZE: u32 = 1024; }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConst {
+    pub name: ApiIdent,
+    pub ty: ApiType,
+    /// the value as a Dart literal
+    pub value: String,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiField {
     pub ty: ApiType,
     pub name: ApiIdent,
     pub comments: Vec<Comment>,
+    /// The message of `#[deprecated]`, see [ApiStruct::deprecated]
+    pub deprecated: Option<String>,
+    /// `#[frb(name = "...")]` on a struct field, see [ApiFunc::dart_name_override]
+    pub dart_name_override: Option<String>,
+    /// `#[frb(into)]` or `impl Into<T>` on a function parameter: Dart accepts more types than `T`
+    pub use_into: bool,
+    /// `#[frb(default = ...)]` on a struct field: the Dart expression of its default value, which
+    /// makes the field optional in the Dart constructor
+    pub default: Option<String>,
 }
 
 impl ApiField {
+    pub fn dart_name(&self) -> String {
+        self.dart_name_override
+            .clone()
+            .unwrap_or_else(|| self.name.dart_style())
+    }
+
     pub fn name_rust_style(&self, is_fields_named: bool) -> String {
         if is_fields_named {
             self.name.rust_style().to_string()
@@ -532,13 +1574,24 @@ impl ApiField {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTypeBoxed {
     /// if false, means that we automatically add it when transforming it - it does not exist in real api.
     pub exist_in_real_api: bool,
     pub inner: ApiType,
 }
 
+impl ApiTypeBoxed {
+    /// Whether the box holds a plain value on the wire, e.g. `*mut i32`, rather than a wire struct
+    pub fn is_primitive(&self) -> bool {
+        match &self.inner {
+            Primitive(_) | EnumRef(_) => true,
+            Delegate(d) => !d.rust_wire_is_pointer(),
+            _ => false,
+        }
+    }
+}
+
 impl ApiTypeChild for ApiTypeBoxed {
     fn safe_ident(&self) -> String {
         format!(
@@ -557,10 +1610,14 @@ impl ApiTypeChild for ApiTypeBoxed {
     }
 
     fn dart_wire_type(&self) -> String {
-        let wire_type = if let Primitive(prim) = &self.inner {
-            prim.dart_native_type().to_owned()
-        } else {
-            self.inner.dart_wire_type()
+        let wire_type = match &self.inner {
+            Primitive(prim) => prim.dart_native_type().to_owned(),
+            EnumRef(_) => ApiTypePrimitive::I32.dart_native_type().to_owned(),
+            Delegate(d) => match d.get_delegate() {
+                Primitive(prim) => prim.dart_native_type().to_owned(),
+                _ => self.inner.dart_wire_type(),
+            },
+            _ => self.inner.dart_wire_type(),
         };
         format!("ffi.Pointer<{}>", wire_type)
     }
@@ -582,7 +1639,7 @@ impl ApiTypeChild for ApiTypeBoxed {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiTypeOptional {
     pub inner: Box<ApiType>,
 }
@@ -604,11 +1661,14 @@ impl ApiTypeOptional {
     }
 
     pub fn is_primitive(&self) -> bool {
-        matches!(&*self.inner, Boxed(boxed) if matches!(boxed.inner, ApiType::Primitive(_)))
+        matches!(&*self.inner, Boxed(boxed) if boxed.is_primitive())
     }
 
     pub fn is_list(&self) -> bool {
-        matches!(&*self.inner, GeneralList(_) | PrimitiveList(_))
+        matches!(
+            &*self.inner,
+            GeneralList(_) | PrimitiveList(_) | AssocList(_) | Map(_) | Tuple(_)
+        )
     }
 
     pub fn is_delegate(&self) -> bool {
@@ -641,7 +1701,7 @@ impl ApiTypeChild for ApiTypeOptional {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment(String);
 
 impl Comment {
@@ -655,10 +1715,26 @@ impl From<&str> for Comment {
         if input.contains('\n') {
             // Dart's formatter has issues with block comments
             // so we convert them ahead of time.
-            let formatted = input
-                .split('\n')
-                .into_iter()
-                .map(|e| format!("///{}", e))
+            // The lines of block comments after the first one are indented like the code around
+            // them, so their common indentation is replaced by the single space of `/// `.
+            let mut lines = input.split('\n').map(str::trim_end);
+            let first_line = lines.next().unwrap().trim_start();
+            let lines = lines.collect::<Vec<_>>();
+            let indent = lines
+                .iter()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(0);
+            let formatted = std::iter::once(first_line)
+                .chain(lines.iter().map(|line| line.get(indent..).unwrap_or("")))
+                .map(|line| {
+                    if line.is_empty() {
+                        "///".to_string()
+                    } else {
+                        format!("/// {}", line)
+                    }
+                })
                 .collect::<Vec<_>>()
                 .join("\n");
             Self(formatted)
@@ -667,3 +1743,141 @@ impl From<&str> for Comment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn merges_files_without_conflicts() {
+        let merged = ApiFile::merge(vec![
+            (
+                "src/a.rs".to_string(),
+                parse_source("pub struct A { pub x: i32 } pub fn a(a: A) -> Result<i32> { Ok(0) }"),
+            ),
+            (
+                "src/b.rs".to_string(),
+                parse_source("pub enum B { X, Y } pub fn b(b: B) -> Result<i32> { Ok(0) }"),
+            ),
+        ])
+        .unwrap();
+
+        let names: Vec<_> = merged.funcs.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(merged.struct_pool.contains_key("A"));
+        assert!(merged.enum_pool.contains_key("B"));
+    }
+
+    #[test]
+    fn reports_both_paths_of_merge_conflicts() {
+        for (source, expected) in [
+            (
+                "pub fn f() -> Result<i32> { Ok(0) }",
+                MergeError::DuplicateFunction {
+                    name: "f".to_string(),
+                    first_path: "src/a.rs".to_string(),
+                    second_path: "src/c.rs".to_string(),
+                },
+            ),
+            (
+                "pub struct S { pub x: i32 } pub fn g(s: S) -> Result<i32> { Ok(0) }",
+                MergeError::DuplicateStruct {
+                    name: "S".to_string(),
+                    first_path: "src/a.rs".to_string(),
+                    second_path: "src/c.rs".to_string(),
+                },
+            ),
+            (
+                "pub enum E { X } pub fn g(e: E) -> Result<i32> { Ok(0) }",
+                MergeError::DuplicateEnum {
+                    name: "E".to_string(),
+                    first_path: "src/b.rs".to_string(),
+                    second_path: "src/c.rs".to_string(),
+                },
+            ),
+        ] {
+            let err = ApiFile::merge(vec![
+                (
+                    "src/a.rs".to_string(),
+                    parse_source(
+                        "pub struct S { pub x: i32 } pub fn f(s: S) -> Result<i32> { Ok(0) }",
+                    ),
+                ),
+                (
+                    "src/b.rs".to_string(),
+                    parse_source("pub enum E { X } pub fn e(e: E) -> Result<i32> { Ok(0) }"),
+                ),
+                ("src/c.rs".to_string(), parse_source(source)),
+            ])
+            .unwrap_err();
+            assert_eq!(err, expected);
+        }
+    }
+
+    #[test]
+    fn merge_conflicts_name_both_files() {
+        let err = ApiFile::merge(vec![
+            (
+                "src/a.rs".to_string(),
+                parse_source("pub fn f() -> Result<i32> { Ok(0) }"),
+            ),
+            (
+                "src/b.rs".to_string(),
+                parse_source("pub fn f() -> Result<i32> { Ok(0) }"),
+            ),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "function `f` is defined in both src/a.rs and src/b.rs"
+        );
+    }
+
+    #[test]
+    fn saves_and_loads_nested_structs() {
+        let api_file = parse_source(
+            "
+            pub struct Inner { pub values: Vec<i32>, pub name: Option<String> }
+            pub struct Outer { pub inner: Inner, pub inners: Vec<Inner> }
+            pub fn f(outer: Outer) -> Result<Outer> { Ok(outer) }
+            ",
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.json");
+
+        save_api_file(&api_file, &path).unwrap();
+        let loaded = load_api_file(&path).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&api_file).unwrap()
+        );
+        let outer = &loaded.struct_pool["Outer"];
+        assert!(matches!(&outer.fields[0].ty, StructRef(s) if s.name == "Inner"));
+        assert!(matches!(
+            &loaded.struct_pool["Inner"].fields[1].ty,
+            Optional(optional) if matches!(&*optional.inner, Delegate(ApiTypeDelegate::String))
+        ));
+    }
+
+    #[test]
+    fn dart_case_capitalizes_acronyms() {
+        for (raw, camel, pascal) in [
+            (
+                "get_http_response_url",
+                "getHttpResponseUrl",
+                "GetHttpResponseUrl",
+            ),
+            ("parseXMLDocument", "parseXmlDocument", "ParseXmlDocument"),
+            ("JSONValue", "jsonValue", "JsonValue"),
+            ("user_id", "userId", "UserId"),
+            ("read_io", "readIO", "ReadIO"),
+            ("io_sink", "ioSink", "IOSink"),
+            ("field0", "field0", "Field0"),
+        ] {
+            assert_eq!(ApiIdent::to_dart_camel_case(raw), camel);
+            assert_eq!(ApiIdent::to_dart_pascal_case(raw), pascal);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Cache of the parsed input files next to the Rust output, so that unchanged inputs are not
+//! parsed again, see [RawOpts::no_cache](crate::config::RawOpts::no_cache)
+
+use std::convert::TryInto;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::api_types::ApiFile;
+use crate::config::Opts;
+
+/// Bumped whenever the cache format changes without a new version of the code generator
+const CACHE_FORMAT_VERSION: u32 = 8;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ParseCache {
+    /// See [cache_version], a cache of any other version is discarded
+    version: String,
+    /// The options that affect parsing, a cache parsed with other ones is discarded
+    parse_options: String,
+    entries: Vec<CacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    path: String,
+    metadata: FileMetadata,
+    api_file: ApiFile,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileMetadata {
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    size: u64,
+    sha256: String,
+}
+
+fn cache_version() -> String {
+    format!("{}+{}", env!("CARGO_PKG_VERSION"), CACHE_FORMAT_VERSION)
+}
+
+fn parse_options(config: &Opts) -> String {
+    format!(
+        "{:?} {:?} {:?} {:?}",
+        config.skip_unsupported_functions,
+        config.dart_uuid_style,
+        config.included_visibility,
+        config.features,
+    )
+}
+
+/// e.g. `src/bridge_generated.rs.frb-cache`
+pub(crate) fn cache_path(config: &Opts) -> String {
+    format!("{}.frb-cache", config.rust_output_path)
+}
+
+impl ParseCache {
+    /// Load the cache of the previous run, or an empty one if there is none or it is outdated
+    pub fn load(config: &Opts) -> ParseCache {
+        let path = cache_path(config);
+        let empty = ParseCache {
+            version: cache_version(),
+            parse_options: parse_options(config),
+            entries: vec![],
+        };
+        match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(serde_json::from_str::<ParseCache>(&content)?))
+        {
+            Ok(cache) if cache.version == empty.version => {
+                if cache.parse_options == empty.parse_options {
+                    cache
+                } else {
+                    debug!("Discard cache {} parsed with other options", path);
+                    empty
+                }
+            }
+            Ok(cache) => {
+                debug!("Discard cache {} of version {}", path, cache.version);
+                empty
+            }
+            Err(err) => {
+                debug!("No usable cache {}: {}", path, err);
+                empty
+            }
+        }
+    }
+
+    /// The cached [ApiFile] of the input at [path] if it is unchanged since it was cached, or
+    /// otherwise the result of [parse] on its content, which replaces the cached one. The content
+    /// is only read and hashed if the modification time or size differ from the cached ones.
+    pub fn get_or_parse(&mut self, path: &str, parse: impl FnOnce(&str) -> ApiFile) -> ApiFile {
+        let (mtime, size) = mtime_and_size(Path::new(path));
+        let idx = self.entries.iter().position(|entry| entry.path == path);
+        if let Some(entry) = idx.map(|idx| &self.entries[idx]) {
+            let cached = &entry.metadata;
+            if (cached.mtime_secs, cached.mtime_nanos, cached.size) == (mtime.0, mtime.1, size) {
+                debug!("Unchanged since cached: {}", path);
+                return entry.api_file.clone();
+            }
+        }
+
+        let content = fs::read_to_string(path).unwrap();
+        let metadata = FileMetadata {
+            mtime_secs: mtime.0,
+            mtime_nanos: mtime.1,
+            size,
+            sha256: sha256_hex(content.as_bytes()),
+        };
+        if let Some(entry) = idx.map(|idx| &mut self.entries[idx]) {
+            // touched without being changed, e.g. by switching git branches back and forth
+            if entry.metadata.sha256 == metadata.sha256 {
+                debug!("Touched but unchanged since cached: {}", path);
+                entry.metadata = metadata;
+                return entry.api_file.clone();
+            }
+        }
+
+        let api_file = parse(&content);
+        let entry = CacheEntry {
+            path: path.to_string(),
+            metadata,
+            api_file: api_file.clone(),
+        };
+        match idx {
+            Some(idx) => self.entries[idx] = entry,
+            None => self.entries.push(entry),
+        }
+        api_file
+    }
+
+    /// Store the entries of the current inputs, called after a successful run
+    pub fn save(mut self, config: &Opts) -> Result<()> {
+        self.entries
+            .retain(|entry| config.rust_input_paths.contains(&entry.path));
+        fs::write(cache_path(config), serde_json::to_string(&self)?)?;
+        Ok(())
+    }
+}
+
+/// Modification time as seconds and nanoseconds since the Unix epoch, and size in bytes
+fn mtime_and_size(path: &Path) -> ((u64, u32), u64) {
+    let metadata = fs::metadata(path).unwrap();
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    ((mtime.as_secs(), mtime.subsec_nanos()), metadata.len())
+}
+
+/// SHA-256 of [data] as lowercase hex, see FIPS 180-4
+fn sha256_hex(data: &[u8]) -> String {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes(bytes.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for (k, w) in K.iter().zip(&w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(*w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    }
+
+    state.iter().map(|word| format!("{:08x}", word)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::parser;
+
+    #[test]
+    fn get_or_parse_reuses_unchanged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("api.rs").to_str().unwrap().to_string();
+        let mut cache = ParseCache {
+            version: cache_version(),
+            parse_options: String::new(),
+            entries: vec![],
+        };
+        let parse_count = Cell::new(0);
+        let mut get_func_names = || {
+            cache
+                .get_or_parse(&path, |content| {
+                    parse_count.set(parse_count.get() + 1);
+                    parser::parse_source(content)
+                })
+                .funcs
+                .into_iter()
+                .map(|func| func.name)
+                .collect::<Vec<_>>()
+        };
+
+        fs::write(&path, "pub fn f() -> Result<i32> { Ok(1) }").unwrap();
+        assert_eq!(get_func_names(), ["f"]);
+        assert_eq!(get_func_names(), ["f"]);
+        assert_eq!(parse_count.get(), 1);
+
+        fs::write(&path, "pub fn f() -> Result<i32> { Ok(1) }").unwrap();
+        assert_eq!(get_func_names(), ["f"]);
+        assert_eq!(parse_count.get(), 1);
+
+        fs::write(&path, "pub fn g(a: i32) -> Result<i32> { Ok(1) }").unwrap();
+        assert_eq!(get_func_names(), ["g"]);
+        assert_eq!(parse_count.get(), 2);
+    }
+
+    #[test]
+    fn sha256_matches_known_digests() {
+        for (data, digest) in [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ] {
+            assert_eq!(sha256_hex(data.as_bytes()), digest);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Verify that the generated files are up to date, see [crate::config::RawOpts::check]
+
+use std::cmp::max;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use log::info;
+use tempfile::TempDir;
+
+use crate::config::Opts;
+use crate::frb_codegen;
+use crate::others::strip_generation_timestamp;
+
+/// Unchanged lines shown around each change
+const DIFF_CONTEXT: usize = 3;
+
+/// Above this, changed regions are shown as fully removed and re-added instead of computing
+/// the minimal diff, which needs memory proportional to this
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Generate into a temporary directory and compare with the existing output files, printing a
+/// diff for each outdated one. Nothing is written to the output paths.
+pub fn check(config: &Opts) -> bool {
+    let (_staging_dir, outputs) = generate_staged(config);
+
+    let mut up_to_date = true;
+    for output in outputs {
+        // otherwise it is a temporary file, see [crate::config::parse]
+        if output.label == "C" && Path::new(&output.path).starts_with(env::temp_dir()) {
+            continue;
+        }
+        let existing = fs::read_to_string(&output.path).unwrap_or_default();
+        let generated = fs::read_to_string(&output.staged_path).unwrap();
+        let existing = strip_generation_timestamp(&existing);
+        let generated = strip_generation_timestamp(&generated);
+        if existing == generated {
+            info!("Up to date: {}", output.path);
+        } else {
+            up_to_date = false;
+            println!("{}", format_diff(&output.path, &existing, &generated));
+        }
+    }
+    up_to_date
+}
+
+/// An output file, generated into [StagedOutput::staged_path] instead of [StagedOutput::path]
+pub(crate) struct StagedOutput {
+    pub label: &'static str,
+    pub path: String,
+    pub staged_path: String,
+}
+
+/// Run the generation with all outputs redirected into a temporary directory, which is deleted
+/// when the returned [TempDir] is dropped
+pub(crate) fn generate_staged(config: &Opts) -> (TempDir, Vec<StagedOutput>) {
+    // inside the crate, so that `rustfmt` picks up the same `rustfmt.toml`
+    let staging_dir = tempfile::Builder::new()
+        .prefix(".frb_check")
+        .tempdir_in(&config.rust_crate_dir)
+        .unwrap();
+    let stage = |path: &str| staged_path(staging_dir.path(), path);
+
+    let staged_config = Opts {
+        rust_output_path: stage(&config.rust_output_path),
+        c_output_paths: config
+            .c_output_paths
+            .iter()
+            .map(|path| stage(path))
+            .collect(),
+        dart_output_path: stage(&config.dart_output_path),
+        dart_test_output_path: config.dart_test_output_path.as_deref().map(stage),
+        dart_impl_output_path: config.dart_impl_output_path.as_deref().map(stage),
+        skip_add_mod_to_lib: true,
+        ..config.clone()
+    };
+    frb_codegen(&staged_config);
+
+    let output = |label, path: &String, staged_path: &String| StagedOutput {
+        label,
+        path: path.clone(),
+        staged_path: staged_path.clone(),
+    };
+    let mut outputs = if config.split_output {
+        let mut names: Vec<_> = fs::read_dir(staged_config.rust_output_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != "mod.rs")
+            .collect();
+        names.sort();
+        names.insert(0, "mod.rs".to_string());
+        names
+            .iter()
+            .map(|name| {
+                let join = |dir: String| Path::new(&dir).join(name).to_str().unwrap().to_string();
+                output(
+                    "Rust",
+                    &join(config.rust_output_dir()),
+                    &join(staged_config.rust_output_dir()),
+                )
+            })
+            .collect()
+    } else {
+        vec![output(
+            "Rust",
+            &config.rust_output_path,
+            &staged_config.rust_output_path,
+        )]
+    };
+    outputs.push(output(
+        "Dart",
+        &config.dart_output_path,
+        &staged_config.dart_output_path,
+    ));
+    if let (Some(path), Some(staged_path)) = (
+        &config.dart_impl_output_path,
+        &staged_config.dart_impl_output_path,
+    ) {
+        outputs.push(output("Dart impl", path, staged_path));
+    }
+    outputs.extend(
+        config
+            .c_output_paths
+            .iter()
+            .zip(&staged_config.c_output_paths)
+            .map(|(path, staged_path)| output("C", path, staged_path)),
+    );
+    if let (Some(path), Some(staged_path)) = (
+        &config.dart_test_output_path,
+        &staged_config.dart_test_output_path,
+    ) {
+        outputs.push(output("Dart test stubs", path, staged_path));
+    }
+    (staging_dir, outputs)
+}
+
+/// e.g. `/app/rust/../lib/api.dart` => `<staging_dir>/app/lib/api.dart`, keeping the relative
+/// paths between the outputs, which may appear in the generated code
+pub(crate) fn staged_path(staging_dir: &Path, path: &str) -> String {
+    let mut staged = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(name) => staged.push(name),
+            Component::ParentDir => {
+                staged.pop();
+            }
+            _ => {}
+        }
+    }
+    let staged = staging_dir.join(staged);
+    fs::create_dir_all(staged.parent().unwrap()).unwrap();
+    staged.to_str().unwrap().to_string()
+}
+
+/// A unified diff from [old] to [new]
+fn format_diff(path: &str, old: &str, new: &str) -> String {
+    let ops = diff_lines(
+        &old.lines().collect::<Vec<_>>(),
+        &new.lines().collect::<Vec<_>>(),
+    );
+    let changes: Vec<_> = (0..ops.len()).filter(|&idx| ops[idx].0 != ' ').collect();
+
+    let mut ans = format!("--- {} (on disk)\n+++ {} (generated)\n", path, path);
+    let mut idx = 0;
+    while idx < changes.len() {
+        let start = changes[idx].saturating_sub(DIFF_CONTEXT);
+        let mut end = changes[idx] + 1;
+        while idx < changes.len() && changes[idx] <= end + 2 * DIFF_CONTEXT {
+            end = changes[idx] + 1;
+            idx += 1;
+        }
+        let end = (end + DIFF_CONTEXT).min(ops.len());
+
+        // an empty range starts at the line before it, e.g. `-0,0` for an empty old file
+        let range = |excluded: char| {
+            let count =
+                |ops: &[(char, &str)]| ops.iter().filter(|(tag, _)| *tag != excluded).count();
+            let len = count(&ops[start..end]);
+            let first = count(&ops[..start]) + usize::from(len > 0);
+            format!("{},{}", first, len)
+        };
+        ans += &format!("@@ -{} +{} @@\n", range('+'), range('-'));
+        for (tag, line) in &ops[start..end] {
+            ans += &format!("{}{}\n", tag, line);
+        }
+    }
+    ans
+}
+
+/// Each line of [old] and [new] tagged by ` ` (unchanged), `-` (removed) or `+` (added)
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<_> = old[..prefix].iter().map(|line| (' ', *line)).collect();
+    let (mut i, mut j) = (0, 0);
+    if old_mid.len() * new_mid.len() <= MAX_LCS_CELLS {
+        // lcs[i][j] is the length of the longest common subsequence of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![vec![0u32; new_mid.len() + 1]; old_mid.len() + 1];
+        for i in (0..old_mid.len()).rev() {
+            for j in (0..new_mid.len()).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    max(lcs[i + 1][j], lcs[i][j + 1])
+                };
+            }
+        }
+        while i < old_mid.len() && j < new_mid.len() {
+            if old_mid[i] == new_mid[j] {
+                ops.push((' ', old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                ops.push(('-', old_mid[i]));
+                i += 1;
+            } else {
+                ops.push(('+', new_mid[j]));
+                j += 1;
+            }
+        }
+    }
+    ops.extend(old_mid[i..].iter().map(|line| ('-', *line)));
+    ops.extend(new_mid[j..].iter().map(|line| ('+', *line)));
+    ops.extend(old[old.len() - suffix..].iter().map(|line| (' ', *line)));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_inserted_deleted_and_replaced_lines() {
+        assert_eq!(
+            diff_lines(&["a", "c"], &["a", "b", "c"]),
+            [(' ', "a"), ('+', "b"), (' ', "c")]
+        );
+        assert_eq!(
+            diff_lines(&["a", "b", "c"], &["a", "c"]),
+            [(' ', "a"), ('-', "b"), (' ', "c")]
+        );
+        assert_eq!(
+            diff_lines(&["a", "b", "c"], &["a", "x", "c"]),
+            [(' ', "a"), ('-', "b"), ('+', "x"), (' ', "c")]
+        );
+        assert_eq!(diff_lines(&["a"], &["a"]), [(' ', "a")]);
+    }
+
+    #[test]
+    fn diffs_empty_files() {
+        assert_eq!(diff_lines(&[], &[]), []);
+        assert_eq!(diff_lines(&[], &["a", "b"]), [('+', "a"), ('+', "b")]);
+        assert_eq!(diff_lines(&["a", "b"], &[]), [('-', "a"), ('-', "b")]);
+    }
+
+    #[test]
+    fn formats_hunks_with_context() {
+        let old: Vec<_> = (1..=20).map(|i| i.to_string()).collect();
+        let mut new = old.clone();
+        new[9] = "ten".to_string();
+        new.insert(15, "inserted".to_string());
+        new.remove(1);
+
+        assert_eq!(
+            format_diff("a.rs", &old.join("\n"), &new.join("\n")),
+            "--- a.rs (on disk)
++++ a.rs (generated)
+@@ -1,5 +1,4 @@
+ 1
+-2
+ 3
+ 4
+ 5
+@@ -7,12 +6,13 @@
+ 7
+ 8
+ 9
+-10
++ten
+ 11
+ 12
+ 13
+ 14
+ 15
++inserted
+ 16
+ 17
+ 18
+"
+        );
+    }
+
+    #[test]
+    fn formats_diffs_of_empty_files() {
+        assert_eq!(
+            format_diff("a.rs", "", "a\nb\n"),
+            "--- a.rs (on disk)\n+++ a.rs (generated)\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+        assert_eq!(
+            format_diff("a.rs", "a\n", ""),
+            "--- a.rs (on disk)\n+++ a.rs (generated)\n@@ -1,1 +0,0 @@\n-a\n"
+        );
+        assert_eq!(
+            format_diff("a.rs", "a\n", "a\n"),
+            "--- a.rs (on disk)\n+++ a.rs (generated)\n"
+        );
+    }
+}
@@ -1,26 +1,56 @@
+use std::fs;
 use std::io::Write;
 use std::path::Path;
 use std::process::Command;
 
 use log::{debug, warn};
+use toml::Value;
 
+use crate::config::Opts;
+
+/// The crate, LLVM and `cbindgen.toml` are those of [config], while the outputs are written to
+/// the given paths, which are usually temporary
 pub fn bindgen_rust_to_dart(
-    rust_crate_dir: &str,
+    config: &Opts,
+    rust_output_path: &str,
     c_output_path: &str,
     dart_output_path: &str,
-    dart_class_name: &str,
     c_struct_names: Vec<String>,
-    llvm_install_path: &str,
 ) {
-    cbindgen(rust_crate_dir, c_output_path, c_struct_names);
+    let features = config.features.as_deref().unwrap_or_default();
+    cbindgen(
+        &config.rust_crate_dir,
+        rust_output_path,
+        c_output_path,
+        c_struct_names,
+        config.cbindgen_toml_path.as_deref(),
+        features,
+    );
     ffigen(
         c_output_path,
         dart_output_path,
-        dart_class_name,
-        llvm_install_path,
+        &config.dart_wire_class_name(),
+        &config.llvm_path,
+        features,
     );
 }
 
+/// The C macro that guards items behind `#[cfg(feature = "...")]` in the cbindgen output, and that
+/// is defined for ffigen when the feature is enabled
+fn feature_define(feature: &str) -> String {
+    let name: String = feature
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("FRB_FEATURE_{}", name)
+}
+
 fn execute_command(arg: &str, current_dir: Option<&str>) {
     let mut cmd = if cfg!(target_os = "windows") {
         let mut cmd = Command::new("cmd");
@@ -80,29 +110,28 @@ fn execute_command(arg: &str, current_dir: Option<&str>) {
     }
 }
 
-fn cbindgen(rust_crate_dir: &str, c_output_path: &str, c_struct_names: Vec<String>) {
+fn cbindgen(
+    rust_crate_dir: &str,
+    rust_output_path: &str,
+    c_output_path: &str,
+    c_struct_names: Vec<String>,
+    cbindgen_toml_path: Option<&str>,
+    features: &[String],
+) {
     debug!(
-        "execute cbindgen rust_crate_dir={} c_output_path={}",
-        rust_crate_dir, c_output_path
+        "execute cbindgen rust_crate_dir={} c_output_path={} cbindgen_toml_path={:?} features={:?}",
+        rust_crate_dir, c_output_path, cbindgen_toml_path, features
     );
 
-    let config = format!(
-        r#"
-language = "C"
-
-# do NOT include "stdarg.h", see #108 and #53
-sys_includes = ["stdbool.h", "stdint.h", "stdlib.h"]
-no_includes = true
-
-[export]
-include = [{}]
-"#,
-        c_struct_names
-            .iter()
-            .map(|name| format!("\"{}\"", name))
-            .collect::<Vec<_>>()
-            .join(", ")
-    );
+    let config = match cbindgen_toml_path {
+        Some(path) => merge_cbindgen_config(
+            &fs::read_to_string(path)
+                .unwrap_or_else(|_| panic!("fail to read cbindgen toml: {}", path)),
+            &c_struct_names,
+            features,
+        ),
+        None => default_cbindgen_config(&c_struct_names, features),
+    };
     debug!("cbindgen config: {}", config);
 
     let mut config_file = tempfile::NamedTempFile::new().unwrap();
@@ -119,20 +148,120 @@ include = [{}]
         path = &path[r"\\?\".len()..];
     }
 
+    // cbindgen only follows `mod` declarations of the crate, so it cannot see generated code
+    // outside of `src` (e.g. in `OUT_DIR` when generating from `build.rs`) unless given explicitly
+    let input = if Path::new(rust_output_path).starts_with(Path::new(rust_crate_dir).join("src")) {
+        ""
+    } else {
+        rust_output_path
+    };
+
     execute_command(
         &format!(
-            "cbindgen -v --config {} --output {}",
+            "cbindgen -v --config {} --output {} {}",
             config_file.path().to_str().unwrap(),
             c_output_path,
+            input,
         ),
         Some(path),
     );
 }
 
-fn ffigen(c_path: &str, dart_path: &str, dart_class_name: &str, llvm_path: &str) {
+fn default_cbindgen_config(c_struct_names: &[String], features: &[String]) -> String {
+    format!(
+        r#"
+language = "C"
+
+# do NOT include "stdarg.h", see #108 and #53
+sys_includes = ["stdbool.h", "stdint.h", "stdlib.h"]
+no_includes = true
+
+[export]
+include = [{}]
+
+[defines]
+{}
+"#,
+        c_struct_names
+            .iter()
+            .map(|name| format!("\"{}\"", name))
+            .collect::<Vec<_>>()
+            .join(", "),
+        features
+            .iter()
+            .map(|feature| format!(
+                "\"feature = {}\" = \"{}\"",
+                feature,
+                feature_define(feature)
+            ))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Use the user-provided cbindgen config, while still ensuring the wire structs are exported and
+/// the features are defined
+fn merge_cbindgen_config(
+    user_config: &str,
+    c_struct_names: &[String],
+    features: &[String],
+) -> String {
+    let mut config = user_config
+        .parse::<Value>()
+        .expect("fail to parse cbindgen toml");
+    let default_config = default_cbindgen_config(c_struct_names, features)
+        .parse::<Value>()
+        .unwrap();
+
+    let table = config
+        .as_table_mut()
+        .expect("cbindgen toml should be a table");
+    for key in ["language", "sys_includes", "no_includes"] {
+        if !table.contains_key(key) {
+            table.insert(key.to_string(), default_config[key].clone());
+        }
+    }
+
+    let include = table
+        .entry("export")
+        .or_insert_with(|| Value::Table(Default::default()))
+        .as_table_mut()
+        .expect("`export` in cbindgen toml should be a table")
+        .entry("include")
+        .or_insert_with(|| Value::Array(vec![]))
+        .as_array_mut()
+        .expect("`export.include` in cbindgen toml should be an array");
+    for name in c_struct_names {
+        let name = Value::String(name.clone());
+        if !include.contains(&name) {
+            include.push(name);
+        }
+    }
+
+    let defines = table
+        .entry("defines")
+        .or_insert_with(|| Value::Table(Default::default()))
+        .as_table_mut()
+        .expect("`defines` in cbindgen toml should be a table");
+    for (key, value) in default_config["defines"].as_table().unwrap() {
+        if !defines.contains_key(key) {
+            defines.insert(key.clone(), value.clone());
+        }
+    }
+
+    toml::to_string(&config).unwrap()
+}
+
+fn ffigen(
+    c_path: &str,
+    dart_path: &str,
+    dart_class_name: &str,
+    llvm_path: &str,
+    features: &[String],
+) {
     debug!(
-        "execute ffigen c_path={} dart_path={} llvm_path={:?}",
-        c_path, dart_path, llvm_path
+        "execute ffigen c_path={} dart_path={} llvm_path={:?} features={:?}",
+        c_path, dart_path, llvm_path, features
     );
     let mut config = format!(
         "
@@ -158,6 +287,19 @@ fn ffigen(c_path: &str, dart_path: &str, dart_class_name: &str, llvm_path: &str)
             config, llvm_path
         );
     }
+    if !features.is_empty() {
+        config = format!(
+            "{}
+        compiler-opts:
+{}",
+            config,
+            features
+                .iter()
+                .map(|feature| format!("            - '-D{}'", feature_define(feature)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
 
     debug!("ffigen config: {}", config);
 
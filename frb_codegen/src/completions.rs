@@ -0,0 +1,192 @@
+//! Print shell completion scripts, see [crate::config::RawOpts::completions]
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use structopt::clap::Shell;
+use structopt::StructOpt;
+
+use crate::config::RawOpts;
+
+/// What the value of an option is completed as. clap itself only completes `possible_values`,
+/// and otherwise completes anything as files in bash, and nothing at all in zsh.
+#[derive(Clone, Copy)]
+enum ValueHint {
+    File,
+    Directory,
+    /// e.g. a class name, which is not completed
+    Other,
+}
+
+/// Every option taking a value without `possible_values`, by its long name
+const VALUE_HINTS: &[(&str, ValueHint)] = &[
+    ("rust-input", ValueHint::File),
+    ("dart-output", ValueHint::File),
+    ("c-output", ValueHint::File),
+    ("rust-crate-dir", ValueHint::Directory),
+    ("rust-output", ValueHint::File),
+    ("class-name", ValueHint::Other),
+    ("dart-library-name", ValueHint::Other),
+    ("dart-format-line-length", ValueHint::Other),
+    ("llvm-path", ValueHint::Directory),
+    ("dart-test-output", ValueHint::File),
+    ("dart-impl-output", ValueHint::File),
+    ("features", ValueHint::Other),
+    ("cbindgen-toml", ValueHint::File),
+    ("config", ValueHint::File),
+];
+
+fn value_hint(long: &str) -> ValueHint {
+    VALUE_HINTS
+        .iter()
+        .find(|(name, _)| *name == long)
+        .map(|(_, hint)| *hint)
+        .unwrap_or_else(|| panic!("--{} should be added to VALUE_HINTS", long))
+}
+
+/// Print the completion script for `shell`, one of the `possible_values` of
+/// [RawOpts::completions]
+pub fn completions(bin_name: &str, shell: &str) {
+    io::stdout()
+        .write_all(generate(bin_name, shell).as_bytes())
+        .unwrap();
+}
+
+fn generate(bin_name: &str, shell: &str) -> String {
+    let shell: Shell = shell.parse().unwrap();
+    let mut script = Vec::new();
+    RawOpts::clap().gen_completions_to(bin_name, shell, &mut script);
+    let script = String::from_utf8(script).unwrap();
+
+    let mut script = match shell {
+        Shell::Bash => complete_values_bash(&script),
+        Shell::Zsh => complete_values_zsh(&script),
+        Shell::Fish => complete_values_fish(&script),
+        _ => script,
+    };
+    if !script.ends_with('\n') {
+        script.push('\n');
+    }
+    script
+}
+
+/// Values without `possible_values` are completed as files by clap. The case of a short option
+/// directly follows the one of its long option.
+fn complete_values_bash(script: &str) -> String {
+    lazy_static! {
+        static ref CASE_LONG: Regex = Regex::new(r"^\s*--([a-z-]+)\)$").unwrap();
+    }
+    const COMPLETE_FILES: &str = r#"COMPREPLY=($(compgen -f "${cur}"))"#;
+
+    let mut long = String::new();
+    script
+        .lines()
+        .map(|line| {
+            if let Some(captures) = CASE_LONG.captures(line) {
+                long = captures[1].to_string();
+            }
+            if !line.contains(COMPLETE_FILES) {
+                return line.to_string();
+            }
+            match value_hint(&long) {
+                ValueHint::File => line.to_string(),
+                ValueHint::Directory => {
+                    line.replace(COMPLETE_FILES, r#"COMPREPLY=($(compgen -d "${cur}"))"#)
+                }
+                ValueHint::Other => line.replace(COMPLETE_FILES, "COMPREPLY=()"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Options taking values are declared without any argument by clap. A short option has the same
+/// description as its long one.
+fn complete_values_zsh(script: &str) -> String {
+    lazy_static! {
+        static ref SPEC: Regex =
+            Regex::new(r"^'(\([^)]*\))?(-[a-zA-Z]\+|--([a-z-]+)=)\[(.*)\]' \\$").unwrap();
+    }
+
+    let actions: HashMap<_, _> = script
+        .lines()
+        .filter_map(|line| SPEC.captures(line))
+        .filter_map(|captures| {
+            let long = captures.get(3)?.as_str();
+            let action = match value_hint(long) {
+                ValueHint::File => ": :_files",
+                ValueHint::Directory => ": :_files -/",
+                ValueHint::Other => ": : ",
+            };
+            Some((captures[4].to_string(), action))
+        })
+        .collect();
+
+    script
+        .lines()
+        .map(|line| match SPEC.captures(line) {
+            Some(captures) => line.replacen("]' \\", &format!("]{}' \\", actions[&captures[4]]), 1),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Options taking values are declared like flags by clap, whose values fish completes as files
+fn complete_values_fish(script: &str) -> String {
+    lazy_static! {
+        static ref LONG: Regex = Regex::new(r" -l ([a-z-]+) ").unwrap();
+    }
+
+    script
+        .lines()
+        .map(|line| match LONG.captures(line) {
+            Some(captures)
+                if !line.ends_with('"')
+                    && VALUE_HINTS.iter().any(|(name, _)| *name == &captures[1]) =>
+            {
+                let arguments = match value_hint(&captures[1]) {
+                    ValueHint::File => " -r",
+                    ValueHint::Directory => " -r -f -a \"(__fish_complete_directories)\"",
+                    ValueHint::Other => " -r -f",
+                };
+                format!("{}{}", line, arguments)
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Also makes sure that [VALUE_HINTS] covers all options
+    #[test]
+    fn completes_values_of_options() {
+        let bash = generate("frb", "bash");
+        assert!(bash.contains(
+            "--rust-crate-dir)
+                    COMPREPLY=($(compgen -d \"${cur}\"))"
+        ));
+        assert!(bash.contains(
+            "--class-name)
+                    COMPREPLY=()"
+        ));
+
+        let zsh = generate("frb", "zsh");
+        assert!(zsh.contains("'-r+[Path of input Rust code, or comma-separated paths of multiple files to bridge together]: :_files' \\"));
+        assert!(zsh
+            .contains("'--rust-crate-dir=[Crate directory for your Rust project]: :_files -/' \\"));
+        assert!(zsh.contains("'--dart-enum-style=[Generate enums"));
+
+        let fish = generate("frb", "fish");
+        assert!(fish.contains(
+            "-l llvm-path -d 'Path to the installed LLVM' -r -f -a \"(__fish_complete_directories)\"\n"
+        ));
+        assert!(fish.contains("-l watch -d 'Keep running and generate again whenever the input Rust code, or a module it declares, changes'\n"));
+    }
+}
@@ -1,36 +1,69 @@
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use anyhow::{anyhow, Result};
 use convert_case::{Case, Casing};
+use log::{info, warn};
 use serde::Deserialize;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
 use toml::Value;
 
-#[derive(StructOpt, Debug, PartialEq, Deserialize)]
+use crate::api_types::ApiIdent;
+use crate::others::{format_iso8601_utc, generation_time};
+
+/// Name of the config file, see [RawOpts::config]
+pub const CONFIG_FILE_NAME: &str = "flutter_rust_bridge.toml";
+
+/// A header of the LLVM C API within [Opts::llvm_path], which every LLVM installation with
+/// development files has
+const LLVM_CORE_HEADER_SUB_PATH: &str = "include/llvm-c/Core.h";
+
+const LLVM_INSTALL_HINT: &str = if cfg!(target_os = "windows") {
+    "please install LLVM, e.g. by `choco install llvm`, or pass its path via `--llvm-path`"
+} else if cfg!(target_os = "macos") {
+    "please install LLVM, e.g. by `brew install llvm`, or pass its path via `--llvm-path`"
+} else {
+    "please install LLVM, e.g. by `sudo apt install llvm-dev libclang-dev`, or pass its path via `--llvm-path`"
+};
+
+/// Line lengths accepted by [Opts::validate], beyond which `dart format` output is unreadable
+const DART_FORMAT_LINE_LENGTH_RANGE: RangeInclusive<i32> = 40..=200;
+
+/// Options from command line arguments or from a config file, whose keys are the field names
+#[derive(StructOpt, Debug, PartialEq, Deserialize, Default)]
 #[structopt(setting(AppSettings::DeriveDisplayOrder))]
+#[serde(default, deny_unknown_fields)]
 pub struct RawOpts {
-    /// Path of input Rust code
+    /// Path of input Rust code, or comma-separated paths of multiple files to bridge together
     #[structopt(short, long)]
-    pub rust_input: String,
-    /// Path of output generated Dart code
+    pub rust_input: Option<String>,
+    /// Path of output generated Dart code, may contain `{class_name}`, `{crate_name}` and `{date}`;
+    /// `lib/generated/bridge.dart` of `--rust-crate-dir` if absent
     #[structopt(short, long)]
-    pub dart_output: String,
+    pub dart_output: Option<String>,
 
-    /// Path of output generated C header
-    #[structopt(short, long)]
-    pub c_output: Option<String>,
+    /// Path of output generated C header, may contain the same placeholders as `--dart-output`;
+    /// give it multiple times to write the same header to several places
+    #[structopt(short, long, number_of_values = 1)]
+    #[serde(deserialize_with = "deserialize_paths")]
+    pub c_output: Option<Vec<String>>,
     /// Crate directory for your Rust project
     #[structopt(long)]
     pub rust_crate_dir: Option<String>,
-    /// Path of output generated Rust code
+    /// Path of output generated Rust code, may contain the same placeholders as `--dart-output`
     #[structopt(long)]
     pub rust_output: Option<String>,
     /// Generated class name
     #[structopt(long)]
     pub class_name: Option<String>,
+    /// Name of the `library` declaration of the generated Dart code; the class name in snake case
+    /// if absent, e.g. `my_api` of `MyApi`
+    #[structopt(long)]
+    pub dart_library_name: Option<String>,
     /// Line length for dart formatting
     #[structopt(long)]
     pub dart_format_line_length: Option<i32>,
@@ -40,52 +73,435 @@ pub struct RawOpts {
     /// Path to the installed LLVM
     #[structopt(long)]
     pub llvm_path: Option<String>,
+    /// Path of output generated Dart test stubs for functions marked `#[frb(test)]`
+    #[structopt(long)]
+    pub dart_test_output: Option<String>,
+    /// Path of output generated Dart code of the implementation class, a `part` of `--dart-output`
+    /// which then only contains the API class; may contain the same placeholders as `--dart-output`
+    #[structopt(long)]
+    pub dart_impl_output: Option<String>,
+    /// Path of a `cbindgen.toml` to customize the generated C header
+    #[structopt(long)]
+    pub cbindgen_toml: Option<String>,
+    /// Write the generation time as a comment in each output file
+    #[structopt(long)]
+    pub output_timestamps: bool,
+    /// Skip functions using unsupported types with a warning, instead of failing
+    #[structopt(long)]
+    pub skip_unsupported_functions: bool,
+    /// Also bridge `pub(crate)`, `pub(super)` and `pub(in ...)` items, which the generated Rust
+    /// code can only use if it is within their visibility, e.g. in the same crate for `pub(crate)`
+    #[structopt(long)]
+    pub include_pub_crate: bool,
+    /// Also bridge private functions and methods. Only meant for testing, since the generated Rust
+    /// code then only compiles as a child module of the module of the input Rust code
+    #[structopt(long)]
+    pub include_private: bool,
+    /// Comma-separated Cargo features the Rust crate is built with: items behind a
+    /// `#[cfg(feature = "...")]` of other features are then not bridged, and cbindgen and ffigen get
+    /// the features as `FRB_FEATURE_<NAME>` defines. Other `cfg` predicates than `feature` and
+    /// `test`, and `cfg_attr` of `cfg` or `frb`, cannot be evaluated and are rejected. Without this
+    /// option `cfg` attributes are ignored
+    #[structopt(long, use_delimiter = true)]
+    pub features: Option<Vec<String>>,
+    /// Keep running and generate again whenever the input Rust code, or a module it declares, changes
+    #[structopt(long)]
+    #[serde(skip)]
+    pub watch: bool,
+    /// Do not write anything, but print the differences to the existing output files and fail if
+    /// they are outdated
+    #[structopt(long, conflicts_with = "watch")]
+    #[serde(skip)]
+    pub check: bool,
+    /// Do not write anything, but print the generated code, or the differences to the existing
+    /// output files together with `--check`
+    #[structopt(long, conflicts_with = "watch")]
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// Write the generated Rust code as a module tree instead of a single file: a directory at
+    /// `--rust-output` without the `.rs`, with one file per input module and a re-exporting `mod.rs`
+    #[structopt(long)]
+    pub split_output: bool,
+    /// Also generate a wrapper of the Dart API class, which forwards calls in the `Zone` it is
+    /// created in and fails fast when used by another isolate
+    #[structopt(long)]
+    pub dart_isolate_safe: bool,
+    /// Parse all input files again, instead of reusing the results cached next to `--rust-output`
+    /// for those unchanged since the previous run
+    #[structopt(long)]
+    pub no_cache: bool,
+    /// Generate a separate Dart class for each struct, instead of a `typedef` of the first one by
+    /// name for structs with the same fields
+    #[structopt(long)]
+    pub no_dedup: bool,
+    /// Do not allow `clippy::all` in the generated Rust code, e.g. to audit its quality
+    #[structopt(long)]
+    pub no_allow_clippy: bool,
+    /// Do not generate `==`, `hashCode` and `toString` for the Dart classes of structs, e.g. to
+    /// compare them by identity
+    #[structopt(long)]
+    pub no_dart_equality: bool,
+    /// Do not drop the Rust objects of opaque handles when Dart garbage collects the handles, which
+    /// needs `Finalizable` of Dart 2.17, but only when they are disposed
+    #[structopt(long)]
+    pub no_finalizer: bool,
+    /// Return `Vec<u8>` as is, which copies the bytes into Dart, instead of as `ZeroCopyBuffer`
+    #[structopt(long)]
+    pub no_auto_zero_copy: bool,
+    /// Append the location of the bridged Rust function, e.g. ` [rust: src/api.rs:42:8]`, to the
+    /// messages of its panics, which Dart parses into `FfiException.rustLocation`
+    #[structopt(long)]
+    pub source_locations: bool,
+    /// Generate enums with data as sealed class hierarchies (`sealed`, the default) or as unions
+    /// of the `freezed` package (`freezed`), which then need `build_runner` to be run
+    #[structopt(long, possible_values = &["sealed", "freezed"])]
+    pub dart_enum_style: Option<String>,
+    /// Receive `uuid::Uuid` as `UuidValue` of the `uuid` package (`value`, the default), which the
+    /// Dart project then depends on, or as a `String` (`string`)
+    #[structopt(long, possible_values = &["value", "string"])]
+    pub dart_uuid_style: Option<String>,
+    /// Line endings of the output files: `lf` (the default), `crlf`, or `auto` for `crlf` on
+    /// Windows and `lf` elsewhere
+    #[structopt(long, possible_values = &["lf", "crlf", "auto"])]
+    pub line_ending: Option<String>,
+    /// Path of a config file providing defaults for these options. If absent, `flutter_rust_bridge.toml`
+    /// or the `[package.metadata.flutter_rust_bridge]` of `Cargo.toml` is searched for from the current
+    /// directory up to the workspace root. Relative paths in it are relative to the config file.
+    /// `watch`, `check` and `dry_run` select what a run does, so they are only accepted on the
+    /// command line
+    #[structopt(long)]
+    #[serde(skip)]
+    pub config: Option<String>,
+    /// Print the completion script for the given shell instead of generating code, e.g.
+    /// `--completions bash > /etc/bash_completion.d/flutter_rust_bridge_codegen`
+    #[structopt(long, possible_values = &["bash", "zsh", "fish"])]
+    #[serde(skip)]
+    pub completions: Option<String>,
+}
+
+impl RawOpts {
+    /// Options given in [self] take precedence over those in [file]. Flags are enabled if either
+    /// enables them, since a flag absent from the command line cannot tell a disabled one apart,
+    /// except for the modes `watch`, `check` and `dry_run`, which config files cannot set
+    fn merge(self, file: RawOpts) -> RawOpts {
+        RawOpts {
+            rust_input: self.rust_input.or(file.rust_input),
+            dart_output: self.dart_output.or(file.dart_output),
+            c_output: self.c_output.or(file.c_output),
+            rust_crate_dir: self.rust_crate_dir.or(file.rust_crate_dir),
+            rust_output: self.rust_output.or(file.rust_output),
+            class_name: self.class_name.or(file.class_name),
+            dart_library_name: self.dart_library_name.or(file.dart_library_name),
+            dart_format_line_length: self
+                .dart_format_line_length
+                .or(file.dart_format_line_length),
+            skip_add_mod_to_lib: self.skip_add_mod_to_lib || file.skip_add_mod_to_lib,
+            llvm_path: self.llvm_path.or(file.llvm_path),
+            dart_test_output: self.dart_test_output.or(file.dart_test_output),
+            dart_impl_output: self.dart_impl_output.or(file.dart_impl_output),
+            cbindgen_toml: self.cbindgen_toml.or(file.cbindgen_toml),
+            output_timestamps: self.output_timestamps || file.output_timestamps,
+            skip_unsupported_functions: self.skip_unsupported_functions
+                || file.skip_unsupported_functions,
+            include_pub_crate: self.include_pub_crate || file.include_pub_crate,
+            include_private: self.include_private || file.include_private,
+            features: self.features.or(file.features),
+            watch: self.watch,
+            check: self.check,
+            dry_run: self.dry_run,
+            split_output: self.split_output || file.split_output,
+            dart_isolate_safe: self.dart_isolate_safe || file.dart_isolate_safe,
+            no_cache: self.no_cache || file.no_cache,
+            no_dedup: self.no_dedup || file.no_dedup,
+            no_allow_clippy: self.no_allow_clippy || file.no_allow_clippy,
+            no_dart_equality: self.no_dart_equality || file.no_dart_equality,
+            no_finalizer: self.no_finalizer || file.no_finalizer,
+            no_auto_zero_copy: self.no_auto_zero_copy || file.no_auto_zero_copy,
+            source_locations: self.source_locations || file.source_locations,
+            dart_enum_style: self.dart_enum_style.or(file.dart_enum_style),
+            dart_uuid_style: self.dart_uuid_style.or(file.dart_uuid_style),
+            line_ending: self.line_ending.or(file.line_ending),
+            config: self.config,
+            completions: self.completions,
+        }
+    }
+
+    fn resolve_relative_paths(&mut self, base_dir: &Path) {
+        if let Some(rust_input) = &mut self.rust_input {
+            *rust_input = split_rust_input(rust_input)
+                .map(|path| base_dir.join(path).to_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+        }
+        for path in vec![
+            &mut self.dart_output,
+            &mut self.rust_crate_dir,
+            &mut self.rust_output,
+            &mut self.llvm_path,
+            &mut self.dart_test_output,
+            &mut self.dart_impl_output,
+            &mut self.cbindgen_toml,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.c_output.iter_mut().flatten())
+        {
+            *path = base_dir.join(&path).to_str().unwrap().to_string();
+        }
+    }
+}
+
+/// Accepts either a single path or a list of paths, so that existing config files with
+/// `c_output = "..."` keep working
+fn deserialize_paths<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Paths {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(
+        Option::<Paths>::deserialize(deserializer)?.map(|paths| match paths {
+            Paths::One(path) => vec![path],
+            Paths::Many(paths) => paths,
+        }),
+    )
+}
+
+/// How enums with data are represented in Dart, see [RawOpts::dart_enum_style]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DartEnumStyle {
+    Sealed,
+    Freezed,
+}
+
+/// How `uuid::Uuid` is represented in Dart, see [RawOpts::dart_uuid_style]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DartUuidStyle {
+    Value,
+    String,
 }
 
-#[derive(Debug)]
+/// Line endings of the output files, see [RawOpts::line_ending]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    /// [content] with each line ending, whether `\n` or `\r\n`, replaced by this one
+    pub fn apply(self, content: &str) -> String {
+        let content = content.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => content,
+            LineEnding::Crlf => content.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Items bridged besides `pub` ones, see [RawOpts::include_pub_crate] and
+/// [RawOpts::include_private]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IncludedVisibility {
+    pub pub_crate: bool,
+    pub private_functions: bool,
+}
+
+/// How the Dart code is generated, see the flags of the same names in [RawOpts]
+#[derive(Debug, Clone, Copy)]
+pub struct DartCodeOpts {
+    pub dart_enum_style: DartEnumStyle,
+    pub dart_isolate_safe: bool,
+    pub no_dart_equality: bool,
+    pub no_finalizer: bool,
+}
+
+#[derive(Debug, Clone)]
 pub struct Opts {
-    pub rust_input_path: String,
+    pub rust_input_paths: Vec<String>,
     pub dart_output_path: String,
-    pub c_output_path: String,
+    pub c_output_paths: Vec<String>,
     pub rust_crate_dir: String,
     pub rust_output_path: String,
     pub class_name: String,
+    pub dart_library_name: String,
     pub dart_format_line_length: i32,
     pub skip_add_mod_to_lib: bool,
     pub llvm_path: String,
+    pub dart_test_output_path: Option<String>,
+    pub dart_impl_output_path: Option<String>,
+    pub cbindgen_toml_path: Option<String>,
+    pub output_timestamps: bool,
+    pub skip_unsupported_functions: bool,
+    pub included_visibility: IncludedVisibility,
+    /// [None] to bridge items regardless of their `cfg` attributes, see [RawOpts::features]
+    pub features: Option<Vec<String>>,
+    pub watch: bool,
+    pub check: bool,
+    pub dry_run: bool,
+    pub split_output: bool,
+    pub dart_isolate_safe: bool,
+    pub no_cache: bool,
+    pub no_dedup: bool,
+    pub no_allow_clippy: bool,
+    pub no_dart_equality: bool,
+    pub no_finalizer: bool,
+    pub no_auto_zero_copy: bool,
+    pub source_locations: bool,
+    pub dart_enum_style: DartEnumStyle,
+    pub dart_uuid_style: DartUuidStyle,
+    pub line_ending: LineEnding,
 }
 
 pub fn parse(raw: RawOpts) -> Opts {
-    let rust_input_path = canon_path(&raw.rust_input);
+    parse_with_config_file(raw, true)
+}
+
+/// Like [parse], but only reads a config file given by [RawOpts::config], since one found by
+/// searching upwards may belong to another crate than the one `build.rs` is run for
+pub(crate) fn parse_in_build_script(raw: RawOpts) -> Opts {
+    parse_with_config_file(raw, false)
+}
+
+fn parse_with_config_file(raw: RawOpts, search_config_file: bool) -> Opts {
+    let file = if raw.config.is_some() || search_config_file {
+        load_config_file(raw.config.as_deref())
+    } else {
+        None
+    };
+    let raw = match file {
+        Some(file) => raw.merge(file),
+        None => raw,
+    };
+
+    let rust_input_paths: Vec<_> = split_rust_input(
+        &raw.rust_input
+            .unwrap_or_else(|| panic!("{}", format_missing_error("rust_input"))),
+    )
+    .map(canon_path)
+    .collect();
+    if rust_input_paths.is_empty() {
+        panic!("{}", format_missing_error("rust_input"));
+    }
+    // the first input decides the defaults
+    let rust_input_path = &rust_input_paths[0];
 
     let rust_crate_dir = canon_path(&raw.rust_crate_dir.unwrap_or_else(|| {
-        fallback_rust_crate_dir(&rust_input_path)
+        fallback_rust_crate_dir(rust_input_path)
             .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("rust_crate_dir")))
     }));
-    let rust_output_path = canon_path(&raw.rust_output.unwrap_or_else(|| {
-        fallback_rust_output_path(&rust_input_path)
-            .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("rust_output")))
-    }));
     let class_name = raw.class_name.unwrap_or_else(|| {
         fallback_class_name(&*rust_crate_dir)
             .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("class_name")))
     });
-    let c_output_path = canon_path(&raw.c_output.unwrap_or_else(|| {
-        fallback_c_output_path()
-            .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("c_output")))
-    }));
+    let dart_library_name = raw
+        .dart_library_name
+        .unwrap_or_else(|| class_name.to_case(Case::Snake));
+    let expand = |template: &str| expand_path_template(template, &class_name, &rust_crate_dir);
+    let rust_output_path = canon_path(&raw.rust_output.map_or_else(
+        || {
+            fallback_rust_output_path(rust_input_path)
+                .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("rust_output")))
+        },
+        |template| expand(&template),
+    ));
+    let c_output_paths = raw.c_output.map_or_else(
+        || {
+            vec![canon_path(&fallback_c_output_path().unwrap_or_else(|_| {
+                panic!("{}", format_fail_to_guess_error("c_output"))
+            }))]
+        },
+        |templates| {
+            templates
+                .iter()
+                .map(|template| canon_path(&expand(template)))
+                .collect()
+        },
+    );
+    let dart_output_path = canon_path(&raw.dart_output.map_or_else(
+        || {
+            fallback_dart_output_path(&rust_crate_dir)
+                .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("dart_output")))
+        },
+        |template| expand(&template),
+    ));
+    let dart_impl_output_path = raw
+        .dart_impl_output
+        .map(|template| canon_path(&expand(&template)));
+    if raw.include_private {
+        warn!("include_private is only meant for testing, private functions are bridged");
+    }
 
-    Opts {
-        rust_input_path,
-        dart_output_path: canon_path(&raw.dart_output),
-        c_output_path,
+    let opts = Opts {
+        rust_input_paths,
+        dart_output_path,
+        c_output_paths,
         rust_crate_dir,
         rust_output_path,
         class_name,
+        dart_library_name,
         dart_format_line_length: raw.dart_format_line_length.unwrap_or(80),
         skip_add_mod_to_lib: raw.skip_add_mod_to_lib,
-        llvm_path: raw.llvm_path.unwrap_or_else(|| "".to_string()),
-    }
+        llvm_path: raw.llvm_path.unwrap_or_else(|| {
+            // empty lets `ffigen` search by itself, which is what fails in the end anyway
+            fallback_llvm_path().unwrap_or_else(|err| {
+                warn!("{}", err);
+                "".to_string()
+            })
+        }),
+        dart_test_output_path: raw.dart_test_output.as_deref().map(canon_path),
+        dart_impl_output_path,
+        cbindgen_toml_path: raw.cbindgen_toml.as_deref().map(canon_path),
+        output_timestamps: raw.output_timestamps,
+        skip_unsupported_functions: raw.skip_unsupported_functions,
+        included_visibility: IncludedVisibility {
+            pub_crate: raw.include_pub_crate,
+            private_functions: raw.include_private,
+        },
+        features: raw.features,
+        watch: raw.watch,
+        check: raw.check,
+        dry_run: raw.dry_run,
+        split_output: raw.split_output,
+        dart_isolate_safe: raw.dart_isolate_safe,
+        no_cache: raw.no_cache,
+        no_dedup: raw.no_dedup,
+        no_allow_clippy: raw.no_allow_clippy,
+        no_dart_equality: raw.no_dart_equality,
+        no_finalizer: raw.no_finalizer,
+        no_auto_zero_copy: raw.no_auto_zero_copy,
+        source_locations: raw.source_locations,
+        dart_enum_style: match raw.dart_enum_style.as_deref() {
+            None | Some("sealed") => DartEnumStyle::Sealed,
+            Some("freezed") => DartEnumStyle::Freezed,
+            Some(other) => panic!("unknown dart_enum_style `{}`", other),
+        },
+        dart_uuid_style: match raw.dart_uuid_style.as_deref() {
+            None | Some("value") => DartUuidStyle::Value,
+            Some("string") => DartUuidStyle::String,
+            Some(other) => panic!("unknown dart_uuid_style `{}`", other),
+        },
+        line_ending: match raw.line_ending.as_deref() {
+            None | Some("lf") => LineEnding::Lf,
+            Some("crlf") => LineEnding::Crlf,
+            Some("auto") if cfg!(windows) => LineEnding::Crlf,
+            Some("auto") => LineEnding::Lf,
+            Some(other) => panic!("unknown line_ending `{}`", other),
+        },
+    };
+    opts.validate().unwrap_or_else(|err| panic!("{}", err));
+    opts
+}
+
+/// e.g. `src/api/a.rs, src/api/b.rs` => `["src/api/a.rs", "src/api/b.rs"]`
+fn split_rust_input(rust_input: &str) -> impl Iterator<Item = &str> {
+    rust_input
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
 }
 
 fn format_fail_to_guess_error(name: &str) -> String {
@@ -95,20 +511,97 @@ fn format_fail_to_guess_error(name: &str) -> String {
     )
 }
 
+fn format_missing_error(name: &str) -> String {
+    format!(
+        "missing {}, please specify it in command line arguments or in {}",
+        name, CONFIG_FILE_NAME
+    )
+}
+
+/// Load the config file at [path], or search for one if [path] is [None]
+fn load_config_file(path: Option<&str>) -> Option<RawOpts> {
+    let (path, mut raw) = match path {
+        Some(path) => {
+            let path = PathBuf::from(canon_path(path));
+            let raw = read_config_file(&path)
+                .unwrap_or_else(|err| panic!("fail to read config file {:?}: {}", path, err))
+                .unwrap_or_else(|| panic!("no flutter_rust_bridge config in {:?}", path));
+            (path, raw)
+        }
+        None => find_config_file(env::current_dir().ok()?)?,
+    };
+    info!("Use config file {:?}", path);
+    raw.resolve_relative_paths(path.parent().unwrap());
+    Some(raw)
+}
+
+/// Look for a config file from [dir] up to the workspace root
+fn find_config_file(mut dir: PathBuf) -> Option<(PathBuf, RawOpts)> {
+    loop {
+        for file_name in [CONFIG_FILE_NAME, "Cargo.toml"] {
+            let path = dir.join(file_name);
+            if path.exists() {
+                let raw = read_config_file(&path)
+                    .unwrap_or_else(|err| panic!("fail to read config file {:?}: {}", path, err));
+                if let Some(raw) = raw {
+                    return Some((path, raw));
+                }
+            }
+        }
+        if is_workspace_root(&dir) || !dir.pop() {
+            return None;
+        }
+    }
+}
+
+/// [None] if [path] is a `Cargo.toml` without a `[package.metadata.flutter_rust_bridge]` section
+fn read_config_file(path: &Path) -> Result<Option<RawOpts>> {
+    let content = fs::read_to_string(path)?;
+    if path.file_name() != Some("Cargo.toml".as_ref()) {
+        return Ok(Some(toml::from_str(&content)?));
+    }
+    let section = content
+        .parse::<Value>()?
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("flutter_rust_bridge"))
+        .cloned();
+    Ok(match section {
+        Some(section) => Some(section.try_into()?),
+        None => None,
+    })
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| content.parse::<Value>().ok())
+        .is_some_and(|value| value.get("workspace").is_some())
+}
+
+/// The innermost ancestor of [rust_input_path] with a `Cargo.toml` containing `[package]`, which
+/// skips the virtual manifest of a workspace, i.e. one with only `[workspace]`
 fn fallback_rust_crate_dir(rust_input_path: &str) -> Result<String> {
     let mut dir_curr = Path::new(rust_input_path)
         .parent()
         .ok_or_else(|| anyhow!(""))?;
+    let mut workspace_dir = None;
 
     loop {
         let path_cargo_toml = dir_curr.join("Cargo.toml");
 
         if path_cargo_toml.exists() {
-            return Ok(dir_curr
-                .as_os_str()
-                .to_str()
-                .ok_or_else(|| anyhow!(""))?
-                .to_string());
+            let cargo_toml_value = fs::read_to_string(&path_cargo_toml)?.parse::<Value>()?;
+            if cargo_toml_value.get("package").is_some() {
+                return Ok(dir_curr
+                    .as_os_str()
+                    .to_str()
+                    .ok_or_else(|| anyhow!(""))?
+                    .to_string());
+            }
+            if cargo_toml_value.get("workspace").is_some() {
+                workspace_dir.get_or_insert(dir_curr);
+            }
         }
 
         if let Some(next_parent) = dir_curr.parent() {
@@ -117,9 +610,14 @@ fn fallback_rust_crate_dir(rust_input_path: &str) -> Result<String> {
             break;
         }
     }
-    Err(anyhow!(
-        "look at parent directories but none contains Cargo.toml"
-    ))
+    Err(match workspace_dir {
+        Some(workspace_dir) => anyhow!(
+            "{:?} is only in the workspace {:?}, not in one of its member crates",
+            rust_input_path,
+            workspace_dir
+        ),
+        None => anyhow!("look at parent directories but none contains Cargo.toml"),
+    })
 }
 
 fn fallback_c_output_path() -> Result<String> {
@@ -141,7 +639,107 @@ fn fallback_rust_output_path(rust_input_path: &str) -> Result<String> {
         .to_string())
 }
 
+/// `<rust_crate_dir>/lib/generated/bridge.dart`, where `lib/generated` is only created when the
+/// code is written
+fn fallback_dart_output_path(rust_crate_dir: &str) -> Result<String> {
+    Ok(Path::new(rust_crate_dir)
+        .join("lib")
+        .join("generated")
+        .join("bridge.dart")
+        .to_str()
+        .ok_or_else(|| anyhow!(""))?
+        .to_string())
+}
+
+/// The first LLVM installation among the usual locations of each platform that passes
+/// [check_llvm_path], since [Opts::validate_llvm_path] would reject any other
+fn fallback_llvm_path() -> Result<String> {
+    let mut tried = Vec::new();
+    let mut candidates = Vec::new();
+
+    tried.push("$LLVM_PATH".to_string());
+    if let Ok(path) = env::var("LLVM_PATH") {
+        candidates.push(PathBuf::from(path));
+    }
+
+    tried.push("`llvm-config --prefix`".to_string());
+    if let Ok(output) = Command::new("llvm-config").arg("--prefix").output() {
+        if output.status.success() {
+            let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            candidates.push(PathBuf::from(prefix));
+        }
+    }
+
+    for path in ["/opt/homebrew/opt/llvm", "/usr/local/opt/llvm"] {
+        tried.push(path.to_string());
+        candidates.push(PathBuf::from(path));
+    }
+
+    tried.push("/usr/lib/llvm-*".to_string());
+    let mut linux_paths: Vec<_> = fs::read_dir("/usr/lib")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let version = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix("llvm-")?
+                .parse::<u32>()
+                .ok()?;
+            Some((version, path))
+        })
+        .collect();
+    // the newest version first
+    linux_paths.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    candidates.extend(linux_paths.into_iter().map(|(_, path)| path));
+
+    let windows_path = r"C:\Program Files\LLVM";
+    tried.push(windows_path.to_string());
+    candidates.push(PathBuf::from(windows_path));
+
+    first_valid_llvm_path(candidates).ok_or_else(|| {
+        anyhow!(
+            "fail to find LLVM with {}, so leave the search for libclang to ffigen. Tried: {}",
+            LLVM_CORE_HEADER_SUB_PATH,
+            tried.join(", ")
+        )
+    })
+}
+
+fn first_valid_llvm_path(candidates: Vec<PathBuf>) -> Option<String> {
+    candidates
+        .into_iter()
+        .find(|path| check_llvm_path(path).is_ok())
+        .and_then(|path| path.to_str().map(str::to_string))
+}
+
+/// Whether [llvm_path] is an LLVM installation, by its [LLVM_CORE_HEADER_SUB_PATH]
+fn check_llvm_path(llvm_path: &Path) -> Result<()> {
+    if !llvm_path.is_dir() {
+        return Err(anyhow!(
+            "llvm_path {} does not exist, {}",
+            llvm_path.display(),
+            LLVM_INSTALL_HINT
+        ));
+    }
+    let header_path = llvm_path.join(LLVM_CORE_HEADER_SUB_PATH);
+    if !header_path.is_file() {
+        return Err(anyhow!(
+            "llvm_path {} has no {}, {}",
+            llvm_path.display(),
+            header_path.display(),
+            LLVM_INSTALL_HINT
+        ));
+    }
+    Ok(())
+}
+
 fn fallback_class_name(rust_crate_dir: &str) -> Result<String> {
+    Ok(ApiIdent::to_dart_pascal_case(&crate_name(rust_crate_dir)?))
+}
+
+fn crate_name(rust_crate_dir: &str) -> Result<String> {
     let cargo_toml_path = Path::new(rust_crate_dir).join("Cargo.toml");
     let cargo_toml_content = fs::read_to_string(cargo_toml_path)?;
 
@@ -150,11 +748,81 @@ fn fallback_class_name(rust_crate_dir: &str) -> Result<String> {
         .get("package")
         .ok_or_else(|| anyhow!("no `package` in Cargo.toml"))?
         .get("name")
-        .ok_or_else(|| anyhow!("no `name` in Cargo.toml"))?
+        .ok_or_else(|| anyhow!("no `name` in Cargo.toml"))?;
+
+    // `name.workspace = true` inherits the name from `[workspace.package]` of the workspace root
+    if package_name.get("workspace").and_then(Value::as_bool) == Some(true) {
+        let workspace_cargo_toml_path = workspace_dir(Path::new(rust_crate_dir))
+            .ok_or_else(|| anyhow!("`name.workspace = true`, but no workspace contains the crate"))?
+            .join("Cargo.toml");
+        let workspace_cargo_toml_value =
+            fs::read_to_string(&workspace_cargo_toml_path)?.parse::<Value>()?;
+        return workspace_cargo_toml_value
+            .get("workspace")
+            .and_then(|workspace| workspace.get("package"))
+            .and_then(|package| package.get("name"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no `workspace.package.name` in {:?}",
+                    workspace_cargo_toml_path
+                )
+            });
+    }
+
+    Ok(package_name
         .as_str()
-        .ok_or_else(|| anyhow!(""))?;
+        .ok_or_else(|| anyhow!(""))?
+        .to_string())
+}
 
-    Ok(package_name.to_case(Case::Pascal))
+/// The closest directory from [dir] upwards with a `Cargo.toml` of a `[workspace]`, the same one
+/// [fallback_rust_crate_dir] reports when the input is not in one of its members
+fn workspace_dir(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|dir| is_workspace_root(dir))
+}
+
+/// e.g. `lib/src/{crate_name}.dart` => `lib/src/my_crate.dart`
+fn expand_path_template(template: &str, class_name: &str, rust_crate_dir: &str) -> String {
+    let mut path = template
+        .replace("{class_name}", class_name)
+        .replace("{date}", &format_iso8601_utc(generation_time())[..10]);
+    if path.contains("{crate_name}") {
+        let crate_name = crate_name(rust_crate_dir)
+            .unwrap_or_else(|_| panic!("{}", format_fail_to_guess_error("crate_name")));
+        path = path.replace("{crate_name}", &crate_name);
+    }
+
+    if let (Some(start), Some(end)) = (path.find('{'), path.rfind('}')) {
+        if start < end {
+            panic!(
+                "unknown placeholder {} in path `{}`, supported: {{class_name}}, {{crate_name}}, {{date}}",
+                &path[start..=end],
+                template
+            );
+        }
+    }
+    if Path::new(&path).file_name().is_none() {
+        panic!(
+            "path `{}` expands to `{}`, which is not a file path",
+            template, path
+        );
+    }
+    path
+}
+
+/// Whether [path], which may not exist yet, refers to the existing file [existing]
+fn is_same_file(existing: &Path, path: &Path) -> bool {
+    let resolve = |path: &Path| -> Option<PathBuf> {
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        Some(
+            fs::canonicalize(dir.unwrap_or_else(|| Path::new(".")))
+                .ok()?
+                .join(path.file_name()?),
+        )
+    };
+    matches!((resolve(existing), resolve(path)), (Some(a), Some(b)) if a == b)
 }
 
 fn canon_path(sub_path: &str) -> String {
@@ -167,6 +835,97 @@ fn canon_path(sub_path: &str) -> String {
 }
 
 impl Opts {
+    /// Reject options which would otherwise only fail later with a confusing error, e.g. of
+    /// `dart format`. Paths are checked separately by [Opts::validate_paths].
+    pub fn validate(&self) -> Result<()> {
+        if !DART_FORMAT_LINE_LENGTH_RANGE.contains(&self.dart_format_line_length) {
+            return Err(anyhow!(
+                "dart_format_line_length={} is out of the supported range {}..={}",
+                self.dart_format_line_length,
+                DART_FORMAT_LINE_LENGTH_RANGE.start(),
+                DART_FORMAT_LINE_LENGTH_RANGE.end(),
+            ));
+        }
+        // e.g. `my_api` or `my_package.my_api`
+        let is_dart_identifier = |segment: &str| {
+            segment.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        if !self.dart_library_name.split('.').all(is_dart_identifier) {
+            return Err(anyhow!(
+                "dart_library_name={} is not a valid Dart library name",
+                self.dart_library_name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the paths against the file system before generating anything, instead of panicking
+    /// halfway when writing the outputs. Paths need no check for valid UTF-8, which [parse]
+    /// already ensures.
+    pub fn validate_paths(&self) -> Result<()> {
+        if let Some(path) = self
+            .rust_input_paths
+            .iter()
+            .find(|path| !Path::new(path).is_file())
+        {
+            return Err(anyhow!("rust_input {} does not exist", path));
+        }
+        let outputs = vec![
+            ("dart_output", Some(&self.dart_output_path)),
+            ("rust_output", Some(&self.rust_output_path)),
+            ("dart_test_output", self.dart_test_output_path.as_ref()),
+            ("dart_impl_output", self.dart_impl_output_path.as_ref()),
+        ]
+        .into_iter()
+        .chain(
+            self.c_output_paths
+                .iter()
+                .map(|path| ("c_output", Some(path))),
+        );
+        for (name, path) in outputs {
+            let path = match path {
+                Some(path) => Path::new(path),
+                None => continue,
+            };
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            if !dir.as_os_str().is_empty() && !dir.is_dir() && !self.is_fallback_dart_output(path) {
+                return Err(anyhow!(
+                    "directory {} of {} {} does not exist",
+                    dir.display(),
+                    name,
+                    path.display()
+                ));
+            }
+            if let Some(input) = self
+                .rust_input_paths
+                .iter()
+                .find(|input| is_same_file(Path::new(input), path))
+            {
+                return Err(anyhow!("{} {} would overwrite rust_input", name, input));
+            }
+        }
+        self.validate_llvm_path()
+    }
+
+    /// Whether [path] is the default `--dart-output`, whose directory is created when needed
+    fn is_fallback_dart_output(&self, path: &Path) -> bool {
+        path == Path::new(&self.dart_output_path)
+            && matches!(fallback_dart_output_path(&self.rust_crate_dir),
+                Ok(fallback) if Path::new(&canon_path(&fallback)) == path)
+    }
+
+    /// `ffigen` loads libclang from [Opts::llvm_path], and otherwise fails with an error about
+    /// missing symbols. An empty path lets `ffigen` search by itself.
+    pub fn validate_llvm_path(&self) -> Result<()> {
+        if self.llvm_path.is_empty() {
+            return Ok(());
+        }
+        check_llvm_path(Path::new(&self.llvm_path))
+    }
+
     pub fn dart_api_class_name(&self) -> String {
         self.class_name.clone()
     }
@@ -178,4 +937,446 @@ impl Opts {
     pub fn dart_wire_class_name(&self) -> String {
         format!("{}Wire", self.class_name)
     }
+
+    pub fn dart_code_opts(&self) -> DartCodeOpts {
+        DartCodeOpts {
+            dart_enum_style: self.dart_enum_style,
+            dart_isolate_safe: self.dart_isolate_safe,
+            no_dart_equality: self.no_dart_equality,
+            no_finalizer: self.no_finalizer,
+        }
+    }
+
+    /// Directory of the generated Rust module tree, e.g. `src/bridge_generated` for
+    /// `src/bridge_generated.rs`, see [RawOpts::split_output]
+    pub fn rust_output_dir(&self) -> String {
+        Path::new(&self.rust_output_path)
+            .with_extension("")
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    /// The file containing the generated Rust code, which is the `mod.rs` of
+    /// [Self::rust_output_dir] with [Self::split_output]
+    pub fn rust_output_root_path(&self) -> String {
+        if self.split_output {
+            Path::new(&self.rust_output_dir())
+                .join("mod.rs")
+                .to_str()
+                .unwrap()
+                .to_string()
+        } else {
+            self.rust_output_path.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_prefers_command_line_but_keeps_flags_of_file() {
+        let merged = RawOpts {
+            class_name: Some("Cli".to_string()),
+            no_cache: true,
+            ..Default::default()
+        }
+        .merge(RawOpts {
+            class_name: Some("File".to_string()),
+            dart_output: Some("file.dart".to_string()),
+            no_dedup: true,
+            ..Default::default()
+        });
+        assert_eq!(merged.class_name.as_deref(), Some("Cli"));
+        assert_eq!(merged.dart_output.as_deref(), Some("file.dart"));
+        assert!(merged.no_cache);
+        // not given on the command line is the same as disabled there
+        assert!(merged.no_dedup);
+    }
+
+    /// A crate in a temporary directory with an empty `src/api.rs`
+    fn temp_crate() -> tempfile::TempDir {
+        let crate_dir = tempfile::tempdir().unwrap();
+        write_in(&crate_dir, "src/api.rs", "");
+        crate_dir
+    }
+
+    /// [sub_path] of [dir] like the paths of [Opts]
+    fn path_in(dir: &tempfile::TempDir, sub_path: &str) -> String {
+        dir.path().join(sub_path).to_str().unwrap().to_string()
+    }
+
+    /// Writes [content] to [sub_path] of [dir], creating the directories in between
+    fn write_in(dir: &tempfile::TempDir, sub_path: &str, content: &str) {
+        let path = dir.path().join(sub_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// The options of the crate in [crate_dir] (see [temp_crate]), where [raw] overrides the class
+    /// name `Api`, the `src/api.rs` input, the `bridge_generated.h` C output and the empty LLVM path
+    fn opts_of(crate_dir: &tempfile::TempDir, raw: RawOpts) -> Opts {
+        let path = |sub_path: &str| path_in(crate_dir, sub_path);
+        parse(raw.merge(RawOpts {
+            rust_input: Some(path("src/api.rs")),
+            c_output: Some(vec![path("bridge_generated.h")]),
+            rust_crate_dir: Some(path("")),
+            class_name: Some("Api".to_string()),
+            llvm_path: Some("".to_string()),
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn validate_rejects_bad_options() {
+        let crate_dir = temp_crate();
+        let mut opts = opts_of(&crate_dir, RawOpts::default());
+        assert!(opts.validate().is_ok());
+
+        for line_length in [0, -1, 39, 201] {
+            opts.dart_format_line_length = line_length;
+            let err = opts.validate().unwrap_err();
+            assert!(err.to_string().contains("40..=200"));
+        }
+        opts.dart_format_line_length = 80;
+
+        assert_eq!(opts.dart_library_name, "api");
+        for library_name in ["my_package.api", "_api2"] {
+            opts.dart_library_name = library_name.to_string();
+            assert!(opts.validate().is_ok());
+        }
+        for library_name in ["", "2api", "my-api", "my_package..api"] {
+            opts.dart_library_name = library_name.to_string();
+            let err = opts.validate().unwrap_err();
+            assert!(err.to_string().contains("not a valid Dart library name"));
+        }
+    }
+
+    #[test]
+    fn validate_paths_rejects_bad_paths() {
+        let crate_dir = temp_crate();
+        let path = |sub_path: &str| path_in(&crate_dir, sub_path);
+        let mut opts = opts_of(
+            &crate_dir,
+            RawOpts {
+                dart_output: Some(path("bridge_generated.dart")),
+                ..Default::default()
+            },
+        );
+        assert!(opts.validate_paths().is_ok());
+
+        opts.rust_input_paths.push(path("src/missing.rs"));
+        let err = opts.validate_paths().unwrap_err();
+        assert!(err.to_string().contains("missing.rs does not exist"));
+        opts.rust_input_paths.pop();
+
+        opts.c_output_paths.push(path("missing/bridge_generated.h"));
+        let err = opts.validate_paths().unwrap_err();
+        assert!(err.to_string().ends_with(&format!(
+            "of c_output {} does not exist",
+            path("missing/bridge_generated.h")
+        )));
+        opts.c_output_paths.pop();
+
+        opts.rust_output_path = path("src/../src/api.rs");
+        let err = opts.validate_paths().unwrap_err();
+        assert!(err.to_string().contains("would overwrite rust_input"));
+    }
+
+    #[test]
+    fn validate_llvm_path_requires_llvm_headers() {
+        let llvm_dir = tempfile::tempdir().unwrap();
+        let crate_dir = temp_crate();
+        let mut opts = opts_of(&crate_dir, RawOpts::default());
+        assert!(opts.validate_llvm_path().is_ok());
+
+        let missing_path = llvm_dir.path().join("missing");
+        opts.llvm_path = missing_path.to_str().unwrap().to_string();
+        let err = opts.validate_llvm_path().unwrap_err();
+        assert!(err.to_string().starts_with(&format!(
+            "llvm_path {} does not exist, please install",
+            missing_path.display()
+        )));
+
+        opts.llvm_path = llvm_dir.path().to_str().unwrap().to_string();
+        let header_path = llvm_dir.path().join("include/llvm-c/Core.h");
+        let err = opts.validate_llvm_path().unwrap_err();
+        assert!(err.to_string().starts_with(&format!(
+            "llvm_path {} has no {}, please install",
+            llvm_dir.path().display(),
+            header_path.display()
+        )));
+
+        write_in(&llvm_dir, "include/llvm-c/Core.h", "");
+        assert!(opts.validate_llvm_path().is_ok());
+    }
+
+    #[test]
+    fn fallback_llvm_path_skips_installations_without_headers() {
+        let root = tempfile::tempdir().unwrap();
+        let without_headers = root.path().join("llvm-15");
+        let with_headers = root.path().join("llvm-14");
+        fs::create_dir_all(&without_headers).unwrap();
+        write_in(&root, "llvm-14/include/llvm-c/Core.h", "");
+
+        assert_eq!(
+            first_valid_llvm_path(vec![without_headers.clone(), with_headers.clone()]),
+            Some(with_headers.to_str().unwrap().to_string())
+        );
+        assert_eq!(first_valid_llvm_path(vec![without_headers]), None);
+    }
+
+    #[test]
+    fn fallback_rust_crate_dir_finds_workspace_member() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| path_in(&workspace, sub_path);
+        write_in(
+            &workspace,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"app\", \"crates/core\", \"crates/ffi\"]\n",
+        );
+        for member in ["app", "crates/core", "crates/ffi"] {
+            write_in(
+                &workspace,
+                &format!("{}/Cargo.toml", member),
+                "[package]\nname = \"member\"\nversion = \"0.1.0\"\n",
+            );
+            write_in(&workspace, &format!("{}/src/api.rs", member), "");
+        }
+        write_in(&workspace, "scripts/api.rs", "");
+
+        for member in ["app", "crates/core", "crates/ffi"] {
+            assert_eq!(
+                fallback_rust_crate_dir(&path(&format!("{}/src/api.rs", member))).unwrap(),
+                path(member)
+            );
+        }
+        let err = fallback_rust_crate_dir(&path("scripts/api.rs")).unwrap_err();
+        assert!(err.to_string().contains("not in one of its member crates"));
+    }
+
+    #[test]
+    fn crate_name_inherits_workspace_package_name() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| path_in(&workspace, sub_path);
+        write_in(
+            &workspace,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nname = \"my_app\"\nversion = \"0.1.0\"\n",
+        );
+        write_in(
+            &workspace,
+            "crates/ffi/Cargo.toml",
+            "[package]\nname.workspace = true\nversion.workspace = true\n",
+        );
+        write_in(
+            &workspace,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"my_core\"\nversion.workspace = true\n",
+        );
+
+        assert_eq!(crate_name(&path("crates/ffi")).unwrap(), "my_app");
+        assert_eq!(fallback_class_name(&path("crates/ffi")).unwrap(), "MyApp");
+        assert_eq!(crate_name(&path("crates/core")).unwrap(), "my_core");
+
+        write_in(
+            &workspace,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        let err = crate_name(&path("crates/ffi")).unwrap_err();
+        assert!(err.to_string().contains("no `workspace.package.name`"));
+    }
+
+    #[test]
+    fn dart_output_falls_back_to_lib_generated() {
+        let crate_dir = temp_crate();
+        let path = |sub_path: &str| path_in(&crate_dir, sub_path);
+        let opts = opts_of(&crate_dir, RawOpts::default());
+        assert_eq!(opts.dart_output_path, path("lib/generated/bridge.dart"));
+        assert!(!Path::new(&path("lib")).exists());
+        assert!(opts.validate_paths().is_ok());
+    }
+
+    #[test]
+    fn dart_impl_output_expands_placeholders() {
+        let crate_dir = temp_crate();
+        let path = |sub_path: &str| path_in(&crate_dir, sub_path);
+        let opts = opts_of(
+            &crate_dir,
+            RawOpts {
+                dart_impl_output: Some(path("{class_name}_impl.dart")),
+                ..Default::default()
+            },
+        );
+        assert_eq!(opts.dart_impl_output_path, Some(path("Api_impl.dart")));
+
+        let opts = Opts {
+            dart_impl_output_path: Some(path("missing/impl.dart")),
+            ..opts
+        };
+        let err = opts.validate_paths().unwrap_err();
+        assert!(err.to_string().contains("of dart_impl_output"));
+    }
+
+    #[test]
+    fn c_output_accepts_one_or_many_paths() {
+        let raw: RawOpts = toml::from_str("c_output = \"ios/api.h\"").unwrap();
+        assert_eq!(raw.c_output, Some(vec!["ios/api.h".to_string()]));
+        let raw: RawOpts = toml::from_str("c_output = [\"ios/api.h\", \"macos/api.h\"]").unwrap();
+        assert_eq!(
+            raw.c_output,
+            Some(vec!["ios/api.h".to_string(), "macos/api.h".to_string()])
+        );
+        let raw = RawOpts::from_iter(&[
+            "flutter_rust_bridge_codegen",
+            "--c-output",
+            "ios/api.h",
+            "--c-output",
+            "macos/api.h",
+        ]);
+        assert_eq!(
+            raw.c_output,
+            Some(vec!["ios/api.h".to_string(), "macos/api.h".to_string()])
+        );
+
+        let crate_dir = temp_crate();
+        let path = |sub_path: &str| path_in(&crate_dir, sub_path);
+        let opts = opts_of(
+            &crate_dir,
+            RawOpts {
+                c_output: Some(vec![
+                    path("ios/{class_name}.h"),
+                    path("macos/{class_name}.h"),
+                ]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            opts.c_output_paths,
+            [path("ios/Api.h"), path("macos/Api.h")]
+        );
+    }
+
+    #[test]
+    fn config_file_is_searched_for_up_to_the_workspace_root() {
+        let root = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| root.path().join(sub_path);
+        write_in(&root, CONFIG_FILE_NAME, "class_name = \"Outside\"\n");
+        write_in(
+            &root,
+            "app/Cargo.toml",
+            "[workspace]\nmembers = [\"rust\"]\n",
+        );
+        write_in(
+            &root,
+            "app/rust/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        );
+        write_in(&root, "app/rust/src/api.rs", "");
+
+        // the file beyond the workspace root does not belong to it
+        assert_eq!(find_config_file(path("app/rust/src")), None);
+
+        write_in(
+            &root,
+            "app/flutter_rust_bridge.toml",
+            "class_name = \"Root\"\n",
+        );
+        let (found, raw) = find_config_file(path("app/rust/src")).unwrap();
+        assert_eq!(found, path("app/flutter_rust_bridge.toml"));
+        assert_eq!(raw.class_name.as_deref(), Some("Root"));
+
+        write_in(
+            &root,
+            "app/rust/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+            [package.metadata.flutter_rust_bridge]\nclass_name = \"Metadata\"\n",
+        );
+        let (found, raw) = find_config_file(path("app/rust/src")).unwrap();
+        assert_eq!(found, path("app/rust/Cargo.toml"));
+        assert_eq!(raw.class_name.as_deref(), Some("Metadata"));
+
+        // a config file takes precedence over `Cargo.toml` in the same directory
+        write_in(
+            &root,
+            "app/rust/flutter_rust_bridge.toml",
+            "class_name = \"Crate\"\n",
+        );
+        let (found, raw) = find_config_file(path("app/rust/src")).unwrap();
+        assert_eq!(found, path("app/rust/flutter_rust_bridge.toml"));
+        assert_eq!(raw.class_name.as_deref(), Some("Crate"));
+    }
+
+    #[test]
+    fn config_file_paths_are_relative_to_it() {
+        let root = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| path_in(&root, sub_path);
+        write_in(
+            &root,
+            "rust/flutter_rust_bridge.toml",
+            "rust_input = \"src/api.rs,src/more.rs\"\n\
+            dart_output = \"../lib/bridge_generated.dart\"\n\
+            c_output = [\"../ios/bridge_generated.h\"]\n",
+        );
+
+        let raw = load_config_file(Some(&path("rust/flutter_rust_bridge.toml"))).unwrap();
+        assert_eq!(
+            raw.rust_input,
+            Some(format!(
+                "{},{}",
+                path("rust/src/api.rs"),
+                path("rust/src/more.rs")
+            ))
+        );
+        assert_eq!(
+            raw.dart_output,
+            Some(path("rust/../lib/bridge_generated.dart"))
+        );
+        assert_eq!(
+            raw.c_output,
+            Some(vec![path("rust/../ios/bridge_generated.h")])
+        );
+    }
+
+    #[test]
+    fn command_line_takes_precedence_over_config_file() {
+        let crate_dir = temp_crate();
+        let path = |sub_path: &str| path_in(&crate_dir, sub_path);
+        write_in(
+            &crate_dir,
+            CONFIG_FILE_NAME,
+            "dart_library_name = \"from_file\"\ndart_format_line_length = 100\nno_dedup = true\n",
+        );
+        let opts = opts_of(
+            &crate_dir,
+            RawOpts {
+                dart_format_line_length: Some(120),
+                config: Some(path(CONFIG_FILE_NAME)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(opts.dart_format_line_length, 120);
+        assert_eq!(opts.dart_library_name, "from_file");
+        assert!(opts.no_dedup);
+    }
+
+    #[test]
+    fn config_file_cannot_select_modes() {
+        for mode in ["watch", "check", "dry_run"] {
+            let err = toml::from_str::<RawOpts>(&format!("{} = true", mode)).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("unknown field `{}`", mode)));
+        }
+    }
+
+    #[test]
+    fn line_ending_replaces_both_endings() {
+        let content = "a\r\nb\nc\n";
+        assert_eq!(LineEnding::Lf.apply(content), "a\nb\nc\n");
+        assert_eq!(LineEnding::Crlf.apply(content), "a\r\nb\r\nc\r\n");
+    }
 }
@@ -0,0 +1,157 @@
+//! Print the generated code instead of writing it, see [crate::config::RawOpts::dry_run]
+
+use std::fs;
+use std::io::{self, Write};
+
+use crate::check::{generate_staged, StagedOutput};
+use crate::config::Opts;
+
+/// Generate into a temporary directory and print each output file to stdout. Nothing is written
+/// to the output paths.
+pub fn dry_run(config: &Opts) {
+    let (_staging_dir, outputs) = generate_staged(config);
+    write_staged_outputs(&outputs, &mut io::stdout().lock()).unwrap();
+}
+
+/// Each output is preceded by a banner with its label and the path it would be written to
+fn write_staged_outputs<W: Write>(outputs: &[StagedOutput], writer: &mut W) -> io::Result<()> {
+    for output in outputs {
+        let content = fs::read_to_string(&output.staged_path)?;
+        writeln!(
+            writer,
+            "==================== {}: {} ====================",
+            output.label, output.path
+        )?;
+        writeln!(writer, "{}", content.trim_end())?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::check::staged_path;
+    use crate::config::{DartEnumStyle, DartUuidStyle, IncludedVisibility, LineEnding};
+    use crate::{generator_dart, generator_rust, parse_api};
+
+    const SOURCE: &str = "
+        pub struct Point {
+            pub x: i32,
+            pub y: i32,
+        }
+
+        pub fn norm(point: Point) -> Result<f64> {
+            Ok(((point.x * point.x + point.y * point.y) as f64).sqrt())
+        }
+    ";
+
+    fn opts(app_dir: &Path) -> Opts {
+        let path = |relative: &str| app_dir.join(relative).to_str().unwrap().to_string();
+        Opts {
+            rust_input_paths: vec![path("rust/src/api.rs")],
+            dart_output_path: path("lib/bridge_generated.dart"),
+            c_output_paths: vec![path("ios/bridge_generated.h")],
+            rust_crate_dir: path("rust"),
+            rust_output_path: path("rust/src/bridge_generated.rs"),
+            class_name: "Api".to_string(),
+            dart_library_name: "api".to_string(),
+            dart_format_line_length: 80,
+            skip_add_mod_to_lib: false,
+            llvm_path: "".to_string(),
+            dart_test_output_path: None,
+            dart_impl_output_path: None,
+            cbindgen_toml_path: None,
+            output_timestamps: false,
+            skip_unsupported_functions: false,
+            included_visibility: IncludedVisibility::default(),
+            features: None,
+            watch: false,
+            check: false,
+            dry_run: true,
+            split_output: false,
+            dart_isolate_safe: false,
+            no_cache: true,
+            no_dedup: false,
+            no_allow_clippy: false,
+            no_dart_equality: false,
+            no_finalizer: false,
+            no_auto_zero_copy: false,
+            source_locations: false,
+            dart_enum_style: DartEnumStyle::Sealed,
+            dart_uuid_style: DartUuidStyle::Value,
+            line_ending: LineEnding::Lf,
+        }
+    }
+
+    /// Trailing whitespace of the unformatted generated code is not significant, and neither are
+    /// the temporary directory and the version of the codegen
+    fn normalize(s: &str, app_dir: &Path) -> String {
+        s.replace(app_dir.to_str().unwrap(), "/app")
+            .replace(env!("CARGO_PKG_VERSION"), "{version}")
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The same steps as [crate::frb_codegen] on a fixture crate, except for the formatters and
+    /// the C header, which need `rustfmt`, `cbindgen` and `dart`
+    #[test]
+    fn write_staged_outputs_matches_snapshot() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let opts = opts(app_dir.path());
+        fs::create_dir_all(app_dir.path().join("rust/src")).unwrap();
+        fs::write(&opts.rust_input_paths[0], SOURCE).unwrap();
+
+        let (api_file, _) = parse_api(&opts);
+        let rust = generator_rust::generate(&api_file, &opts).code;
+        let dart = generator_dart::generate(
+            &api_file,
+            &opts.dart_api_class_name(),
+            &opts.dart_api_impl_class_name(),
+            &opts.dart_wire_class_name(),
+            opts.dart_code_opts(),
+            "bridge_generated.dart",
+        );
+        let dart = format!(
+            "{}\n{}\n{}\n{}",
+            dart.header,
+            generator_dart::generate_directives(
+                &opts.dart_library_name,
+                &dart.imports,
+                &dart.parts
+            ),
+            dart.api_class,
+            dart.other
+        );
+
+        let staging_dir = tempfile::tempdir().unwrap();
+        let outputs: Vec<_> = vec![
+            ("Rust", &opts.rust_output_path, rust),
+            ("Dart", &opts.dart_output_path, dart),
+        ]
+        .into_iter()
+        .map(|(label, path, content)| {
+            let staged_path = staged_path(staging_dir.path(), path);
+            fs::create_dir_all(Path::new(&staged_path).parent().unwrap()).unwrap();
+            fs::write(&staged_path, content).unwrap();
+            StagedOutput {
+                label,
+                path: path.clone(),
+                staged_path,
+            }
+        })
+        .collect();
+
+        let mut writer = Vec::new();
+        write_staged_outputs(&outputs, &mut writer).unwrap();
+
+        assert_eq!(
+            normalize(&String::from_utf8(writer).unwrap(), app_dir.path()),
+            normalize(include_str!("../snapshots/dry_run.txt"), app_dir.path()),
+        );
+    }
+}
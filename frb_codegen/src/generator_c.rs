@@ -1,3 +1,13 @@
+use std::collections::HashSet;
+
+use convert_case::{Case, Casing};
+
+use crate::api_types::ApiType::*;
+use crate::api_types::*;
+use crate::config::Opts;
+use crate::generator_rust;
+use crate::others::*;
+
 pub fn generate_dummy(func_names: &[String]) -> String {
     format!(
         r#"static int64_t dummy_method_to_enforce_bundling(void) {{
@@ -12,3 +22,247 @@ pub fn generate_dummy(func_names: &[String]) -> String {
             .join("\n"),
     )
 }
+
+/// A standalone C header declaring the same wire structs and functions as the generated Rust code,
+/// e.g. for build scripts to feed into other tools without running `cbindgen`.
+pub fn generate_c_header_string(api_file: &ApiFile, opts: &Opts) -> String {
+    let generated_rust = generator_rust::generate(api_file, opts);
+
+    let mut wire_structs = Vec::new();
+    let mut seen_wire_structs = HashSet::new();
+    for ty in api_file.distinct_types(true, false) {
+        generate_c_wire_struct(&ty, api_file, &mut seen_wire_structs, &mut wire_structs);
+    }
+
+    let funcs = generated_rust
+        .extern_funcs
+        .iter()
+        .map(|func| {
+            format!(
+                "{} {}({});",
+                func.return_type
+                    .as_deref()
+                    .map_or("void".to_string(), rust_type_to_c),
+                func.name,
+                if func.params.is_empty() {
+                    "void".to_string()
+                } else {
+                    func.params
+                        .iter()
+                        .map(|param| rust_field_to_c(param))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let include_guard = format!("{}_H", opts.class_name.to_case(Case::UpperSnake));
+    let effective_func_names = [
+        generated_rust.extern_func_names,
+        EXTRA_EXTERN_FUNC_NAMES.to_vec(),
+    ]
+    .concat();
+
+    format!(
+        r#"{}
+
+#ifndef {}
+#define {}
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef int64_t DartPort;
+
+typedef bool (*DartPostCObjectFnType)(DartPort port_id, void *message);
+
+typedef struct WireSyncReturnStruct {{
+  uint8_t *ptr;
+  int32_t len;
+  bool success;
+}} WireSyncReturnStruct;
+
+{}
+
+void store_dart_post_cobject(DartPostCObjectFnType ptr);
+
+{}
+
+{}
+
+#endif /* {} */
+"#,
+        CODE_HEADER,
+        include_guard,
+        include_guard,
+        wire_structs.join("\n\n"),
+        funcs.join("\n\n"),
+        generate_dummy(&effective_func_names),
+        include_guard,
+    )
+}
+
+/// Emits the wire struct of [ty] after the ones it contains by value, since C needs complete types
+fn generate_c_wire_struct(
+    ty: &ApiType,
+    api_file: &ApiFile,
+    seen: &mut HashSet<String>,
+    output: &mut Vec<String>,
+) {
+    let fields = match generator_rust::wire_struct_fields(ty, api_file) {
+        Some(fields) => fields,
+        None => return,
+    };
+    if !seen.insert(ty.rust_wire_type()) {
+        return;
+    }
+
+    if let StructRef(s) = ty {
+        for field in &s.get(api_file).fields {
+            if !field.ty.rust_wire_is_pointer() {
+                generate_c_wire_struct(&field.ty, api_file, seen, output);
+            }
+        }
+    }
+
+    output.push(format!(
+        "typedef struct {} {{\n{}\n}} {};",
+        ty.rust_wire_type(),
+        fields
+            .iter()
+            .map(|field| format!("  {};", rust_field_to_c(field)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ty.rust_wire_type(),
+    ));
+}
+
+/// e.g. `ptr: *mut u8` => `uint8_t *ptr`
+fn rust_field_to_c(field: &str) -> String {
+    let (name, ty) = field
+        .split_once(": ")
+        .unwrap_or_else(|| panic!("unexpected field: {}", field));
+    let ty = rust_type_to_c(ty);
+    if ty.ends_with('*') {
+        format!("{}{}", ty, name)
+    } else {
+        format!("{} {}", ty, name)
+    }
+}
+
+/// e.g. `*mut wire_uint_8_list` => `struct wire_uint_8_list *`
+fn rust_type_to_c(ty: &str) -> String {
+    if let Some(inner) = ty.strip_prefix("*mut ") {
+        return format!("{} *", rust_type_to_c(inner));
+    }
+    match ty {
+        "u8" => "uint8_t",
+        "i8" => "int8_t",
+        "u16" => "uint16_t",
+        "i16" => "int16_t",
+        "u32" => "uint32_t",
+        "i32" => "int32_t",
+        "u64" => "uint64_t",
+        "i64" => "int64_t",
+        "f32" => "float",
+        "f64" => "double",
+        "bool" => "bool",
+        "support::WireSyncReturnStruct" => "WireSyncReturnStruct",
+        "std::ffi::c_void" => "void",
+        _ if ty.starts_with("wire_") => return format!("struct {}", ty),
+        _ => ty,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::config::{parse, RawOpts};
+    use crate::{parser, transformer};
+
+    const SOURCE: &str = "
+        pub struct Point {
+            pub x: i32,
+            pub y: i32,
+        }
+
+        pub struct Line {
+            pub start: Point,
+            pub end: Point,
+        }
+
+        #[frb(opaque)]
+        pub struct Connection {
+            fd: i32,
+        }
+
+        pub fn length(line: Line, bytes: Vec<u8>) -> Result<f64> {
+            Ok(0.0)
+        }
+
+        pub fn connect() -> Result<Connection> {
+            Ok(Connection { fd: 0 })
+        }
+
+        #[frb(sync)]
+        pub fn version() -> Result<String> {
+            Ok(String::new())
+        }
+    ";
+
+    #[test]
+    fn generates_c_header() {
+        let crate_dir = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| {
+            crate_dir
+                .path()
+                .join(sub_path)
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+        fs::create_dir_all(path("src")).unwrap();
+        fs::write(path("src/api.rs"), SOURCE).unwrap();
+        let opts = parse(RawOpts {
+            rust_input: Some(path("src/api.rs")),
+            dart_output: Some(path("bridge_generated.dart")),
+            c_output: Some(vec![path("bridge_generated.h")]),
+            rust_crate_dir: Some(path("")),
+            class_name: Some("Api".to_string()),
+            llvm_path: Some("".to_string()),
+            ..Default::default()
+        });
+        let api_file = transformer::transform(parser::parse_source(SOURCE), true);
+
+        let header = generate_c_header_string(&api_file, &opts);
+        // structs contained by value come first, since C needs complete types
+        let point = header
+            .find("typedef struct wire_Point {\n  int32_t x;\n  int32_t y;\n} wire_Point;")
+            .unwrap();
+        let line = header
+            .find(
+                "typedef struct wire_Line {\n  \
+                struct wire_Point start;\n  struct wire_Point end;\n} wire_Line;",
+            )
+            .unwrap();
+        assert!(point < line);
+        for declaration in [
+            "void wire_length(int64_t port, struct wire_Line *line, struct wire_uint_8_list *bytes);",
+            "void wire_connect(int64_t port);",
+            "WireSyncReturnStruct wire_version(void);",
+            "void drop_opaque_connection(int64_t handle);",
+            "void frb_dispose_opaque_connection(void *handle);",
+            "struct wire_uint_8_list * new_uint_8_list(int32_t len);",
+            "dummy_var ^= ((int64_t) (void*) frb_dispose_opaque_connection);",
+            "#endif /* API_H */",
+        ] {
+            assert!(header.contains(declaration), "missing {}", declaration);
+        }
+        assert!(!header.contains("::"));
+    }
+}
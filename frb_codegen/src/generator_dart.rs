@@ -1,22 +1,95 @@
-use convert_case::{Case, Casing};
-use log::debug;
+use std::collections::HashSet;
+
+use log::{debug, warn};
 
 use crate::api_types::ApiType::*;
 use crate::api_types::*;
+use crate::config::{DartCodeOpts, DartEnumStyle};
 use crate::others::*;
+use crate::sorter::sort_structs;
 
 pub struct Output {
     pub header: String,
+    /// e.g. `import 'dart:convert';`, see [generate_directives]
+    pub imports: Vec<String>,
+    /// e.g. `part 'bridge_generated.freezed.dart';` for `freezed`
+    pub parts: Vec<String>,
     pub api_class: String,
     pub other: String,
 }
 
+/// Lints are ignored per file, so a `part` file needs them as well
+const DART_IGNORE_FOR_FILE: &str = "// ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments, camel_case_types";
+
+/// The `library` declaration, e.g. `library api;`, followed by the [imports] in the order of the
+/// `directives_ordering` lint: `dart:` ones, then `package:` ones and then relative ones, each
+/// sorted and without duplicates, and finally the [parts]
+pub fn generate_directives(library_name: &str, imports: &[String], parts: &[String]) -> String {
+    let section = |import: &str| {
+        let uri = import.split('\'').nth(1).unwrap_or_default();
+        if uri.starts_with("dart:") {
+            0
+        } else if uri.starts_with("package:") {
+            1
+        } else {
+            2
+        }
+    };
+    let mut imports: Vec<_> = imports
+        .iter()
+        .map(|import| import.trim())
+        .filter(|import| !import.is_empty())
+        .collect();
+    imports.sort_by_key(|import| (section(import), *import));
+    imports.dedup();
+    let mut parts: Vec<_> = parts.iter().map(|part| part.trim()).collect();
+    parts.sort_unstable();
+    parts.dedup();
+
+    let mut directives = format!("library {};\n", library_name);
+    let mut prev_section = None;
+    for import in imports {
+        if prev_section != Some(section(import)) {
+            directives.push('\n');
+            prev_section = Some(section(import));
+        }
+        directives += import;
+        directives.push('\n');
+    }
+    if !parts.is_empty() {
+        directives += &format!("\n{}\n", parts.join("\n"));
+    }
+    directives
+}
+
+/// The beginning of the `part` file with the implementation class, see
+/// [crate::config::RawOpts::dart_impl_output]. [dart_output_import] is the library it is part of.
+pub fn generate_impl_part_header(dart_output_import: &str) -> String {
+    format!(
+        "{}
+
+        {}
+
+        part of '{}';
+        ",
+        CODE_HEADER, DART_IGNORE_FOR_FILE, dart_output_import,
+    )
+}
+
 pub fn generate(
     api_file: &ApiFile,
     dart_api_class_name: &str,
     dart_api_impl_class_name: &str,
     dart_wire_class_name: &str,
+    dart_code_opts: DartCodeOpts,
+    dart_output_file_name: &str,
 ) -> Output {
+    let DartCodeOpts {
+        dart_enum_style,
+        dart_isolate_safe,
+        no_dart_equality,
+        no_finalizer,
+    } = dart_code_opts;
     let distinct_types = api_file.distinct_types(true, true);
     let distinct_input_types = api_file.distinct_types(true, false);
     let distinct_output_types = api_file.distinct_types(false, true);
@@ -28,20 +101,47 @@ pub fn generate(
         .iter()
         .map(generate_api_func)
         .collect::<Vec<_>>();
-    let dart_structs = distinct_types
+    let dart_struct_names = distinct_types
+        .iter()
+        .filter_map(|ty| match ty {
+            StructRef(s) => Some(s.name.as_str()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    // classes of field types first, which reads better, although Dart needs no declaration order
+    let sorted_structs = sort_structs(&api_file.struct_pool).unwrap_or_else(|cycle| {
+        warn!("{}, so they are generated by name", cycle);
+        let mut structs: Vec<_> = api_file.struct_pool.values().collect();
+        structs.sort_by_key(|s| &s.name);
+        structs
+    });
+    let dart_structs = sorted_structs
+        .into_iter()
+        .filter(|s| dart_struct_names.contains(s.name.as_str()))
+        .map(|s| {
+            generate_api_struct(
+                s,
+                api_file,
+                dart_api_class_name,
+                dart_api_impl_class_name,
+                !no_dart_equality,
+            )
+        })
+        .collect::<Vec<_>>();
+    let dart_enums = distinct_types
         .iter()
         .filter_map(|ty| {
-            if let StructRef(s) = ty {
-                Some(s.get(api_file))
+            if let EnumRef(e) = ty {
+                Some(e.get(api_file))
             } else {
                 None
             }
         })
-        .map(generate_api_struct)
+        .map(|e| generate_api_enum(e, dart_enum_style, is_error_enum(api_file, &e.name)))
         .collect::<Vec<_>>();
     let dart_api2wire_funcs = distinct_input_types
         .iter()
-        .map(generate_api2wire_func)
+        .map(|ty| generate_api2wire_func(ty, api_file))
         .collect::<Vec<_>>();
     let dart_api_fill_to_wire_funcs = distinct_input_types
         .iter()
@@ -49,23 +149,69 @@ pub fn generate(
         .collect::<Vec<_>>();
     let dart_wire2api_funcs = distinct_output_types
         .iter()
+        .filter(|ty| !is_on_change_struct(ty, api_file))
         .map(|ty| generate_wire2api_func(ty, api_file))
         .collect::<Vec<_>>();
+    let dart_wire2api_weak_funcs = distinct_output_types
+        .iter()
+        .filter_map(|ty| match ty {
+            OpaqueWeak(weak) => Some(generate_wire2api_weak_func(weak)),
+            Opaque(opaque) => Some(generate_wire2api_opaque_func(opaque, !no_finalizer)),
+            StructRef(s) if is_on_change_struct(ty, api_file) => {
+                Some(generate_wire2api_on_change_func(s, api_file))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let dart_opaques = distinct_types
+        .iter()
+        .filter_map(|ty| match ty {
+            Opaque(opaque) => Some(generate_api_opaque(opaque, !no_finalizer)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
 
-    let header = format!(
-        "{}
+    check_weak_only_direct_output(api_file);
 
-        // ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments
-        import 'dart:convert';
-        import 'dart:typed_data';
+    let needs_freezed = dart_enum_style == DartEnumStyle::Freezed
+        && distinct_types
+            .iter()
+            .any(|ty| matches!(ty, EnumRef(e) if e.get(api_file).is_struct()));
+    let needs_uuid = distinct_types
+        .iter()
+        .any(|ty| matches!(ty, Delegate(ApiTypeDelegate::Uuid(false))));
+    let needs_change_notifier = distinct_types
+        .iter()
+        .any(|ty| is_on_change_struct(ty, api_file));
 
-        import 'package:flutter_rust_bridge/flutter_rust_bridge.dart';",
-        CODE_HEADER,
-    );
+    let header = format!("{}\n\n{}", CODE_HEADER, DART_IGNORE_FOR_FILE);
+    let mut imports = vec![
+        "import 'dart:convert';".to_string(),
+        "import 'dart:typed_data';".to_string(),
+        "import 'package:flutter_rust_bridge/flutter_rust_bridge.dart';".to_string(),
+    ];
+    let mut parts = Vec::new();
+    if needs_change_notifier {
+        imports.push("import 'package:flutter/foundation.dart';".to_string());
+    }
+    if needs_uuid {
+        imports.push("import 'package:uuid/uuid.dart';".to_string());
+    }
+    if needs_freezed {
+        imports.push("import 'package:freezed_annotation/freezed_annotation.dart';".to_string());
+        parts.push(format!(
+            "part '{}.freezed.dart';",
+            dart_output_file_name.trim_end_matches(".dart")
+        ));
+    }
 
     let api_class = format!(
         "abstract class {} extends FlutterRustBridgeBase<{}> {{
-            factory {}(ffi.DynamicLibrary dylib) => {}.raw({}(dylib));
+            factory {}(ffi.DynamicLibrary dylib) {{
+                final bridge = {}.raw({}(dylib));
+                {}.instance.register({}.defaultName, bridge);
+                return bridge;
+            }}
 
             {}.raw({} inner) : super(inner);
 
@@ -74,6 +220,10 @@ pub fn generate(
 
         {}
 
+        {}
+
+        {}
+
         // ------------------------- Implementation Details -------------------------
         ",
         dart_api_class_name,
@@ -81,6 +231,8 @@ pub fn generate(
         dart_api_class_name,
         dart_api_impl_class_name,
         dart_wire_class_name,
+        dart_registry_class_name(dart_api_class_name),
+        dart_registry_class_name(dart_api_class_name),
         dart_api_class_name,
         dart_wire_class_name,
         dart_func_signatures_and_implementations
@@ -88,7 +240,17 @@ pub fn generate(
             .map(|(sig, _, comm)| format!("{}\n{}", comm, sig))
             .collect::<Vec<_>>()
             .join("\n\n"),
+        if dart_isolate_safe {
+            format!(
+                "{}\n\n{}",
+                generate_registry(dart_api_class_name),
+                generate_isolate_safe(api_file, dart_api_class_name)
+            )
+        } else {
+            generate_registry(dart_api_class_name)
+        },
         dart_structs.join("\n\n"),
+        [dart_enums, dart_opaques].concat().join("\n\n"),
     );
 
     let other = format!(
@@ -105,6 +267,9 @@ pub fn generate(
 
             // Section: api_fill_to_wire
             {}
+
+            // Section: weak references
+            {}
         }}
 
         // Section: wire2api
@@ -123,17 +288,333 @@ pub fn generate(
             .join("\n\n"),
         dart_api2wire_funcs.join("\n\n"),
         dart_api_fill_to_wire_funcs.join("\n\n"),
+        dart_wire2api_weak_funcs.join("\n\n"),
         dart_wire2api_funcs.join("\n\n"),
     );
 
     Output {
         header,
+        imports,
+        parts,
         api_class,
         other,
     }
 }
 
-fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
+/// Test stubs for functions marked `#[frb(test)]`. Only functions without arguments that return
+/// a primitive or a string are supported; the expected values are left for the user to fill in.
+pub fn generate_test_stubs(
+    api_file: &ApiFile,
+    dart_api_class_name: &str,
+    dart_output_import: &str,
+) -> String {
+    let tests = api_file
+        .funcs
+        .iter()
+        .filter(|func| func.is_test)
+        .filter(|func| {
+            let supported = func.inputs.is_empty()
+                && func.mode != ApiFuncMode::Stream
+                && matches!(
+                    func.output,
+                    Primitive(_) | Delegate(ApiTypeDelegate::String)
+                );
+            if !supported {
+                warn!(
+                    "skip test stub for `{}`: only functions without arguments returning a primitive or a string are supported",
+                    func.name
+                );
+            }
+            supported
+        })
+        .map(|func| {
+            // `#[frb(sync)]` functions return the value itself rather than a `Future`
+            let (body_modifier, call_modifier) = match func.mode {
+                ApiFuncMode::Sync => ("", ""),
+                _ => (" async", "await "),
+            };
+            format!(
+                "test('{}', (){} {{
+                    final result = {}api.{}();
+                    // TODO: replace with the expected value
+                    expect(result, isNotNull);
+                }});",
+                func.name,
+                body_modifier,
+                call_modifier,
+                func.dart_name(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "{}
+
+        import 'dart:ffi' as ffi;
+
+        import 'package:test/test.dart';
+
+        import '{}';
+
+        void main() {{
+            // TODO: load the dynamic library of your Rust crate
+            final api = {}(ffi.DynamicLibrary.executable());
+
+            {}
+        }}
+        ",
+        CODE_HEADER,
+        dart_output_import,
+        dart_api_class_name,
+        tests.join("\n\n"),
+    )
+}
+
+/// `WeakRef<T>` and opaque handles need the bridge to dispose themselves, so unlike other types
+/// they are converted in a method of the implementation class instead of a top-level function,
+/// which is only reachable from the functions themselves.
+/// Opaque handles are only supported as the input types themselves as well, which the wire
+/// function asserts to be unwind safe, and so is `NonNull<T>`, which the wire function creates in
+/// the task since it is not `Send`.
+fn check_weak_only_direct_output(api_file: &ApiFile) {
+    for func in &api_file.funcs {
+        for input in &func.inputs {
+            check_only_direct(
+                &input.ty,
+                |ty| matches!(ty, Opaque(_) | Delegate(ApiTypeDelegate::NonNull(_))),
+                "input",
+                func,
+                api_file,
+            );
+        }
+        check_only_direct(
+            &func.output,
+            |ty| matches!(ty, OpaqueWeak(_) | Opaque(_)) || is_on_change_struct(ty, api_file),
+            "output",
+            func,
+            api_file,
+        );
+    }
+}
+
+fn check_only_direct(
+    top: &ApiType,
+    is_restricted: impl Fn(&ApiType) -> bool,
+    direction: &str,
+    func: &ApiFunc,
+    api_file: &ApiFile,
+) {
+    let mut seen_idents = HashSet::new();
+    top.visit_types(
+        &mut |ty| {
+            if is_restricted(ty) && !std::ptr::eq(ty, top) {
+                panic!(
+                    "{} is only supported as the {} type itself, see {}",
+                    ty.rust_api_type(),
+                    direction,
+                    func.name
+                );
+            }
+            // stop at recursive types
+            !seen_idents.insert(ty.safe_ident())
+        },
+        api_file,
+    );
+}
+
+fn generate_wire2api_weak_func(weak: &ApiTypeOpaqueWeak) -> String {
+    format!(
+        "{} _wire2api_{}(dynamic raw) {{
+            return WeakRef(raw as int,
+                upgrade: (address) => executeNormal(FlutterRustBridgeTask(
+                    debugName: '{}',
+                    callFfi: (port) => inner.wire_{}(port, address),
+                    parseSuccessData: (raw) => raw == null ? null : _wire2api_{}(raw),
+                )),
+                dispose: inner.{});
+        }}",
+        weak.dart_api_type(),
+        weak.safe_ident(),
+        weak.upgrade_func_name(),
+        weak.upgrade_func_name(),
+        weak.inner.safe_ident(),
+        weak.drop_func_name(),
+    )
+}
+
+fn is_on_change_struct(ty: &ApiType, api_file: &ApiFile) -> bool {
+    matches!(ty, StructRef(s) if s.get(api_file).notify_on_change)
+}
+
+/// The handle of the Rust copy comes before the fields, see [generate_api_struct_change_notifier]
+fn generate_wire2api_on_change_func(s_ref: &ApiTypeStructRef, api_file: &ApiFile) -> String {
+    let s = s_ref.get(api_file);
+    let fields = s
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            format!(
+                "{}: _wire2api_{}(arr[{}]),",
+                field.dart_name(),
+                field.ty.safe_ident(),
+                idx + 1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{name} _wire2api_{ident}(dynamic raw) {{
+            final arr = raw as List<dynamic>;
+            if (arr.length != {len}) throw Exception('unexpected arr length: expect {len} but see ${{arr.length}}');
+            return {name}._fromRust(this, arr[0] as int, {fields});
+        }}",
+        name = s.name,
+        ident = s_ref.safe_ident(),
+        len = s.fields.len() + 1,
+        fields = fields,
+    )
+}
+
+fn generate_wire2api_opaque_func(opaque: &ApiTypeOpaque, finalizer: bool) -> String {
+    if !finalizer {
+        return format!(
+            "{} _wire2api_{}(dynamic raw) {{
+                return {}._(raw as int, dispose: inner.{});
+            }}",
+            opaque.dart_api_type(),
+            opaque.safe_ident(),
+            opaque.dart_api_type(),
+            opaque.drop_func_name(),
+        );
+    }
+    // looked up once per bridge, since the function pointer belongs to the library it loaded
+    format!(
+        "late final _finalizer_{ident} = ffi.NativeFinalizer(inner._lookup<ffi.NativeFinalizerFunction>('{dispose}'));
+
+        {ty} _wire2api_{ident}(dynamic raw) {{
+            return {ty}._(raw as int, dispose: inner.{drop}, finalizer: _finalizer_{ident});
+        }}",
+        ty = opaque.dart_api_type(),
+        ident = opaque.safe_ident(),
+        dispose = opaque.dispose_func_name(),
+        drop = opaque.drop_func_name(),
+    )
+}
+
+/// With [finalizer], the Rust object is also dropped once the handle is garbage collected, unless
+/// it is disposed or moved to Rust before, see [crate::config::RawOpts::no_finalizer]
+fn generate_api_opaque(opaque: &ApiTypeOpaque, finalizer: bool) -> String {
+    if !finalizer {
+        return format!(
+            "/// An opaque handle to a Rust `{}`, see [OpaqueHandle].
+            class {} extends OpaqueHandle {{
+                {}._(int handle, {{required void Function(int handle) dispose}}) : super(handle, dispose: dispose);
+            }}",
+            opaque.rust_api_type(),
+            opaque.dart_api_type(),
+            opaque.dart_api_type(),
+        );
+    }
+    format!(
+        "/// An opaque handle to a Rust `{rust_ty}`, see [OpaqueHandle]. The Rust object is dropped
+        /// once the handle is garbage collected, unless it is disposed or moved to Rust before.
+        class {ty} extends OpaqueHandle implements ffi.Finalizable {{
+            final ffi.NativeFinalizer _finalizer;
+
+            {ty}._(int handle, {{required void Function(int handle) dispose, required ffi.NativeFinalizer finalizer}})
+                : _finalizer = finalizer,
+                  super(handle, dispose: dispose) {{
+                _finalizer.attach(this, ffi.Pointer.fromAddress(handle), detach: this);
+            }}
+
+            @override
+            int move() {{
+                _finalizer.detach(this);
+                return super.move();
+            }}
+        }}",
+        rust_ty = opaque.rust_api_type(),
+        ty = opaque.dart_api_type(),
+    )
+}
+
+fn dart_registry_class_name(dart_api_class_name: &str) -> String {
+    format!("{}Registry", dart_api_class_name)
+}
+
+fn generate_registry(dart_api_class_name: &str) -> String {
+    let registry = dart_registry_class_name(dart_api_class_name);
+    format!(
+        "/// Look up instances of [{api}] by name, e.g. when an app uses multiple bridges.
+        /// Instances created by the [{api}] factory are registered as [defaultName].
+        class {registry} {{
+            static const defaultName = 'default';
+            static final instance = {registry}._();
+
+            final _bridges = <String, Object>{{}};
+
+            {registry}._();
+
+            void register(String name, {api} bridge) => _bridges[name] = bridge;
+
+            T? lookup<T extends {api}>(String name) {{
+                final bridge = _bridges[name];
+                return bridge is T ? bridge : null;
+            }}
+
+            /// Drop all registered instances
+            void dispose() => _bridges.clear();
+        }}",
+        api = dart_api_class_name,
+        registry = registry,
+    )
+}
+
+/// Forwards each function to the API class, see [crate::config::RawOpts::dart_isolate_safe]
+fn generate_isolate_safe(api_file: &ApiFile, dart_api_class_name: &str) -> String {
+    let methods = api_file
+        .funcs
+        .iter()
+        .map(|func| {
+            let args = func
+                .inputs
+                .iter()
+                .map(|input| format!("{0}: {0}", input.name.dart_style()))
+                .chain(["hint: hint".to_string()])
+                .collect::<Vec<_>>();
+            format!(
+                "{}\n{} => {}(() => inner.{}({}));",
+                dart_comments_and_deprecated(&func.comments, &func.deprecated),
+                dart_func_partial(func),
+                if func.mode == ApiFuncMode::Stream {
+                    "runStream"
+                } else {
+                    "run"
+                },
+                func.dart_name(),
+                args.join(", "),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "/// Forwards to [inner] in the zone this is created in, so that futures complete and stream
+        /// events arrive in it, and throws a [StateError] when used by another isolate.
+        class {api}IsolateSafe extends FlutterRustBridgeIsolateSafe {{
+            final {api} inner;
+
+            {api}IsolateSafe(this.inner);
+
+            {}
+        }}",
+        methods.join("\n\n"),
+        api = dart_api_class_name,
+    )
+}
+
+/// e.g. `Future<int> add({ required int a,required int b,dynamic hint })`
+fn dart_func_partial(func: &ApiFunc) -> String {
     let raw_func_param_list = func
         .inputs
         .iter()
@@ -141,7 +622,7 @@ fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
             format!(
                 "{}{} {}",
                 input.ty.required_modifier(),
-                input.ty.dart_api_type(),
+                dart_param_type(input),
                 input.name.dart_style()
             )
         })
@@ -149,6 +630,15 @@ fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
 
     let full_func_param_list = [raw_func_param_list, vec!["dynamic hint".to_string()]].concat();
 
+    format!(
+        "{} {}({{ {} }})",
+        dart_return_type(func),
+        func.dart_name(),
+        full_func_param_list.join(","),
+    )
+}
+
+fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
     let wire_param_list = [
         if func.mode.has_port_argument() {
             vec!["port".to_string()]
@@ -161,48 +651,73 @@ fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
                 format!(
                     "_api2wire_{}({})",
                     &input.ty.safe_ident(),
-                    &input.name.dart_style()
+                    dart_param_into(input)
                 )
             })
             .collect::<Vec<_>>(),
     ]
     .concat();
 
-    let partial = format!(
-        "{} {}({{ {} }})",
-        func.mode.dart_return_type(&func.output.dart_api_type()),
-        func.name.to_case(Case::Camel),
-        full_func_param_list.join(","),
-    );
+    let parse_success_data = if func.throw_on_null {
+        format!(
+            "(raw) => _wire2api_{}(raw) ?? (throw const FrbNullException('{} returned null'))",
+            func.output.safe_ident(),
+            func.name
+        )
+    } else {
+        format!("_wire2api_{}", func.output.safe_ident())
+    };
+
+    let partial = dart_func_partial(func);
 
     let execute_func_name = match func.mode {
         ApiFuncMode::Normal => "executeNormal",
         ApiFuncMode::Sync => "executeSync",
+        ApiFuncMode::Stream if func.stream_error_output.is_some() => "executeResultStream",
         ApiFuncMode::Stream => "executeStream",
     };
 
     let signature = format!("{};", partial);
 
-    let comments = dart_comments(&func.comments);
+    let comments = dart_comments_and_deprecated(&func.comments, &func.deprecated);
 
     let implementation = match func.mode {
-        ApiFuncMode::Sync => format!(
-            "{} => {}(FlutterRustBridgeSyncTask(
-            debugName: '{}',
-            callFfi: () => inner.{}({}),
-            hint: hint
-        ));",
-            partial,
-            execute_func_name,
-            func.name,
-            func.wire_func_name(),
-            wire_param_list.join(", "),
-        ),
+        ApiFuncMode::Sync => {
+            let execute = format!(
+                "{}(FlutterRustBridgeSyncTask(
+                debugName: '{}',
+                callFfi: () => inner.{}({}),
+                hint: hint
+            ))",
+                execute_func_name,
+                func.name,
+                func.wire_func_name(),
+                wire_param_list.join(", "),
+            );
+            // see `into_sync_return` of the Rust side for the bytes of `#[frb(sync)]` outputs
+            match &func.output {
+                Delegate(ApiTypeDelegate::SyncReturnString | ApiTypeDelegate::String) => {
+                    format!("{} => utf8.decode({});", partial, execute)
+                }
+                Primitive(ApiTypePrimitive::Bool) => format!("{} => {}[0] != 0;", partial, execute),
+                Primitive(primitive) => format!(
+                    "{} => ByteData.sublistView({}).get{}(0{});",
+                    partial,
+                    execute,
+                    primitive.byte_data_type(),
+                    match primitive {
+                        ApiTypePrimitive::U8 | ApiTypePrimitive::I8 => "",
+                        _ => ", Endian.little",
+                    }
+                ),
+                _ => format!("{} => {};", partial, execute),
+            }
+        }
         _ => format!(
             "{} => {}(FlutterRustBridgeTask(
             debugName: '{}',
             callFfi: (port) => inner.{}({}),
-            parseSuccessData: _wire2api_{},
+            parseSuccessData: {},{}{}
             hint: hint
         ));",
             partial,
@@ -210,24 +725,120 @@ fn generate_api_func(func: &ApiFunc) -> (String, String, String) {
             func.name,
             func.wire_func_name(),
             wire_param_list.join(", "),
-            func.output.safe_ident(),
+            parse_success_data,
+            match &func.error_output {
+                Some(ApiFuncError::Custom(ty)) =>
+                    format!("\nparseErrorData: _wire2api_{},", ty.safe_ident()),
+                _ => "".to_string(),
+            },
+            match &func.stream_error_output {
+                Some(ty) => format!("\nparseStreamErrorData: _wire2api_{},", ty.safe_ident()),
+                None => "".to_string(),
+            },
         ),
     };
 
     (signature, implementation, comments)
 }
 
-fn generate_api2wire_func(ty: &ApiType) -> String {
+/// e.g. `Future<int>`
+fn dart_return_type(func: &ApiFunc) -> String {
+    let output = match &func.output {
+        Optional(opt) if func.throw_on_null => opt.inner.dart_api_type(),
+        ty => ty.dart_api_type(),
+    };
+    func.mode.dart_return_type(&output)
+}
+
+/// Parameters with `use_into` accept anything with `toString()` for `String`, and any `List<int>`
+/// for `Vec<u8>`
+fn dart_param_type(input: &ApiField) -> String {
+    match &input.ty {
+        Delegate(ApiTypeDelegate::String) if input.use_into => "Object".to_string(),
+        PrimitiveList(_) if input.use_into => "List<int>".to_string(),
+        ty => ty.dart_api_type(),
+    }
+}
+
+/// Converts a parameter typed by [dart_param_type] back to its [ApiType::dart_api_type]
+fn dart_param_into(input: &ApiField) -> String {
+    let name = input.name.dart_style();
+    match &input.ty {
+        Delegate(ApiTypeDelegate::String) if input.use_into => format!("{}.toString()", name),
+        PrimitiveList(_) if input.use_into => format!(
+            "{} is Uint8List ? {} : Uint8List.fromList({})",
+            name, name, name
+        ),
+        _ => name,
+    }
+}
+
+fn generate_api2wire_func(ty: &ApiType, api_file: &ApiFile) -> String {
     let body = match ty {
         Primitive(_) => "return raw;".to_string(),
         Delegate(d) => match d {
-            ApiTypeDelegate::String => {
+            ApiTypeDelegate::String | ApiTypeDelegate::DynError(_) | ApiTypeDelegate::CowStr => {
                 "return _api2wire_uint_8_list(utf8.encoder.convert(raw));".to_string()
             }
-            ApiTypeDelegate::SyncReturnVecU8 => "/*unsupported*/".to_string(),
-            ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
+            ApiTypeDelegate::CowBytes => "return _api2wire_uint_8_list(raw);".to_string(),
+            ApiTypeDelegate::SyncReturnVecU8 | ApiTypeDelegate::SyncReturnString => {
+                "/*unsupported*/".to_string()
+            }
+            ApiTypeDelegate::ZeroCopyBufferVec(_)
+            | ApiTypeDelegate::ZeroCopyBufferVecString
+            | ApiTypeDelegate::StringRef
+            | ApiTypeDelegate::SliceU8 => {
                 format!("return _api2wire_{}(raw);", d.get_delegate().safe_ident())
             }
+            ApiTypeDelegate::NonNull(_) => "return raw;".to_string(),
+            ApiTypeDelegate::Range(..) => {
+                let list = d.get_delegate();
+                format!(
+                    "return _api2wire_{}({}.fromList([raw.start, raw.end]));",
+                    list.safe_ident(),
+                    list.dart_api_type()
+                )
+            }
+            ApiTypeDelegate::I128 | ApiTypeDelegate::U128 => {
+                "return _api2wire_int_64_list(Int64List.fromList([raw.high, raw.low]));"
+                    .to_string()
+            }
+            ApiTypeDelegate::Array(array) => format!(
+                "if (raw.length != {}) throw ArgumentError('unexpected arr length: expect {} but see ${{raw.length}}');
+                return _api2wire_{}(raw);",
+                array.length,
+                array.length,
+                d.get_delegate().safe_ident()
+            ),
+            ApiTypeDelegate::Set(_) => match d.get_delegate() {
+                PrimitiveList(list) => format!(
+                    "return _api2wire_{}({}.fromList(raw.toList()));",
+                    list.safe_ident(),
+                    list.dart_api_type()
+                ),
+                list => format!("return _api2wire_{}(raw.toList());", list.safe_ident()),
+            },
+            ApiTypeDelegate::Chrono(ApiTypeChrono::Utc | ApiTypeChrono::Local) => {
+                "return raw.millisecondsSinceEpoch;".to_string()
+            }
+            // keep the wall-clock time, whatever the time zone of `raw`
+            ApiTypeDelegate::Chrono(ApiTypeChrono::NaiveDate) => {
+                "return DateTime.utc(raw.year, raw.month, raw.day).millisecondsSinceEpoch;"
+                    .to_string()
+            }
+            ApiTypeDelegate::Chrono(ApiTypeChrono::NaiveDateTime) => "return DateTime.utc(
+                raw.year, raw.month, raw.day, raw.hour, raw.minute, raw.second, raw.millisecond,
+            ).millisecondsSinceEpoch;"
+                .to_string(),
+            ApiTypeDelegate::Uuid(as_string) => format!(
+                "return _api2wire_{}({});",
+                d.get_delegate().safe_ident(),
+                if *as_string {
+                    "frbUuidToBytes(raw)"
+                } else {
+                    "raw.toBytes()"
+                }
+            ),
         },
         Optional(opt) => format!(
             "return raw == null ? ffi.nullptr : _api2wire_{}(raw);",
@@ -250,6 +861,15 @@ fn generate_api2wire_func(ty: &ApiType) -> String {
                 ty.safe_ident(),
             )
         }
+        GeneralList(list) if !matches!(list.inner, StructRef(_)) => format!(
+            "final ans = inner.new_{}(raw.length);
+            for (var i = 0; i < raw.length; ++i) {{
+                ans.ref.ptr[i] = _api2wire_{}(raw[i]);
+            }}
+            return ans;",
+            ty.safe_ident(),
+            list.inner.safe_ident()
+        ),
         GeneralList(list) => {
             // NOTE 内存策略同PrimitiveList（比如Uint8List之类），见那边的注释
             format!(
@@ -266,6 +886,13 @@ fn generate_api2wire_func(ty: &ApiType) -> String {
             Primitive(_) => {
                 format!("return inner.new_{}(raw);", ty.safe_ident())
             }
+            inner @ (EnumRef(_) | Delegate(_)) if b.is_primitive() => {
+                format!(
+                    "return inner.new_{}(_api2wire_{}(raw));",
+                    ty.safe_ident(),
+                    inner.safe_ident()
+                )
+            }
             inner => {
                 format!(
                     "final ptr = inner.new_{}();
@@ -276,6 +903,55 @@ fn generate_api2wire_func(ty: &ApiType) -> String {
                 )
             }
         },
+        EnumRef(e) => {
+            let e = e.get(api_file);
+            let check_unknown = if e.is_non_exhaustive {
+                format!(
+                    "if (raw == {}.{}) throw ArgumentError.value(raw, 'raw', 'cannot be passed to Rust');\n",
+                    e.name,
+                    ApiEnum::UNKNOWN_VARIANT_DART_NAME
+                )
+            } else {
+                "".to_string()
+            };
+            if e.is_enum_class {
+                format!("{}return raw.toRust();", check_unknown)
+            } else {
+                format!("{}return raw.index;", check_unknown)
+            }
+        }
+        Map(map) => generate_api2wire_key_value_body(ty, &map.key, &map.value),
+        // Dart maps have no duplicate keys, so the Rust list has none either
+        AssocList(list) => generate_api2wire_key_value_body(ty, &list.key, &list.value),
+        Tuple(tuple) => format!(
+            "final ans = inner.new_{}();
+            {}
+            return ans;",
+            ty.safe_ident(),
+            tuple
+                .elements
+                .iter()
+                .zip(tuple.field_names())
+                .map(|(element, name)| match element {
+                    StructRef(_) => format!(
+                        "_api_fill_to_wire_{}(raw.{}, ans.ref.{});",
+                        element.safe_ident(),
+                        name,
+                        name
+                    ),
+                    _ => format!(
+                        "ans.ref.{} = _api2wire_{}(raw.{});",
+                        name,
+                        element.safe_ident(),
+                        name
+                    ),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+        OpaqueWeak(_) => "return raw.address;".to_string(),
+        Opaque(_) => "return raw.move();".to_string(),
+        Callback(callback) => generate_api2wire_callback(callback),
         // skip
         StructRef(_) => return "".to_string(),
     };
@@ -292,6 +968,64 @@ fn generate_api2wire_func(ty: &ApiType) -> String {
     )
 }
 
+/// Fills the keys and values of a map or an association list from the entries of a Dart map
+fn generate_api2wire_key_value_body(ty: &ApiType, key: &ApiType, value: &ApiType) -> String {
+    let assign = |ty: &ApiType, wire_field: &str, api_field: &str| match ty {
+        StructRef(_) => format!(
+            "_api_fill_to_wire_{}(entry.{}, ans.ref.{}[i]);",
+            ty.safe_ident(),
+            api_field,
+            wire_field
+        ),
+        _ => format!(
+            "ans.ref.{}[i] = _api2wire_{}(entry.{});",
+            wire_field,
+            ty.safe_ident(),
+            api_field
+        ),
+    };
+    format!(
+        "final ans = inner.new_{}(raw.length);
+        var i = 0;
+        for (final entry in raw.entries) {{
+            {}
+            {}
+            ++i;
+        }}
+        return ans;",
+        ty.safe_ident(),
+        assign(key, "keys", "key"),
+        assign(value, "values", "value"),
+    )
+}
+
+/// Listens to the calls of the Rust closure, see `frbListenCallback`
+fn generate_api2wire_callback(callback: &ApiTypeCallback) -> String {
+    let args = callback
+        .args
+        .iter()
+        .enumerate()
+        .map(|(idx, arg)| format!("_wire2api_{}(args[{}])", arg.safe_ident(), idx))
+        .collect::<Vec<_>>();
+    format!(
+        "{}return frbListenCallback((args) => raw({}){});",
+        if callback.is_optional {
+            "if (raw == null) return 0;\n"
+        } else {
+            ""
+        },
+        args.join(", "),
+        match callback.reply_func_name() {
+            Some(reply_func_name) => format!(
+                ", reply: inner.{}, reject: inner.{}",
+                reply_func_name,
+                ApiTypeCallback::REJECT_FUNC_NAME
+            ),
+            None => "".to_string(),
+        }
+    )
+}
+
 fn generate_api_fill_to_wire_func(ty: &ApiType, api_file: &ApiFile) -> String {
     let body = match &ty {
         StructRef(s) => {
@@ -303,7 +1037,7 @@ fn generate_api_fill_to_wire_func(ty: &ApiType, api_file: &ApiFile) -> String {
                         "wireObj.{} = _api2wire_{}(apiObj.{});",
                         field.name.rust_style(),
                         field.ty.safe_ident(),
-                        field.name.dart_style()
+                        field.dart_name()
                     )
                 })
                 .collect::<Vec<_>>()
@@ -318,11 +1052,12 @@ fn generate_api_fill_to_wire_func(ty: &ApiType, api_file: &ApiFile) -> String {
                 opt.inner.safe_ident()
             )
         }
-        Boxed(boxed) if !matches!(boxed.inner, Primitive(_)) => format!(
+        Boxed(boxed) if !boxed.is_primitive() => format!(
             " _api_fill_to_wire_{}(apiObj, wireObj.ref);",
             boxed.inner.safe_ident()
         ),
-        Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | Boxed(_) => {
+        Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | AssocList(_) | Map(_)
+        | Tuple(_) | EnumRef(_) | Boxed(_) | OpaqueWeak(_) | Opaque(_) | Callback(_) => {
             return "".to_string();
         }
     };
@@ -346,9 +1081,62 @@ fn generate_wire2api_func(ty: &ApiType, api_file: &ApiFile) -> String {
         Delegate(d) => match d {
             ApiTypeDelegate::String
             | ApiTypeDelegate::SyncReturnVecU8
-            | ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
-                gen_simple_type_cast(&d.dart_api_type())
-            }
+            | ApiTypeDelegate::SyncReturnString
+            | ApiTypeDelegate::NonNull(_)
+            | ApiTypeDelegate::DynError(_)
+            | ApiTypeDelegate::CowBytes
+            | ApiTypeDelegate::CowStr
+            | ApiTypeDelegate::StringRef
+            | ApiTypeDelegate::SliceU8 => gen_simple_type_cast(&d.dart_api_type()),
+            ApiTypeDelegate::ZeroCopyBufferVec(inner) => match &**inner {
+                Primitive(_) => gen_simple_type_cast(&d.dart_api_type()),
+                inner => format!(
+                    "final arr = raw as List<dynamic>;
+                    return frbSplitZeroCopyFrames(arr[0] as Uint64List, arr[1] as {list}, {list}.sublistView);",
+                    list = inner.dart_api_type()
+                ),
+            },
+            ApiTypeDelegate::ZeroCopyBufferVecString => "final arr = raw as List<dynamic>;
+                return frbSplitZeroCopyFrames(arr[0] as Uint64List, arr[1] as Uint8List, Uint8List.sublistView)
+                    .map(utf8.decode)
+                    .toList();"
+                .to_string(),
+            ApiTypeDelegate::Range(_, inclusive) => format!(
+                "final arr = raw as {};
+                return FrbRange(arr[0], arr[1], inclusive: {});",
+                d.get_delegate().dart_api_type(),
+                inclusive
+            ),
+            ApiTypeDelegate::I128 | ApiTypeDelegate::U128 => format!(
+                "final arr = raw as Int64List;
+                return {}(arr[0], arr[1]);",
+                d.dart_api_type()
+            ),
+            ApiTypeDelegate::Array(array) => format!(
+                "final arr = _wire2api_{}(raw);
+                if (arr.length != {}) throw Exception('unexpected arr length: expect {} but see ${{arr.length}}');
+                return arr;",
+                d.get_delegate().safe_ident(),
+                array.length,
+                array.length
+            ),
+            ApiTypeDelegate::Set(_) => format!(
+                "return _wire2api_{}(raw).toSet();",
+                d.get_delegate().safe_ident()
+            ),
+            ApiTypeDelegate::Chrono(chrono) => format!(
+                "return DateTime.fromMillisecondsSinceEpoch(raw as int, isUtc: {});",
+                !matches!(chrono, ApiTypeChrono::Local)
+            ),
+            ApiTypeDelegate::Uuid(as_string) => format!(
+                "return {}(_wire2api_{}(raw));",
+                if *as_string {
+                    "frbUuidFromBytes"
+                } else {
+                    "UuidValue.fromByteList"
+                },
+                d.get_delegate().safe_ident()
+            ),
         },
         Optional(opt) => format!(
             "return raw == null ? null : _wire2api_{}(raw);",
@@ -359,36 +1147,138 @@ fn generate_wire2api_func(ty: &ApiType, api_file: &ApiFile) -> String {
             "return (raw as List<dynamic>).map(_wire2api_{}).toList();",
             list.inner.safe_ident()
         ),
-        StructRef(s_ref) => {
-            let s = s_ref.get(api_file);
-            let inner = s
-                .fields
+        // the last value of duplicate keys is kept, see the warning of the Rust generator
+        AssocList(list) => format!(
+            "final arr = raw as List<dynamic>;
+            return Map.fromEntries([
+                for (var i = 0; i < arr.length; i += 2)
+                    MapEntry(_wire2api_{}(arr[i]), _wire2api_{}(arr[i + 1])),
+            ]);",
+            list.key.safe_ident(),
+            list.value.safe_ident()
+        ),
+        Map(map) => format!(
+            "final arr = raw as List<dynamic>;
+            return {{
+                for (var i = 0; i < arr.length; i += 2)
+                    _wire2api_{}(arr[i]): _wire2api_{}(arr[i + 1]),
+            }};",
+            map.key.safe_ident(),
+            map.value.safe_ident()
+        ),
+        Tuple(tuple) => format!(
+            "final arr = raw as List<dynamic>;
+            if (arr.length != {}) throw Exception('unexpected arr length: expect {} but see ${{arr.length}}');
+            return {}({});",
+            tuple.elements.len(),
+            tuple.elements.len(),
+            ty.dart_api_type(),
+            tuple
+                .elements
                 .iter()
                 .enumerate()
-                .map(|(idx, field)| {
-                    format!(
-                        "{}: _wire2api_{}(arr[{}]),",
-                        field.name.dart_style(),
-                        field.ty.safe_ident(),
-                        idx
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join("\n");
+                .map(|(idx, element)| format!("_wire2api_{}(arr[{}]),", element.safe_ident(), idx))
+                .collect::<String>()
+        ),
+        StructRef(s_ref) => {
+            let s = s_ref.get(api_file);
+            // the other struct may only be an input, which has no `_wire2api_` to share
+            let alias_of_output = api_file.struct_dart_aliases.get(&s.name).and_then(|alias_of| {
+                api_file
+                    .distinct_types(false, true)
+                    .into_iter()
+                    .find(|ty| matches!(ty, StructRef(other) if &other.name == alias_of))
+            });
+            if let Some(alias_of) = alias_of_output {
+                format!("return _wire2api_{}(raw);", alias_of.safe_ident())
+            } else {
+                let inner = s
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, field)| {
+                        format!(
+                            "{}: _wire2api_{}(arr[{}]),",
+                            field.dart_name(),
+                            field.ty.safe_ident(),
+                            idx
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
 
-            format!(
-                "final arr = raw as List<dynamic>;
-                if (arr.length != {}) throw Exception('unexpected arr length: expect {} but see ${{arr.length}}');
-                return {}({});",
-                s.fields.len(),
-                s.fields.len(),
-                s.name, inner,
-            )
+                format!(
+                    "final arr = raw as List<dynamic>;
+                    if (arr.length != {}) throw Exception('unexpected arr length: expect {} but see ${{arr.length}}');
+                    return {}({});",
+                    s.fields.len(),
+                    s.fields.len(),
+                    s.name, inner,
+                )
+            }
         }
-        Boxed(boxed) => match &boxed.inner {
+        EnumRef(e_ref) => {
+            let e = e_ref.get(api_file);
+            if e.is_struct() {
+                let variants = e
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, variant)| {
+                        let fields = match &variant.kind {
+                            ApiVariantKind::Value => vec![],
+                            ApiVariantKind::Struct(st) => st
+                                .fields
+                                .iter()
+                                .enumerate()
+                                .map(|(field_idx, field)| {
+                                    format!(
+                                        "{}: _wire2api_{}(arr[{}]),",
+                                        field.dart_name(),
+                                        field.ty.safe_ident(),
+                                        field_idx + 1
+                                    )
+                                })
+                                .collect(),
+                        };
+                        format!(
+                            "case {}: return {}({});",
+                            idx,
+                            variant.dart_class_name(&e.name),
+                            fields.join("")
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!(
+                    "final arr = raw as List<dynamic>;
+                    switch (arr[0]) {{
+                        {}
+                        default: throw Exception('unexpected variant of {}: ${{arr[0]}}');
+                    }}",
+                    variants, e.name
+                )
+            } else if e.is_non_exhaustive {
+                format!(
+                    "return frbNonExhaustiveEnumValue({}.values, raw as int, {}.{});",
+                    e.name,
+                    e.name,
+                    ApiEnum::UNKNOWN_VARIANT_DART_NAME
+                )
+            } else {
+                format!("return {}.values[raw as int];", e.name)
+            }
+        }
+        Boxed(boxed) => match &boxed.inner {
             StructRef(inner) => format!("return _wire2api_{}(raw);", inner.safe_ident()),
+            EnumRef(inner) => format!("return _wire2api_{}(raw);", inner.safe_ident()),
+            Delegate(inner) => format!("return _wire2api_{}(raw);", inner.safe_ident()),
             _ => gen_simple_type_cast(&ty.dart_api_type()),
         },
+        // see [generate_wire2api_weak_func] and [generate_wire2api_opaque_func]
+        OpaqueWeak(_) | Opaque(_) => return "".to_string(),
+        // only supported as input
+        Callback(_) => return "".to_string(),
     };
 
     format!(
@@ -410,7 +1300,52 @@ fn dart_comments(comments: &[Comment]) -> String {
         .join("\n")
 }
 
-fn generate_api_struct(s: &ApiStruct) -> String {
+/// The doc comments followed by `@Deprecated(...)` if deprecated, see [ApiStruct::deprecated]
+fn dart_comments_and_deprecated(comments: &[Comment], deprecated: &Option<String>) -> String {
+    let annotation = deprecated.as_ref().map(|message| {
+        if message.is_empty() {
+            "@deprecated".to_string()
+        } else {
+            format!(
+                "@Deprecated('{}')",
+                message
+                    .replace('\\', "\\\\")
+                    .replace('\'', "\\'")
+                    .replace('$', "\\$")
+                    .replace('\n', "\\n")
+            )
+        }
+    });
+    comments
+        .iter()
+        .map(Comment::comment)
+        .chain(annotation.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn generate_api_struct(
+    s: &ApiStruct,
+    api_file: &ApiFile,
+    dart_api_class_name: &str,
+    dart_api_impl_class_name: &str,
+    equality: bool,
+) -> String {
+    if let Some(alias_of) = api_file.struct_dart_aliases.get(&s.name) {
+        return format!(
+            "{}
+            typedef {} = {};",
+            dart_comments_and_deprecated(&s.comments, &s.deprecated),
+            s.name,
+            alias_of,
+        );
+    }
+
+    let methods = generate_api_struct_methods(s, api_file, dart_api_class_name);
+    if s.notify_on_change {
+        return generate_api_struct_change_notifier(s, &methods, dart_api_impl_class_name);
+    }
+
     let field_declarations = s
         .fields
         .iter()
@@ -418,9 +1353,9 @@ fn generate_api_struct(s: &ApiStruct) -> String {
             format!(
                 "{}
                 final {} {};",
-                dart_comments(&f.comments),
+                dart_comments_and_deprecated(&f.comments, &f.deprecated),
                 f.ty.dart_api_type(),
-                f.name.dart_style()
+                f.dart_name()
             )
         })
         .collect::<Vec<_>>()
@@ -429,19 +1364,1382 @@ fn generate_api_struct(s: &ApiStruct) -> String {
     let constructor_params = s
         .fields
         .iter()
-        .map(|f| format!("{}this.{},", f.ty.required_modifier(), f.name.dart_style()))
+        .map(|f| dart_constructor_param(f, &format!("this.{}", f.dart_name())))
         .collect::<Vec<_>>()
         .join("");
 
-    let comments = dart_comments(&s.comments);
+    let comments = dart_comments_and_deprecated(&s.comments, &s.deprecated);
 
     format!(
         "{}
         class {} {{
             {}
 
+            {}{}
+
             {}({{{}}});
+
+            {}
+
+            {}
+        }}",
+        comments,
+        s.name,
+        generate_api_struct_consts(s),
+        field_declarations,
+        generate_api_struct_private_fields_note(s),
+        s.name,
+        constructor_params,
+        if equality {
+            generate_api_struct_equality(s)
+        } else {
+            "".to_string()
+        },
+        methods,
+    )
+}
+
+/// Fields printed by the generated `toString`, which ends with `...` for structs with more
+const DART_TO_STRING_MAX_FIELDS: usize = 10;
+
+/// `==` and `hashCode` of the fields, comparing lists by their elements, and `toString` of the
+/// first [DART_TO_STRING_MAX_FIELDS] fields, unless `--no-dart-equality`. Private fields are
+/// not in Dart, so they are ignored.
+fn generate_api_struct_equality(s: &ApiStruct) -> String {
+    let mut comparisons = vec![format!("other is {}", s.name)];
+    let mut hashed = Vec::new();
+    for field in &s.fields {
+        let name = field.dart_name();
+        if is_dart_list(&field.ty) {
+            comparisons.push(format!("frbListEquals(other.{}, {})", name, name));
+            hashed.push(format!("frbListHash({})", name));
+        } else {
+            comparisons.push(format!("other.{} == {}", name, name));
+            hashed.push(name);
+        }
+    }
+
+    let mut printed: Vec<_> = s
+        .fields
+        .iter()
+        .take(DART_TO_STRING_MAX_FIELDS)
+        .map(|field| format!("{name}: ${name}", name = field.dart_name()))
+        .collect();
+    if s.fields.len() > DART_TO_STRING_MAX_FIELDS {
+        printed.push("...".to_string());
+    }
+
+    format!(
+        "@override
+        bool operator ==(Object other) => {};
+
+        @override
+        int get hashCode => Object.hashAll([{}]);
+
+        @override
+        String toString() => '{}({})';",
+        comparisons.join(" && "),
+        hashed.join(", "),
+        s.name,
+        printed.join(", "),
+    )
+}
+
+/// Whether the Dart type of [ty] is a [List], including typed data such as `Uint8List`, whose `==`
+/// compares identities
+fn is_dart_list(ty: &ApiType) -> bool {
+    match ty {
+        PrimitiveList(_) | GeneralList(_) => true,
+        Delegate(delegate) => matches!(
+            delegate,
+            ApiTypeDelegate::SyncReturnVecU8
+                | ApiTypeDelegate::ZeroCopyBufferVec(_)
+                | ApiTypeDelegate::ZeroCopyBufferVecString
+                | ApiTypeDelegate::CowBytes
+                | ApiTypeDelegate::SliceU8
+                | ApiTypeDelegate::Array(_)
+        ),
+        Optional(optional) => is_dart_list(&optional.inner),
+        Boxed(boxed) => is_dart_list(&boxed.inner),
+        _ => false,
+    }
+}
+
+/// e.g. `required this.name,`, or `this.count = 1,` with `#[frb(default = 1)]`
+fn dart_constructor_param(field: &ApiField, param: &str) -> String {
+    match &field.default {
+        Some(default) => format!("{} = {},", param, default),
+        None => format!("{}{},", field.ty.required_modifier(), param),
+    }
+}
+
+/// Whether the enum is the `E` of a function returning `Result<T, E>`, or of a
+/// `StreamSink<Result<T, E>>`
+fn is_error_enum(api_file: &ApiFile, name: &str) -> bool {
+    api_file.funcs.iter().any(|func| {
+        matches!(&func.error_output, Some(ApiFuncError::Custom(EnumRef(e))) if e.name == name)
+            || matches!(&func.stream_error_output, Some(EnumRef(e)) if e.name == name)
+    })
+}
+
+/// Error enums are thrown as is, so the classes of those with fields implement [Exception]. Plain
+/// enums cannot implement anything before Dart 2.17, and are thrown as the enum values themselves.
+fn generate_api_enum(e: &ApiEnum, dart_enum_style: DartEnumStyle, is_error: bool) -> String {
+    if e.is_struct() {
+        let implements = if is_error {
+            " implements Exception"
+        } else {
+            ""
+        };
+        return match dart_enum_style {
+            DartEnumStyle::Sealed => generate_api_enum_sealed(e, implements),
+            DartEnumStyle::Freezed => generate_api_enum_freezed(e, implements),
+        };
+    }
+
+    let variants = e
+        .variants
+        .iter()
+        .map(|variant| {
+            format!(
+                "{}
+                {},",
+                dart_comments(&variant.comments),
+                variant.name.dart_style()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let unknown_variant = if e.is_non_exhaustive {
+        format!(
+            "
+            /// A variant unknown to this version of the bindings, since the Rust enum is `#[non_exhaustive]`
+            {},",
+            ApiEnum::UNKNOWN_VARIANT_DART_NAME
+        )
+    } else {
+        "".to_string()
+    };
+
+    // enums with members need Dart 2.17, see `check_dart_sdk_version`
+    let members = if e.is_enum_class {
+        ";
+
+        int toRust() => index;"
+    } else {
+        ""
+    };
+
+    format!(
+        "{}
+        enum {} {{
+            {}{}{}
+        }}",
+        dart_comments(&e.comments),
+        e.name,
+        variants,
+        unknown_variant,
+        members,
+    )
+}
+
+/// An abstract class with a subclass per variant, which cannot be extended by other libraries
+fn generate_api_enum_sealed(e: &ApiEnum, implements: &str) -> String {
+    let subclasses = e
+        .variants
+        .iter()
+        .map(|variant| {
+            let class_name = variant.dart_class_name(&e.name);
+            let fields = match &variant.kind {
+                ApiVariantKind::Value => &[][..],
+                ApiVariantKind::Struct(st) => &st.fields[..],
+            };
+            let field_declarations = fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{}
+                        final {} {};",
+                        dart_comments_and_deprecated(&f.comments, &f.deprecated),
+                        f.ty.dart_api_type(),
+                        f.dart_name()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let constructor_params = fields
+                .iter()
+                .map(|f| format!("{}this.{},", f.ty.required_modifier(), f.dart_name()))
+                .collect::<Vec<_>>()
+                .join("");
+            format!(
+                "{}
+                class {} extends {} {{
+                    {}
+
+                    {}({}) : super._();
+                }}",
+                dart_comments(&variant.comments),
+                class_name,
+                e.name,
+                field_declarations,
+                class_name,
+                if fields.is_empty() {
+                    "".to_string()
+                } else {
+                    format!("{{{}}}", constructor_params)
+                },
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        "{}
+        abstract class {}{} {{
+            {}._();
+        }}
+
+        {}",
+        dart_comments(&e.comments),
+        e.name,
+        implements,
+        e.name,
+        subclasses,
+    )
+}
+
+/// A union of the `freezed` package, whose variants are generated by `build_runner`
+fn generate_api_enum_freezed(e: &ApiEnum, implements: &str) -> String {
+    let factories = e
+        .variants
+        .iter()
+        .map(|variant| {
+            let params = match &variant.kind {
+                ApiVariantKind::Value => "".to_string(),
+                ApiVariantKind::Struct(st) => format!(
+                    "{{{}}}",
+                    st.fields
+                        .iter()
+                        .map(|f| format!(
+                            "{}{} {},",
+                            f.ty.required_modifier(),
+                            f.ty.dart_api_type(),
+                            f.dart_name()
+                        ))
+                        .collect::<Vec<_>>()
+                        .join("")
+                ),
+            };
+            format!(
+                "{}
+                const factory {}.{}({}) = {};",
+                dart_comments(&variant.comments),
+                e.name,
+                variant.name.dart_style(),
+                params,
+                variant.dart_class_name(&e.name),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{}
+        @freezed
+        class {} with _${}{} {{
+            {}
+        }}",
+        dart_comments(&e.comments),
+        e.name,
+        e.name,
+        implements,
+        factories,
+    )
+}
+
+fn generate_api_struct_private_fields_note(s: &ApiStruct) -> String {
+    if s.has_private_fields() {
+        format!("\n// {} private fields omitted", s.num_private_fields)
+    } else {
+        "".to_string()
+    }
+}
+
+fn generate_api_struct_consts(s: &ApiStruct) -> String {
+    s.associated_consts
+        .iter()
+        .map(|c| {
+            format!(
+                "{}
+                static const {} {} = {};",
+                dart_comments(&c.comments),
+                c.ty.dart_api_type(),
+                c.name.dart_style(),
+                c.value
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Methods of [s] on the Dart class, which forward to the functions of the bridge given as the
+/// first argument, e.g. `point.norm(api)` for `api.pointNorm(that: point)`
+fn generate_api_struct_methods(
+    s: &ApiStruct,
+    api_file: &ApiFile,
+    dart_api_class_name: &str,
+) -> String {
+    api_file
+        .funcs
+        .iter()
+        .filter_map(|func| match &func.method {
+            Some(method) if method.struct_name == s.name => Some((func, method)),
+            _ => None,
+        })
+        .map(|(func, method)| {
+            let inputs = &func.inputs[if method.receiver.is_some() { 1 } else { 0 }..];
+            let params = inputs
+                .iter()
+                .map(|input| {
+                    format!(
+                        "{}{} {},",
+                        input.ty.required_modifier(),
+                        dart_param_type(input),
+                        input.name.dart_style()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("");
+            let args = method
+                .receiver
+                .map(|_| format!("{}: this", ApiMethod::RECEIVER_NAME))
+                .into_iter()
+                .chain(inputs.iter().map(|input| {
+                    format!("{}: {}", input.name.dart_style(), input.name.dart_style())
+                }))
+                .chain(std::iter::once("hint: hint".to_string()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "{}
+                {}{} {}({} bridge, {{ {}dynamic hint }}) => bridge.{}({});",
+                dart_comments_and_deprecated(&func.comments, &func.deprecated),
+                if method.receiver.is_some() {
+                    ""
+                } else {
+                    "static "
+                },
+                dart_return_type(func),
+                method.dart_method_name(),
+                dart_api_class_name,
+                params,
+                func.dart_name(),
+                args,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Objects received from Rust hold the handle of a Rust copy, which each setter updates, until
+/// they are disposed. Objects created in Dart only notify listeners.
+fn generate_api_struct_change_notifier(
+    s: &ApiStruct,
+    methods: &str,
+    dart_api_impl_class_name: &str,
+) -> String {
+    let callback_call = s
+        .on_change_callback
+        .as_ref()
+        .map_or("".to_string(), |callback| format!("{}?.call();", callback));
+    let field_declarations = s
+        .fields
+        .iter()
+        .map(|f| {
+            format!(
+                "{comments}
+                {ty} _{name};
+                {ty} get {name} => _{name};
+                set {name}({ty} value) {{
+                    _{name} = value;
+                    final handle = _rustHandle;
+                    if (handle != null) _rustBridge!.inner.{setter}(handle, _rustBridge!._api2wire_{ident}(value));
+                    notifyListeners();
+                    {callback_call}
+                }}",
+                comments = dart_comments_and_deprecated(&f.comments, &f.deprecated),
+                ty = f.ty.dart_api_type(),
+                name = f.dart_name(),
+                setter = s.on_change_setter_func_name(f),
+                ident = f.ty.safe_ident(),
+                callback_call = callback_call,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let callback_declaration = s
+        .on_change_callback
+        .as_ref()
+        .map_or("".to_string(), |callback| {
+            format!(
+                "/// Called after each change, like the listeners.
+            void Function()? {};",
+                callback
+            )
+        });
+
+    let constructor_params = s
+        .fields
+        .iter()
+        .map(|f| dart_constructor_param(f, &format!("{} {}", f.ty.dart_api_type(), f.dart_name())))
+        .collect::<Vec<_>>()
+        .join("");
+    let callback_param = s
+        .on_change_callback
+        .as_ref()
+        .map_or("".to_string(), |callback| format!("this.{},", callback));
+
+    let field_initializers = s
+        .fields
+        .iter()
+        .map(|f| format!(", _{} = {}", f.dart_name(), f.dart_name()))
+        .collect::<String>();
+
+    let comments = dart_comments_and_deprecated(&s.comments, &s.deprecated);
+
+    format!(
+        "{comments}
+        class {name} extends ChangeNotifier {{
+            {consts}
+
+            final {bridge}? _rustBridge;
+            int? _rustHandle;
+
+            {callback_declaration}
+
+            {field_declarations}{private_fields_note}
+
+            {name}({{{constructor_params}{callback_param}}}) : _rustBridge = null, _rustHandle = null{field_initializers};
+
+            {name}._fromRust({bridge} rustBridge, int rustHandle, {{{constructor_params}}}) : _rustBridge = rustBridge, _rustHandle = rustHandle{field_initializers};
+
+            /// Also drops the Rust copy of an object received from Rust.
+            @override
+            void dispose() {{
+                final handle = _rustHandle;
+                _rustHandle = null;
+                if (handle != null) _rustBridge!.inner.{drop}(handle);
+                super.dispose();
+            }}
+
+            {methods}
         }}",
-        comments, s.name, field_declarations, s.name, constructor_params
+        comments = comments,
+        name = s.name,
+        consts = generate_api_struct_consts(s),
+        bridge = dart_api_impl_class_name,
+        callback_declaration = callback_declaration,
+        field_declarations = field_declarations,
+        private_fields_note = generate_api_struct_private_fields_note(s),
+        constructor_params = constructor_params,
+        callback_param = callback_param,
+        field_initializers = field_initializers,
+        drop = s.on_change_drop_func_name(),
+        methods = methods,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser, transformer};
+
+    fn generate_output(source: &str, dart_isolate_safe: bool, dedup_structs: bool) -> Output {
+        let api_file = transformer::transform(parser::parse_source(source), dedup_structs);
+        generate(
+            &api_file,
+            "Api",
+            "ApiImpl",
+            "ApiWire",
+            DartCodeOpts {
+                dart_enum_style: DartEnumStyle::Sealed,
+                dart_isolate_safe,
+                no_dart_equality: false,
+                no_finalizer: false,
+            },
+            "bridge_generated.dart",
+        )
+    }
+
+    fn generate_api_class(source: &str, dart_isolate_safe: bool) -> String {
+        generate_output(source, dart_isolate_safe, true).api_class
+    }
+
+    #[test]
+    fn sorts_and_dedups_directives() {
+        let imports = [
+            "import 'package:uuid/uuid.dart';",
+            "import 'dart:ffi' as ffi;",
+            "import 'package:ffi/ffi.dart';",
+            "import 'models.dart';",
+            "import 'dart:convert';",
+            "import 'dart:ffi' as ffi;",
+            "",
+        ]
+        .map(str::to_string);
+        let parts = ["part 'bridge_generated.freezed.dart';".to_string()];
+        assert_eq!(
+            generate_directives("my_api", &imports, &parts),
+            "library my_api;
+
+import 'dart:convert';
+import 'dart:ffi' as ffi;
+
+import 'package:ffi/ffi.dart';
+import 'package:uuid/uuid.dart';
+
+import 'models.dart';
+
+part 'bridge_generated.freezed.dart';
+"
+        );
+    }
+
+    #[test]
+    fn impl_part_has_the_same_ignores_as_the_library() {
+        let header = generate_impl_part_header("../bridge_generated.dart");
+        assert!(header.starts_with(CODE_HEADER));
+        // the wire structs of ffigen, e.g. `wire_Point`, are in the part file as well
+        assert!(header.contains(DART_IGNORE_FOR_FILE));
+        assert!(DART_IGNORE_FOR_FILE.contains("camel_case_types"));
+        assert!(header.contains("part of '../bridge_generated.dart';"));
+
+        let output = generate_output("pub fn answer() -> Result<i32> { Ok(42) }", false, true);
+        assert!(output.header.contains(DART_IGNORE_FOR_FILE));
+    }
+
+    #[test]
+    fn forwards_doc_comments() {
+        let api_class = generate_api_class(
+            "
+            /// Adds two numbers.
+            ///
+            /// Overflows wrap around.
+            pub fn add(a: i32, b: i32) -> Result<i32> {
+                Ok(a.wrapping_add(b))
+            }
+
+            /**
+                Subtracts two numbers.
+
+                    b - a
+            */
+            pub fn sub(a: i32, b: i32) -> Result<i32> {
+                Ok(b - a)
+            }
+            ",
+            false,
+        );
+        assert!(api_class.contains(
+            "/// Adds two numbers.
+///
+/// Overflows wrap around.
+Future<int> add("
+        ));
+        assert!(api_class.contains(
+            "///
+/// Subtracts two numbers.
+///
+///     b - a
+///
+Future<int> sub("
+        ));
+    }
+
+    #[test]
+    fn forwards_deprecated_attributes() {
+        let api_class = generate_api_class(
+            "
+            /// Adds two numbers.
+            #[deprecated(since = \"1.2\", note = \"use add_checked instead\")]
+            pub fn add(a: i32, b: i32) -> Result<i32> {
+                Ok(a.wrapping_add(b))
+            }
+
+            #[deprecated = \"it's slow\"]
+            pub fn sub(a: i32, b: i32) -> Result<i32> {
+                Ok(b - a)
+            }
+
+            #[deprecated]
+            pub fn mul(a: i32, b: i32) -> Result<i32> {
+                Ok(a * b)
+            }
+            ",
+            false,
+        );
+        assert!(api_class.contains(
+            "/// Adds two numbers.
+@Deprecated('since 1.2: use add_checked instead')
+Future<int> add("
+        ));
+        assert!(api_class.contains("@Deprecated('it\\'s slow')\nFuture<int> sub("));
+        assert!(api_class.contains("@deprecated\nFuture<int> mul("));
+    }
+
+    #[test]
+    fn generates_nested_lists() {
+        let output = generate_output(
+            "
+            pub fn nested(
+                ints: Vec<Vec<i32>>,
+                strings: Vec<Vec<String>>,
+                optional_ints: Vec<Option<Vec<i32>>>,
+            ) -> Result<Vec<Option<Vec<i32>>>> {
+                Ok(optional_ints)
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains(
+            "Future<List<Int32List?>> nested({ required List<Int32List> ints,\
+            required List<List<String>> strings,required List<Int32List?> optionalInts,dynamic hint });"
+        ));
+        for (api2wire, element) in [
+            (
+                "_api2wire_list_int_32_list(List<Int32List> raw)",
+                "_api2wire_int_32_list(raw[i])",
+            ),
+            (
+                "_api2wire_list_list_String(List<List<String>> raw)",
+                "_api2wire_list_String(raw[i])",
+            ),
+            (
+                "_api2wire_list_opt_int_32_list(List<Int32List?> raw)",
+                "_api2wire_opt_int_32_list(raw[i])",
+            ),
+        ] {
+            assert!(code.contains(api2wire));
+            assert!(code.contains(&format!("ans.ref.ptr[i] = {};", element)));
+        }
+        assert!(code.contains("List<Int32List?> _wire2api_list_opt_int_32_list(dynamic raw) {"));
+        assert!(
+            code.contains("return (raw as List<dynamic>).map(_wire2api_opt_int_32_list).toList();")
+        );
+    }
+
+    #[test]
+    fn emits_associated_consts_verbatim() {
+        let output = generate_output(
+            r#"
+            pub struct Buffer {
+                pub len: u32,
+            }
+
+            impl Buffer {
+                /// Largest buffer in bytes
+                pub const MAX_SIZE: u32 = 1024u32;
+                pub const MIN_GAIN: f64 = -0.5;
+                pub const ENABLED: bool = true;
+                pub const NAME: &'static str = "it's $1";
+                const PRIVATE: u32 = 1;
+            }
+
+            pub fn make_buffer() -> Result<Buffer> {
+                Ok(Buffer { len: 0 })
+            }
+            "#,
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains("/// Largest buffer in bytes\n"));
+        for constant in [
+            "static const int maxSize = 1024;",
+            "static const double minGain = -0.5;",
+            "static const bool enabled = true;",
+            r"static const String name = 'it\'s \$1';",
+        ] {
+            assert!(code.contains(constant), "missing {}", constant);
+        }
+        assert!(!code.contains("static const int private"));
+    }
+
+    #[test]
+    fn throws_when_functions_marked_throw_on_null_output_return_none() {
+        let output = generate_output(
+            "
+            #[frb(throw_on_null_output)]
+            pub fn find_user(id: u32) -> Result<Option<String>> {
+                Ok(None)
+            }
+
+            pub fn find_pet(id: u32) -> Result<Option<String>> {
+                Ok(None)
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains("Future<String> findUser({ required int id,dynamic hint });"));
+        assert!(code.contains(
+            "parseSuccessData: (raw) => _wire2api_opt_String(raw) ?? (throw const FrbNullException('find_user returned null')),"
+        ));
+        assert!(code.contains("Future<String?> findPet({ required int id,dynamic hint });"));
+        assert!(code.contains("parseSuccessData: _wire2api_opt_String,"));
+        assert_eq!(code.matches("FrbNullException").count(), 1);
+    }
+
+    #[test]
+    fn passes_maps_as_assoc_lists() {
+        let output = generate_output(
+            "
+            pub fn total(counts: Vec<(String, u32)>) -> Result<u32> {
+                Ok(counts.iter().map(|(_, count)| count).sum())
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(
+            code.contains("Future<int> total({ required Map<String, int> counts,dynamic hint });")
+        );
+        assert!(code.contains(
+            "ffi.Pointer<wire_assoc_list_String_u32> _api2wire_assoc_list_String_u32(Map<String, int> raw) {"
+        ));
+        assert!(code.contains("final ans = inner.new_assoc_list_String_u32(raw.length);"));
+        assert!(code.contains("ans.ref.keys[i] = _api2wire_String(entry.key);"));
+        assert!(code.contains("ans.ref.values[i] = _api2wire_u32(entry.value);"));
+    }
+
+    #[test]
+    fn receives_assoc_lists_as_maps() {
+        let output = generate_output(
+            "
+            pub fn word_counts(text: String) -> Result<Vec<(String, u32)>> {
+                let mut counts: Vec<(String, u32)> = Vec::new();
+                for word in text.split_whitespace() {
+                    match counts.iter_mut().find(|(known, _)| known == word) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((word.to_string(), 1)),
+                    }
+                }
+                Ok(counts)
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains(
+            "Future<Map<String, int>> wordCounts({ required String text,dynamic hint });"
+        ));
+        assert!(code.contains("parseSuccessData: _wire2api_assoc_list_String_u32,"));
+        assert!(code.contains("Map<String, int> _wire2api_assoc_list_String_u32(dynamic raw) {"));
+        assert!(code.contains("return Map.fromEntries(["));
+        assert!(code.contains("for (var i = 0; i < arr.length; i += 2)"));
+        assert!(code.contains("MapEntry(_wire2api_String(arr[i]), _wire2api_u32(arr[i + 1])),"));
+    }
+
+    #[test]
+    fn sends_ranges_as_start_and_end() {
+        let output = generate_output(
+            "
+            use std::ops::Range;
+
+            pub fn clamp_values(data: Vec<f64>, range: Range<f64>) -> Result<Vec<f64>> {
+                Ok(data
+                    .into_iter()
+                    .map(|value| value.max(range.start).min(range.end))
+                    .collect())
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains(
+            "Future<Float64List> clampValues({ required Float64List data,required FrbRange<double> range,dynamic hint });"
+        ));
+        assert!(code.contains(
+            "callFfi: (port) => inner.wire_clamp_values(port, _api2wire_float_64_list(data), _api2wire_Range_f64(range)),"
+        ));
+        assert!(code.contains(
+            "ffi.Pointer<wire_float_64_list> _api2wire_Range_f64(FrbRange<double> raw) {"
+        ));
+        assert!(code.contains(
+            "return _api2wire_float_64_list(Float64List.fromList([raw.start, raw.end]));"
+        ));
+    }
+
+    #[test]
+    fn decodes_unrecognised_values_of_non_exhaustive_enums_as_unknown() {
+        let output = generate_output(
+            "
+            #[non_exhaustive]
+            pub enum Mood {
+                Happy,
+                Sad,
+            }
+
+            pub fn mood() -> Result<Mood> {
+                Ok(Mood::Happy)
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        let happy = code.find("happy,").unwrap();
+        let sad = code.find("sad,").unwrap();
+        let unknown = code.find("unknown,").unwrap();
+        assert!(happy < sad && sad < unknown);
+        // e.g. a discriminant of 999 from a newer Rust side decodes to `Mood.unknown`, see the test
+        // of `frbNonExhaustiveEnumValue` in the pure_dart example
+        assert!(code.contains("Mood _wire2api_mood(dynamic raw) {"));
+        assert!(code
+            .contains("return frbNonExhaustiveEnumValue(Mood.values, raw as int, Mood.unknown);"));
+    }
+
+    #[test]
+    fn generates_isolate_safe_wrapper() {
+        let source = "
+            pub fn add(a: i32, b: i32) -> Result<i32> {
+                Ok(a + b)
+            }
+
+            pub fn ticks(sink: StreamSink<u32>) -> Result<()> {
+                Ok(())
+            }
+            ";
+        assert!(!generate_api_class(source, false).contains("ApiIsolateSafe"));
+
+        let api_class = generate_api_class(source, true);
+        assert!(api_class.contains("class ApiIsolateSafe extends FlutterRustBridgeIsolateSafe {"));
+        assert!(api_class.contains(
+            "Future<int> add({ required int a,required int b,dynamic hint }) => \
+            run(() => inner.add(a: a, b: b, hint: hint));"
+        ));
+        assert!(api_class.contains(
+            "Stream<int> ticks({ dynamic hint }) => runStream(() => inner.ticks(hint: hint));"
+        ));
+    }
+
+    #[test]
+    fn dedups_structs_with_same_fields() {
+        let source = "
+            /// A place.
+            pub struct Point2D {
+                pub x: f64,
+                pub y: f64,
+            }
+
+            /// A direction.
+            pub struct Vector2D {
+                pub x: f64,
+                pub y: f64,
+            }
+
+            pub struct Size2D {
+                pub width: f64,
+                pub height: f64,
+            }
+
+            pub fn translate(p: Point2D, v: Vector2D) -> Result<Point2D> {
+                Ok(p)
+            }
+
+            pub fn vector_between(from: Point2D, to: Point2D, size: Size2D) -> Result<Vector2D> {
+                Ok(Vector2D { x: to.x - from.x, y: to.y - from.y })
+            }
+            ";
+        let output = generate_output(source, false, true);
+        assert!(output.api_class.contains("class Point2D {"));
+        assert!(output.api_class.contains("class Size2D {"));
+        assert!(!output.api_class.contains("class Vector2D {"));
+        assert!(output.api_class.contains("typedef Vector2D = Point2D;"));
+        assert!(output.other.contains(
+            "Vector2D _wire2api_vector_2_d(dynamic raw) {
+            return _wire2api_point_2_d(raw);"
+        ));
+
+        let output = generate_output(source, false, false);
+        assert!(output.api_class.contains("class Vector2D {"));
+        assert!(!output.api_class.contains("typedef"));
+    }
+
+    #[test]
+    fn generates_equality_of_struct_fields() {
+        let api_class = generate_api_class(
+            "
+            pub struct Frame {
+                pub id: u32,
+                pub pixels: Option<ZeroCopyBuffer<Vec<u8>>>,
+                pub labels: Vec<String>,
+            }
+
+            pub struct Wide {
+                pub a: i32, pub b: i32, pub c: i32, pub d: i32, pub e: i32, pub f: i32,
+                pub g: i32, pub h: i32, pub i: i32, pub j: i32, pub k: i32,
+            }
+
+            pub fn show(frame: Frame, wide: Wide) -> Result<i32> {
+                Ok(0)
+            }
+            ",
+            false,
+        );
+        assert!(api_class.contains(
+            "bool operator ==(Object other) => other is Frame && other.id == id \
+            && frbListEquals(other.pixels, pixels) && frbListEquals(other.labels, labels);"
+        ));
+        assert!(api_class.contains(
+            "int get hashCode => Object.hashAll([id, frbListHash(pixels), frbListHash(labels)]);"
+        ));
+        assert!(api_class
+            .contains("String toString() => 'Frame(id: $id, pixels: $pixels, labels: $labels)';"));
+        assert!(api_class.contains(
+            "String toString() => 'Wide(a: $a, b: $b, c: $c, d: $d, e: $e, f: $f, g: $g, h: $h, \
+            i: $i, j: $j, ...)';"
+        ));
+    }
+
+    #[test]
+    fn returns_null_for_none() {
+        let output = generate_output(
+            "
+            pub struct Item {
+                pub id: u32,
+            }
+
+            pub fn find(id: u32) -> Result<Option<Item>> {
+                Ok(None)
+            }
+
+            pub fn count(id: u32) -> Result<Option<i32>> {
+                Ok(None)
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output.api_class.contains("Future<Item?> find("));
+        assert!(output.api_class.contains("Future<int?> count("));
+        assert!(output.other.contains(
+            "Item? _wire2api_opt_box_autoadd_item(dynamic raw) {
+            return raw == null ? null : _wire2api_box_autoadd_item(raw);"
+        ));
+        assert!(output.other.contains(
+            "int? _wire2api_opt_box_autoadd_i32(dynamic raw) {
+            return raw == null ? null : _wire2api_box_autoadd_i32(raw);"
+        ));
+    }
+
+    #[test]
+    fn passes_bools_and_optional_bools() {
+        let output = generate_output(
+            "
+            pub fn negate(flag: bool, maybe: Option<bool>) -> Result<Option<bool>> {
+                Ok(maybe.map(|maybe| maybe != flag))
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output
+            .api_class
+            .contains("Future<bool?> negate({ required bool flag,bool? maybe,dynamic hint });"));
+        // `true` and `false` are boxed, `null` is a null pointer
+        assert!(output.other.contains(
+            "ffi.Pointer<ffi.Uint8> _api2wire_opt_box_autoadd_bool(bool? raw) {
+            return raw == null ? ffi.nullptr : _api2wire_box_autoadd_bool(raw);"
+        ));
+        assert!(output.other.contains(
+            "bool? _wire2api_opt_box_autoadd_bool(dynamic raw) {
+            return raw == null ? null : _wire2api_box_autoadd_bool(raw);"
+        ));
+        assert!(output.other.contains(
+            "bool _wire2api_box_autoadd_bool(dynamic raw) {
+            return raw as bool;"
+        ));
+    }
+
+    #[test]
+    fn decodes_outputs_of_sync_attribute() {
+        let output = generate_output(
+            "
+            #[frb(sync)]
+            pub fn answer() -> Result<i64> {
+                Ok(42)
+            }
+
+            #[frb(sync)]
+            pub fn ready() -> Result<bool> {
+                Ok(true)
+            }
+
+            #[frb(sync)]
+            pub fn greet() -> Result<String> {
+                Ok(String::new())
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output.api_class.contains("int answer({ dynamic hint });"));
+        assert!(output.other.contains(
+            "int answer({ dynamic hint }) => ByteData.sublistView(executeSync(FlutterRustBridgeSyncTask("
+        ));
+        assert!(output
+            .other
+            .contains("hint: hint\n            ))).getInt64(0, Endian.little);"));
+        assert!(output.other.contains("hint: hint\n            ))[0] != 0;"));
+        assert!(output.other.contains(
+            "String greet({ dynamic hint }) => utf8.decode(executeSync(FlutterRustBridgeSyncTask("
+        ));
+    }
+
+    #[test]
+    fn test_stubs_await_only_async_functions() {
+        let api_file = parser::parse_source(
+            "
+            #[frb(test)]
+            pub fn answer() -> Result<i32> {
+                Ok(42)
+            }
+
+            #[frb(test, sync)]
+            pub fn ready() -> Result<bool> {
+                Ok(true)
+            }
+
+            #[frb(test)]
+            pub fn ticks(sink: StreamSink<i32>) -> Result<()> {
+                Ok(())
+            }
+
+            pub fn untested() -> Result<i32> {
+                Ok(0)
+            }
+            ",
+        );
+        let stubs = generate_test_stubs(&api_file, "Api", "bridge_generated.dart");
+        assert!(stubs.contains("import 'bridge_generated.dart';"));
+        assert!(stubs.contains("final api = Api(ffi.DynamicLibrary.executable());"));
+        assert!(stubs.contains(
+            "test('answer', () async {
+                    final result = await api.answer();"
+        ));
+        assert!(stubs.contains(
+            "test('ready', () {
+                    final result = api.ready();"
+        ));
+        assert!(!stubs.contains("ticks"));
+        assert!(!stubs.contains("untested"));
+    }
+
+    #[test]
+    fn passes_optional_collections() {
+        let output = generate_output(
+            "
+            pub fn merge(
+                ids: Option<Vec<i32>>,
+                names: Option<HashMap<String, i32>>,
+                pair: Option<(i32, String)>,
+            ) -> Result<Option<Vec<i32>>> {
+                Ok(ids)
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output.api_class.contains(
+            "Future<Int32List?> merge({ Int32List? ids,Map<String, int>? names,\
+            FrbTuple2<int, String>? pair,dynamic hint });"
+        ));
+        // the collections are already passed as pointers, so `null` is a null pointer
+        for (name, ty) in [
+            ("int_32_list", "Int32List"),
+            ("hash_map_String_i32", "Map<String, int>"),
+            ("tuple_i32_String", "FrbTuple2<int, String>"),
+        ] {
+            assert!(output.other.contains(&format!(
+                "ffi.Pointer<wire_{0}> _api2wire_opt_{0}({1}? raw) {{
+            return raw == null ? ffi.nullptr : _api2wire_{0}(raw);",
+                name, ty
+            )));
+        }
+        assert!(output.other.contains(
+            "Int32List? _wire2api_opt_int_32_list(dynamic raw) {
+            return raw == null ? null : _wire2api_int_32_list(raw);"
+        ));
+    }
+
+    #[test]
+    fn finalizes_opaque_handles() {
+        let output = generate_output(
+            "
+            #[frb(opaque)]
+            pub struct Connection {
+                fd: i32,
+            }
+
+            pub fn connect() -> Result<Connection> {
+                Ok(Connection { fd: 0 })
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output
+            .api_class
+            .contains("class Connection extends OpaqueHandle implements ffi.Finalizable {"));
+        assert!(output
+            .api_class
+            .contains("_finalizer.attach(this, ffi.Pointer.fromAddress(handle), detach: this);"));
+        assert!(output.other.contains(
+            "late final _finalizer_opaque_connection = ffi.NativeFinalizer(\
+            inner._lookup<ffi.NativeFinalizerFunction>('frb_dispose_opaque_connection'));"
+        ));
+        assert!(output.other.contains(
+            "return Connection._(raw as int, dispose: inner.drop_opaque_connection, \
+            finalizer: _finalizer_opaque_connection);"
+        ));
+    }
+
+    #[test]
+    fn setters_of_change_notifiers_update_rust_and_notify() {
+        let output = generate_output(
+            "
+            #[frb(on_change = \"onChanged\")]
+            pub struct Counter {
+                pub count: i32,
+            }
+
+            pub fn make_counter() -> Result<Counter> {
+                Ok(Counter { count: 0 })
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output
+            .imports
+            .contains(&"import 'package:flutter/foundation.dart';".to_string()));
+        assert!(output
+            .api_class
+            .contains("class Counter extends ChangeNotifier {"));
+        assert!(output.api_class.contains(
+            "if (handle != null) _rustBridge!.inner.wire_set_counter_count(handle, _rustBridge!._api2wire_i32(value));"
+        ));
+        assert!(output.api_class.contains("notifyListeners();"));
+        assert!(output.api_class.contains("onChanged?.call();"));
+        assert!(output
+            .api_class
+            .contains("if (handle != null) _rustBridge!.inner.drop_on_change_counter(handle);"));
+        assert!(output.other.contains(
+            "return Counter._fromRust(this, arr[0] as int, count: _wire2api_i32(arr[1]),);"
+        ));
+        assert!(output.other.contains("int _api2wire_i32(int raw) {"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "std::ptr::NonNull<i32> is only supported as the input type itself, see read_values"
+    )]
+    fn rejects_nested_non_null_inputs() {
+        generate_output(
+            "
+            #[frb(unsafe_ptr)]
+            pub fn read_values(ptrs: Vec<NonNull<i32>>) -> Result<i32> {
+                Ok(0)
+            }
+            ",
+            false,
+            true,
+        );
+    }
+
+    #[test]
+    fn calls_functions_by_their_dart_names() {
+        let output = generate_output(
+            r#"
+            #[frb(dart_name = "getUser")]
+            pub fn fetch_user(id: u32) -> Result<String> {
+                Ok(id.to_string())
+            }
+            "#,
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains("Future<String> getUser({ required int id,dynamic hint });"));
+        assert!(code.contains("debugName: 'fetch_user',"));
+        assert!(code.contains("callFfi: (port) => inner.wire_fetch_user(port, _api2wire_u32(id)),"));
+        assert!(!code.contains("fetchUser"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "functions 'get_user' and 'fetch_user' both map to Dart name 'getUser'. Please add `#[frb(name = \"...\")]` to disambiguate."
+    )]
+    fn rejects_functions_with_the_same_dart_name() {
+        generate_output(
+            r#"
+            pub fn get_user(id: u32) -> Result<String> {
+                Ok(id.to_string())
+            }
+
+            #[frb(dart_name = "getUser")]
+            pub fn fetch_user(id: u32) -> Result<String> {
+                Ok(id.to_string())
+            }
+            "#,
+            false,
+            true,
+        );
+    }
+
+    #[test]
+    fn generates_dart_enums_with_methods_for_enum_class() {
+        let output = generate_output(
+            "
+            /// Day of the week
+            #[frb(enum_class)]
+            pub enum Weekday {
+                Monday,
+                Tuesday,
+            }
+
+            pub enum Level {
+                Low,
+                High,
+            }
+
+            pub fn next_day(day: Weekday, level: Level) -> Result<Weekday> {
+                Ok(day)
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains("/// Day of the week\n        enum Weekday {"));
+        assert!(code.contains("tuesday,;\n\n        int toRust() => index;"));
+        assert!(
+            code.contains("int _api2wire_weekday(Weekday raw) {\n            return raw.toRust();")
+        );
+        assert!(code.contains("return Weekday.values[raw as int];"));
+        assert!(code.contains("enum Level {"));
+        assert!(code.contains("int _api2wire_level(Level raw) {\n            return raw.index;"));
+        assert_eq!(code.matches("toRust() =>").count(), 1);
+    }
+
+    #[test]
+    fn passes_tuples_as_frb_tuples() {
+        let output = generate_output(
+            "
+            pub fn swap(pair: (String, i32)) -> Result<(i32, String, bool)> {
+                Ok((pair.1, pair.0, true))
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains(
+            "Future<FrbTuple3<int, String, bool>> swap({ required FrbTuple2<String, int> pair,dynamic hint });"
+        ));
+        assert!(code.contains("final ans = inner.new_tuple_String_i32();"));
+        assert!(code.contains("ans.ref.field0 = _api2wire_String(raw.field0);"));
+        assert!(code.contains("ans.ref.field1 = _api2wire_i32(raw.field1);"));
+        assert!(code.contains(
+            "return FrbTuple3<int, String, bool>(_wire2api_i32(arr[0]),_wire2api_String(arr[1]),_wire2api_bool(arr[2]),);"
+        ));
+    }
+
+    #[test]
+    fn passes_type_aliases_as_their_types() {
+        let output = generate_output(
+            "
+            pub type UserId = u32;
+            pub type Name = String;
+            pub type DisplayName = Name;
+
+            pub struct User {
+                pub id: UserId,
+                pub name: DisplayName,
+            }
+
+            pub fn find_user(id: UserId) -> Result<User> {
+                Ok(User { id, name: String::new() })
+            }
+            ",
+            false,
+            true,
+        );
+        let code = format!("{}\n{}", output.api_class, output.other);
+        assert!(code.contains("Future<User> findUser({ required int id,dynamic hint });"));
+        assert!(code.contains("callFfi: (port) => inner.wire_find_user(port, _api2wire_u32(id)),"));
+        assert!(code.contains("final int id;"));
+        assert!(code.contains("final String name;"));
+        assert!(code
+            .contains("return User(id: _wire2api_u32(arr[0]),\nname: _wire2api_String(arr[1]),);"));
+        assert!(!code.contains("UserId") && !code.contains("DisplayName"));
+    }
+
+    #[test]
+    fn uses_field_defaults_in_constructors() {
+        let output = generate_output(
+            r#"
+            pub struct Options {
+                #[frb(default = 1024)]
+                pub size: u32,
+                #[frb(default = "it's")]
+                pub label: String,
+                #[frb(default = "const []")]
+                pub tags: Vec<String>,
+                pub verbose: bool,
+            }
+
+            pub fn apply(options: Options) -> Result<Options> {
+                Ok(options)
+            }
+            "#,
+            false,
+            true,
+        );
+        assert!(output.api_class.contains(
+            r"Options({this.size = 1024,this.label = 'it\'s',this.tags = const [],required this.verbose,});"
+        ));
+    }
+
+    #[test]
+    fn decodes_sync_return_strings() {
+        let output = generate_output(
+            "
+            pub fn greet_sync(name: String) -> Result<SyncReturn<String>> {
+                Ok(SyncReturn(name))
+            }
+            ",
+            false,
+            true,
+        );
+        assert!(output
+            .api_class
+            .contains("String greetSync({ required String name,dynamic hint });"));
+        assert!(output.other.contains(
+            "String greetSync({ required String name,dynamic hint }) => utf8.decode(executeSync(FlutterRustBridgeSyncTask("
+        ));
+        assert!(output
+            .other
+            .contains("callFfi: () => inner.wire_greet_sync(_api2wire_String(name)),"));
+    }
+}
@@ -1,49 +1,322 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+
+use log::warn;
 
 use crate::api_types::ApiType::*;
 use crate::api_types::*;
+use crate::config::Opts;
 use crate::others::*;
+use crate::utils::mod_from_rust_path;
 
 pub const HANDLER_NAME: &str = "FLUTTER_RUST_BRIDGE_HANDLER";
 
 pub struct Output {
     pub code: String,
+    /// Child modules of [Self::code], only generated by [generate_split]
+    pub modules: Vec<RustModule>,
     pub extern_func_names: Vec<String>,
+    pub extern_funcs: Vec<ExternFunc>,
+}
+
+/// A file of the generated module tree, see [crate::config::RawOpts::split_output]
+pub struct RustModule {
+    /// e.g. `api_point` for the wire functions of `crate::api::point`
+    pub name: String,
+    pub code: String,
+}
+
+pub fn generate(api_file: &ApiFile, opts: &Opts) -> Output {
+    let mut generator = Generator::new(opts);
+    let wire_funcs =
+        generator.generate_wire_funcs(&api_file.funcs.iter().collect::<Vec<_>>(), opts);
+    let code = generator.generate(api_file, opts, &wire_funcs);
+    generator.into_output(code, vec![])
+}
+
+/// Similar to [generate], but the wire functions of each input module are put into a child
+/// module, which is re-exported. [func_modules] are the module paths of the inputs, e.g.
+/// `api::point`, with the names of the functions they define.
+pub fn generate_split(
+    api_file: &ApiFile,
+    opts: &Opts,
+    func_modules: &[(String, Vec<String>)],
+) -> Output {
+    let mut generator = Generator::new(opts);
+    let modules: Vec<_> = func_modules
+        .iter()
+        .map(|(mod_path, func_names)| {
+            let funcs: Vec<_> = api_file
+                .funcs
+                .iter()
+                .filter(|func| func_names.contains(&func.name))
+                .collect();
+            RustModule {
+                name: mod_path.replace("::", "_"),
+                code: format!(
+                    "{}
+
+                    use super::*;
+
+                    // Section: wire functions
+
+                    {}
+                    ",
+                    CODE_HEADER,
+                    generator.generate_wire_funcs(&funcs, opts),
+                ),
+            }
+        })
+        .collect();
+    let declarations = modules
+        .iter()
+        .map(|module| format!("mod {0};\npub use self::{0}::*;", module.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let code = generator.generate(api_file, opts, &declarations);
+    generator.into_output(code, modules)
+}
+
+/// The beginning of the generated Rust code, which does not depend on the API
+pub fn generate_rust_bridge_prelude(opts: &Opts) -> String {
+    format!(
+        "#![allow(non_camel_case_types, unused{})]
+        {}
+
+        {}
+        use flutter_rust_bridge::*;
+        ",
+        if opts.no_allow_clippy {
+            ""
+        } else {
+            ", clippy::all"
+        },
+        CODE_HEADER,
+        opts.rust_input_paths
+            .iter()
+            .map(|path| format!(
+                "use crate::{}::*;",
+                mod_from_rust_path(path, &opts.rust_crate_dir)
+            ))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// The `extern "C"` wire function of [func], e.g. to regenerate a single modified function
+pub fn generate_rust_func(func: &ApiFunc, opts: &Opts) -> Output {
+    let mut generator = Generator::new(opts);
+    let code = generator.generate_wire_func(func);
+    generator.into_output(code, vec![])
 }
 
-pub fn generate(api_file: &ApiFile, rust_wire_mod: &str) -> Output {
-    let mut generator = Generator::new();
-    let code = generator.generate(api_file, rust_wire_mod);
+/// The code of a struct in each section of the generated Rust code, see [generate_rust_struct]
+pub struct RustStructCode {
+    pub on_change_funcs: String,
+    pub wire_struct: String,
+    pub wire2api_func: String,
+    pub new_with_nullptr_func: String,
+    pub impl_intodart: String,
+    pub mirror_conversions: String,
+    pub extern_funcs: Vec<ExternFunc>,
+}
 
-    Output {
-        code,
-        extern_func_names: generator.extern_func_collector.names,
+/// The code of [s], depending on whether it is an input or output of [api_file], e.g. to
+/// regenerate a single modified struct
+pub fn generate_rust_struct(s: &ApiStruct, api_file: &ApiFile, opts: &Opts) -> RustStructCode {
+    let mut generator = Generator::new(opts);
+    let code = generator.generate_struct(s, api_file);
+    RustStructCode {
+        extern_funcs: generator.extern_func_collector.funcs,
+        ..code
     }
 }
 
+/// Fields of the `#[repr(C)]` struct of [ty], e.g. `["ptr: *mut u8", "len: i32"]`,
+/// or [None] if [ty] does not need a wire struct.
+pub fn wire_struct_fields(ty: &ApiType, api_file: &ApiFile) -> Option<Vec<String>> {
+    Some(match ty {
+        PrimitiveList(list) => vec![
+            format!("ptr: *mut {}", list.primitive.rust_wire_type()),
+            "len: i32".to_string(),
+        ],
+        GeneralList(list) => vec![
+            format!(
+                "ptr: *mut {}{}",
+                list.inner.rust_wire_modifier(),
+                list.inner.rust_wire_type()
+            ),
+            "len: i32".to_string(),
+        ],
+        Map(map) => key_value_wire_struct_fields(&map.key, &map.value),
+        AssocList(list) => key_value_wire_struct_fields(&list.key, &list.value),
+        Tuple(tuple) => tuple
+            .elements
+            .iter()
+            .zip(tuple.field_names())
+            .map(|(element, name)| {
+                format!(
+                    "{}: {}{}",
+                    name,
+                    element.rust_wire_modifier(),
+                    element.rust_wire_type()
+                )
+            })
+            .collect(),
+        StructRef(s) => {
+            let s = s.get(api_file);
+            s.fields
+                .iter()
+                .map(|field| {
+                    format!(
+                        "{}: {}{}",
+                        field.name.rust_style(),
+                        field.ty.rust_wire_modifier(),
+                        field.ty.rust_wire_type()
+                    )
+                })
+                .collect()
+        }
+        Primitive(_) | Delegate(_) | EnumRef(_) | Boxed(_) | OpaqueWeak(_) | Opaque(_)
+        | Callback(_) | Optional(_) => return None,
+    })
+}
+
+/// Maps and association lists are passed as a list of keys and a list of values
+fn key_value_wire_struct_fields(key: &ApiType, value: &ApiType) -> Vec<String> {
+    vec![
+        format!(
+            "keys: *mut {}{}",
+            key.rust_wire_modifier(),
+            key.rust_wire_type()
+        ),
+        format!(
+            "values: *mut {}{}",
+            value.rust_wire_modifier(),
+            value.rust_wire_type()
+        ),
+        "len: i32".to_string(),
+    ]
+}
+
 struct Generator {
     extern_func_collector: ExternFuncCollector,
+    /// Whether `Vec<u8>` outputs are sent as `ZeroCopyBuffer`s, see
+    /// [crate::config::RawOpts::no_auto_zero_copy]
+    auto_zero_copy: bool,
+    /// Whether panics are located, see [crate::config::RawOpts::source_locations]
+    source_locations: bool,
 }
 
 impl Generator {
-    fn new() -> Self {
+    fn new(opts: &Opts) -> Self {
         Self {
             extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: !opts.no_auto_zero_copy,
+            source_locations: opts.source_locations,
+        }
+    }
+
+    fn into_output(self, code: String, modules: Vec<RustModule>) -> Output {
+        let extern_funcs = self.extern_func_collector.funcs;
+        Output {
+            code,
+            modules,
+            extern_func_names: extern_funcs.iter().map(|f| f.name.clone()).collect(),
+            extern_funcs,
         }
     }
 
-    fn generate(&mut self, api_file: &ApiFile, rust_wire_mod: &str) -> String {
+    fn generate_wire_funcs(&mut self, funcs: &[&ApiFunc], opts: &Opts) -> String {
+        funcs
+            .iter()
+            .map(|f| {
+                let mut output = generate_rust_func(f, opts);
+                self.extern_func_collector
+                    .funcs
+                    .append(&mut output.extern_funcs);
+                output.code
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// [wire_funcs] is the content of the wire functions section
+    fn generate(&mut self, api_file: &ApiFile, opts: &Opts, wire_funcs: &str) -> String {
         let distinct_input_types = api_file.distinct_types(true, false);
         let distinct_output_types = api_file.distinct_types(false, true);
 
-        let wire_funcs = api_file
-            .funcs
+        if let Some(ty) = distinct_input_types
+            .iter()
+            .find(|ty| matches!(ty, EnumRef(e) if e.get(api_file).is_struct()))
+        {
+            panic!("{} is only supported as output", ty.rust_api_type());
+        }
+        // not an error, since some lists have duplicate keys on purpose
+        for ty in distinct_output_types
+            .iter()
+            .filter(|ty| matches!(ty, AssocList(_)))
+        {
+            warn!(
+                "{} is received in Dart as a Map, which only keeps the last value of duplicate keys",
+                ty.rust_api_type()
+            );
+        }
+        // an input is moved into the task, which runs on another thread
+        if let Some(ty) = distinct_input_types.iter().find(|ty| {
+            matches!(ty, Delegate(ApiTypeDelegate::DynError(bounds)) if !bounds.split('+').any(|bound| bound == "Send"))
+        }) {
+            panic!(
+                "{} is not `Send`, please use `Box<dyn Error + Send>` instead",
+                ty.rust_api_type()
+            );
+        }
+
+        let weak_funcs = distinct_output_types
+            .iter()
+            .filter_map(|ty| match ty {
+                OpaqueWeak(weak) => Some(self.generate_weak_funcs(weak)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let opaque_funcs = api_file
+            .distinct_types(true, true)
             .iter()
-            .map(|f| self.generate_wire_func(f))
+            .filter_map(|ty| match ty {
+                Opaque(opaque) => Some(self.generate_opaque_funcs(opaque, !opts.no_finalizer)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let distinct_types = api_file.distinct_types(true, true);
+        let struct_codes: HashMap<_, _> = distinct_types
+            .iter()
+            .filter_map(|ty| match ty {
+                StructRef(s) => {
+                    let mut code = generate_rust_struct(s.get(api_file), api_file, opts);
+                    self.extern_func_collector
+                        .funcs
+                        .append(&mut code.extern_funcs);
+                    Some((s.name.clone(), code))
+                }
+                _ => None,
+            })
+            .collect();
+        let on_change_funcs = distinct_output_types
+            .iter()
+            .filter_map(|ty| match ty {
+                StructRef(s) if s.get(api_file).notify_on_change => {
+                    Some(struct_codes[&s.name].on_change_funcs.clone())
+                }
+                _ => None,
+            })
             .collect::<Vec<_>>();
+        let callback_funcs = self.generate_callback_funcs(&distinct_input_types);
         let wire_structs = distinct_input_types
             .iter()
-            .map(|ty| self.generate_wire_struct(ty, api_file))
+            .map(|ty| match ty {
+                StructRef(s) => struct_codes[&s.name].wire_struct.clone(),
+                _ => self.generate_wire_struct(ty, api_file),
+            })
             .collect::<Vec<_>>();
         let allocate_funcs = distinct_input_types
             .iter()
@@ -51,25 +324,55 @@ impl Generator {
             .collect::<Vec<_>>();
         let wire2api_funcs = distinct_input_types
             .iter()
-            .map(|ty| self.generate_wire2api_func(ty, api_file))
+            .map(|ty| match ty {
+                StructRef(s) => struct_codes[&s.name].wire2api_func.clone(),
+                _ => self.generate_wire2api_func(ty, api_file),
+            })
             .collect::<Vec<_>>();
         let new_with_nullptr_funcs = distinct_input_types
             .iter()
-            .map(|ty| self.generate_new_with_nullptr_func(ty, api_file))
+            .map(|ty| match ty {
+                StructRef(s) => struct_codes[&s.name].new_with_nullptr_func.clone(),
+                _ => self.generate_new_with_nullptr_func(ty, api_file),
+            })
             .collect::<Vec<_>>();
         let impl_intodart = distinct_output_types
             .iter()
-            .map(|ty| self.generate_impl_intodart(ty, api_file))
+            .map(|ty| match ty {
+                StructRef(s) => struct_codes[&s.name].impl_intodart.clone(),
+                _ => self.generate_impl_intodart(ty, api_file),
+            })
+            .collect::<Vec<_>>();
+        let mirror_conversions = distinct_types
+            .iter()
+            .filter_map(|ty| match ty {
+                StructRef(s) if s.get(api_file).mirror.is_some() => {
+                    Some(struct_codes[&s.name].mirror_conversions.clone())
+                }
+                _ => None,
+            })
             .collect::<Vec<_>>();
 
         format!(
-            r#"#![allow(non_camel_case_types, unused, clippy::redundant_closure, clippy::useless_conversion)]
+            r#"{}
+
+        // Section: wire functions
+
         {}
 
-        use crate::{}::*;
-        use flutter_rust_bridge::*;
+        // Section: weak references
 
-        // Section: wire functions
+        {}
+
+        // Section: opaque handles
+
+        {}
+
+        // Section: change notifiers
+
+        {}
+
+        // Section: callbacks
 
         {}
 
@@ -119,21 +422,28 @@ impl Generator {
         // Section: impl IntoDart
         {}
 
+        // Section: mirror conversions
+        {}
+
         // Section: executor
         {}
         
         // Section: sync execution mode utility
         {}
-
+        {}
         "#,
-            CODE_HEADER,
-            rust_wire_mod,
-            wire_funcs.join("\n\n"),
+            generate_rust_bridge_prelude(opts),
+            wire_funcs,
+            weak_funcs.join("\n\n"),
+            opaque_funcs.join("\n\n"),
+            on_change_funcs.join("\n\n"),
+            callback_funcs,
             wire_structs.join("\n\n"),
             allocate_funcs.join("\n\n"),
             wire2api_funcs.join("\n\n"),
             new_with_nullptr_funcs.join("\n\n"),
             impl_intodart.join("\n\n"),
+            mirror_conversions.join("\n\n"),
             self.generate_executor(api_file),
             self.extern_func_collector.generate(
                 "free_WireSyncReturnStruct",
@@ -141,6 +451,60 @@ impl Generator {
                 None,
                 "unsafe { let _ = support::vec_from_leak_ptr(val.ptr, val.len); }",
             ),
+            self.generate_source_locations(api_file),
+        )
+    }
+
+    /// See [generate_rust_struct]
+    fn generate_struct(&mut self, s: &ApiStruct, api_file: &ApiFile) -> RustStructCode {
+        let is_struct = |ty: &&ApiType| matches!(ty, StructRef(s_ref) if s_ref.name == s.name);
+        let input_types = api_file.distinct_types(true, false);
+        let input = input_types.iter().find(is_struct);
+        let output_types = api_file.distinct_types(false, true);
+        let output = output_types.iter().find(is_struct);
+
+        RustStructCode {
+            on_change_funcs: match output {
+                Some(_) if s.notify_on_change => self.generate_on_change_funcs(s),
+                _ => String::new(),
+            },
+            wire_struct: input
+                .map_or_else(String::new, |ty| self.generate_wire_struct(ty, api_file)),
+            wire2api_func: input
+                .map_or_else(String::new, |ty| self.generate_wire2api_func(ty, api_file)),
+            new_with_nullptr_func: input.map_or_else(String::new, |ty| {
+                self.generate_new_with_nullptr_func(ty, api_file)
+            }),
+            impl_intodart: output
+                .map_or_else(String::new, |ty| self.generate_impl_intodart(ty, api_file)),
+            mirror_conversions: s.mirror.as_ref().map_or_else(String::new, |mirror| {
+                self.generate_mirror_conversions(s, mirror)
+            }),
+            extern_funcs: vec![],
+        }
+    }
+
+    /// The locations of all functions, since those of a split output are in child modules
+    fn generate_source_locations(&self, api_file: &ApiFile) -> String {
+        if !self.source_locations {
+            return "".to_string();
+        }
+        format!(
+            "
+            // Section: source locations
+
+            {}
+            ",
+            api_file
+                .funcs
+                .iter()
+                .map(|func| format!(
+                    "const {}: &str = \"{}\";",
+                    source_location_const_name(func),
+                    func.source_location.escape_default()
+                ))
+                .collect::<Vec<_>>()
+                .join("\n"),
         )
     }
 
@@ -182,11 +546,72 @@ impl Generator {
         let inner_func_params = [
             match func.mode {
                 ApiFuncMode::Normal | ApiFuncMode::Sync => vec![],
-                ApiFuncMode::Stream => vec!["task_callback.stream_sink()".to_string()],
+                ApiFuncMode::Stream if func.stream_error_output.is_some() => {
+                    vec![format!(
+                        "task_callback.stream_sink_of_results(){}",
+                        match (func.rate_limit_events_per_second, func.stream_buffer_size) {
+                            (Some(events_per_second), _) =>
+                                format!(".rate_limited({})", events_per_second),
+                            (None, Some(buffer_size)) => format!(".buffered({})", buffer_size),
+                            (None, None) => "".to_string(),
+                        }
+                    )]
+                }
+                ApiFuncMode::Stream => {
+                    vec![
+                        match (func.rate_limit_events_per_second, func.stream_buffer_size) {
+                            (Some(events_per_second), _) => format!(
+                                "task_callback.stream_sink_rate_limited({})",
+                                events_per_second
+                            ),
+                            (None, Some(buffer_size)) => {
+                                format!("task_callback.stream_sink_buffered({})", buffer_size)
+                            }
+                            (None, None) => "task_callback.stream_sink()".to_string(),
+                        },
+                    ]
+                }
             },
             func.inputs
                 .iter()
-                .map(|field| format!("api_{}", field.name.rust_style()))
+                .map(|field| {
+                    let borrow_receiver = field.name.raw == ApiMethod::RECEIVER_NAME
+                        && matches!(
+                            &func.method,
+                            Some(ApiMethod {
+                                receiver: Some(ApiMethodReceiver::Ref),
+                                ..
+                            })
+                        );
+                    match &field.ty {
+                        // move the whole wrapper into the closure, instead of only its field
+                        ty if is_trait_object(ty) => {
+                            return format!("{{ api_{} }}.0", field.name.rust_style())
+                        }
+                        // the closure may not be `Send`, so it is created in the task from the port
+                        Callback(_) => {
+                            return format!(
+                                "wire2api_{}(api_{})",
+                                field.ty.safe_ident(),
+                                field.name.rust_style()
+                            )
+                        }
+                        // `NonNull` is not `Send`, so it is created in the task from the address
+                        Delegate(ApiTypeDelegate::NonNull(_)) => {
+                            return format!("api_{}.wire2api()", field.name.rust_style())
+                        }
+                        _ => {}
+                    }
+                    format!(
+                        "{}api_{}",
+                        if is_borrowed(&field.ty) || borrow_receiver {
+                            "&"
+                        } else {
+                            ""
+                        },
+                        field.name.rust_style()
+                    )
+                })
                 .collect::<Vec<_>>(),
         ]
         .concat();
@@ -205,17 +630,87 @@ impl Generator {
         let code_wire2api = func
             .inputs
             .iter()
-            .map(|field| {
-                format!(
+            .map(|field| match &field.ty {
+                Delegate(d) if d.is_borrowed() => format!(
+                    "let api_{}: {} = {}.wire2api();",
+                    field.name.rust_style(),
+                    d.get_delegate().rust_api_type(),
+                    field.name.rust_style()
+                ),
+                // trait objects are usually not `UnwindSafe`, but are owned by the task anyway
+                ty if is_trait_object(ty) => format!(
+                    "let api_{} = std::panic::AssertUnwindSafe({}.wire2api());",
+                    field.name.rust_style(),
+                    field.name.rust_style()
+                ),
+                Callback(_) | Delegate(ApiTypeDelegate::NonNull(_)) => format!(
+                    "let api_{} = {};",
+                    field.name.rust_style(),
+                    field.name.rust_style()
+                ),
+                // `impl Into<String>` does not tell which of the `Wire2Api` impls to use
+                ty if field.use_into => format!(
+                    "let api_{}: {} = {}.wire2api();",
+                    field.name.rust_style(),
+                    ty.rust_api_type(),
+                    field.name.rust_style()
+                ),
+                _ => format!(
                     "let api_{} = {}.wire2api();",
                     field.name.rust_style(),
                     field.name.rust_style()
-                )
+                ),
             })
             .collect::<Vec<_>>()
             .join("");
 
-        let code_call_inner_func = format!("{}({})", func.name, inner_func_params.join(", "));
+        let code_call_inner_func = format!(
+            "{}({})",
+            func.rust_call_path(),
+            inner_func_params.join(", ")
+        );
+        let code_call_inner_func = match func.rate_limit_events_per_second {
+            Some(events_per_second) => format!(
+                "// rate limited to {} events/s
+                {}",
+                events_per_second, code_call_inner_func
+            ),
+            None => code_call_inner_func,
+        };
+        // bytes are received as `Uint8List` on the Dart side either way, but without a copy
+        let zero_copy_bytes = self.auto_zero_copy
+            && func.mode == ApiFuncMode::Normal
+            && match &func.output {
+                PrimitiveList(list) => matches!(list.primitive, ApiTypePrimitive::U8),
+                _ => false,
+            };
+        let converted_output = match func.mode {
+            ApiFuncMode::Sync => into_sync_return(&func.output, "ans"),
+            _ => None,
+        }
+        .or_else(|| into_dart_compatible(&func.output, "ans"));
+        let code_call_inner_func = match converted_output {
+            Some(converted) => format!("{}.map(|ans| {})", code_call_inner_func, converted),
+            None if zero_copy_bytes => format!("{}.map(ZeroCopyBuffer)", code_call_inner_func),
+            None => code_call_inner_func,
+        };
+        let code_call_inner_func = format!(
+            "{}.map_err(support::{})",
+            code_call_inner_func,
+            match &func.error_output {
+                Some(ApiFuncError::Custom(_)) => "custom_error",
+                Some(ApiFuncError::String) | None => "string_error",
+            }
+        );
+        let code_call_inner_func = if self.source_locations {
+            format!(
+                "support::with_source_location({}, move || {})",
+                source_location_const_name(func),
+                code_call_inner_func
+            )
+        } else {
+            code_call_inner_func
+        };
 
         let (handler_func_name, return_type, code_closure) = match func.mode {
             ApiFuncMode::Sync => (
@@ -257,36 +752,161 @@ impl Generator {
         )
     }
 
-    fn generate_wire_struct(&mut self, ty: &ApiType, api_file: &ApiFile) -> String {
-        // println!("generate_wire_struct: {:?}", ty);
-        let fields = match ty {
-            PrimitiveList(list) => vec![
-                format!("ptr: *mut {}", list.primitive.rust_wire_type()),
-                "len: i32".to_string(),
-            ],
-            GeneralList(list) => vec![
-                format!(
-                    "ptr: *mut {}{}",
-                    list.inner.optional_ptr_modifier(),
-                    list.inner.rust_wire_type()
-                ),
-                "len: i32".to_string(),
-            ],
-            StructRef(s) => {
-                let s = s.get(api_file);
-                s.fields
+    /// `Weak<T>` is leaked into a box when sent to Dart, which holds it until it calls the drop
+    /// function. Upgrading sends a copy of the value, so `T` must implement `Clone`.
+    fn generate_weak_funcs(&mut self, weak: &ApiTypeOpaqueWeak) -> String {
+        let upgrade_func_name = weak.upgrade_func_name();
+        let upgrade = self.extern_func_collector.generate(
+            &format!("wire_{}", upgrade_func_name),
+            &["port: i64", "weak: i64"],
+            None,
+            &format!(
+                "
+                {}.wrap(WrapInfo{{ debug_name: \"{}\", port: Some(port), mode: FfiCallMode::Normal }}, move || {{
+                    let api_weak = unsafe {{ support::clone_leaked_weak::<{}>(weak) }};
+                    move |task_callback| Ok(api_weak.upgrade().map(|arc| (*arc).clone()))
+                }})
+                ",
+                HANDLER_NAME,
+                upgrade_func_name,
+                weak.inner.rust_api_type(),
+            ),
+        );
+        let drop = self.extern_func_collector.generate(
+            &weak.drop_func_name(),
+            &["weak: i64"],
+            None,
+            &format!(
+                "unsafe {{ support::box_from_leak_ptr(weak as *mut {}); }}",
+                weak.rust_api_type()
+            ),
+        );
+        format!("{}\n{}", upgrade, drop)
+    }
+
+    /// Trait objects and opaque structs are kept in a slab while Dart holds their handles, until they are either
+    /// passed back to Rust or dropped
+    fn generate_opaque_funcs(&mut self, opaque: &ApiTypeOpaque, finalizer: bool) -> String {
+        let drop = self.extern_func_collector.generate(
+            &opaque.drop_func_name(),
+            &["handle: i64"],
+            None,
+            &format!("{}.remove(handle);", opaque.slab_name()),
+        );
+        // the `NativeFinalizer` of the Dart handle passes the handle as a pointer-sized token
+        let dispose = if finalizer {
+            self.extern_func_collector.generate(
+                &opaque.dispose_func_name(),
+                &["handle: *mut std::ffi::c_void"],
+                None,
+                &format!("{}.remove(handle as i64);", opaque.slab_name()),
+            )
+        } else {
+            "".to_string()
+        };
+        format!(
+            "support::lazy_static! {{
+                static ref {}: support::OpaqueSlab<{}> = Default::default();
+            }}
+
+            {}
+            {}",
+            opaque.slab_name(),
+            opaque.rust_api_type(),
+            drop,
+            dispose,
+        )
+    }
+
+    /// A struct marked `#[frb(on_change)]` is copied into a slab when sent to Dart, where each
+    /// setter updates the copy until the object is disposed, so it must implement `Clone`
+    fn generate_on_change_funcs(&mut self, s: &ApiStruct) -> String {
+        let setters = s
+            .fields
+            .iter()
+            .map(|field| {
+                let param = format!(
+                    "value: {}{}",
+                    field.ty.rust_wire_modifier(),
+                    field.ty.rust_wire_type()
+                );
+                self.extern_func_collector.generate(
+                    &s.on_change_setter_func_name(field),
+                    &["handle: i64", &param],
+                    None,
+                    &format!(
+                        "{}.with_mut(handle, |s| s.{} = value.wire2api());",
+                        s.on_change_slab_name(),
+                        field.name_rust_style(s.is_fields_named),
+                    ),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let drop = self.extern_func_collector.generate(
+            &s.on_change_drop_func_name(),
+            &["handle: i64"],
+            None,
+            &format!("{}.remove(handle);", s.on_change_slab_name()),
+        );
+        format!(
+            "support::lazy_static! {{
+                static ref {}: support::OpaqueSlab<{}> = Default::default();
+            }}
+
+            {}
+            {}",
+            s.on_change_slab_name(),
+            s.rust_type(),
+            setters,
+            drop,
+        )
+    }
+
+    /// Dart replies to the calls of callbacks returning values by the reply function of the type,
+    /// or by the reject function if the callback throws
+    fn generate_callback_funcs(&mut self, distinct_input_types: &[ApiType]) -> String {
+        let mut rets: Vec<&ApiTypePrimitive> = vec![];
+        for ty in distinct_input_types {
+            if let Callback(ApiTypeCallback { ret: Some(ret), .. }) = ty {
+                if !rets
                     .iter()
-                    .map(|field| {
-                        format!(
-                            "{}: {}{}",
-                            field.name.rust_style(),
-                            field.ty.rust_wire_modifier(),
-                            field.ty.rust_wire_type()
-                        )
-                    })
-                    .collect()
+                    .any(|other| other.safe_ident() == ret.safe_ident())
+                {
+                    rets.push(ret);
+                }
             }
-            Primitive(_) | Delegate(_) | Boxed(_) | Optional(_) => return "".to_string(),
+        }
+        rets.sort_by_key(|ret| ret.safe_ident());
+        if rets.is_empty() {
+            return String::new();
+        }
+
+        let mut funcs = rets
+            .into_iter()
+            .map(|ret| {
+                self.extern_func_collector.generate(
+                    &format!("wire_reply_callback_{}", ret.safe_ident()),
+                    &["call_id: i64", &format!("result: {}", ret.rust_wire_type())],
+                    None,
+                    "DartCallback::reply(call_id, result);",
+                )
+            })
+            .collect::<Vec<_>>();
+        funcs.push(self.extern_func_collector.generate(
+            ApiTypeCallback::REJECT_FUNC_NAME,
+            &["call_id: i64"],
+            None,
+            "DartCallback::reject(call_id);",
+        ));
+        funcs.join("\n\n")
+    }
+
+    fn generate_wire_struct(&mut self, ty: &ApiType, api_file: &ApiFile) -> String {
+        // println!("generate_wire_struct: {:?}", ty);
+        let fields = match wire_struct_fields(ty, api_file) {
+            Some(fields) => fields,
+            None => return "".to_string(),
         };
 
         format!(
@@ -306,7 +926,9 @@ impl Generator {
         // println!("generate_allocate_funcs: {:?}", ty);
 
         match ty {
-            Primitive(_) | Delegate(_) | Optional(_) => "".to_string(),
+            Primitive(_) | Delegate(_) | Optional(_) | OpaqueWeak(_) | Opaque(_) | Callback(_) => {
+                "".to_string()
+            }
             PrimitiveList(list) => self.extern_func_collector.generate(
                 &format!("new_{}", list.safe_ident()),
                 &["len: i32"],
@@ -325,21 +947,38 @@ impl Generator {
                     list.rust_wire_type().as_str()
                 ].concat()),
                 &format!(
-                    "let wrap = {} {{ ptr: support::new_leak_vec_ptr(<{}{}>::new_with_null_ptr(), len), len }};
+                    "let wrap = {} {{ ptr: support::new_leak_vec_ptr({}, len), len }};
                     support::new_leak_box_ptr(wrap)",
                     list.rust_wire_type(),
-                    list.inner.optional_ptr_modifier(),
-                    list.inner.rust_wire_type()
+                    wire_default_value(&list.inner),
                 ),
             ),
-            StructRef(_) => "".to_string(),
+            Map(map) => self.generate_allocate_key_value_func(ty, &map.key, &map.value),
+            AssocList(list) => self.generate_allocate_key_value_func(ty, &list.key, &list.value),
+            Tuple(tuple) => self.extern_func_collector.generate(
+                &format!("new_{}", tuple.safe_ident()),
+                &[],
+                Some(&[tuple.rust_wire_modifier(), tuple.rust_wire_type()].concat()),
+                &format!(
+                    "support::new_leak_box_ptr({} {{ {} }})",
+                    tuple.rust_wire_type(),
+                    tuple
+                        .elements
+                        .iter()
+                        .zip(tuple.field_names())
+                        .map(|(element, name)| format!("{}: {},", name, wire_default_value(element)))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ),
+            ),
+            StructRef(_) | EnumRef(_) => "".to_string(),
             Boxed(b) => {
                 match &b.inner {
-                    Primitive(prim) => {
+                    inner if b.is_primitive() => {
                         self.extern_func_collector.generate(
                             &format!("new_{}", ty.safe_ident()),
-                            &[&format!("value: {}", prim.rust_wire_type())],
-                            Some(&format!("*mut {}", prim.rust_wire_type())),
+                            &[&format!("value: {}", inner.rust_wire_type())],
+                            Some(&format!("*mut {}", inner.rust_wire_type())),
                             "support::new_leak_box_ptr(value)",
                         )
                     }
@@ -359,19 +998,113 @@ impl Generator {
         }
     }
 
+    /// See [key_value_wire_struct_fields]
+    fn generate_allocate_key_value_func(
+        &mut self,
+        ty: &ApiType,
+        key: &ApiType,
+        value: &ApiType,
+    ) -> String {
+        self.extern_func_collector.generate(
+            &format!("new_{}", ty.safe_ident()),
+            &["len: i32"],
+            Some(&[ty.rust_wire_modifier(), ty.rust_wire_type()].concat()),
+            &format!(
+                "let wrap = {} {{
+                    keys: support::new_leak_vec_ptr({}, len),
+                    values: support::new_leak_vec_ptr({}, len),
+                    len,
+                }};
+                support::new_leak_box_ptr(wrap)",
+                ty.rust_wire_type(),
+                wire_default_value(key),
+                wire_default_value(value),
+            ),
+        )
+    }
+
     fn generate_wire2api_func(&mut self, ty: &ApiType, api_file: &ApiFile) -> String {
         // println!("generate_wire2api_func: {:?}", ty);
         let body: Cow<str> = match ty {
+            // a no-op on 64-bit targets
+            Primitive(prim @ (ApiTypePrimitive::Usize | ApiTypePrimitive::Isize)) => {
+                format!("self as {}", prim.rust_api_type()).into()
+            }
             Primitive(_) => "self".into(),
             Delegate(d) => match d {
                 ApiTypeDelegate::String => "let vec: Vec<u8> = self.wire2api();
                 String::from_utf8_lossy(&vec).into_owned()"
                     .into(),
-                ApiTypeDelegate::SyncReturnVecU8 => "/*unsupported*/".into(),
-                ApiTypeDelegate::ZeroCopyBufferVecPrimitive(_) => {
+                ApiTypeDelegate::SyncReturnVecU8 | ApiTypeDelegate::SyncReturnString => {
+                    "/*unsupported*/".into()
+                }
+                ApiTypeDelegate::ZeroCopyBufferVec(_) | ApiTypeDelegate::ZeroCopyBufferVecString => {
                     "ZeroCopyBuffer(self.wire2api())".into()
                 }
+                ApiTypeDelegate::Range(_, false) => "let vec: Vec<_> = self.wire2api();
+                vec[0]..vec[1]"
+                    .into(),
+                ApiTypeDelegate::Range(_, true) => "let vec: Vec<_> = self.wire2api();
+                vec[0]..=vec[1]"
+                    .into(),
+                // the elements are moved out of the buffer filled by Dart, see `PrimitiveList`
+                ApiTypeDelegate::Array(array) => format!(
+                    "let vec: Vec<{}> = self.wire2api();
+                    std::convert::TryInto::try_into(vec).unwrap_or_else(|vec: Vec<_>| {{
+                        panic!(\"unexpected arr length: expect {} but see {{}}\", vec.len())
+                    }})",
+                    array.element.rust_api_type(),
+                    array.length
+                )
+                .into(),
+                ApiTypeDelegate::Set(set) => format!(
+                    "{}
+                    let vec: Vec<{}> = self.wire2api();
+                    vec.into_iter().collect()",
+                    set_element_assertion(set),
+                    set.element.rust_api_type()
+                )
+                .into(),
+                ApiTypeDelegate::Chrono(chrono) => format!(
+                    "support::timestamp_from_millis(self){}",
+                    match chrono {
+                        ApiTypeChrono::Utc => "",
+                        ApiTypeChrono::Local => ".with_timezone(&chrono::Local)",
+                        ApiTypeChrono::NaiveDate => ".date_naive()",
+                        ApiTypeChrono::NaiveDateTime => ".naive_utc()",
+                    }
+                )
+                .into(),
+                ApiTypeDelegate::Uuid(_) => "let bytes: [u8; 16] = self.wire2api();
+                support::uuid_from_bytes(bytes)"
+                    .into(),
+                ApiTypeDelegate::I128 => "let vec: Vec<i64> = self.wire2api();
+                ((vec[0] as i128) << 64) | (vec[1] as u64 as i128)"
+                    .into(),
+                ApiTypeDelegate::U128 => "let vec: Vec<i64> = self.wire2api();
+                ((vec[0] as u64 as u128) << 64) | (vec[1] as u64 as u128)"
+                    .into(),
+                ApiTypeDelegate::CowBytes => "let vec: Vec<u8> = self.wire2api();
+                std::borrow::Cow::Owned(vec)"
+                    .into(),
+                ApiTypeDelegate::CowStr => "let vec: Vec<u8> = self.wire2api();
+                std::borrow::Cow::Owned(String::from_utf8_lossy(&vec).into_owned())"
+                    .into(),
+                ApiTypeDelegate::DynError(_) => "let vec: Vec<u8> = self.wire2api();
+                Box::<dyn std::error::Error + Send + Sync>::from(String::from_utf8_lossy(&vec).into_owned())"
+                    .into(),
+                // received as the delegate, see [generate_wire_func]
+                ApiTypeDelegate::StringRef | ApiTypeDelegate::SliceU8 => return String::new(),
+                ApiTypeDelegate::NonNull(inner) => format!(
+                    "// SAFETY: caller guarantees pointer validity
+                    std::ptr::NonNull::new(self as *mut {}).expect(\"received a null pointer for NonNull\")",
+                    inner
+                )
+                .into(),
             },
+            // NOTE inputs such as `Vec<u8>` are already zero-copy: the buffer is allocated by Rust
+            // (see `new_uint_8_list`), filled by Dart, and here we take back its ownership without
+            // copying. Copying via `slice::from_raw_parts(..).to_vec()` would instead leak it.
             PrimitiveList(_) => "unsafe {
                 let wrap = support::box_from_leak_ptr(self);
                 support::vec_from_leak_ptr(wrap.ptr, wrap.len)
@@ -384,11 +1117,50 @@ impl Generator {
             };
             vec.into_iter().map(Wire2Api::wire2api).collect()"
                 .into(),
-            Boxed(_) => "let wrap = unsafe { support::box_from_leak_ptr(self) };
-            (*wrap).wire2api().into()"
+            // collected into a map or a list of pairs
+            Map(_) | AssocList(_) => "
+            let (keys, values) = unsafe {
+                let wrap = support::box_from_leak_ptr(self);
+                (
+                    support::vec_from_leak_ptr(wrap.keys, wrap.len),
+                    support::vec_from_leak_ptr(wrap.values, wrap.len),
+                )
+            };
+            keys.into_iter()
+                .map(Wire2Api::wire2api)
+                .zip(values.into_iter().map(Wire2Api::wire2api))
+                .collect()"
                 .into(),
-            StructRef(struct_ref) => {
-                let api_struct = struct_ref.get(api_file);
+            Tuple(tuple) => format!(
+                "let wrap = unsafe {{ support::box_from_leak_ptr(self) }};
+                ({})",
+                tuple
+                    .field_names()
+                    .map(|name| format!("wrap.{}.wire2api(),", name))
+                    .collect::<String>()
+            )
+            .into(),
+            // the wire type may convert into several types, e.g. `u64` into `u64` and `usize`
+            Boxed(boxed) => format!(
+                "let wrap = unsafe {{ support::box_from_leak_ptr(self) }};
+                Wire2Api::<{}>::wire2api(*wrap).into()",
+                boxed.inner.rust_api_type()
+            )
+            .into(),
+            // the box is still owned by Dart, so only clone the `Weak<T>` inside
+            OpaqueWeak(weak) => format!(
+                "unsafe {{ support::clone_leaked_weak::<{}>(self) }}",
+                weak.inner.rust_api_type()
+            )
+            .into(),
+            Opaque(opaque) => format!(
+                "{}.remove(self).unwrap_or_else(|| panic!(\"opaque handle {{}} is already released\", self))",
+                opaque.slab_name()
+            )
+            .into(),
+            Callback(callback) => return generate_wire2api_callback(callback),
+            StructRef(struct_ref) => {
+                let api_struct = struct_ref.get(api_file);
 
                 let fields_str = &api_struct
                     .fields
@@ -406,14 +1178,57 @@ impl Generator {
                     })
                     .collect::<Vec<_>>()
                     .join(",");
+                // e.g. `ApiResponse::<String>` of a generic struct
+                let constructor = ty.rust_api_type().replacen('<', "::<", 1);
 
-                if api_struct.is_fields_named {
-                    format!("{} {{ {} }}", ty.rust_api_type(), fields_str)
+                if api_struct.has_private_fields() && !api_struct.implements_default {
+                    panic!(
+                        "struct {} has private fields, so it needs `#[derive(Default)]` or \
+                        `impl Default` to be constructed from Dart",
+                        api_struct.name
+                    )
+                } else if api_struct.is_fields_named {
+                    format!(
+                        "{} {{ {}{} }}",
+                        constructor,
+                        fields_str,
+                        // private fields are not bridged, so fill them with their defaults
+                        if api_struct.has_private_fields() {
+                            ", ..Default::default()"
+                        } else {
+                            ""
+                        }
+                    )
+                } else if api_struct.has_private_fields() {
+                    panic!(
+                        "tuple struct {} has private fields, so it cannot be constructed from Dart",
+                        api_struct.name
+                    )
                 } else {
-                    format!("{}({})", ty.rust_api_type(), fields_str)
+                    format!("{}({})", constructor, fields_str)
                 }
                 .into()
             }
+            EnumRef(enum_ref) => {
+                let api_enum = enum_ref.get(api_file);
+                let variants = api_enum
+                    .variants
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, variant)| {
+                        format!("{} => {}::{},", idx, api_enum.name, variant.name.rust_style())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!(
+                    "match self {{
+                        {}
+                        _ => unreachable!(\"Invalid variant for {}: {{}}\", self),
+                    }}",
+                    variants, api_enum.name
+                )
+                .into()
+            }
             // handled by common impl
             Optional(_) => return String::new(),
         };
@@ -436,8 +1251,9 @@ impl Generator {
         match ty {
             StructRef(st) => self
                 .generate_new_with_nullptr_func_for_struct(st.get(api_file), &ty.rust_wire_type()),
-            Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | Boxed(_)
-            | Optional(_) => String::new(),
+            Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | AssocList(_)
+            | Map(_) | Tuple(_) | EnumRef(_) | Boxed(_) | OpaqueWeak(_) | Opaque(_)
+            | Callback(_) | Optional(_) => String::new(),
         }
     }
 
@@ -445,11 +1261,50 @@ impl Generator {
         // println!("generate_impl_intodart: {:?}", ty);
         match ty {
             StructRef(s) => self.generate_impl_intodart_for_struct(s.get(api_file)),
-            Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | Boxed(_)
+            EnumRef(e) => self.generate_impl_intodart_for_enum(e.get(api_file)),
+            Primitive(_) | Delegate(_) | PrimitiveList(_) | GeneralList(_) | AssocList(_)
+            | Map(_) | Tuple(_) | Boxed(_) | OpaqueWeak(_) | Opaque(_) | Callback(_)
             | Optional(_) => "".to_string(),
         }
     }
 
+    /// Converts each field with `into()`, so that fields may be mirrors of fields as well
+    fn generate_mirror_conversions(&self, s: &ApiStruct, mirror: &str) -> String {
+        let construct = |source: &str| {
+            let fields = s.fields.iter().map(|field| {
+                let name = field.name_rust_style(s.is_fields_named);
+                if s.is_fields_named {
+                    format!("{}: {}.{}.into(),", name, source, name)
+                } else {
+                    format!("{}.{}.into(),", source, name)
+                }
+            });
+            if s.is_fields_named {
+                format!("Self {{ {} }}", fields.collect::<Vec<_>>().join("\n"))
+            } else {
+                format!("Self({})", fields.collect::<Vec<_>>().join(" "))
+            }
+        };
+
+        format!(
+            "impl From<{mirror}> for {name} {{
+                fn from(mirrored: {mirror}) -> Self {{
+                    {from_mirrored}
+                }}
+            }}
+
+            impl From<{name}> for {mirror} {{
+                fn from(mirror: {name}) -> Self {{
+                    {from_mirror}
+                }}
+            }}",
+            mirror = mirror,
+            name = s.rust_type(),
+            from_mirrored = construct("mirrored"),
+            from_mirror = construct("mirror"),
+        )
+    }
+
     fn generate_new_with_nullptr_func_for_struct(
         &self,
         s: &ApiStruct,
@@ -488,13 +1343,24 @@ impl Generator {
             .fields
             .iter()
             .map(|field| {
+                let expr = format!("self.{}", field.name_rust_style(s.is_fields_named));
                 format!(
-                    "self.{}.into_dart()",
-                    field.name_rust_style(s.is_fields_named)
+                    "{}.into_dart()",
+                    into_dart_compatible(&field.ty, &expr).unwrap_or(expr)
                 )
             })
             .collect::<Vec<_>>()
             .join(",\n");
+        // the handle of the copy, which the Dart setters update
+        let body = if s.notify_on_change {
+            format!(
+                "{}.insert(self.clone()).into_dart(),\n{}",
+                s.on_change_slab_name(),
+                body
+            )
+        } else {
+            body
+        };
 
         format!(
             "impl support::IntoDart for {} {{
@@ -506,18 +1372,350 @@ impl Generator {
             }}
             impl support::IntoDartExceptPrimitive for {} {{}}
             ",
-            s.name, body, s.name,
+            s.rust_type(),
+            body,
+            s.rust_type(),
+        )
+    }
+
+    fn generate_impl_intodart_for_enum(&mut self, e: &ApiEnum) -> String {
+        let variants = e
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(idx, variant)| match &variant.kind {
+                ApiVariantKind::Value if !e.is_struct() => {
+                    format!("Self::{} => {},", variant.name.rust_style(), idx)
+                }
+                ApiVariantKind::Value => {
+                    format!(
+                        "Self::{} => vec![{}.into_dart()],",
+                        variant.name.rust_style(),
+                        idx
+                    )
+                }
+                ApiVariantKind::Struct(st) => {
+                    let names = st
+                        .fields
+                        .iter()
+                        .map(|field| field.name.rust_style())
+                        .collect::<Vec<_>>();
+                    let pattern = if st.is_fields_named {
+                        format!("{{ {} }}", names.join(", "))
+                    } else {
+                        format!("({})", names.join(", "))
+                    };
+                    let fields = st
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            let name = field.name.rust_style();
+                            format!(
+                                "{}.into_dart()",
+                                into_dart_compatible(&field.ty, name)
+                                    .unwrap_or_else(|| name.to_string())
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    format!(
+                        "Self::{}{} => vec![{}.into_dart(), {}],",
+                        variant.name.rust_style(),
+                        pattern,
+                        idx,
+                        fields.join(", ")
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "impl support::IntoDart for {} {{
+                fn into_dart(self) -> support::DartCObject {{
+                    match self {{
+                        {}
+                    }}.into_dart()
+                }}
+            }}
+            impl support::IntoDartExceptPrimitive for {} {{}}
+            ",
+            e.name, variants, e.name,
         )
     }
 }
 
+/// A closure posting its arguments to the native port of the Dart function, see `DartCallback`
+/// of `flutter_rust_bridge`. The port is 0 for `null` if the callback is optional.
+/// Not an impl of `Wire2Api`, which may already exist for an opaque handle of the same type.
+fn generate_wire2api_callback(callback: &ApiTypeCallback) -> String {
+    let names: Vec<_> = (0..callback.args.len())
+        .map(|idx| format!("arg{}", idx))
+        .collect();
+    let params = callback
+        .args
+        .iter()
+        .zip(&names)
+        .map(|(arg, name)| format!("{}: {}", name, arg.rust_api_type()))
+        .collect::<Vec<_>>();
+    let args = callback
+        .args
+        .iter()
+        .zip(&names)
+        .map(|(arg, name)| into_dart_object(arg, name))
+        .collect::<Vec<_>>();
+    let call = match &callback.ret {
+        Some(ret) => {
+            let call = format!(
+                "callback.call::<{}>(vec![{}])",
+                ret.rust_wire_type(),
+                args.join(", ")
+            );
+            let call = if ret.rust_api_type() != ret.rust_wire_type() {
+                format!("{}.map(|result| result as {})", call, ret.rust_api_type())
+            } else {
+                call
+            };
+            if callback.ret_is_result {
+                call
+            } else {
+                format!("{}.unwrap_or_else(|err| panic!(\"{{}}\", err))", call)
+            }
+        }
+        None => format!("callback.post(vec![{}]);", args.join(", ")),
+    };
+    format!(
+        "fn wire2api_{}(port: i64) -> {} {{
+            {}let callback = DartCallback::new(port);
+            let func: {} = Box::new(move |{}| {{ {} }});
+            {}
+        }}
+        ",
+        callback.safe_ident(),
+        callback.rust_api_type(),
+        if callback.is_optional {
+            "if port == 0 {
+                return None;
+            }
+            "
+        } else {
+            ""
+        },
+        callback.rust_box_type,
+        params.join(", "),
+        call,
+        if callback.is_optional {
+            "Some(func)"
+        } else {
+            "func"
+        },
+    )
+}
+
+/// e.g. `SOURCE_LOCATION_NORM` of the location of `norm`, see [ApiFunc::source_location]
+fn source_location_const_name(func: &ApiFunc) -> String {
+    format!("SOURCE_LOCATION_{}", func.name.to_uppercase())
+}
+
+/// The outputs of `#[frb(sync)]` functions are returned like `SyncReturn<Vec<u8>>`, as the
+/// little-endian bytes of primitives or the UTF-8 bytes of strings
+fn into_sync_return(ty: &ApiType, expr: &str) -> Option<String> {
+    Some(match ty {
+        Primitive(ApiTypePrimitive::Bool) => format!("SyncReturn(vec![{} as u8])", expr),
+        Primitive(ApiTypePrimitive::Usize) => {
+            format!("SyncReturn(({} as u64).to_le_bytes().to_vec())", expr)
+        }
+        Primitive(ApiTypePrimitive::Isize) => {
+            format!("SyncReturn(({} as i64).to_le_bytes().to_vec())", expr)
+        }
+        Primitive(_) => format!("SyncReturn({}.to_le_bytes().to_vec())", expr),
+        Delegate(ApiTypeDelegate::String) => format!("SyncReturn({}.into_bytes())", expr),
+        PrimitiveList(_) => format!("SyncReturn({})", expr),
+        _ => return None,
+    })
+}
+
+/// Some types cannot implement `IntoDart` (e.g. types from std), so convert them into
+/// one that can. Returns [None] if [expr] can be used as is.
+fn into_dart_compatible(ty: &ApiType, expr: &str) -> Option<String> {
+    match ty {
+        // Dart integers are 64-bit signed, and are sent as such
+        Primitive(ApiTypePrimitive::Usize | ApiTypePrimitive::Isize) => {
+            Some(format!("({} as i64)", expr))
+        }
+        Delegate(ApiTypeDelegate::NonNull(_)) => Some(format!(
+            "/* SAFETY: caller guarantees pointer validity */ {}.as_ptr() as i64",
+            expr
+        )),
+        Delegate(ApiTypeDelegate::DynError(_)) => Some(format!("{}.to_string()", expr)),
+        OpaqueWeak(_) => Some(format!("support::new_leak_box_ptr({}) as i64", expr)),
+        Opaque(opaque) => Some(format!("{}.insert({})", opaque.slab_name(), expr)),
+        Delegate(ApiTypeDelegate::CowBytes) => {
+            Some(format!("ZeroCopyBuffer({}.into_owned())", expr))
+        }
+        Delegate(ApiTypeDelegate::CowStr) => Some(format!("{}.into_owned()", expr)),
+        // not `IntoDart`, but the bytes returned by `Handler::wrap_sync`
+        Delegate(ApiTypeDelegate::SyncReturnString) => {
+            Some(format!("SyncReturn({}.0.into_bytes())", expr))
+        }
+        Delegate(ApiTypeDelegate::ZeroCopyBufferVec(inner)) if !matches!(**inner, Primitive(_)) => {
+            Some(format!("support::zero_copy_frames({}.0)", expr))
+        }
+        Delegate(ApiTypeDelegate::ZeroCopyBufferVecString) => {
+            Some(format!("support::zero_copy_strings({}.0)", expr))
+        }
+        Delegate(ApiTypeDelegate::Range(_, false)) => {
+            Some(format!("vec![{}.start, {}.end]", expr, expr))
+        }
+        Delegate(ApiTypeDelegate::Range(_, true)) => Some(format!(
+            "{{ let (start, end) = {}.into_inner(); vec![start, end] }}",
+            expr
+        )),
+        Delegate(ApiTypeDelegate::Chrono(ApiTypeChrono::Utc | ApiTypeChrono::Local)) => {
+            Some(format!("{}.timestamp_millis()", expr))
+        }
+        Delegate(ApiTypeDelegate::Chrono(_)) => {
+            Some(format!("support::naive_timestamp_millis({})", expr))
+        }
+        Delegate(ApiTypeDelegate::Uuid(_)) => Some(format!("support::uuid_to_bytes({})", expr)),
+        Delegate(ApiTypeDelegate::I128 | ApiTypeDelegate::U128) => Some(format!(
+            "{{ let value = {}; vec![(value >> 64) as i64, value as i64] }}",
+            expr
+        )),
+        // moved into a `Vec<T>` once, whose buffer Dart then uses as typed data without a copy
+        Delegate(ApiTypeDelegate::Array(array))
+            if matches!(&array.element, Primitive(primitive) if !matches!(
+                primitive,
+                ApiTypePrimitive::Bool | ApiTypePrimitive::Usize | ApiTypePrimitive::Isize
+            )) =>
+        {
+            Some(format!("ZeroCopyBuffer(Vec::from({}))", expr))
+        }
+        Delegate(d @ ApiTypeDelegate::Array(_)) => {
+            let vec = format!("Vec::from({})", expr);
+            Some(into_dart_compatible(&d.get_delegate(), &vec).unwrap_or(vec))
+        }
+        Delegate(d @ ApiTypeDelegate::Set(set)) => {
+            let vec = format!("{}.into_iter().collect::<Vec<_>>()", expr);
+            Some(format!(
+                "{{ {} {} }}",
+                set_element_assertion(set),
+                into_dart_compatible(&d.get_delegate(), &vec).unwrap_or(vec)
+            ))
+        }
+        AssocList(list) => Some(into_dart_interleaved(&list.key, &list.value, expr)),
+        Map(map) => Some(into_dart_interleaved(&map.key, &map.value, expr)),
+        Tuple(tuple) => {
+            let names = tuple.field_names().collect::<Vec<_>>();
+            Some(format!(
+                "{{ let ({}) = {}; vec![{}] }}",
+                names.join(", "),
+                expr,
+                tuple
+                    .elements
+                    .iter()
+                    .zip(&names)
+                    .map(|(element, name)| into_dart_object(element, name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        }
+        // `Vec<T>` is `IntoDart` only if `T: IntoDartExceptPrimitive`, which no `Vec` is
+        GeneralList(list)
+            if matches!(list.inner, PrimitiveList(_) | GeneralList(_))
+                || into_dart_compatible(&list.inner, "inner").is_some() =>
+        {
+            Some(format!(
+                "{}.into_iter().map(|inner| {}).collect::<Vec<_>>()",
+                expr,
+                into_dart_object(&list.inner, "inner")
+            ))
+        }
+        Optional(opt) => into_dart_compatible(&opt.inner, "inner")
+            .map(|converted| format!("{}.map(|inner| {})", expr, converted)),
+        // `Box<T>` is not `IntoDart`, e.g. in fields of recursive structs
+        Boxed(boxed) if boxed.exist_in_real_api => {
+            let unboxed = format!("(*{})", expr);
+            Some(into_dart_compatible(&boxed.inner, &unboxed).unwrap_or(unboxed))
+        }
+        // e.g. `Option<usize>`, which is boxed only on the wire
+        Boxed(boxed) => into_dart_compatible(&boxed.inner, expr),
+        _ => None,
+    }
+}
+
+/// Fails to compile if the element type cannot be in the set, pointing at the element type
+/// instead of somewhere in the conversion
+fn set_element_assertion(set: &ApiTypeSet) -> String {
+    format!(
+        "const _: fn() = support::assert_{}_element::<{}>;",
+        if set.is_ordered {
+            "b_tree_set"
+        } else {
+            "hash_set"
+        },
+        set.element.rust_api_type()
+    )
+}
+
+/// Opaque handles and `Box<dyn Error>`, possibly optional
+fn is_trait_object(ty: &ApiType) -> bool {
+    match ty {
+        Opaque(_) | Delegate(ApiTypeDelegate::DynError(_)) => true,
+        Optional(opt) => matches!(&*opt.inner, Delegate(ApiTypeDelegate::DynError(_))),
+        _ => false,
+    }
+}
+
+fn is_borrowed(ty: &ApiType) -> bool {
+    matches!(ty, Delegate(d) if d.is_borrowed())
+}
+
+/// e.g. `[(1, "a"), (2, "b")]` => `[1, "a", 2, "b"]`
+fn into_dart_interleaved(key: &ApiType, value: &ApiType, expr: &str) -> String {
+    format!(
+        "{}.into_iter().flat_map(|(key, value)| vec![{}, {}]).collect::<Vec<_>>()",
+        expr,
+        into_dart_object(key, "key"),
+        into_dart_object(value, "value")
+    )
+}
+
+/// Converts to a `DartCObject`. Calls the trait method explicitly since `IntoDart` may not be in scope.
+fn into_dart_object(ty: &ApiType, expr: &str) -> String {
+    format!(
+        "support::IntoDart::into_dart({})",
+        into_dart_compatible(ty, expr).unwrap_or_else(|| expr.to_string())
+    )
+}
+
+/// The initial value of an element in an array allocated for Dart to fill
+fn wire_default_value(ty: &ApiType) -> String {
+    match ty {
+        Primitive(_) | EnumRef(_) => "Default::default()".to_string(),
+        // e.g. `chrono` types, which are passed as integers
+        Delegate(d) if !d.rust_wire_is_pointer() => "Default::default()".to_string(),
+        _ => format!(
+            "<{}{}>::new_with_null_ptr()",
+            ty.rust_wire_modifier(),
+            ty.rust_wire_type()
+        ),
+    }
+}
+
+pub struct ExternFunc {
+    pub name: String,
+    /// e.g. `port: i64`
+    pub params: Vec<String>,
+    pub return_type: Option<String>,
+}
+
 struct ExternFuncCollector {
-    names: Vec<String>,
+    funcs: Vec<ExternFunc>,
 }
 
 impl ExternFuncCollector {
     fn new() -> Self {
-        ExternFuncCollector { names: vec![] }
+        ExternFuncCollector { funcs: vec![] }
     }
 
     fn generate(
@@ -527,7 +1725,11 @@ impl ExternFuncCollector {
         return_type: Option<&str>,
         body: &str,
     ) -> String {
-        self.names.push(func_name.to_string());
+        self.funcs.push(ExternFunc {
+            name: func_name.to_string(),
+            params: params.iter().map(|param| param.to_string()).collect(),
+            return_type: return_type.map(str::to_string),
+        });
 
         format!(
             r#"
@@ -543,3 +1745,807 @@ impl ExternFuncCollector {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DartUuidStyle, IncludedVisibility};
+    use crate::{parser, transformer};
+
+    /// [parser::parse_source] of [source] after [transformer::transform]
+    fn transform_source(source: &str) -> ApiFile {
+        transformer::transform(parser::parse_source(source), true)
+    }
+
+    fn generate_wire_funcs(api_file: &ApiFile) -> String {
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        api_file
+            .funcs
+            .iter()
+            .map(|func| generator.generate_wire_func(func))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// The options of a crate in a temporary directory, whose `src/api.rs` is [source]
+    fn opts_of(source: &str) -> (tempfile::TempDir, Opts) {
+        let crate_dir = tempfile::tempdir().unwrap();
+        let path = |sub_path: &str| {
+            crate_dir
+                .path()
+                .join(sub_path)
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+        std::fs::create_dir_all(path("src")).unwrap();
+        std::fs::write(path("src/api.rs"), source).unwrap();
+        let opts = crate::config::parse(crate::config::RawOpts {
+            rust_input: Some(path("src/api.rs")),
+            dart_output: Some(path("bridge_generated.dart")),
+            rust_crate_dir: Some(path("")),
+            class_name: Some("Api".to_string()),
+            llvm_path: Some("".to_string()),
+            ..Default::default()
+        });
+        (crate_dir, opts)
+    }
+
+    fn account_type(api_file: &ApiFile) -> ApiType {
+        api_file
+            .distinct_types(true, false)
+            .into_iter()
+            .find(|ty| matches!(ty, StructRef(s) if s.name == "Account"))
+            .unwrap()
+    }
+
+    #[test]
+    fn callbacks_return_errors_of_dart_only_if_declared() {
+        let source = "
+            pub fn apply(f: impl Fn(i32) -> Result<u64>, x: i32) -> Result<u64> {
+                f(x)
+            }
+
+            pub fn check(f: impl Fn(i32) -> bool, x: i32) -> Result<bool> {
+                Ok(f(x))
+            }
+            ";
+        let (_crate_dir, opts) = opts_of(source);
+        let code = generate(&transform_source(source), &opts).code;
+        assert!(code.contains(
+            "Box::new(move |arg0: i32| { callback.call::<u64>(vec![support::IntoDart::into_dart(arg0)]) })"
+        ));
+        assert!(code.contains(
+            "Box::new(move |arg0: i32| { callback.call::<bool>(vec![support::IntoDart::into_dart(arg0)])\
+            .unwrap_or_else(|err| panic!(\"{}\", err)) })"
+        ));
+    }
+
+    #[test]
+    fn annotates_into_params() {
+        let api_file = transform_source(
+            "
+            pub fn greet(name: impl Into<String>, #[frb(into)] data: Vec<u8>) -> Result<String> {
+                Ok(name.into())
+            }
+            ",
+        );
+        let code = generate_wire_funcs(&api_file);
+        assert!(code.contains("let api_name: String = name.wire2api();"));
+        assert!(code.contains("let api_data: Vec<u8> = data.wire2api();"));
+    }
+
+    #[test]
+    fn generates_wire_code_of_nested_lists() {
+        let api_file = transform_source(
+            "
+            pub fn nested(
+                ints: Vec<Vec<i32>>,
+                strings: Vec<Vec<String>>,
+                optional_ints: Vec<Option<Vec<i32>>>,
+            ) -> Result<Vec<Vec<i32>>> {
+                Ok(ints)
+            }
+            ",
+        );
+        let inputs = &api_file.funcs[0].inputs;
+        for (ty, ptr) in [
+            (&inputs[0].ty, "ptr: *mut *mut wire_int_32_list"),
+            (&inputs[1].ty, "ptr: *mut *mut wire_list_String"),
+            (&inputs[2].ty, "ptr: *mut *mut wire_int_32_list"),
+        ] {
+            assert_eq!(
+                wire_struct_fields(ty, &api_file).unwrap(),
+                [ptr.to_string(), "len: i32".to_string()]
+            );
+        }
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let allocate = generator.generate_allocate_funcs(&inputs[2].ty);
+        assert!(allocate.contains(
+            "support::new_leak_vec_ptr(<*mut wire_int_32_list>::new_with_null_ptr(), len)"
+        ));
+        let wire2api = generator.generate_wire2api_func(&inputs[1].ty, &api_file);
+        assert!(wire2api.contains("impl Wire2Api<Vec<Vec<String>>> for *mut wire_list_list_String"));
+        assert!(wire2api.contains("vec.into_iter().map(Wire2Api::wire2api).collect()"));
+
+        assert!(generate_wire_funcs(&api_file).contains(
+            ".map(|ans| ans.into_iter().map(|inner| support::IntoDart::into_dart(inner)).collect::<Vec<_>>())"
+        ));
+    }
+
+    #[test]
+    fn sends_arrays_of_numbers_without_a_copy() {
+        let api_file = transform_source(
+            "
+            pub fn rgba(rgba: [u8; 4]) -> Result<[u8; 4]> {
+                Ok(rgba)
+            }
+
+            pub fn names() -> Result<[String; 2]> {
+                Ok([\"a\".to_string(), \"b\".to_string()])
+            }
+            ",
+        );
+        let code = generate_wire_funcs(&api_file);
+        assert!(code.contains("rgba(api_rgba).map(|ans| ZeroCopyBuffer(Vec::from(ans)))"));
+        assert!(code.contains("names().map(|ans| Vec::from(ans))"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let wire2api = generator.generate_wire2api_func(&api_file.funcs[0].inputs[0].ty, &api_file);
+        assert!(wire2api.contains("impl Wire2Api<[u8; 4]> for *mut wire_uint_8_list"));
+        assert!(
+            wire2api.contains("panic!(\"unexpected arr length: expect 4 but see {}\", vec.len())")
+        );
+    }
+
+    #[test]
+    fn receives_ranges_as_start_and_end() {
+        let api_file = transform_source(
+            "
+            use std::ops::Range;
+
+            pub fn clamp_values(data: Vec<f64>, range: Range<f64>) -> Result<Vec<f64>> {
+                Ok(data
+                    .into_iter()
+                    .map(|value| value.max(range.start).min(range.end))
+                    .collect())
+            }
+            ",
+        );
+        let range = &api_file.funcs[0].inputs[1].ty;
+        assert!(matches!(
+            range,
+            Delegate(ApiTypeDelegate::Range(ApiTypePrimitive::F64, false))
+        ));
+        assert!(generate_wire_funcs(&api_file).contains(
+            "pub extern \"C\" fn wire_clamp_values(port: i64, data: *mut wire_float_64_list, range: *mut wire_float_64_list)"
+        ));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let wire2api = generator.generate_wire2api_func(range, &api_file);
+        assert!(
+            wire2api.contains("impl Wire2Api<std::ops::Range<f64>> for *mut wire_float_64_list")
+        );
+        assert!(wire2api.contains("vec[0]..vec[1]"));
+    }
+
+    #[test]
+    fn sends_borrowed_and_owned_cows_as_owned_values() {
+        let api_file = transform_source(
+            "
+            use std::borrow::Cow;
+
+            pub fn handle_cow_bytes(borrowed: bool) -> Result<Cow<'static, [u8]>> {
+                Ok(if borrowed {
+                    Cow::Borrowed(&[1, 2, 3])
+                } else {
+                    Cow::Owned(vec![2, 4, 6])
+                })
+            }
+
+            pub fn handle_cow_str(text: std::borrow::Cow<'_, str>, borrowed: bool) -> Result<Cow<'static, str>> {
+                Ok(if borrowed {
+                    Cow::Borrowed(\"borrowed\")
+                } else {
+                    Cow::Owned(format!(\"{}, owned\", text))
+                })
+            }
+            ",
+        );
+        assert!(matches!(
+            api_file.funcs[0].output,
+            Delegate(ApiTypeDelegate::CowBytes)
+        ));
+        let text = &api_file.funcs[1].inputs[0].ty;
+        assert!(matches!(text, Delegate(ApiTypeDelegate::CowStr)));
+        assert!(matches!(
+            api_file.funcs[1].output,
+            Delegate(ApiTypeDelegate::CowStr)
+        ));
+
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs.contains(".map(|ans| ZeroCopyBuffer(ans.into_owned()))"));
+        assert!(wire_funcs.contains(".map(|ans| ans.into_owned())"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let wire2api = generator.generate_wire2api_func(text, &api_file);
+        assert!(wire2api
+            .contains("impl Wire2Api<std::borrow::Cow<'static, str>> for *mut wire_uint_8_list"));
+        assert!(wire2api
+            .contains("std::borrow::Cow::Owned(String::from_utf8_lossy(&vec).into_owned())"));
+    }
+
+    #[test]
+    fn sends_assoc_lists_as_alternating_keys_and_values() {
+        let api_file = transform_source(
+            "
+            pub fn word_counts(text: String) -> Result<Vec<(String, u32)>> {
+                let mut counts: Vec<(String, u32)> = Vec::new();
+                for word in text.split_whitespace() {
+                    match counts.iter_mut().find(|(known, _)| known == word) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((word.to_string(), 1)),
+                    }
+                }
+                Ok(counts)
+            }
+            ",
+        );
+        match &api_file.funcs[0].output {
+            AssocList(list) => {
+                assert!(matches!(list.key, Delegate(ApiTypeDelegate::String)));
+                assert!(matches!(list.value, Primitive(ApiTypePrimitive::U32)));
+            }
+            ty => panic!("not an assoc list: {:?}", ty),
+        }
+        assert!(generate_wire_funcs(&api_file).contains(
+            ".flat_map(|(key, value)| vec![support::IntoDart::into_dart(key), support::IntoDart::into_dart(value)])"
+        ));
+    }
+
+    #[test]
+    fn keeps_copies_of_change_notifiers() {
+        let api_file = transform_source(
+            "
+            #[frb(on_change)]
+            pub struct Counter {
+                pub count: i32,
+            }
+
+            pub fn make_counter() -> Result<Counter> {
+                Ok(Counter { count: 0 })
+            }
+            ",
+        );
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let counter = &api_file.struct_pool["Counter"];
+        let code = format!(
+            "{}\n{}",
+            generator.generate_on_change_funcs(counter),
+            generator.generate_impl_intodart_for_struct(counter)
+        );
+        assert!(code.contains("ON_CHANGE_COUNTER.insert(self.clone()).into_dart(),"));
+        assert!(
+            code.contains("pub extern \"C\" fn wire_set_counter_count(handle: i64, value: i32)")
+        );
+        assert!(
+            code.contains("ON_CHANGE_COUNTER.with_mut(handle, |s| s.count = value.wire2api());")
+        );
+        assert!(code.contains("ON_CHANGE_COUNTER.remove(handle);"));
+    }
+
+    #[test]
+    fn fills_private_fields_with_defaults() {
+        let api_file = transform_source(
+            "
+            #[derive(Default)]
+            pub struct Account {
+                pub name: String,
+                secret: String,
+            }
+
+            pub fn rename(account: Account) -> Result<Account> {
+                Ok(account)
+            }
+            ",
+        );
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let code = generator.generate_wire2api_func(&account_type(&api_file), &api_file);
+        assert!(code.contains("Account { name:  self.name.wire2api(), ..Default::default() }"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "struct Account has private fields, so it needs `#[derive(Default)]` or `impl Default` to be constructed from Dart"
+    )]
+    fn rejects_inputs_with_private_fields_but_no_default() {
+        let api_file = transform_source(
+            "
+            pub struct Account {
+                pub name: String,
+                secret: String,
+            }
+
+            pub fn rename(account: Account) -> Result<Account> {
+                Ok(account)
+            }
+            ",
+        );
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        generator.generate_wire2api_func(&account_type(&api_file), &api_file);
+    }
+
+    #[test]
+    fn generates_single_functions_and_structs_as_in_the_whole_file() {
+        let source = "
+            pub struct Point {
+                pub x: i32,
+                pub y: i32,
+            }
+
+            #[frb(on_change)]
+            #[derive(Clone)]
+            pub struct Counter {
+                pub count: i32,
+            }
+
+            pub fn counter_at(point: Point) -> Result<Counter> {
+                Ok(Counter { count: point.x })
+            }
+        ";
+        let (_crate_dir, opts) = opts_of(source);
+        let api_file = transform_source(source);
+        let whole = generate(&api_file, &opts);
+
+        let func = generate_rust_func(&api_file.funcs[0], &opts);
+        assert!(whole.code.contains(&func.code));
+        assert_eq!(func.extern_func_names, vec!["wire_counter_at"]);
+
+        let point = generate_rust_struct(&api_file.struct_pool["Point"], &api_file, &opts);
+        for code in [
+            &point.wire_struct,
+            &point.wire2api_func,
+            &point.new_with_nullptr_func,
+        ] {
+            assert!(code.contains("wire_Point"));
+            assert!(whole.code.contains(code.as_str()));
+        }
+        assert!(point.impl_intodart.is_empty());
+        assert!(point.extern_funcs.is_empty());
+
+        let counter = generate_rust_struct(&api_file.struct_pool["Counter"], &api_file, &opts);
+        assert!(counter.wire_struct.is_empty());
+        for code in [&counter.impl_intodart, &counter.on_change_funcs] {
+            assert!(!code.is_empty());
+            assert!(whole.code.contains(code.as_str()));
+        }
+        assert!(!counter.extern_funcs.is_empty());
+        for extern_func in &counter.extern_funcs {
+            assert!(whole.extern_func_names.contains(&extern_func.name));
+        }
+    }
+
+    #[test]
+    fn rebuilds_non_null_inputs_inside_the_task() {
+        let api_file = transform_source(
+            "
+            #[frb(unsafe_ptr)]
+            pub fn read_value(ptr: NonNull<i32>) -> Result<i32> {
+                Ok(unsafe { *ptr.as_ptr() })
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs.contains("pub extern \"C\" fn wire_read_value(port: i64, ptr: i64)"));
+        assert!(wire_funcs.contains("let api_ptr = ptr;"));
+        assert!(wire_funcs.contains("move |task_callback| read_value(api_ptr.wire2api())"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let ptr = Delegate(ApiTypeDelegate::NonNull("i32".to_string()));
+        let wire2api = generator.generate_wire2api_func(&ptr, &api_file);
+        assert!(wire2api.contains("impl Wire2Api<std::ptr::NonNull<i32>> for i64"));
+        assert!(wire2api.contains("// SAFETY: caller guarantees pointer validity"));
+    }
+
+    #[test]
+    fn receives_dyn_errors_as_send_and_sync_boxes() {
+        let api_file = transform_source(
+            "
+            pub fn describe_error(
+                error: Option<Box<dyn std::error::Error + Send>>,
+            ) -> Result<Option<Box<dyn std::error::Error>>> {
+                Ok(error.map(|error| format!(\"described: {}\", error).into()))
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(
+            wire_funcs.contains("let api_error = std::panic::AssertUnwindSafe(error.wire2api());")
+        );
+        assert!(wire_funcs.contains("describe_error({ api_error }.0)"));
+        assert!(wire_funcs.contains(".map(|ans| ans.map(|inner| inner.to_string()))"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let error = Delegate(ApiTypeDelegate::DynError("+Send".to_string()));
+        let wire2api = generator.generate_wire2api_func(&error, &api_file);
+        assert!(wire2api.contains(
+            "impl Wire2Api<Box<dyn std::error::Error + Send>> for *mut wire_uint_8_list"
+        ));
+        assert!(wire2api.contains(
+            "Box::<dyn std::error::Error + Send + Sync>::from(String::from_utf8_lossy(&vec).into_owned())"
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Box<dyn std::error::Error> is not `Send`, please use `Box<dyn Error + Send>` instead"
+    )]
+    fn rejects_dyn_error_inputs_without_send() {
+        let source = "
+            pub fn describe_error(error: Box<dyn std::error::Error>) -> Result<String> {
+                Ok(error.to_string())
+            }
+        ";
+        let (_crate_dir, opts) = opts_of(source);
+        generate(&transform_source(source), &opts);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "unsupported type Rc<String>: Rc<T> is not Send and cannot be used across a thread boundary; use Arc<T> instead."
+    )]
+    fn rejects_rc_before_generating_wire_code() {
+        let source = "
+            pub fn share(name: Rc<String>) -> Result<String> {
+                Ok(name.to_string())
+            }
+        ";
+        let (_crate_dir, opts) = opts_of(source);
+        generate(&transform_source(source), &opts);
+    }
+
+    #[test]
+    fn only_warns_about_misspelled_frb_attributes() {
+        let api_file = transform_source(
+            "
+            #[frb_skip]
+            pub fn misspelled_skip() -> Result<u32> {
+                Ok(1)
+            }
+
+            #[flutter_rust_bridge(skip)]
+            pub fn long_skip() -> Result<u32> {
+                Ok(2)
+            }
+
+            #[frb(skip)]
+            pub fn skipped() -> Result<u32> {
+                Ok(3)
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs.contains("pub extern \"C\" fn wire_misspelled_skip(port: i64)"));
+        assert!(wire_funcs.contains("pub extern \"C\" fn wire_long_skip(port: i64)"));
+        assert!(!wire_funcs.contains("wire_skipped"));
+    }
+
+    #[test]
+    fn omits_unsupported_functions_and_their_types_when_skipping_them() {
+        let source = "
+            pub struct Account {
+                pub name: String,
+            }
+
+            pub fn share(account: Account, owner: Rc<String>) -> Result<String> {
+                Ok(account.name)
+            }
+
+            pub fn greet(name: String) -> Result<String> {
+                Ok(name)
+            }
+        ";
+        let api_file = transformer::transform(
+            parser::parse(
+                source,
+                syn::parse_file(source).unwrap(),
+                true,
+                DartUuidStyle::Value,
+                IncludedVisibility::default(),
+                None,
+            ),
+            true,
+        );
+        let (_crate_dir, opts) = opts_of(source);
+        let code = generate(&api_file, &opts).code;
+        assert!(
+            code.contains("pub extern \"C\" fn wire_greet(port: i64, name: *mut wire_uint_8_list)")
+        );
+        assert!(!code.contains("wire_share"));
+        assert!(!code.contains("Account"));
+    }
+
+    #[test]
+    fn passes_maps_as_keys_and_values() {
+        let api_file = transform_source(
+            "
+            pub fn count_words(counts: HashMap<String, u32>) -> Result<BTreeMap<String, u32>> {
+                Ok(counts.into_iter().collect())
+            }
+            ",
+        );
+        let input = &api_file.funcs[0].inputs[0].ty;
+        assert_eq!(
+            wire_struct_fields(input, &api_file).unwrap(),
+            [
+                "keys: *mut *mut wire_uint_8_list",
+                "values: *mut u32",
+                "len: i32"
+            ]
+        );
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let wire2api = generator.generate_wire2api_func(input, &api_file);
+        assert!(wire2api.contains(
+            "impl Wire2Api<std::collections::HashMap<String, u32>> for *mut wire_hash_map_String_u32"
+        ));
+        assert!(wire2api.contains(".zip(values.into_iter().map(Wire2Api::wire2api))"));
+        let allocate = generator.generate_allocate_funcs(input);
+        assert!(allocate.contains(
+            "pub extern \"C\" fn new_hash_map_String_u32(len: i32) -> *mut wire_hash_map_String_u32"
+        ));
+        assert!(allocate.contains("values: support::new_leak_vec_ptr(Default::default(), len),"));
+
+        assert!(generate_wire_funcs(&api_file).contains(
+            ".flat_map(|(key, value)| vec![support::IntoDart::into_dart(key), support::IntoDart::into_dart(value)])"
+        ));
+    }
+
+    #[test]
+    fn lends_borrowed_inputs_to_the_function() {
+        let api_file = transform_source(
+            "
+            pub fn checksum(name: &str, data: &[u8]) -> Result<u32> {
+                Ok(name.len() as u32 + data.len() as u32)
+            }
+            ",
+        );
+        let code = generate_wire_funcs(&api_file);
+        assert!(code.contains(
+            "pub extern \"C\" fn wire_checksum(port: i64, name: *mut wire_uint_8_list, data: *mut wire_uint_8_list)"
+        ));
+        assert!(code.contains("let api_name: String = name.wire2api();"));
+        assert!(code.contains("let api_data: Vec<u8> = data.wire2api();"));
+        assert!(code.contains("move |task_callback| checksum(&api_name, &api_data)"));
+    }
+
+    #[test]
+    fn casts_usize_and_isize_to_and_from_i64() {
+        let api_file = transform_source(
+            "
+            pub fn offset(len: usize, delta: Option<isize>) -> Result<usize> {
+                Ok(len)
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs
+            .contains("pub extern \"C\" fn wire_offset(port: i64, len: u64, delta: *mut i64)"));
+        assert!(wire_funcs.contains("offset(api_len, api_delta).map(|ans| (ans as i64))"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let usize =
+            generator.generate_wire2api_func(&Primitive(ApiTypePrimitive::Usize), &api_file);
+        assert!(usize.contains("impl Wire2Api<usize> for u64"));
+        assert!(usize.contains("self as usize"));
+        let boxed_isize = Boxed(Box::new(ApiTypeBoxed {
+            exist_in_real_api: false,
+            inner: Primitive(ApiTypePrimitive::Isize),
+        }));
+        let boxed_isize = generator.generate_wire2api_func(&boxed_isize, &api_file);
+        assert!(boxed_isize.contains("impl Wire2Api<isize> for *mut i64"));
+        assert!(boxed_isize.contains("Wire2Api::<isize>::wire2api(*wrap).into()"));
+    }
+
+    #[test]
+    fn passes_sets_as_lists() {
+        let api_file = transform_source(
+            "
+            pub fn dedup_ids(ids: HashSet<u32>, tags: BTreeSet<String>) -> Result<HashSet<u32>> {
+                Ok(ids)
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs.contains(
+            "pub extern \"C\" fn wire_dedup_ids(port: i64, ids: *mut wire_uint_32_list, tags: *mut wire_list_String)"
+        ));
+        assert!(wire_funcs.contains(
+            ".map(|ans| { const _: fn() = support::assert_hash_set_element::<u32>; ans.into_iter().collect::<Vec<_>>() })"
+        ));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let tags = generator.generate_wire2api_func(&api_file.funcs[0].inputs[1].ty, &api_file);
+        assert!(tags.contains(
+            "impl Wire2Api<std::collections::BTreeSet<String>> for *mut wire_list_String"
+        ));
+        assert!(tags.contains("const _: fn() = support::assert_b_tree_set_element::<String>;"));
+        assert!(tags.contains("let vec: Vec<String> = self.wire2api();"));
+    }
+
+    #[test]
+    fn converts_mirrors_from_and_into_the_mirrored_types() {
+        let source = r#"
+            #[frb(mirror = "image::Rgb<u8>")]
+            pub struct Rgb(pub [u8; 3]);
+
+            #[frb(mirror = "geo::Point")]
+            pub struct Point {
+                pub x: f64,
+                pub y: f64,
+            }
+
+            pub fn blend(color: Rgb, at: Point) -> Result<Rgb> {
+                Ok(color)
+            }
+        "#;
+        let (_crate_dir, opts) = opts_of(source);
+        let code = generate(&transform_source(source), &opts).code;
+        for conversion in [
+            "impl From<geo::Point> for Point {",
+            "Self { x: mirrored.x.into(),\ny: mirrored.y.into(), }",
+            "impl From<Point> for geo::Point {",
+            "Self { x: mirror.x.into(),\ny: mirror.y.into(), }",
+            "impl From<image::Rgb<u8>> for Rgb {",
+            "Self(mirrored.0.into(),)",
+            "impl From<Rgb> for image::Rgb<u8> {",
+            "Self(mirror.0.into(),)",
+        ] {
+            assert!(code.contains(conversion), "missing {}", conversion);
+        }
+    }
+
+    #[test]
+    fn generates_wire_code_of_generic_structs() {
+        let source = "
+            pub struct ApiResponse<T> {
+                pub data: T,
+                pub code: u32,
+            }
+
+            pub fn fetch(response: ApiResponse<String>) -> Result<ApiResponse<String>> {
+                Ok(response)
+            }
+        ";
+        let (_crate_dir, opts) = opts_of(source);
+        let code = generate(&transform_source(source), &opts).code;
+        for generated in [
+            "pub extern \"C\" fn wire_fetch(port: i64, response: *mut wire_ApiResponseString)",
+            "pub struct wire_ApiResponseString {",
+            "impl Wire2Api<ApiResponse<String>> for wire_ApiResponseString {",
+            "ApiResponse::<String> { data:  self.data.wire2api(),code:  self.code.wire2api() }",
+            "impl support::IntoDart for ApiResponse<String> {",
+            "impl support::IntoDartExceptPrimitive for ApiResponse<String> {}",
+        ] {
+            assert!(code.contains(generated), "missing {}", generated);
+        }
+    }
+
+    #[test]
+    fn passes_128_bit_integers_as_two_halves() {
+        let api_file = transform_source(
+            "
+            pub fn negate(value: i128, mask: u128) -> Result<i128> {
+                Ok(-value)
+            }
+            ",
+        );
+        assert!(generate_wire_funcs(&api_file)
+            .contains(".map(|ans| { let value = ans; vec![(value >> 64) as i64, value as i64] })"));
+
+        let mut generator = Generator {
+            extern_func_collector: ExternFuncCollector::new(),
+            auto_zero_copy: true,
+            source_locations: false,
+        };
+        let inputs = &api_file.funcs[0].inputs;
+        let i128 = generator.generate_wire2api_func(&inputs[0].ty, &api_file);
+        assert!(i128.contains("impl Wire2Api<i128> for *mut wire_int_64_list"));
+        assert!(i128.contains("((vec[0] as i128) << 64) | (vec[1] as u64 as i128)"));
+        let u128 = generator.generate_wire2api_func(&inputs[1].ty, &api_file);
+        assert!(u128.contains("impl Wire2Api<u128> for *mut wire_int_64_list"));
+        assert!(u128.contains("((vec[0] as u64 as u128) << 64) | (vec[1] as u64 as u128)"));
+    }
+
+    #[test]
+    fn returns_sync_strings_as_bytes() {
+        let api_file = transform_source(
+            "
+            pub fn greet_sync(name: String) -> Result<SyncReturn<String>> {
+                Ok(SyncReturn(name))
+            }
+            ",
+        );
+        let wire_funcs = generate_wire_funcs(&api_file);
+        assert!(wire_funcs.contains(
+            "pub extern \"C\" fn wire_greet_sync(name: *mut wire_uint_8_list) -> support::WireSyncReturnStruct"
+        ));
+        assert!(
+            wire_funcs.contains("greet_sync(api_name).map(|ans| SyncReturn(ans.0.into_bytes()))")
+        );
+    }
+
+    #[test]
+    fn receives_assoc_lists_as_keys_and_values() {
+        let source = "
+            pub fn total(counts: Vec<(String, u32)>) -> Result<u32> {
+                Ok(counts.iter().map(|(_, count)| count).sum())
+            }
+        ";
+        let (_crate_dir, opts) = opts_of(source);
+        let code = generate(&transform_source(source), &opts).code;
+        assert!(code.contains("pub struct wire_assoc_list_String_u32 {"));
+        assert!(code.contains("keys: *mut *mut wire_uint_8_list,"));
+        assert!(code.contains("values: *mut u32,"));
+        assert!(code.contains(
+            "pub extern \"C\" fn new_assoc_list_String_u32(len: i32) -> *mut wire_assoc_list_String_u32"
+        ));
+        assert!(
+            code.contains("impl Wire2Api<Vec<(String, u32)>> for *mut wire_assoc_list_String_u32")
+        );
+        assert!(code.contains(".zip(values.into_iter().map(Wire2Api::wire2api))"));
+    }
+}
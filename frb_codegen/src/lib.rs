@@ -0,0 +1,407 @@
+//! Generates the Dart, Rust and C glue code of flutter_rust_bridge. Usually used via the
+//! `flutter_rust_bridge_codegen` command, or [generate_from_build_script] in `build.rs`.
+
+use std::path::Path;
+use std::{env, fs};
+
+use log::{debug, info, warn};
+use pathdiff::diff_paths;
+
+use crate::api_types::{ApiFile, ApiType};
+use crate::cache::ParseCache;
+use crate::config::{Opts, RawOpts};
+use crate::others::*;
+use crate::utils::*;
+
+pub mod api_types;
+mod cache;
+pub mod check;
+mod commands;
+pub mod completions;
+pub mod config;
+pub mod dry_run;
+pub mod generator_c;
+mod generator_dart;
+pub mod generator_rust;
+mod others;
+mod parser;
+mod sorter;
+mod transformer;
+mod utils;
+pub mod watch;
+
+/// Parse and transform all the Rust inputs into one [ApiFile], together with the names of the
+/// functions of each input module for [generator_rust::generate_split]
+pub(crate) fn parse_api(config: &Opts) -> (ApiFile, Vec<(String, Vec<String>)>) {
+    let parse = |rust_input_path: &str, source_rust_content: &str| {
+        info!("Phase: Parse source code to AST ({})", rust_input_path);
+        let file_ast = syn::parse_file(source_rust_content).unwrap();
+
+        info!("Phase: Parse AST to IR ({})", rust_input_path);
+        let mut api_file = parser::parse(
+            source_rust_content,
+            file_ast,
+            config.skip_unsupported_functions,
+            config.dart_uuid_style,
+            config.included_visibility,
+            config.features.as_deref(),
+        );
+        let file = crate_relative_path(rust_input_path, config);
+        for func in &mut api_file.funcs {
+            func.source_location = format!("{}:{}", file, func.source_location);
+        }
+        api_file
+    };
+    let mut cache = (!config.no_cache).then(|| ParseCache::load(config));
+    let raw_api_files: Vec<_> = config
+        .rust_input_paths
+        .iter()
+        .map(|rust_input_path| match &mut cache {
+            Some(cache) => {
+                cache.get_or_parse(rust_input_path, |content| parse(rust_input_path, content))
+            }
+            None => parse(
+                rust_input_path,
+                &fs::read_to_string(rust_input_path).unwrap(),
+            ),
+        })
+        .collect();
+
+    if let Some(cache) = cache {
+        if let Err(err) = cache.save(config) {
+            warn!("Fail to save the parse cache: {}", err);
+        }
+    }
+
+    let func_modules: Vec<_> = config
+        .rust_input_paths
+        .iter()
+        .zip(&raw_api_files)
+        .map(|(rust_input_path, api_file)| {
+            (
+                mod_from_rust_path(rust_input_path, &config.rust_crate_dir),
+                api_file
+                    .funcs
+                    .iter()
+                    .map(|func| func.name.clone())
+                    .collect(),
+            )
+        })
+        .collect();
+    let raw_api_file = ApiFile::merge(
+        config
+            .rust_input_paths
+            .iter()
+            .map(|rust_input_path| crate_relative_path(rust_input_path, config))
+            .zip(raw_api_files)
+            .collect(),
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+    parser::check_dart_name_collisions(&raw_api_file.funcs);
+    debug!("parsed functions: {:?}", &raw_api_file);
+
+    if raw_api_file.enum_pool.values().any(|e| e.is_enum_class) {
+        check_dart_sdk_version(&config.dart_output_path, (2, 17, 0), "#[frb(enum_class)]");
+    }
+
+    info!("Phase: Transform IR");
+    let api_file = transformer::transform(raw_api_file, !config.no_dedup);
+    if !api_file.struct_dart_aliases.is_empty() {
+        check_dart_sdk_version(
+            &config.dart_output_path,
+            (2, 13, 0),
+            "`typedef`s of structs with the same fields (disable by `--no-dedup`)",
+        );
+    }
+    if !config.no_finalizer
+        && api_file
+            .distinct_types(true, true)
+            .iter()
+            .any(|ty| matches!(ty, ApiType::Opaque(_)))
+    {
+        check_dart_sdk_version(
+            &config.dart_output_path,
+            (2, 17, 0),
+            "`Finalizable` opaque handles (disable by `--no-finalizer`)",
+        );
+    }
+    debug!("transformed functions: {:?}", &api_file);
+
+    (api_file, func_modules)
+}
+
+/// [rust_input_path] relative to the crate like `file!()`
+fn crate_relative_path(rust_input_path: &str, config: &Opts) -> String {
+    diff_paths(rust_input_path, &config.rust_crate_dir)
+        .map(|path| path.to_str().unwrap().replace('\\', "/"))
+        .unwrap_or_else(|| rust_input_path.to_string())
+}
+
+/// Generate and write the Rust code of [api_file], returning it for the other outputs
+fn write_rust_output(
+    config: &Opts,
+    api_file: &ApiFile,
+    func_modules: &[(String, Vec<String>)],
+    with_timestamp: impl Fn(String) -> String,
+) -> generator_rust::Output {
+    info!("Phase: Generate Rust code");
+    let mut generated_rust = if config.split_output {
+        generator_rust::generate_split(api_file, config, func_modules)
+    } else {
+        generator_rust::generate(api_file, config)
+    };
+    if config.split_output {
+        let rust_output_dir = Path::new(&config.rust_output_dir()).to_path_buf();
+        fs::create_dir_all(&rust_output_dir).unwrap();
+        for module in std::mem::take(&mut generated_rust.modules) {
+            write_output(
+                rust_output_dir.join(format!("{}.rs", module.name)),
+                &with_timestamp(module.code),
+                config.line_ending,
+            );
+        }
+    }
+    write_output(
+        config.rust_output_root_path(),
+        &with_timestamp(generated_rust.code.clone()),
+        config.line_ending,
+    );
+    generated_rust
+}
+
+/// Run the whole generation pipeline
+pub fn frb_codegen(config: &Opts) {
+    let timestamp_comment = config.output_timestamps.then(generation_timestamp_comment);
+    let with_timestamp = |content: String| match &timestamp_comment {
+        Some(comment) => add_generation_timestamp(&content, comment),
+        None => content,
+    };
+
+    let (api_file, func_modules) = parse_api(config);
+
+    let generated_rust = write_rust_output(config, &api_file, &func_modules, with_timestamp);
+    let rust_output_root_path = config.rust_output_root_path();
+
+    info!("Phase: Generate Dart code");
+    let generated_dart_api = generator_dart::generate(
+        &api_file,
+        &config.dart_api_class_name(),
+        &config.dart_api_impl_class_name(),
+        &config.dart_wire_class_name(),
+        config.dart_code_opts(),
+        Path::new(&config.dart_output_path)
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap(),
+    );
+
+    info!("Phase: Other things");
+
+    // also formats the child modules of a split output
+    commands::format_rust(&rust_output_root_path);
+
+    if !config.skip_add_mod_to_lib {
+        others::try_add_mod_to_lib(
+            &config.rust_crate_dir,
+            &config.rust_output_path,
+            config.split_output,
+        );
+    }
+
+    let c_struct_names = api_file
+        .distinct_types(true, true)
+        .iter()
+        .filter_map(|ty| {
+            if let ApiType::StructRef(_) = ty {
+                Some(ty.rust_wire_type())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let temp_dart_wire_file = tempfile::NamedTempFile::new().unwrap();
+    let temp_bindgen_c_output_file = tempfile::Builder::new().suffix(".h").tempfile().unwrap();
+    with_changed_file(&rust_output_root_path, DUMMY_WIRE_CODE_FOR_BINDGEN, || {
+        commands::bindgen_rust_to_dart(
+            config,
+            &rust_output_root_path,
+            temp_bindgen_c_output_file
+                .path()
+                .as_os_str()
+                .to_str()
+                .unwrap(),
+            temp_dart_wire_file.path().as_os_str().to_str().unwrap(),
+            c_struct_names,
+        );
+    });
+
+    let effective_func_names = [
+        generated_rust.extern_func_names,
+        EXTRA_EXTERN_FUNC_NAMES.to_vec(),
+    ]
+    .concat();
+    let c_dummy_code = generator_c::generate_dummy(&effective_func_names);
+    let c_output = with_timestamp(
+        fs::read_to_string(temp_bindgen_c_output_file).unwrap() + "\n" + &c_dummy_code,
+    );
+    for c_output_path in &config.c_output_paths {
+        write_output(c_output_path, &c_output, config.line_ending);
+    }
+
+    let generated_dart_wire_code_raw = fs::read_to_string(temp_dart_wire_file).unwrap();
+    let (generated_dart_wire_import_code, generated_dart_wire_body_code) =
+        extract_dart_wire_content(&modify_dart_wire_content(
+            &generated_dart_wire_code_raw,
+            &config.dart_wire_class_name(),
+        ));
+
+    sanity_check(
+        &generated_dart_wire_body_code,
+        &config.dart_wire_class_name(),
+    );
+
+    let imports = [
+        generated_dart_api.imports.clone(),
+        generated_dart_wire_import_code
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    ]
+    .concat();
+    let mut parts = generated_dart_api.parts.clone();
+    let generated_dart_code = match &config.dart_impl_output_path {
+        Some(dart_impl_output_path) => {
+            let generated_dart_impl_code = format!(
+                "{}\n{}\n{}",
+                generator_dart::generate_impl_part_header(&dart_import_path(
+                    &config.dart_output_path,
+                    dart_impl_output_path
+                )),
+                generated_dart_api.other,
+                generated_dart_wire_body_code,
+            );
+            write_output(
+                dart_impl_output_path,
+                &with_timestamp(generated_dart_impl_code),
+                config.line_ending,
+            );
+            commands::format_dart(dart_impl_output_path, config.dart_format_line_length);
+
+            parts.push(format!(
+                "part '{}';",
+                dart_import_path(dart_impl_output_path, &config.dart_output_path)
+            ));
+            format!(
+                "{}\n{}\n{}",
+                generated_dart_api.header,
+                generator_dart::generate_directives(&config.dart_library_name, &imports, &parts),
+                generated_dart_api.api_class,
+            )
+        }
+        None => format!(
+            "{}\n{}\n{}\n{}\n{}",
+            generated_dart_api.header,
+            generator_dart::generate_directives(&config.dart_library_name, &imports, &parts),
+            generated_dart_api.api_class,
+            generated_dart_api.other,
+            generated_dart_wire_body_code,
+        ),
+    };
+    // e.g. `lib/generated` of the default `--dart-output`, see [Opts::validate_paths]
+    if let Some(dart_output_dir) = Path::new(&config.dart_output_path).parent() {
+        fs::create_dir_all(dart_output_dir).unwrap();
+    }
+    write_output(
+        &config.dart_output_path,
+        &with_timestamp(generated_dart_code),
+        config.line_ending,
+    );
+    commands::format_dart(&config.dart_output_path, config.dart_format_line_length);
+
+    if let Some(dart_test_output_path) = &config.dart_test_output_path {
+        info!("Phase: Generate Dart test stubs");
+        let generated_dart_test_code = generator_dart::generate_test_stubs(
+            &api_file,
+            &config.dart_api_class_name(),
+            &dart_import_path(&config.dart_output_path, dart_test_output_path),
+        );
+        write_output(
+            dart_test_output_path,
+            &with_timestamp(generated_dart_test_code),
+            config.line_ending,
+        );
+        commands::format_dart(dart_test_output_path, config.dart_format_line_length);
+    }
+}
+
+/// [path] relative to the directory of [importer], as used by `import`, `part` and `part of`
+fn dart_import_path(path: &str, importer: &str) -> String {
+    diff_paths(path, Path::new(importer).parent().unwrap())
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .replace('\\', "/")
+}
+
+/// Generate the Rust code of the bridge from `build.rs`, using [rust_input] (relative to the crate
+/// being built) as input and writing the output to `OUT_DIR`. [raw_opts] may set other options,
+/// whose input and output paths are ignored, and a config file is only read if given by
+/// [RawOpts::config]. Since `include!` does not allow inner attributes, include the generated Rust
+/// code via:
+///
+/// ```ignore
+/// #[allow(non_camel_case_types, unused, clippy::all)]
+/// mod bridge_generated {
+///     include!(concat!(env!("OUT_DIR"), "/bridge_generated.rs"));
+/// }
+/// ```
+///
+/// The Dart code and the C header need `dart format`, ffigen and cbindgen, which are not run
+/// during `cargo build`, so generate them with the `flutter_rust_bridge_codegen` command.
+pub fn generate_from_build_script(rust_input: &str, raw_opts: RawOpts) {
+    let rust_crate_dir = env::var("CARGO_MANIFEST_DIR")
+        .expect("CARGO_MANIFEST_DIR is not set, call this in build.rs");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set, call this in build.rs");
+    let out_path = |file_name: &str| {
+        Path::new(&out_dir)
+            .join(file_name)
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let config = config::parse_in_build_script(RawOpts {
+        rust_input: Some(
+            Path::new(&rust_crate_dir)
+                .join(rust_input)
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ),
+        dart_output: Some(out_path("bridge_generated.dart")),
+        c_output: Some(vec![out_path("bridge_generated.h")]),
+        rust_crate_dir: Some(rust_crate_dir),
+        rust_output: Some(out_path("bridge_generated.rs")),
+        dart_test_output: None,
+        dart_impl_output: None,
+        skip_add_mod_to_lib: true,
+        ..raw_opts
+    });
+    for rust_input_path in &config.rust_input_paths {
+        println!("cargo:rerun-if-changed={}", rust_input_path);
+    }
+    config
+        .validate_paths()
+        .unwrap_or_else(|err| panic!("{}", err));
+    info!("Picked config: {:?}", &config);
+
+    let (api_file, func_modules) = parse_api(&config);
+    let generated_rust = write_rust_output(&config, &api_file, &func_modules, |content| content);
+    write_output(
+        config.rust_output_root_path(),
+        &strip_inner_attributes(&generated_rust.code),
+        config.line_ending,
+    );
+}
@@ -1,11 +1,12 @@
-use std::fs;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
 
 use anyhow::{anyhow, Result};
 use lazy_static::lazy_static;
 use log::{error, info, warn};
 use pathdiff::diff_paths;
-use regex::RegexBuilder;
+use regex::{Regex, RegexBuilder};
 
 // NOTE [DartPostCObjectFnType] was originally [*mut DartCObject] but I changed it to [*mut c_void]
 // because cannot automatically generate things related to [DartCObject]. Anyway this works fine.
@@ -34,8 +35,87 @@ lazy_static! {
         vec!["store_dart_post_cobject".to_string()];
 }
 
-pub const CODE_HEADER: &str = "// AUTO GENERATED FILE, DO NOT EDIT.
-// Generated by `flutter_rust_bridge`.";
+pub const CODE_HEADER: &str = concat!(
+    "// AUTO GENERATED FILE, DO NOT EDIT.
+// Generated by `flutter_rust_bridge` ",
+    env!("CARGO_PKG_VERSION"),
+    "."
+);
+
+/// e.g. `// Generated at: 2024-01-01T12:00:00Z`. Respects `SOURCE_DATE_EPOCH` for reproducible builds.
+const GENERATION_TIMESTAMP_PREFIX: &str = "// Generated at: ";
+
+pub fn generation_timestamp_comment() -> String {
+    format!(
+        "{}{}",
+        GENERATION_TIMESTAMP_PREFIX,
+        format_iso8601_utc(generation_time())
+    )
+}
+
+/// Seconds since the Unix epoch, or `SOURCE_DATE_EPOCH` if set
+pub fn generation_time() -> u64 {
+    match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch
+            .trim()
+            .parse::<u64>()
+            .unwrap_or_else(|_| panic!("invalid SOURCE_DATE_EPOCH: {}", epoch)),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+    }
+}
+
+/// e.g. `2024-01-01T12:00:00Z`
+pub fn format_iso8601_utc(secs_since_epoch: u64) -> String {
+    let days = (secs_since_epoch / 86400) as i64;
+    let secs_of_day = secs_since_epoch % 86400;
+
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+/// Put [timestamp_comment] into the first comment block of a generated file
+pub fn add_generation_timestamp(content: &str, timestamp_comment: &str) -> String {
+    if content.contains(CODE_HEADER) {
+        content.replacen(
+            CODE_HEADER,
+            &format!("{}\n{}", CODE_HEADER, timestamp_comment),
+            1,
+        )
+    } else {
+        format!("{}\n{}", timestamp_comment, content)
+    }
+}
+
+/// Reverse of [add_generation_timestamp], e.g. to compare outputs generated at different times
+pub fn strip_generation_timestamp(content: &str) -> String {
+    content
+        .lines()
+        .filter(|line| !line.starts_with(GENERATION_TIMESTAMP_PREFIX))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
 pub fn modify_dart_wire_content(content_raw: &str, dart_wire_class_name: &str) -> String {
     let content = content_raw.replace(
@@ -78,8 +158,8 @@ pub fn sanity_check(generated_dart_wire_code: &str, dart_wire_class_name: &str)
     }
 }
 
-pub fn try_add_mod_to_lib(rust_crate_dir: &str, rust_output_path: &str) {
-    if let Err(e) = auto_add_mod_to_lib_core(rust_crate_dir, rust_output_path) {
+pub fn try_add_mod_to_lib(rust_crate_dir: &str, rust_output_path: &str, split_output: bool) {
+    if let Err(e) = auto_add_mod_to_lib_core(rust_crate_dir, rust_output_path, split_output) {
         warn!(
             "auto_add_mod_to_lib fail, the generated code may or may not have problems. \
             Please ensure you have add code like `mod the_generated_bridge_code;` to your `lib.rs`. \
@@ -89,7 +169,13 @@ pub fn try_add_mod_to_lib(rust_crate_dir: &str, rust_output_path: &str) {
     }
 }
 
-pub fn auto_add_mod_to_lib_core(rust_crate_dir: &str, rust_output_path: &str) -> Result<()> {
+/// With [split_output], the module is the directory next to [rust_output_path], and public so
+/// that the modules it re-exports can be reached from outside the crate
+pub fn auto_add_mod_to_lib_core(
+    rust_crate_dir: &str,
+    rust_output_path: &str,
+    split_output: bool,
+) -> Result<()> {
     let path_src_folder = Path::new(rust_crate_dir).join("src");
     let rust_output_path_relative_to_src_folder =
         diff_paths(rust_output_path, path_src_folder.clone()).ok_or_else(|| {
@@ -107,7 +193,11 @@ pub fn auto_add_mod_to_lib_core(rust_crate_dir: &str, rust_output_path: &str) ->
         .ok_or_else(|| anyhow!(""))?
         .to_string()
         .replace("/", "::");
-    let expect_code = format!("mod {};", mod_name);
+    let expect_code = format!(
+        "{}mod {};",
+        if split_output { "pub " } else { "" },
+        mod_name
+    );
 
     let path_lib_rs = path_src_folder.join("lib.rs");
 
@@ -124,3 +214,55 @@ pub fn auto_add_mod_to_lib_core(rust_crate_dir: &str, rust_output_path: &str) ->
 
     Ok(())
 }
+
+/// Panic if the `pubspec.yaml` of the Dart package containing [dart_output_path] allows Dart
+/// SDKs older than [min_version], which is needed by [feature].
+pub fn check_dart_sdk_version(dart_output_path: &str, min_version: (u32, u32, u32), feature: &str) {
+    match dart_sdk_lower_bound(dart_output_path) {
+        Ok(lower_bound) if lower_bound < min_version => panic!(
+            "{} requires Dart {}.{}.{}, but pubspec.yaml allows Dart {}.{}.{}. \
+            Please raise the `environment: sdk:` constraint.",
+            feature,
+            min_version.0,
+            min_version.1,
+            min_version.2,
+            lower_bound.0,
+            lower_bound.1,
+            lower_bound.2,
+        ),
+        Ok(_) => {}
+        Err(e) => warn!(
+            "fail to detect the Dart SDK version, please ensure it is at least {}.{}.{} since {} \
+            is used. Details: {}",
+            min_version.0, min_version.1, min_version.2, feature, e
+        ),
+    }
+}
+
+/// The minimum Dart SDK version allowed by the nearest `pubspec.yaml`, e.g. `>=2.12.0 <3.0.0`
+/// gives `(2, 12, 0)`
+fn dart_sdk_lower_bound(dart_output_path: &str) -> Result<(u32, u32, u32)> {
+    lazy_static! {
+        static ref VERSION_LOWER_BOUND: Regex =
+            Regex::new(r"^\s*(>=|\^)?\s*(\d+)\.(\d+)\.(\d+)").unwrap();
+    }
+
+    let pubspec_path = Path::new(dart_output_path)
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join("pubspec.yaml"))
+        .find(|path| path.exists())
+        .ok_or_else(|| anyhow!("look at parent directories but none contains pubspec.yaml"))?;
+    let pubspec: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(&pubspec_path)?)?;
+    let constraint = pubspec
+        .get("environment")
+        .and_then(|env| env.get("sdk"))
+        .and_then(|sdk| sdk.as_str())
+        .ok_or_else(|| anyhow!("no `environment: sdk:` in {:?}", pubspec_path))?;
+
+    let captures = VERSION_LOWER_BOUND
+        .captures(constraint)
+        .ok_or_else(|| anyhow!("cannot parse sdk constraint `{}`", constraint))?;
+    let part = |idx: usize| -> Result<u32> { Ok(captures[idx].parse()?) };
+    Ok((part(2)?, part(3)?, part(4)?))
+}
@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
 use std::string::String;
 
+use convert_case::{Case, Casing};
 use lazy_static::lazy_static;
-use log::debug;
+use log::{debug, warn};
 use quote::quote;
 use regex::Regex;
 use syn::*;
@@ -10,24 +13,106 @@ use syn::*;
 use ApiType::*;
 
 use crate::api_types::*;
+use crate::config::{DartUuidStyle, IncludedVisibility};
 use crate::generator_rust::HANDLER_NAME;
 
 type StructMap<'a> = HashMap<String, &'a ItemStruct>;
+type EnumMap<'a> = HashMap<String, &'a ItemEnum>;
+type ImplConstMap<'a> = HashMap<String, Vec<&'a ImplItemConst>>;
+/// e.g. `pub type UserId = String;` => `{"UserId": "String"}`
+type TypeAliasMap = HashMap<String, String>;
+/// e.g. `pub use geometry::Point as Location;` => `{"Location": "geometry::Point"}`
+type ReExportMap = HashMap<String, String>;
+/// e.g. `impl Default for Config {...}` => `{"Config"}`
+type DefaultImplSet = HashSet<String>;
 
-pub fn parse(source_rust_content: &str, file: File) -> ApiFile {
-    let (src_fns, src_struct_map) = extract_items_from_file(&file);
+/// A `pub fn` to bridge (or a `pub(crate)` or private one, see [IncludedVisibility]), either
+/// free-standing or in an `impl` block of the struct [SrcFn::impl_struct]
+struct SrcFn<'a> {
+    sig: &'a Signature,
+    attrs: &'a [Attribute],
+    impl_struct: Option<String>,
+    /// e.g. `geometry` of a function in `pub mod geometry { .. }`
+    module: Option<String>,
+}
+
+/// With [skip_unsupported_functions], functions that fail to parse are omitted with a warning
+/// instead of failing the whole generation
+pub fn parse(
+    source_rust_content: &str,
+    file: File,
+    skip_unsupported_functions: bool,
+    dart_uuid_style: DartUuidStyle,
+    included_visibility: IncludedVisibility,
+    features: Option<&[String]>,
+) -> ApiFile {
+    let (
+        src_fns,
+        src_struct_map,
+        src_enum_map,
+        src_impl_const_map,
+        default_impls,
+        type_alias_map,
+        re_exports,
+    ) = extract_items_from_file(&file, included_visibility, features);
     let parser = Parser {
         src_struct_map,
+        src_enum_map,
+        src_impl_const_map,
+        default_impls,
+        type_alias_map,
+        re_exports,
         struct_pool: HashMap::new(),
+        enum_pool: HashMap::new(),
         parsing_or_parsed_struct_names: HashSet::new(),
+        parsing_or_parsed_enum_names: HashSet::new(),
+        allow_unsafe_ptr: false,
+        allow_borrowed: false,
+        impl_struct: None,
+        module: None,
+        dart_uuid_style,
+        included_visibility,
     };
-    parser.parse(source_rust_content, src_fns)
+    parser.parse(source_rust_content, src_fns, skip_unsupported_functions)
+}
+
+/// [parse] of [source] with the default options
+#[cfg(test)]
+pub fn parse_source(source: &str) -> ApiFile {
+    parse(
+        source,
+        syn::parse_file(source).unwrap(),
+        false,
+        DartUuidStyle::Value,
+        IncludedVisibility::default(),
+        None,
+    )
 }
 
 struct Parser<'a> {
     src_struct_map: HashMap<String, &'a ItemStruct>,
+    src_enum_map: EnumMap<'a>,
+    src_impl_const_map: ImplConstMap<'a>,
+    default_impls: DefaultImplSet,
+    type_alias_map: TypeAliasMap,
+    /// structs of [Self::src_struct_map] under the names they are re-exported as, and those
+    /// re-exported from outside of the file, which cannot be parsed
+    re_exports: ReExportMap,
     struct_pool: ApiStructPool,
+    enum_pool: ApiEnumPool,
     parsing_or_parsed_struct_names: HashSet<String>,
+    parsing_or_parsed_enum_names: HashSet<String>,
+    /// whether the function being parsed opts in to raw pointers via `#[frb(unsafe_ptr)]`
+    allow_unsafe_ptr: bool,
+    /// whether the type about to be parsed is directly a function parameter, which may be borrowed
+    allow_borrowed: bool,
+    /// the struct whose method is being parsed, which `Self` refers to
+    impl_struct: Option<String>,
+    /// the inline module of the function or struct being parsed, whose structs shadow others
+    module: Option<String>,
+    dart_uuid_style: DartUuidStyle,
+    /// which struct fields are bridged besides `pub` ones
+    included_visibility: IncludedVisibility,
 }
 
 fn extract_comments(attr: &Attribute) -> Option<Comment> {
@@ -41,35 +126,276 @@ fn extract_comments(attr: &Attribute) -> Option<Comment> {
     }
 }
 
+/// The message of `#[deprecated]`, e.g. `#[deprecated(since = "1.2", note = "use bar instead")]`
+/// => `since 1.2: use bar instead`, and an empty one for a bare `#[deprecated]`
+fn extract_deprecated(attrs: &[Attribute]) -> Option<String> {
+    let attr = attrs.iter().find(|attr| attr.path.is_ident("deprecated"))?;
+    let (mut since, mut note) = (None, None);
+    match attr.parse_meta() {
+        Ok(Meta::Path(_)) => {}
+        Ok(Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(lit), ..
+        })) => note = Some(lit.value()),
+        Ok(Meta::List(MetaList { nested, .. })) => {
+            for meta in nested {
+                match meta {
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("since") => since = Some(lit.value()),
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("note") => note = Some(lit.value()),
+                    _ => {}
+                }
+            }
+        }
+        _ => warn!("Malformed `#[deprecated]` attribute is ignored"),
+    }
+    Some(match (since, note) {
+        (Some(since), Some(note)) => format!("since {}: {}", since, note),
+        (Some(since), None) => format!("since {}", since),
+        (None, Some(note)) => note,
+        (None, None) => String::new(),
+    })
+}
+
+/// e.g. `#[frb(on_change = "notify", test)]` => `[on_change = "notify", test]`
+fn extract_frb_metas(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    attrs
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(MetaList { path, nested, .. })) if path.is_ident("frb") => {
+                Some(nested.into_iter())
+            }
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Whether [sig] takes `&mut self`
+fn has_mut_receiver(sig: &Signature) -> bool {
+    matches!(
+        sig.inputs.first(),
+        Some(FnArg::Receiver(Receiver {
+            reference: Some(_),
+            mutability: Some(_),
+            ..
+        }))
+    )
+}
+
+/// Misspelled attributes such as `#[frb_skip]` would otherwise be silently ignored
+fn warn_misspelled_frb_attrs(attrs: &[Attribute]) {
+    for attr in attrs {
+        let attr_path = &attr.path;
+        let path = quote!(#attr_path).to_string().replace(' ', "");
+        let tokens = attr.tokens.to_string().replace(' ', "");
+        let suggestion = match path.as_str() {
+            "frb_skip" => "#[frb(skip)]".to_string(),
+            "frb_name" => "#[frb(name = \"...\")]".to_string(),
+            "flutter_rust_bridge" | "bridge" => format!("#[frb{}]", tokens),
+            _ => continue,
+        };
+        warn!(
+            "Attribute `#[{}{}]` is not recognized; did you mean `{}`?",
+            path, tokens, suggestion
+        );
+    }
+}
+
+/// `usize` and `isize` inputs are sent as 64-bit integers and then cast, which truncates them on
+/// 32-bit targets. This is only known not to happen when generating from `build.rs` for a 64-bit
+/// target.
+fn warn_platform_width_inputs(api_file: &ApiFile) {
+    if env::var("CARGO_CFG_TARGET_POINTER_WIDTH").as_deref() == Ok("64") {
+        return;
+    }
+    for func in &api_file.funcs {
+        let mut seen_idents = HashSet::new();
+        let mut has_platform_width = false;
+        for input in &func.inputs {
+            input.ty.visit_types(
+                &mut |ty| {
+                    has_platform_width |= matches!(
+                        ty,
+                        Primitive(ApiTypePrimitive::Usize | ApiTypePrimitive::Isize)
+                    );
+                    !seen_idents.insert(ty.safe_ident())
+                },
+                api_file,
+            );
+        }
+        if has_platform_width {
+            warn!(
+                "`{}` takes `usize` or `isize`, which is 32-bit on 32-bit targets (e.g. armv7 \
+                Android), where values from Dart beyond its range are truncated",
+                func.name
+            );
+        }
+    }
+}
+
+/// e.g. `#[derive(Debug, Default)]` => `has_derive(attrs, "Default") == true`
+fn has_derive(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| match attr.parse_meta() {
+        Ok(Meta::List(MetaList { path, nested, .. })) if path.is_ident("derive") => {
+            nested.iter().any(|meta| {
+                matches!(meta, NestedMeta::Meta(Meta::Path(path))
+                    if path.segments.last().unwrap().ident == name)
+            })
+        }
+        _ => false,
+    })
+}
+
+/// e.g. `#[frb(test)]` => `has_frb_flag(attrs, "test") == true`
+fn has_frb_flag(attrs: &[Attribute], name: &str) -> bool {
+    extract_frb_metas(attrs)
+        .iter()
+        .any(|meta| matches!(meta, NestedMeta::Meta(Meta::Path(path)) if path.is_ident(name)))
+}
+
+/// e.g. `#[frb(on_change = "notify")]` => `extract_frb_value(attrs, "on_change") == Some("notify")`
+fn extract_frb_value(attrs: &[Attribute], name: &str) -> Option<Lit> {
+    extract_frb_metas(attrs)
+        .into_iter()
+        .find_map(|meta| match meta {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. }))
+                if path.is_ident(name) =>
+            {
+                Some(lit)
+            }
+            _ => None,
+        })
+}
+
+/// `#[frb(name = "...")]`, or `#[frb(dart_name = "...")]` which is kept for compatibility, of
+/// [item] such as a function or `MyStruct.my_field`
+fn extract_frb_dart_name(attrs: &[Attribute], item: &str) -> Option<String> {
+    let name = extract_frb_value(attrs, "name");
+    let dart_name = extract_frb_value(attrs, "dart_name");
+    if name.is_some() && dart_name.is_some() {
+        panic!("name and dart_name cannot be used together, see {}", item);
+    }
+    name.or(dart_name).map(|lit| match lit {
+        Lit::Str(lit) => lit.value(),
+        _ => panic!("name should be a string, see {}", item),
+    })
+}
+
 impl<'a> Parser<'a> {
-    fn parse(mut self, source_rust_content: &str, src_fns: Vec<&ItemFn>) -> ApiFile {
-        let funcs = src_fns.iter().map(|f| self.parse_function(f)).collect();
+    fn parse(
+        mut self,
+        source_rust_content: &str,
+        src_fns: Vec<SrcFn>,
+        skip_unsupported_functions: bool,
+    ) -> ApiFile {
+        let funcs: Vec<_> = if skip_unsupported_functions {
+            src_fns
+                .iter()
+                .filter_map(|f| self.try_parse_function(f))
+                .collect()
+        } else {
+            src_fns.iter().map(|f| self.parse_function(f)).collect()
+        };
+        check_dart_name_collisions(&funcs);
 
         let has_executor = source_rust_content.contains(HANDLER_NAME);
 
-        ApiFile {
+        let api_file = ApiFile {
             funcs,
             struct_pool: self.struct_pool,
+            enum_pool: self.enum_pool,
             has_executor,
+            struct_dart_aliases: HashMap::new(),
+        };
+        warn_platform_width_inputs(&api_file);
+        api_file
+    }
+
+    /// The parser reports unsupported types by panicking, so catch that and roll back the types
+    /// parsed for this function
+    fn try_parse_function(&mut self, func: &SrcFn) -> Option<ApiFunc> {
+        let struct_pool = self.struct_pool.clone();
+        let enum_pool = self.enum_pool.clone();
+        let parsing_or_parsed_struct_names = self.parsing_or_parsed_struct_names.clone();
+        let parsing_or_parsed_enum_names = self.parsing_or_parsed_enum_names.clone();
+
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.parse_function(func)));
+        panic::set_hook(default_hook);
+
+        match result {
+            Ok(api_func) => Some(api_func),
+            Err(err) => {
+                let reason = err
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| err.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                warn!("skip unsupported function `{}`: {}", func.sig.ident, reason);
+                self.struct_pool = struct_pool;
+                self.enum_pool = enum_pool;
+                self.parsing_or_parsed_struct_names = parsing_or_parsed_struct_names;
+                self.parsing_or_parsed_enum_names = parsing_or_parsed_enum_names;
+                None
+            }
         }
     }
 
-    fn parse_function(&mut self, func: &ItemFn) -> ApiFunc {
+    fn parse_function(&mut self, func: &SrcFn) -> ApiFunc {
         debug!("parse_function function name: {:?}", func.sig.ident);
 
         lazy_static! {
             static ref CAPTURE_RESULT: GenericCapture = GenericCapture::new("Result");
+            static ref CAPTURE_IMPL_INTO: GenericCapture = GenericCapture::new("implInto");
         }
 
-        let sig = &func.sig;
-        let func_name = ident_to_string(&sig.ident);
+        let sig = func.sig;
+        let method_name = ident_to_string(&sig.ident);
+        let func_name = match &func.impl_struct {
+            Some(struct_name) => format!("{}_{}", struct_name.to_case(Case::Snake), method_name),
+            None => method_name.clone(),
+        };
+        warn_misspelled_frb_attrs(func.attrs);
+        self.allow_unsafe_ptr = has_frb_flag(func.attrs, "unsafe_ptr");
+        self.impl_struct = func.impl_struct.clone();
+        self.module = func.module.clone();
 
         let mut inputs = Vec::new();
         let mut output = None;
+        let mut stream_error_output = None;
         let mut mode = None;
+        let mut receiver = None;
 
         for sig_input in &sig.inputs {
-            if let FnArg::Typed(ref pat_type) = sig_input {
+            if let (FnArg::Receiver(ref recv), Some(struct_name)) = (sig_input, &func.impl_struct) {
+                receiver = Some(match (&recv.reference, &recv.mutability) {
+                    (None, _) => ApiMethodReceiver::Value,
+                    (Some(_), None) => ApiMethodReceiver::Ref,
+                    (Some(_), Some(_)) => {
+                        unreachable!(
+                            "&mut self methods are skipped when collected, see {}",
+                            func_name
+                        )
+                    }
+                });
+                inputs.push(ApiField {
+                    name: ApiIdent::new(ApiMethod::RECEIVER_NAME.to_string()),
+                    ty: self.parse_type(struct_name),
+                    comments: vec![],
+                    deprecated: None,
+                    dart_name_override: None,
+                    use_into: false,
+                    default: None,
+                });
+            } else if let FnArg::Typed(ref pat_type) = sig_input {
                 let name = if let Pat::Ident(ref pat_ident) = *pat_type.pat {
                     format!("{}", pat_ident.ident)
                 } else {
@@ -77,15 +403,48 @@ impl<'a> Parser<'a> {
                 };
                 let type_string = type_to_string(&pat_type.ty);
 
-                if let Some(stream_sink_inner_type) = self.try_parse_stream_sink(&type_string) {
+                if let Some((stream_sink_inner_type, stream_error_type)) =
+                    self.try_parse_stream_sink(&type_string)
+                {
                     output = Some(stream_sink_inner_type);
+                    stream_error_output = stream_error_type;
                     mode = Some(ApiFuncMode::Stream);
                 } else {
                     let comments = pat_type.attrs.iter().filter_map(extract_comments).collect();
+                    let (type_string, use_into) =
+                        match CAPTURE_IMPL_INTO.captures_n(&type_string, 1) {
+                            Some(mut params) => (params.remove(0), true),
+                            None => (type_string, has_frb_flag(&pat_type.attrs, "into")),
+                        };
+                    let ty = match self.try_parse_callback(&type_string) {
+                        Some(ty) => ty,
+                        None => {
+                            self.allow_borrowed = true;
+                            self.parse_type(&type_string)
+                        }
+                    };
+                    if use_into
+                        && !matches!(
+                            ty,
+                            Delegate(ApiTypeDelegate::String)
+                                | PrimitiveList(ApiTypePrimitiveList {
+                                    primitive: ApiTypePrimitive::U8
+                                })
+                        )
+                    {
+                        panic!(
+                            "#[frb(into)] only supports String and Vec<u8>, but {} of {} is {}",
+                            name, func_name, type_string
+                        );
+                    }
                     inputs.push(ApiField {
                         name: ApiIdent::new(name),
-                        ty: self.parse_type(&type_string),
+                        ty,
                         comments,
+                        deprecated: None,
+                        dart_name_override: None,
+                        use_into,
+                        default: None,
                     });
                 }
             } else {
@@ -95,9 +454,12 @@ impl<'a> Parser<'a> {
 
         if output.is_none() {
             output = Some(if let ReturnType::Type(_, ty) = &sig.output {
-                let type_string = type_to_string(ty);
-                if let Some(inner) = CAPTURE_RESULT.captures(&type_string) {
-                    self.parse_type(&inner)
+                let type_string = self.resolve_type_alias(&type_to_string(ty));
+                if let Some(ok_type) = CAPTURE_RESULT
+                    .captures_all(&type_string)
+                    .and_then(|params| params.into_iter().next())
+                {
+                    self.parse_type(&ok_type)
                 } else {
                     panic!("unsupported type_string: {}", type_string);
                 }
@@ -105,63 +467,352 @@ impl<'a> Parser<'a> {
                 panic!("unsupported output: {:?}", sig.output);
             });
             mode = Some(
-                if let Some(ApiType::Delegate(ApiTypeDelegate::SyncReturnVecU8)) = output {
+                if let Some(ApiType::Delegate(
+                    ApiTypeDelegate::SyncReturnVecU8 | ApiTypeDelegate::SyncReturnString,
+                )) = output
+                {
                     ApiFuncMode::Sync
                 } else {
                     ApiFuncMode::Normal
                 },
             );
         }
+        if has_frb_flag(func.attrs, "sync") {
+            match (&mode, &output) {
+                (Some(ApiFuncMode::Stream), _) => panic!(
+                    "#[frb(sync)] cannot be used with StreamSink, see {}",
+                    func_name
+                ),
+                (Some(ApiFuncMode::Sync), _) => panic!(
+                    "#[frb(sync)] is redundant with SyncReturn, see {}",
+                    func_name
+                ),
+                (
+                    _,
+                    Some(
+                        Primitive(_)
+                        | Delegate(ApiTypeDelegate::String)
+                        | PrimitiveList(ApiTypePrimitiveList {
+                            primitive: ApiTypePrimitive::U8,
+                        }),
+                    ),
+                ) => {}
+                (_, output) => panic!(
+                    "#[frb(sync)] only supports returning primitives, String and Vec<u8>, which \
+                    are returned as bytes, while other types need Dart objects to be allocated \
+                    by the Rust side, but {} returns {}",
+                    func_name,
+                    output.as_ref().unwrap().rust_api_type()
+                ),
+            }
+            mode = Some(ApiFuncMode::Sync);
+        }
+
+        let error_output = match &sig.output {
+            ReturnType::Type(_, ty) => {
+                let type_string = self.resolve_type_alias(&type_to_string(ty));
+                CAPTURE_RESULT
+                    .captures_all(&type_string)
+                    .and_then(|params| self.parse_func_error(&params, &func_name))
+            }
+            ReturnType::Default => None,
+        };
+        if mode == Some(ApiFuncMode::Sync)
+            && inputs
+                .iter()
+                .any(|input| matches!(&input.ty, Callback(callback) if callback.ret.is_some()))
+        {
+            panic!(
+                "SyncReturn and #[frb(sync)] cannot be used with callbacks returning values, which \
+                would wait for \
+                the Dart isolate blocked by the call, see {}",
+                func_name
+            );
+        }
+        if matches!(error_output, Some(ApiFuncError::Custom(_))) && mode == Some(ApiFuncMode::Sync)
+        {
+            panic!(
+                "SyncReturn and #[frb(sync)] cannot be used with a custom error type, see {}",
+                func_name
+            );
+        }
 
         let comments = func.attrs.iter().filter_map(extract_comments).collect();
 
+        let rate_limit_events_per_second =
+            extract_frb_value(func.attrs, "rate_limit").map(|lit| match lit {
+                Lit::Int(lit) if mode == Some(ApiFuncMode::Stream) => lit
+                    .base10_parse::<u32>()
+                    .ok()
+                    .filter(|&events_per_second| events_per_second > 0)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "rate_limit should be a positive number of events per second, see {}",
+                            func_name
+                        )
+                    }),
+                _ => panic!(
+                    "rate_limit should be an integer on a function with StreamSink, see {}",
+                    func_name
+                ),
+            });
+
+        let stream_buffer_size =
+            extract_frb_value(func.attrs, "stream_buffer").map(|lit| match lit {
+                Lit::Int(lit) if mode == Some(ApiFuncMode::Stream) => lit
+                    .base10_parse::<usize>()
+                    .unwrap_or_else(|_| panic!("invalid stream_buffer for {}", func_name)),
+                _ => panic!(
+                    "stream_buffer should be an integer on a function with StreamSink, see {}",
+                    func_name
+                ),
+            });
+        if rate_limit_events_per_second.is_some() && stream_buffer_size.is_some() {
+            panic!(
+                "rate_limit and stream_buffer cannot be used together, see {}",
+                func_name
+            );
+        }
+
+        let throw_on_null = has_frb_flag(func.attrs, "throw_on_null_output");
+        if throw_on_null && !matches!(output, Some(Optional(_))) {
+            panic!(
+                "throw_on_null_output can only be used on functions returning an Option, see {}",
+                func_name
+            );
+        }
+
+        let dart_name_override = extract_frb_dart_name(func.attrs, &func_name);
+
         ApiFunc {
             name: func_name,
             inputs,
             output: output.expect("unsupported output"),
+            error_output,
+            stream_error_output,
             mode: mode.expect("unsupported mode"),
             comments,
+            deprecated: extract_deprecated(func.attrs),
+            is_test: has_frb_flag(func.attrs, "test"),
+            rate_limit_events_per_second,
+            stream_buffer_size,
+            dart_name_override,
+            throw_on_null,
+            method: func.impl_struct.clone().map(|struct_name| ApiMethod {
+                struct_name,
+                method_name,
+                receiver,
+            }),
+            rust_module: func.module.clone(),
+            source_location: {
+                // 1-based columns like `column!()`
+                let start = sig.ident.span().start();
+                format!("{}:{}", start.line, start.column + 1)
+            },
+        }
+    }
+
+    /// e.g. `["u32", "MyError"]` of `Result<u32, MyError>`, where both a missing error type (as in
+    /// `anyhow::Result<u32>`) and `anyhow::Error` give [None]
+    fn parse_func_error(
+        &mut self,
+        result_args: &[String],
+        func_name: &str,
+    ) -> Option<ApiFuncError> {
+        let error_type = self.resolve_type_alias(result_args.get(1)?);
+        if let Some(ty) = self.try_parse_enum(&error_type) {
+            return Some(ApiFuncError::Custom(ty));
+        }
+        match error_type.as_str() {
+            "anyhow::Error" | "Error" => None,
+            "String" => Some(ApiFuncError::String),
+            _ => panic!(
+                "the error type of Result should be String, anyhow::Error or an enum, but the one \
+                of {} is {}",
+                func_name, error_type
+            ),
         }
     }
 
     fn parse_type(&mut self, ty: &str) -> ApiType {
         debug!("parse_type: {}", ty);
+        let alias = self.type_alias_map.contains_key(ty).then(|| ty.to_string());
+        let ty = &self.resolve_type_alias(ty);
+        check_unsupported_type(ty);
+        // nested types, e.g. the `T` of `Vec<T>`, must not be borrowed
+        let allow_borrowed = std::mem::take(&mut self.allow_borrowed);
         None.or_else(|| ApiTypePrimitive::try_from_rust_str(ty).map(Primitive))
+            .or_else(|| self.try_parse_borrowed(ty, allow_borrowed))
             .or_else(|| self.try_parse_api_type_delegate(ty))
+            .or_else(|| self.try_parse_opaque_weak(ty))
+            .or_else(|| try_parse_opaque(ty, alias))
             .or_else(|| self.try_parse_list(ty))
+            .or_else(|| self.try_parse_map(ty))
+            .or_else(|| self.try_parse_set(ty))
+            .or_else(|| self.try_parse_tuple(ty))
+            .or_else(|| self.try_parse_array(ty))
             .or_else(|| self.try_parse_box(ty))
             .or_else(|| self.try_parse_option(ty))
             .or_else(|| self.try_parse_struct(ty))
-            .unwrap_or_else(|| panic!("parse_type failed for ty={}", ty))
+            .or_else(|| self.try_parse_enum(ty))
+            .unwrap_or_else(|| match self.resolve_re_export(ty) {
+                Some(path) => panic!(
+                    "{} is re-exported from {}, which is outside of this file and cannot be \
+                    parsed; bridge a struct with #[frb(mirror = \"{}\")] and the same fields \
+                    instead",
+                    ty, path, path
+                ),
+                None => panic!("parse_type failed for ty={}", ty),
+            })
+    }
+
+    /// e.g. with `type A = B;` and `type B = String;`, both `A` and `B` resolve to `String`
+    /// Also resolves `Self` in methods, which is the struct of the `impl` block
+    fn resolve_type_alias(&self, ty: &str) -> String {
+        let mut resolved = match &self.impl_struct {
+            Some(struct_name) => replace_self_type(ty, struct_name),
+            None => ty.to_string(),
+        };
+        let mut seen = HashSet::new();
+        while let Some(target) = self.type_alias_map.get(&resolved) {
+            if !seen.insert(resolved.clone()) {
+                panic!("type alias {} is cyclic", ty);
+            }
+            resolved = target.clone();
+        }
+        resolved
     }
 
-    fn try_parse_stream_sink(&mut self, ty: &str) -> Option<ApiType> {
+    /// The type of the events, and the `E` of `StreamSink<Result<T, E>>`
+    fn try_parse_stream_sink(&mut self, ty: &str) -> Option<(ApiType, Option<ApiType>)> {
         lazy_static! {
             static ref CAPTURE_STREAM_SINK: GenericCapture = GenericCapture::new("StreamSink");
+            static ref CAPTURE_RESULT: GenericCapture = GenericCapture::new("Result");
         }
 
-        CAPTURE_STREAM_SINK
-            .captures(ty)
-            .map(|inner| self.parse_type(&inner))
+        let inner = CAPTURE_STREAM_SINK.captures_n(ty, 1)?.remove(0);
+        Some(match CAPTURE_RESULT.captures_all(&inner).as_deref() {
+            Some([ok_type, error_type]) => (
+                self.parse_type(ok_type),
+                Some(self.parse_type(error_type)),
+            ),
+            Some(_) => panic!(
+                "the error type of {} is needed to send errors to Dart, e.g. StreamSink<Result<T, String>>",
+                ty
+            ),
+            None => (self.parse_type(&inner), None),
+        })
     }
 
     fn try_parse_api_type_delegate(&mut self, ty: &str) -> Option<ApiType> {
+        if let Some(cow) = try_parse_cow(ty) {
+            return Some(cow);
+        }
         match ty {
             "SyncReturn<Vec<u8>>" => Some(ApiType::Delegate(ApiTypeDelegate::SyncReturnVecU8)),
+            "SyncReturn<String>" => Some(ApiType::Delegate(ApiTypeDelegate::SyncReturnString)),
             "String" => Some(ApiType::Delegate(ApiTypeDelegate::String)),
+            "i128" => Some(ApiType::Delegate(ApiTypeDelegate::I128)),
+            "u128" => Some(ApiType::Delegate(ApiTypeDelegate::U128)),
             _ => {
                 lazy_static! {
                     static ref CAPTURE_ZERO_COPY_BUFFER: GenericCapture =
                         GenericCapture::new("ZeroCopyBuffer");
+                    static ref CAPTURE_NON_NULL: GenericCapture = GenericCapture::new("NonNull");
+                    static ref CAPTURE_RANGE: GenericCapture = GenericCapture::new("Range");
+                    static ref CAPTURE_RANGE_INCLUSIVE: GenericCapture =
+                        GenericCapture::new("RangeInclusive");
+                    static ref CHRONO_DATE_TIME_REGEX: Regex =
+                        Regex::new(r"^(chrono::)?DateTime<(chrono::)?(Utc|Local)>$").unwrap();
+                    static ref CHRONO_NAIVE_REGEX: Regex =
+                        Regex::new(r"^(chrono::)?(NaiveDate|NaiveDateTime)$").unwrap();
+                    static ref UUID_REGEX: Regex = Regex::new(r"^(uuid::)?Uuid$").unwrap();
+                    static ref DYN_ERROR_REGEX: Regex =
+                        Regex::new(r"^Box<dyn(std::error::)?Error((\+(Send|Sync|'static))*)>$")
+                            .unwrap();
                 }
 
-                if let Some(inner_type_str) = CAPTURE_ZERO_COPY_BUFFER.captures(ty) {
-                    if let Some(ApiType::PrimitiveList(ApiTypePrimitiveList { primitive })) =
-                        self.try_parse_list(&inner_type_str)
-                    {
-                        return Some(ApiType::Delegate(
-                            ApiTypeDelegate::ZeroCopyBufferVecPrimitive(primitive),
-                        ));
+                for (capture, inclusive) in
+                    [(&*CAPTURE_RANGE, false), (&*CAPTURE_RANGE_INCLUSIVE, true)]
+                {
+                    if let Some([inner_type_str]) = capture.captures_n(ty, 1).as_deref() {
+                        return match ApiTypePrimitive::try_from_rust_str(inner_type_str) {
+                            Some(ApiTypePrimitive::Bool) | None => {
+                                panic!("only ranges of numbers are supported, see {}", ty)
+                            }
+                            Some(primitive) => Some(ApiType::Delegate(ApiTypeDelegate::Range(
+                                primitive, inclusive,
+                            ))),
+                        };
+                    }
+                }
+
+                if let Some(captures) = CHRONO_DATE_TIME_REGEX.captures(ty) {
+                    return Some(ApiType::Delegate(ApiTypeDelegate::Chrono(
+                        match captures.get(3).unwrap().as_str() {
+                            "Utc" => ApiTypeChrono::Utc,
+                            _ => ApiTypeChrono::Local,
+                        },
+                    )));
+                }
+
+                if let Some(captures) = CHRONO_NAIVE_REGEX.captures(ty) {
+                    return Some(ApiType::Delegate(ApiTypeDelegate::Chrono(
+                        match captures.get(2).unwrap().as_str() {
+                            "NaiveDate" => ApiTypeChrono::NaiveDate,
+                            _ => ApiTypeChrono::NaiveDateTime,
+                        },
+                    )));
+                }
+
+                // unless it is a struct of the API itself
+                if UUID_REGEX.is_match(ty) && !self.src_struct_map.contains_key(ty) {
+                    return Some(ApiType::Delegate(ApiTypeDelegate::Uuid(
+                        self.dart_uuid_style == DartUuidStyle::String,
+                    )));
+                }
+
+                if let Some(captures) = DYN_ERROR_REGEX.captures(ty) {
+                    return Some(ApiType::Delegate(ApiTypeDelegate::DynError(
+                        captures.get(2).unwrap().as_str().to_string(),
+                    )));
+                }
+
+                if let Some(mut params) = CAPTURE_NON_NULL.captures_n(ty, 1) {
+                    if !self.allow_unsafe_ptr {
+                        panic!(
+                            "{} is a raw pointer, please add `#[frb(unsafe_ptr)]` to the function to opt in",
+                            ty
+                        );
+                    }
+                    return Some(ApiType::Delegate(ApiTypeDelegate::NonNull(
+                        params.remove(0),
+                    )));
+                }
+
+                if let Some([inner_type_str]) =
+                    CAPTURE_ZERO_COPY_BUFFER.captures_n(ty, 1).as_deref()
+                {
+                    match self.try_parse_list(inner_type_str) {
+                        Some(ApiType::PrimitiveList(ApiTypePrimitiveList { primitive })) => {
+                            return Some(ApiType::Delegate(ApiTypeDelegate::ZeroCopyBufferVec(
+                                Box::new(ApiType::Primitive(primitive)),
+                            )));
+                        }
+                        Some(ApiType::GeneralList(list)) => match list.inner {
+                            inner @ ApiType::PrimitiveList(_) => {
+                                return Some(ApiType::Delegate(
+                                    ApiTypeDelegate::ZeroCopyBufferVec(Box::new(inner)),
+                                ));
+                            }
+                            ApiType::Delegate(ApiTypeDelegate::String) => {
+                                return Some(ApiType::Delegate(
+                                    ApiTypeDelegate::ZeroCopyBufferVecString,
+                                ));
+                            }
+                            _ => {}
+                        },
+                        _ => {}
                     }
                 }
 
@@ -170,13 +821,137 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn try_parse_borrowed(&mut self, ty: &str, allow_borrowed: bool) -> Option<ApiType> {
+        lazy_static! {
+            static ref BORROWED_REGEX: Regex = Regex::new(r"^&('\w+)?(str|\[u8\])$").unwrap();
+        }
+
+        let captures = BORROWED_REGEX.captures(ty)?;
+        if !allow_borrowed {
+            panic!(
+                "{} is only supported as a function parameter, use an owned type instead",
+                ty
+            );
+        }
+        if captures.get(1).map(|lifetime| lifetime.as_str()) == Some("'static") {
+            panic!(
+                "{} cannot be borrowed from Dart data, use an owned type instead",
+                ty
+            );
+        }
+        Some(Delegate(match captures.get(2).unwrap().as_str() {
+            "str" => ApiTypeDelegate::StringRef,
+            _ => ApiTypeDelegate::SliceU8,
+        }))
+    }
+
+    /// `impl Fn(..)`, `Box<dyn Fn(..)>`, or either in an `Option`, as a function input. Type
+    /// aliases of trait objects are opaque handles instead, see [try_parse_opaque].
+    fn try_parse_callback(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            static ref CAPTURE_OPTION: GenericCapture = GenericCapture::new("Option");
+            // spaces are removed from the type, e.g. `Box<dynFn(u32)->bool+Send>`
+            static ref CALLBACK_REGEX: Regex = Regex::new(
+                r"^(?:impl|Box<dyn)Fn\((.*)\)(?:->(\(\)|\w+|(?:anyhow::)?Result<\w+>))?((?:\+[\w']+)*)(>?)$"
+            )
+            .unwrap();
+        }
+
+        if self.type_alias_map.contains_key(ty) {
+            return None;
+        }
+        let (inner, is_optional) = match CAPTURE_OPTION.captures_n(ty, 1) {
+            Some(mut params) => (params.remove(0), true),
+            None => (ty.to_string(), false),
+        };
+        let captures = CALLBACK_REGEX.captures(&inner)?;
+        let is_boxed = inner.starts_with("Box<");
+        if is_boxed != (&captures[4] == ">") {
+            return None;
+        }
+
+        let args = split_top_level_commas(&captures[1]);
+        if args.len() > ApiTypeCallback::MAX_ARGS {
+            panic!(
+                "callbacks of at most {} arguments are supported, see {}",
+                ApiTypeCallback::MAX_ARGS,
+                ty
+            );
+        }
+        let args: Vec<_> = args.into_iter().map(|arg| self.parse_type(arg)).collect();
+        let ret = captures
+            .get(2)
+            .map(|ret| ret.as_str().trim_start_matches("anyhow::"));
+        let ret_is_result = matches!(ret, Some(ret) if ret.starts_with("Result<"));
+        let ret = match ret.map(|ret| ret.trim_start_matches("Result<").trim_end_matches('>')) {
+            None | Some("()") => None,
+            Some(ret) => Some(ApiTypePrimitive::try_from_rust_str(ret).unwrap_or_else(|| {
+                panic!(
+                    "callbacks may only return `()`, a primitive or a `Result` of a primitive, \
+                    but {} returns {}",
+                    ty, ret
+                )
+            })),
+        };
+
+        let signature = format!(
+            "Fn({}){}",
+            args.iter()
+                .map(ApiType::rust_api_type)
+                .collect::<Vec<_>>()
+                .join(", "),
+            // `DartCallback::call` returns `anyhow::Result`, like the bridged functions must
+            ret.as_ref().map_or(String::new(), |ret| if ret_is_result {
+                format!(" -> anyhow::Result<{}>", ret.rust_api_type())
+            } else {
+                format!(" -> {}", ret.rust_api_type())
+            })
+        );
+        // a closure of `impl Fn(..)` is passed as a box, which may also need to be sent
+        let bounds = if is_boxed {
+            captures[3].replace('+', " + ")
+        } else {
+            " + Send + Sync".to_string()
+        };
+        Some(Callback(ApiTypeCallback {
+            args,
+            ret,
+            ret_is_result,
+            is_optional,
+            rust_box_type: format!("Box<dyn {}{}>", signature, bounds),
+        }))
+    }
+
+    fn try_parse_opaque_weak(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            // NOTE do not use [GenericCapture], which would also match `rc::Weak<T>`
+            static ref WEAK_REGEX: Regex = Regex::new(r"^(std::sync::|sync::)?Weak<(\w+)>$").unwrap();
+        }
+
+        let inner = WEAK_REGEX.captures(ty)?.get(2).unwrap().as_str();
+        match self.try_parse_struct(inner) {
+            Some(StructRef(inner)) => Some(OpaqueWeak(ApiTypeOpaqueWeak { inner })),
+            _ => panic!("only Weak<T> of a struct T is supported, see {}", ty),
+        }
+    }
+
     fn try_parse_list(&mut self, ty: &str) -> Option<ApiType> {
         lazy_static! {
             static ref CAPTURE_VEC: GenericCapture = GenericCapture::new("Vec");
+            static ref VEC_OF_PAIRS_REGEX: Regex = Regex::new(r"^Vec<\((.+)\)>$").unwrap();
         }
 
-        if let Some(inner_type_str) = CAPTURE_VEC.captures(ty) {
-            match self.parse_type(&inner_type_str) {
+        let pair = VEC_OF_PAIRS_REGEX
+            .captures(ty)
+            .map(|captures| split_top_level_commas(captures.get(1).unwrap().as_str()))
+            .filter(|elements| elements.len() == 2);
+        if let Some(elements) = pair {
+            Some(AssocList(Box::new(ApiTypeAssocList {
+                key: self.parse_type(elements[0]),
+                value: self.parse_type(elements[1]),
+            })))
+        } else if let Some([inner_type_str]) = CAPTURE_VEC.captures_n(ty, 1).as_deref() {
+            match self.parse_type(inner_type_str) {
                 Primitive(primitive) => Some(PrimitiveList(ApiTypePrimitiveList { primitive })),
                 others => Some(GeneralList(Box::from(ApiTypeGeneralList { inner: others }))),
             }
@@ -185,15 +960,93 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn try_parse_map(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            static ref MAP_REGEX: Regex =
+                Regex::new(r"^(std::collections::)?(HashMap|BTreeMap)<(.+)>$").unwrap();
+        }
+
+        let captures = MAP_REGEX.captures(ty)?;
+        let elements = split_top_level_commas(captures.get(3).unwrap().as_str());
+        if elements.len() != 2 {
+            panic!("expect a key and a value type in {}", ty);
+        }
+        // check before parsing, so that the error points at the map instead of a nested type
+        if elements[0] != "String" && ApiTypePrimitive::try_from_rust_str(elements[0]).is_none() {
+            panic!(
+                "unsupported key type {} of {}, only primitives and String are supported as keys",
+                elements[0], ty
+            );
+        }
+        Some(Map(Box::new(ApiTypeMap {
+            key: self.parse_type(elements[0]),
+            value: self.parse_type(elements[1]),
+            is_ordered: captures.get(2).unwrap().as_str() == "BTreeMap",
+        })))
+    }
+
+    fn try_parse_set(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            static ref SET_REGEX: Regex =
+                Regex::new(r"^(std::collections::)?(HashSet|BTreeSet)<(.+)>$").unwrap();
+        }
+
+        let captures = SET_REGEX.captures(ty)?;
+        Some(Delegate(ApiTypeDelegate::Set(Box::new(ApiTypeSet {
+            element: self.parse_type(captures.get(3).unwrap().as_str()),
+            is_ordered: captures.get(2).unwrap().as_str() == "BTreeSet",
+        }))))
+    }
+
+    fn try_parse_tuple(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            static ref TUPLE_REGEX: Regex = Regex::new(r"^\((.+)\)$").unwrap();
+        }
+
+        let captures = TUPLE_REGEX.captures(ty)?;
+        let elements = split_top_level_commas(captures.get(1).unwrap().as_str());
+        if !(2..=3).contains(&elements.len()) {
+            panic!(
+                "only tuples of two or three elements are supported, see {}",
+                ty
+            );
+        }
+        Some(Tuple(Box::new(ApiTypeTuple {
+            elements: elements
+                .into_iter()
+                .map(|element| self.parse_type(element))
+                .collect(),
+        })))
+    }
+
+    fn try_parse_array(&mut self, ty: &str) -> Option<ApiType> {
+        lazy_static! {
+            static ref ARRAY_REGEX: Regex = Regex::new(r"^\[(.+);(.+)\]$").unwrap();
+        }
+
+        let captures = ARRAY_REGEX.captures(ty)?;
+        let length = captures
+            .get(2)
+            .unwrap()
+            .as_str()
+            .trim_end_matches("usize")
+            .parse()
+            .unwrap_or_else(|_| panic!("only arrays of literal lengths are supported, see {}", ty));
+        Some(Delegate(ApiTypeDelegate::Array(Box::new(ApiTypeArray {
+            element: self.parse_type(captures.get(1).unwrap().as_str()),
+            length,
+        }))))
+    }
+
     fn try_parse_box(&mut self, ty: &str) -> Option<ApiType> {
         lazy_static! {
             static ref CAPTURE_BOX: GenericCapture = GenericCapture::new("Box");
         }
 
-        CAPTURE_BOX.captures(ty).map(|inner| {
+        CAPTURE_BOX.captures_n(ty, 1).map(|params| {
             Boxed(Box::new(ApiTypeBoxed {
                 exist_in_real_api: true,
-                inner: self.parse_type(&inner),
+                inner: self.parse_type(&params[0]),
             }))
         })
     }
@@ -203,129 +1056,1812 @@ impl<'a> Parser<'a> {
             static ref CAPTURE_OPTION: GenericCapture = GenericCapture::new("Option");
         }
 
-        CAPTURE_OPTION.captures(ty).map(|inner| {
-            let inner_option = CAPTURE_OPTION.captures(&inner);
-            if let Some(inner_option) = inner_option {
+        CAPTURE_OPTION.captures_n(ty, 1).map(|params| {
+            let inner = &params[0];
+            if let Some([inner_option]) = CAPTURE_OPTION.captures_n(inner, 1).as_deref() {
                 panic!(
                     "Nested optionals without indirection are not supported. (Option<Option<{}>>)",
                     inner_option
                 );
             };
-            match self.parse_type(&inner) {
+            match self.parse_type(inner) {
                 Primitive(prim) => ApiType::Optional(ApiTypeOptional::new_prim(prim)),
-                st @ StructRef(_) => {
+                st @ (StructRef(_) | EnumRef(_)) => {
                     ApiType::Optional(ApiTypeOptional::new_ptr(Boxed(Box::new(ApiTypeBoxed {
                         inner: st,
                         exist_in_real_api: false,
                     }))))
                 }
+                // e.g. `chrono` types, which are passed as integers and boxed like them
+                d @ Delegate(_) if !d.rust_wire_is_pointer() => {
+                    ApiType::Optional(ApiTypeOptional::new_ptr(Boxed(Box::new(ApiTypeBoxed {
+                        inner: d,
+                        exist_in_real_api: false,
+                    }))))
+                }
                 other => ApiType::Optional(ApiTypeOptional::new_ptr(other)),
             }
         })
     }
 
+    /// Generic structs are parsed again for each type argument, e.g. `ApiResponse<String>`
     fn try_parse_struct(&mut self, ty: &str) -> Option<ApiType> {
-        if !self.src_struct_map.contains_key(ty) {
-            return None;
+        lazy_static! {
+            static ref GENERIC_STRUCT_REGEX: Regex = Regex::new(r"^([\w:]+)<(.+)>$").unwrap();
+        }
+
+        let (src_name, generic_arg) = match self.resolve_struct(ty) {
+            Some(src_name) => (src_name, None),
+            None => {
+                let captures = GENERIC_STRUCT_REGEX.captures(ty)?;
+                (
+                    self.resolve_struct(captures.get(1).unwrap().as_str())?,
+                    Some(captures.get(2).unwrap().as_str().to_string()),
+                )
+            }
+        };
+        let name = struct_name(&src_name, generic_arg.as_deref());
+        let rust_type = match &generic_arg {
+            Some(arg) => format!("{}<{}>", src_name, arg),
+            None => src_name.clone(),
+        };
+
+        // the fields stay in Rust, e.g. file descriptors or raw pointers that cannot be sent
+        if has_frb_flag(&self.src_struct_map[&src_name].attrs, "opaque") {
+            return Some(Opaque(ApiTypeOpaque { name, rust_type }));
         }
 
-        if !self.parsing_or_parsed_struct_names.contains(ty) {
-            self.parsing_or_parsed_struct_names.insert(ty.to_string());
-            let api_struct = self.parse_struct_core(ty);
-            self.struct_pool.insert(ty.to_string(), api_struct);
+        if !self.parsing_or_parsed_struct_names.contains(&name) {
+            self.parsing_or_parsed_struct_names.insert(name.clone());
+            let api_struct = self.parse_struct_core(&src_name, generic_arg.as_deref());
+            self.struct_pool.insert(name.clone(), api_struct);
         }
 
         Some(StructRef(ApiTypeStructRef {
+            generic_rust_type: (rust_type != name).then_some(rust_type),
+            name,
+        }))
+    }
+
+    /// The key in [Self::src_struct_map] of the struct [ty] refers to, where those of the
+    /// [Self::module] being parsed take precedence, e.g. `geometry::Point` of `Point`
+    fn resolve_struct(&self, ty: &str) -> Option<String> {
+        self.module
+            .iter()
+            .map(|module| format!("{}::{}", module, ty))
+            .chain(std::iter::once(ty.to_string()))
+            .find(|key| self.src_struct_map.contains_key(key))
+    }
+
+    /// The path [ty] is re-exported from by `pub use`, where those of the [Self::module] being
+    /// parsed take precedence like in [Self::resolve_struct]
+    fn resolve_re_export(&self, ty: &str) -> Option<&String> {
+        self.module
+            .iter()
+            .map(|module| format!("{}::{}", module, ty))
+            .chain(std::iter::once(ty.to_string()))
+            .find_map(|key| self.re_exports.get(&key))
+    }
+
+    fn try_parse_enum(&mut self, ty: &str) -> Option<ApiType> {
+        if !self.src_enum_map.contains_key(ty) {
+            return None;
+        }
+
+        if !self.parsing_or_parsed_enum_names.contains(ty) {
+            self.parsing_or_parsed_enum_names.insert(ty.to_string());
+            let api_enum = self.parse_enum_core(ty);
+            self.enum_pool.insert(ty.to_string(), api_enum);
+        }
+
+        Some(EnumRef(ApiTypeEnumRef {
             name: ty.to_string(),
         }))
     }
 
-    fn parse_struct_core(&mut self, ty: &str) -> ApiStruct {
+    /// [generic_arg] is the type argument of a generic struct, which replaces its type parameter
+    fn parse_struct_core(&mut self, ty: &str, generic_arg: Option<&str>) -> ApiStruct {
         let item_struct = self.src_struct_map[ty];
+        warn_misspelled_frb_attrs(&item_struct.attrs);
         let mut fields = Vec::new();
 
+        let generic_param = parse_struct_generic_param(item_struct, generic_arg.is_some());
+        let self_type = match generic_arg {
+            Some(arg) => format!("{}<{}>", ty, arg),
+            None => ty.to_string(),
+        };
+
+        // the field types are written relative to the module of the struct, which for a
+        // re-exported one is where it is defined
+        let defined_at = self.re_exports.get(ty).map_or(ty, String::as_str);
+        let parent_module = std::mem::replace(&mut self.module, module_of(defined_at));
+
         let (is_fields_named, struct_fields) = match &item_struct.fields {
             Fields::Named(FieldsNamed { named, .. }) => (true, named),
             Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => (false, unnamed),
             _ => panic!("unsupported type: {:?}", item_struct.fields),
         };
 
+        let mut num_private_fields = 0;
         for (idx, field) in struct_fields.iter().enumerate() {
+            if !is_included(&field.vis, self.included_visibility) {
+                num_private_fields += 1;
+                continue;
+            }
+
             let field_name = field
                 .ident
                 .as_ref()
                 .map_or(format!("field{}", idx), |id| ident_to_string(id));
-            let field_type_str = type_to_string(&field.ty);
+            // e.g. `Option<Box<Self>>` of recursive structs
+            let mut field_type_str = replace_self_type(&type_to_string(&field.ty), &self_type);
+            if let (Some(param), Some(arg)) = (&generic_param, generic_arg) {
+                field_type_str = replace_generic_param(&field_type_str, param, arg);
+            }
             let field_type = self.parse_type(&field_type_str);
             let comments = field.attrs.iter().filter_map(extract_comments).collect();
+            let default = extract_frb_value(&field.attrs, "default").map(|lit| {
+                field_default_to_dart(&lit, &field_type)
+                    .unwrap_or_else(|| panic!("unsupported default value of {}.{}", ty, field_name))
+            });
+            let dart_name_override =
+                extract_frb_dart_name(&field.attrs, &format!("{}.{}", ty, field_name));
             fields.push(ApiField {
                 name: ApiIdent::new(field_name),
                 ty: field_type,
                 comments,
+                deprecated: extract_deprecated(&field.attrs),
+                dart_name_override,
+                use_into: false,
+                default,
             });
         }
 
-        let name = ident_to_string(&item_struct.ident);
+        check_field_dart_name_collisions(&fields, ty);
+        self.module = parent_module;
+
+        let name = struct_name(ty, generic_arg);
         let comments = item_struct
             .attrs
             .iter()
             .filter_map(extract_comments)
             .collect();
+        let on_change_callback =
+            extract_frb_value(&item_struct.attrs, "on_change").map(|lit| match lit {
+                Lit::Str(lit) => lit.value(),
+                _ => panic!("on_change should be a string, see {}", name),
+            });
+        let notify_on_change =
+            on_change_callback.is_some() || has_frb_flag(&item_struct.attrs, "on_change");
+        let mirror = parse_struct_mirror(&item_struct.attrs, &name, num_private_fields);
+        let implements_default =
+            has_derive(&item_struct.attrs, "Default") || self.default_impls.contains(ty);
+        let associated_consts = self
+            .src_impl_const_map
+            .get(ty)
+            .map(|consts| consts.iter().filter_map(|c| parse_const(c)).collect())
+            .unwrap_or_default();
+        let generic_rust_type = (self_type != name).then_some(self_type);
         ApiStruct {
             name,
             fields,
             is_fields_named,
             comments,
+            deprecated: extract_deprecated(&item_struct.attrs),
+            notify_on_change,
+            on_change_callback,
+            associated_consts,
+            num_private_fields,
+            implements_default,
+            mirror,
+            generic_rust_type,
         }
     }
-}
 
-fn extract_items_from_file(file: &File) -> (Vec<&ItemFn>, StructMap) {
-    let mut src_fns = Vec::new();
-    let mut src_struct_map = HashMap::new();
-    for item in file.items.iter() {
-        match item {
-            Item::Fn(ref item_fn) => {
-                if let Visibility::Public(_) = &item_fn.vis {
-                    src_fns.push(item_fn);
-                }
-            }
-            Item::Struct(ref item_struct) => {
-                if let Visibility::Public(_) = &item_struct.vis {
-                    src_struct_map.insert(item_struct.ident.to_string(), item_struct);
+    fn parse_enum_core(&mut self, ty: &str) -> ApiEnum {
+        let item_enum = self.src_enum_map[ty];
+        let name = ident_to_string(&item_enum.ident);
+        let variants: Vec<_> = item_enum
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = ident_to_string(&variant.ident);
+                let (is_fields_named, variant_fields) = match &variant.fields {
+                    Fields::Unit => (false, None),
+                    Fields::Named(FieldsNamed { named, .. }) => (true, Some(named)),
+                    Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => (false, Some(unnamed)),
+                };
+                let kind = match variant_fields {
+                    None => ApiVariantKind::Value,
+                    Some(variant_fields) => {
+                        // fields of variants are always public
+                        let fields = variant_fields
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, field)| ApiField {
+                                name: ApiIdent::new(
+                                    field
+                                        .ident
+                                        .as_ref()
+                                        .map_or(format!("field{}", idx), ident_to_string),
+                                ),
+                                ty: self.parse_type(&type_to_string(&field.ty)),
+                                comments: field.attrs.iter().filter_map(extract_comments).collect(),
+                                deprecated: extract_deprecated(&field.attrs),
+                                dart_name_override: None,
+                                use_into: false,
+                                default: None,
+                            })
+                            .collect();
+                        ApiVariantKind::Struct(Box::new(ApiStruct {
+                            name: variant_name.clone(),
+                            fields,
+                            is_fields_named,
+                            comments: vec![],
+                            deprecated: None,
+                            notify_on_change: false,
+                            on_change_callback: None,
+                            associated_consts: vec![],
+                            num_private_fields: 0,
+                            implements_default: false,
+                            mirror: None,
+                            generic_rust_type: None,
+                        }))
+                    }
+                };
+                ApiVariant {
+                    name: ApiIdent::new(variant_name),
+                    comments: variant.attrs.iter().filter_map(extract_comments).collect(),
+                    kind,
                 }
-            }
-            _ => {}
+            })
+            .collect();
+        let comments = item_enum
+            .attrs
+            .iter()
+            .filter_map(extract_comments)
+            .collect();
+        let is_enum_class = has_frb_flag(&item_enum.attrs, "enum_class");
+        let is_non_exhaustive = item_enum
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("non_exhaustive"));
+        if is_non_exhaustive
+            && variants
+                .iter()
+                .any(|v: &ApiVariant| v.name.dart_style() == ApiEnum::UNKNOWN_VARIANT_DART_NAME)
+        {
+            panic!(
+                "non-exhaustive enum {} cannot have a variant named `{}` in Dart, since it is reserved for unrecognised values",
+                name,
+                ApiEnum::UNKNOWN_VARIANT_DART_NAME
+            );
         }
+        let api_enum = ApiEnum {
+            name,
+            variants,
+            comments,
+            is_enum_class,
+            is_non_exhaustive,
+        };
+        if api_enum.is_struct() && (is_enum_class || is_non_exhaustive) {
+            panic!(
+                "enum {} has data in its variants, so neither #[frb(enum_class)] nor #[non_exhaustive] is supported",
+                api_enum.name
+            );
+        }
+        api_enum
     }
-    // println!("[Functions]\n{:#?}", src_fns);
-    // println!("[Structs]\n{:#?}", src_struct_map);
-    (src_fns, src_struct_map)
 }
 
-fn ident_to_string(ident: &Ident) -> String {
-    format!("{}", ident)
+/// Two functions mapping to the same Dart name would produce invalid Dart code
+pub(crate) fn check_dart_name_collisions(funcs: &[ApiFunc]) {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for func in funcs {
+        let dart_name = func.dart_name();
+        if let Some(other) = seen.insert(dart_name.clone(), &func.name) {
+            panic!(
+                "functions '{}' and '{}' both map to Dart name '{}'. Please add `#[frb(name = \"...\")]` to disambiguate.",
+                other, func.name, dart_name
+            );
+        }
+    }
 }
 
-/// syn -> string https://github.com/dtolnay/syn/issues/294
-fn type_to_string(ty: &Type) -> String {
-    quote!(#ty).to_string().replace(" ", "")
+/// Like [check_dart_name_collisions], for the fields of the struct [ty]
+fn check_field_dart_name_collisions(fields: &[ApiField], ty: &str) {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for field in fields {
+        let dart_name = field.dart_name();
+        if let Some(other) = seen.insert(dart_name.clone(), field.name.rust_style()) {
+            panic!(
+                "fields '{}' and '{}' of {} both map to Dart name '{}'. Please add `#[frb(name = \"...\")]` to disambiguate.",
+                other, field.name.rust_style(), ty, dart_name
+            );
+        }
+    }
 }
 
-struct GenericCapture {
-    regex: Regex,
+/// `Cow<'_, str>` and `Cow<'_, [u8]>`, with or without the lifetime, are a `String` and a
+/// `Uint8List` in Dart, which does not know whether Rust borrows them
+fn try_parse_cow(ty: &str) -> Option<ApiType> {
+    lazy_static! {
+        static ref COW_REGEX: Regex =
+            Regex::new(r"^(std::borrow::)?Cow<('\w+,)?(\[u8\]|str)>$").unwrap();
+    }
+
+    let captures = COW_REGEX.captures(ty)?;
+    Some(Delegate(match captures.get(3).unwrap().as_str() {
+        "str" => ApiTypeDelegate::CowStr,
+        _ => ApiTypeDelegate::CowBytes,
+    }))
 }
 
-impl GenericCapture {
-    pub fn new(cls_name: &str) -> Self {
-        let regex = Regex::new(&*format!("^[^<]*{}<([a-zA-Z0-9_<>]+)>$", cls_name)).unwrap();
-        Self { regex }
+/// Fail early with an actionable message, instead of an obscure error in the generated code
+/// Trait objects other than `Box<dyn Error>`, which is a delegate passed as its message.
+/// Named after [alias] if the type is a type alias, e.g. `Adder` of
+/// `type Adder = Box<dyn Fn(i32) -> i32 + Send>`, or otherwise after the trait, e.g. `DynFnI32I32`.
+fn try_parse_opaque(ty: &str, alias: Option<String>) -> Option<ApiType> {
+    lazy_static! {
+        static ref BOX_DYN_REGEX: Regex = Regex::new(r"^Box<dyn(.+)>$").unwrap();
+        // spaces are removed from the type, e.g. `Box<dynFn(i32)->i32+Send>`
+        static ref DYN_REGEX: Regex = Regex::new(r"(^|[<(,&+]|->)dyn").unwrap();
+        static ref AUTO_TRAIT_REGEX: Regex = Regex::new(r"\+(Send|Sync|'\w+)").unwrap();
+    }
+
+    let bounds = BOX_DYN_REGEX.captures(ty)?.get(1).unwrap().as_str();
+    let name = alias.unwrap_or_else(|| {
+        let words = AUTO_TRAIT_REGEX
+            .replace_all(bounds, "")
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("_");
+        ApiIdent::to_dart_pascal_case(&format!("Dyn_{}", words))
+    });
+    Some(Opaque(ApiTypeOpaque {
+        name,
+        rust_type: DYN_REGEX
+            .replace_all(ty, "${1}dyn ")
+            .replace("->", " -> ")
+            .replace('+', " + "),
+    }))
+}
+
+fn check_unsupported_type(ty: &str) {
+    lazy_static! {
+        // NOTE do not use [GenericCapture], which would also match `Arc<T>`
+        static ref RC_REGEX: Regex = Regex::new("^(std::rc::|rc::)?Rc<").unwrap();
+        static ref RC_WEAK_REGEX: Regex = Regex::new("^(std::)?rc::Weak<").unwrap();
+        static ref SYNC_RETURN_OPTION_REGEX: Regex =
+            Regex::new("^SyncReturn<(std::option::|option::)?Option<").unwrap();
+    }
+
+    if RC_REGEX.is_match(ty) {
+        panic!(
+            "unsupported type {}: Rc<T> is not Send and cannot be used across a thread boundary; use Arc<T> instead.",
+            ty
+        );
+    }
+    if RC_WEAK_REGEX.is_match(ty) {
+        panic!(
+            "unsupported type {}: rc::Weak<T> is not Send and cannot be used across a thread boundary; use sync::Weak<T> instead.",
+            ty
+        );
     }
+    // NOTE `Option<T>` outputs of normal functions are posted to Dart as `null` for `None`, but
+    // the buffer of a `SyncReturn` has no such value
+    if SYNC_RETURN_OPTION_REGEX.is_match(ty) {
+        panic!(
+            "unsupported type {}: SyncReturn<T> cannot be null; return Result<Option<T>> without SyncReturn instead.",
+            ty
+        );
+    }
+}
 
-    /// e.g. List<Tom> => return Some(Tom)
-    pub fn captures(&self, s: &str) -> Option<String> {
-        self.regex
-            .captures(s)
-            .map(|capture| capture.get(1).unwrap().as_str().to_string())
+fn parse_const(item_const: &ImplItemConst) -> Option<ApiConst> {
+    let name = ident_to_string(&item_const.ident);
+    let ty = match type_to_string(&item_const.ty).as_str() {
+        "&str" | "&'staticstr" => Delegate(ApiTypeDelegate::String),
+        ty => Primitive(ApiTypePrimitive::try_from_rust_str(ty)?),
+    };
+    let value = match &item_const.expr {
+        Expr::Lit(ExprLit { lit, .. }) => lit_to_dart(lit, false),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit { lit, .. }) => lit_to_dart(lit, true),
+            _ => None,
+        },
+        _ => None,
+    };
+    let value = match value {
+        Some(value) => value,
+        None => {
+            warn!(
+                "skip associated const {}: only literal values are supported",
+                name
+            );
+            return None;
+        }
+    };
+    let comments = item_const
+        .attrs
+        .iter()
+        .filter_map(extract_comments)
+        .collect();
+
+    Some(ApiConst {
+        name: ApiIdent::new(name),
+        ty,
+        value,
+        comments,
+    })
+}
+
+/// e.g. `1024u32` => `1024`, `1.` => `1.0`, `"hello"` => `'hello'`, negated if [negative].
+/// Integers beyond the range of a Dart `int` are not supported, e.g. `u64::MAX`.
+fn lit_to_dart(lit: &Lit, negative: bool) -> Option<String> {
+    let sign = if negative { "-" } else { "" };
+    match lit {
+        Lit::Int(lit) => {
+            let max = i64::MAX as u128 + negative as u128;
+            match lit.base10_parse::<u128>() {
+                // e.g. `2f32`, which Rust lexes as an integer with a float suffix
+                Ok(value) if lit.suffix().starts_with('f') => Some(format!("{}{}.0", sign, value)),
+                Ok(value) if value <= max => Some(format!("{}{}", sign, value)),
+                _ => {
+                    warn!(
+                        "integer {}{} does not fit in a Dart int, which is an i64",
+                        sign,
+                        lit.base10_digits()
+                    );
+                    None
+                }
+            }
+        }
+        Lit::Float(lit) => {
+            let digits = lit.base10_digits();
+            // Dart needs digits after the point, e.g. `1.0` rather than `1.`
+            let digits = if digits.ends_with('.') {
+                format!("{}0", digits)
+            } else if !digits.contains(['.', 'e', 'E']) {
+                format!("{}.0", digits)
+            } else {
+                digits.to_string()
+            };
+            Some(format!("{}{}", sign, digits))
+        }
+        Lit::Bool(lit) if !negative => Some(lit.value.to_string()),
+        Lit::Str(lit) if !negative => Some(format!(
+            "'{}'",
+            lit.value()
+                .replace('\\', "\\\\")
+                .replace('\'', "\\'")
+                .replace('$', "\\$")
+                .replace('\n', "\\n")
+        )),
+        _ => None,
+    }
+}
+
+/// `#[frb(default = ...)]` of a struct field, e.g. `1024` => `1024`. Strings are quoted for
+/// `String` fields, and are taken as Dart expressions otherwise, e.g. `"Color.red"` => `Color.red`.
+fn field_default_to_dart(lit: &Lit, ty: &ApiType) -> Option<String> {
+    let is_string = |ty: &ApiType| matches!(ty, Delegate(ApiTypeDelegate::String));
+    match lit {
+        Lit::Str(lit)
+            if !(is_string(ty) || matches!(ty, Optional(opt) if is_string(&opt.inner))) =>
+        {
+            Some(lit.value())
+        }
+        lit => lit_to_dart(lit, false),
+    }
+}
+
+/// The type of another crate in `#[frb(mirror = "...")]`. Only the syntax can be checked here,
+/// while the generated conversions check that the fields match when compiled.
+fn parse_struct_mirror(
+    attrs: &[Attribute],
+    struct_name: &str,
+    num_private_fields: usize,
+) -> Option<String> {
+    if has_frb_flag(attrs, "mirror") {
+        panic!(
+            "#[frb(mirror)] of {} needs the mirrored type, e.g. #[frb(mirror = \"image::Rgb<u8>\")]",
+            struct_name
+        );
+    }
+    let mirror = match extract_frb_value(attrs, "mirror")? {
+        Lit::Str(lit) => lit.value(),
+        _ => panic!("mirror should be a string, see {}", struct_name),
+    };
+    syn::parse_str::<Type>(&mirror).unwrap_or_else(|_| {
+        panic!(
+            "mirror of {} should be a type, but see \"{}\"",
+            struct_name, mirror
+        )
+    });
+    // private fields are not bridged, so they could not be converted
+    if num_private_fields > 0 {
+        panic!(
+            "all fields of {} should be pub, since it mirrors {}",
+            struct_name, mirror
+        );
+    }
+    Some(mirror)
+}
+
+/// Whether an item is bridged: `pub` ones always, `pub(crate)`, `pub(super)` and `pub(in ...)`
+/// ones only with [IncludedVisibility::pub_crate]
+fn is_included(vis: &Visibility, included: IncludedVisibility) -> bool {
+    match vis {
+        Visibility::Public(_) => true,
+        Visibility::Crate(_) | Visibility::Restricted(_) => included.pub_crate,
+        Visibility::Inherited => false,
+    }
+}
+
+/// Functions, structs and enums marked `#[frb(skip)]` are left out, as are private functions
+/// unless [IncludedVisibility::private_functions]. Functions and structs of inline modules such as
+/// `pub mod geometry { .. }` are found as well, where the structs are keyed by their path, e.g.
+/// `geometry::Point`; enums, `impl` blocks and type aliases only at the top level.
+fn extract_items_from_file<'a>(
+    file: &'a File,
+    included: IncludedVisibility,
+    features: Option<&[String]>,
+) -> (
+    Vec<SrcFn<'a>>,
+    StructMap<'a>,
+    EnumMap<'a>,
+    ImplConstMap<'a>,
+    DefaultImplSet,
+    TypeAliasMap,
+    ReExportMap,
+) {
+    let is_fn_included = |vis| is_included(vis, included) || included.private_functions;
+    let mut src_fns = Vec::new();
+    let mut src_struct_map = HashMap::new();
+    let mut src_enum_map = HashMap::new();
+    let mut src_impl_const_map: ImplConstMap = HashMap::new();
+    let mut default_impls = HashSet::new();
+    let mut type_alias_map = HashMap::new();
+    // structs of private modules, which are only bridged if re-exported
+    let mut hidden_struct_map = HashMap::new();
+    // e.g. `(Some("shapes"), "Point", ["geometry", "Point"])` of `pub use geometry::Point;` in
+    // module `shapes`, where a glob has the name `*`
+    let mut uses = Vec::new();
+    // breadth first, so that the functions keep the order of the source within each module
+    let mut modules: Vec<(Option<String>, &[Item], bool)> = vec![(None, &file.items, true)];
+    let mut module_idx = 0;
+    while let Some((module, items, bridged)) = modules.get(module_idx).cloned() {
+        module_idx += 1;
+        let path_of = |ident: &Ident| match &module {
+            Some(module) => format!("{}::{}", module, ident),
+            None => ident.to_string(),
+        };
+        for item in items {
+            if !is_cfg_enabled(item_attrs(item), features) {
+                continue;
+            }
+            if !bridged {
+                match item {
+                    Item::Struct(ref item_struct) if !has_frb_flag(&item_struct.attrs, "skip") => {
+                        hidden_struct_map.insert(path_of(&item_struct.ident), item_struct);
+                    }
+                    Item::Mod(ItemMod {
+                        ident,
+                        content: Some((_, items)),
+                        ..
+                    }) => modules.push((Some(path_of(ident)), items, false)),
+                    _ => {}
+                }
+                continue;
+            }
+            match item {
+                Item::Fn(ref item_fn) if is_fn_included(&item_fn.vis) => {
+                    src_fns.push(SrcFn {
+                        sig: &item_fn.sig,
+                        attrs: &item_fn.attrs,
+                        impl_struct: None,
+                        module: module.clone(),
+                    });
+                }
+                Item::Struct(ref item_struct)
+                    if is_included(&item_struct.vis, included)
+                        && !has_frb_flag(&item_struct.attrs, "skip") =>
+                {
+                    src_struct_map.insert(path_of(&item_struct.ident), item_struct);
+                }
+                Item::Mod(ItemMod {
+                    vis,
+                    attrs,
+                    ident,
+                    content: Some((_, items)),
+                    ..
+                }) if !has_frb_flag(attrs, "skip") => {
+                    modules.push((Some(path_of(ident)), items, is_included(vis, included)));
+                }
+                Item::Use(ItemUse { vis, tree, .. }) if is_included(vis, included) => {
+                    for (name, path) in flatten_use_tree(tree) {
+                        uses.push((module.clone(), name, path));
+                    }
+                }
+                _ if module.is_some() => {}
+                Item::Enum(ref item_enum)
+                    if is_included(&item_enum.vis, included)
+                        && !has_frb_flag(&item_enum.attrs, "skip") =>
+                {
+                    src_enum_map.insert(item_enum.ident.to_string(), item_enum);
+                }
+                Item::Impl(ref item_impl) if item_impl.trait_.is_none() => {
+                    let self_ty = type_to_string(&item_impl.self_ty);
+                    for impl_item in &item_impl.items {
+                        match impl_item {
+                            ImplItem::Const(ref item_const)
+                                if is_included(&item_const.vis, included)
+                                    && is_cfg_enabled(&item_const.attrs, features) =>
+                            {
+                                src_impl_const_map
+                                    .entry(self_ty.clone())
+                                    .or_default()
+                                    .push(item_const);
+                            }
+                            ImplItem::Method(ref item_method)
+                                if has_frb_flag(&item_impl.attrs, "methods")
+                                    && is_fn_included(&item_method.vis)
+                                    && is_cfg_enabled(&item_method.attrs, features) =>
+                            {
+                                if has_mut_receiver(&item_method.sig) {
+                                    warn!(
+                                        "skip `{}::{}`: &mut self is not supported since changes \
+                                        would not be visible in Dart, take self and return the \
+                                        modified value instead",
+                                        self_ty, item_method.sig.ident
+                                    );
+                                    continue;
+                                }
+                                src_fns.push(SrcFn {
+                                    sig: &item_method.sig,
+                                    attrs: &item_method.attrs,
+                                    impl_struct: Some(self_ty.clone()),
+                                    module: None,
+                                });
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Item::Impl(ItemImpl {
+                    trait_: Some((None, trait_path, _)),
+                    self_ty,
+                    ..
+                }) if trait_path.segments.last().unwrap().ident == "Default" => {
+                    default_impls.insert(type_to_string(self_ty));
+                }
+                // generic aliases such as `type MyResult<T> = Result<T, MyError>` are not resolved
+                Item::Type(ref item_type)
+                    if item_type.generics.params.is_empty()
+                        && is_included(&item_type.vis, included) =>
+                {
+                    type_alias_map
+                        .insert(item_type.ident.to_string(), type_to_string(&item_type.ty));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut re_exports = HashMap::new();
+    for (module, name, path) in uses {
+        let path_of = |name: &str| match &module {
+            Some(module) => format!("{}::{}", module, name),
+            None => name.to_string(),
+        };
+        let target = match resolve_use_path(module.as_deref(), &path) {
+            Some(target) => target,
+            // e.g. `crate::models::User` of another file
+            None => {
+                re_exports.insert(path_of(&name), path.join("::"));
+                continue;
+            }
+        };
+        let defined_structs: Vec<_> = if name == "*" {
+            src_struct_map
+                .iter()
+                .chain(&hidden_struct_map)
+                .filter(|(key, _)| module_of(key).as_deref() == Some(target.as_str()))
+                .map(|(key, item_struct)| {
+                    let name = key.rsplit("::").next().unwrap().to_string();
+                    (name, key.clone(), *item_struct)
+                })
+                .collect()
+        } else {
+            src_struct_map
+                .get_key_value(&target)
+                .or_else(|| hidden_struct_map.get_key_value(&target))
+                .map(|(key, item_struct)| (name.clone(), key.clone(), *item_struct))
+                .into_iter()
+                .collect()
+        };
+        if defined_structs.is_empty() {
+            let is_in_file = match module_of(&target) {
+                Some(target_module) => modules
+                    .iter()
+                    .any(|(module, _, _)| module.as_deref() == Some(target_module.as_str())),
+                None => true,
+            };
+            // otherwise e.g. an enum or a function, which is not followed
+            if !is_in_file && name != "*" {
+                re_exports.insert(path_of(&name), target);
+            }
+            continue;
+        }
+        for (name, key, item_struct) in defined_structs {
+            re_exports.insert(path_of(&name), key);
+            src_struct_map.insert(path_of(&name), item_struct);
+        }
+    }
+
+    // methods of types other than the bridged structs, e.g. of enums, are not supported yet, nor
+    // of opaque structs, whose handles would be moved out of the slab by the receiver
+    src_fns.retain(|f| {
+        !has_frb_flag(f.attrs, "skip")
+            && f.impl_struct.iter().all(|name| {
+                matches!(src_struct_map.get(name), Some(s) if !has_frb_flag(&s.attrs, "opaque"))
+            })
+    });
+    // println!("[Functions]\n{:#?}", src_fns);
+    // println!("[Structs]\n{:#?}", src_struct_map);
+    (
+        src_fns,
+        src_struct_map,
+        src_enum_map,
+        src_impl_const_map,
+        default_impls,
+        type_alias_map,
+        re_exports,
+    )
+}
+
+/// e.g. `[("Point", ["geometry", "Point"]), ("Dim", ["geometry", "Size"])]` of
+/// `use geometry::{Point, Size as Dim};`, where a glob has the name `*`
+fn flatten_use_tree(tree: &UseTree) -> Vec<(String, Vec<String>)> {
+    match tree {
+        UseTree::Path(UsePath { ident, tree, .. }) => flatten_use_tree(tree)
+            .into_iter()
+            .map(|(name, mut path)| {
+                path.insert(0, ident.to_string());
+                (name, path)
+            })
+            .collect(),
+        UseTree::Name(UseName { ident }) => vec![(ident.to_string(), vec![ident.to_string()])],
+        UseTree::Rename(UseRename { ident, rename, .. }) => {
+            vec![(rename.to_string(), vec![ident.to_string()])]
+        }
+        UseTree::Glob(_) => vec![("*".to_string(), vec![])],
+        UseTree::Group(UseGroup { items, .. }) => items.iter().flat_map(flatten_use_tree).collect(),
+    }
+}
+
+/// The key of [path] in the maps of [extract_items_from_file] when used in [module], e.g.
+/// `geometry::Point` of `super::geometry::Point` in `shapes`, or [None] if it is outside of the
+/// file, e.g. starts with `crate`
+fn resolve_use_path(module: Option<&str>, path: &[String]) -> Option<String> {
+    let mut segments: Vec<&str> =
+        module.map_or_else(Vec::new, |module| module.split("::").collect());
+    for (idx, segment) in path.iter().enumerate() {
+        match segment.as_str() {
+            "self" if idx == 0 => {}
+            "super" => {
+                segments.pop()?;
+            }
+            "crate" => return None,
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("::"))
+}
+
+fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Fn(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        _ => &[],
+    }
+}
+
+/// Whether the `#[cfg(...)]` attributes hold with [features] enabled, see
+/// [crate::config::RawOpts::features]. Only `feature = "..."` and `test` (which never holds) are
+/// known, also within `all`, `any` and `not`: a predicate that depends on anything else, e.g.
+/// `#[cfg(target_os = "android")]`, cannot be evaluated and is rejected, as is a `#[cfg_attr(...)]`
+/// of `cfg` or `frb` attributes, since it is not expanded.
+fn is_cfg_enabled(attrs: &[Attribute], features: Option<&[String]>) -> bool {
+    let features = match features {
+        Some(features) => features,
+        None => return true,
+    };
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("cfg_attr")) {
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            if list.nested.iter().skip(1).any(|nested| {
+                matches!(nested, NestedMeta::Meta(meta)
+                    if meta.path().is_ident("cfg") || meta.path().is_ident("frb"))
+            }) {
+                panic!(
+                    "`{}` is not supported with --features, use `#[cfg(...)]` or `#[frb(...)]` directly",
+                    quote!(#attr)
+                );
+            }
+        }
+    }
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .all(|attr| {
+            let enabled = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list.nested.first().and_then(|p| eval_cfg(p, features)),
+                _ => None,
+            };
+            enabled.unwrap_or_else(|| {
+                panic!(
+                    "cannot evaluate `{}` with --features, only `feature = \"...\"` and `test` are known",
+                    quote!(#attr)
+                )
+            })
+        })
+}
+
+/// [None] if unknown, e.g. of `unix` or `not(unix)`
+fn eval_cfg(predicate: &NestedMeta, features: &[String]) -> Option<bool> {
+    let list = match predicate {
+        NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("feature") => {
+            return match &name_value.lit {
+                Lit::Str(feature) => Some(features.contains(&feature.value())),
+                _ => None,
+            };
+        }
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("test") => return Some(false),
+        NestedMeta::Meta(Meta::List(list)) => list,
+        _ => return None,
+    };
+    let values: Vec<_> = list.nested.iter().map(|p| eval_cfg(p, features)).collect();
+    // the value of the predicates that decides regardless of the others, e.g. `false` of `all`
+    let decisive = if list.path.is_ident("all") {
+        false
+    } else if list.path.is_ident("any") {
+        true
+    } else if list.path.is_ident("not") {
+        return values.first().copied().flatten().map(|enabled| !enabled);
+    } else {
+        return None;
+    };
+    if values.contains(&Some(decisive)) {
+        Some(decisive)
+    } else if values.contains(&None) {
+        None
+    } else {
+        Some(!decisive)
+    }
+}
+
+/// e.g. `String,Vec<(u8,u8)>` => `["String", "Vec<(u8,u8)>"]`
+fn split_top_level_commas(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        match c {
+            // the arrow of e.g. `Fn(i32)->i32`
+            '>' if s[..idx].ends_with('-') => {}
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&s[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// e.g. `geometry` of `geometry::Point`, see [extract_items_from_file]
+fn module_of(struct_key: &str) -> Option<String> {
+    struct_key
+        .rsplit_once("::")
+        .map(|(module, _)| module.to_string())
+}
+
+fn ident_to_string(ident: &Ident) -> String {
+    format!("{}", ident)
+}
+
+/// e.g. `ApiResponseString` of `ApiResponse<String>`, or `ApiResponseVecU8` of `ApiResponse<Vec<u8>>`
+fn struct_name(src_name: &str, generic_arg: Option<&str>) -> String {
+    // e.g. `geometry::Point` => `GeometryPoint`
+    let src_name = match src_name.rsplit_once("::") {
+        Some((module, ident)) => {
+            module
+                .split("::")
+                .map(ApiIdent::to_dart_pascal_case)
+                .collect::<String>()
+                + ident
+        }
+        None => src_name.to_string(),
+    };
+    let generic_arg = generic_arg.unwrap_or_default();
+    let mangled_arg = generic_arg
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word[..1].to_uppercase() + &word[1..])
+        .collect::<String>();
+    format!("{}{}", src_name, mangled_arg)
+}
+
+/// The name of the type parameter of a generic struct, which is only supported as a single type
+/// parameter with a type argument given
+fn parse_struct_generic_param(item_struct: &ItemStruct, has_generic_arg: bool) -> Option<String> {
+    let name = ident_to_string(&item_struct.ident);
+    let params = &item_struct.generics.params;
+    if params.is_empty() {
+        if has_generic_arg {
+            panic!("{} is not generic, but is used with a type argument", name);
+        }
+        return None;
+    }
+    match params.first() {
+        Some(GenericParam::Type(param)) if params.len() == 1 => {
+            if !has_generic_arg {
+                panic!("generic struct {} is used without a type argument", name);
+            }
+            Some(ident_to_string(&param.ident))
+        }
+        _ => panic!(
+            "only generic structs with a single type parameter are supported, see {}",
+            name
+        ),
+    }
+}
+
+/// e.g. `Vec<T>` => `Vec<String>`
+fn replace_generic_param(ty: &str, param: &str, arg: &str) -> String {
+    Regex::new(&format!(r"\b{}\b", param))
+        .unwrap()
+        .replace_all(ty, regex::NoExpand(arg))
+        .to_string()
+}
+
+/// syn -> string https://github.com/dtolnay/syn/issues/294
+fn type_to_string(ty: &Type) -> String {
+    quote!(#ty).to_string().replace(" ", "")
+}
+
+/// e.g. `Vec<Self>` => `Vec<Point>`
+fn replace_self_type(ty: &str, struct_name: &str) -> String {
+    lazy_static! {
+        static ref SELF_REGEX: Regex = Regex::new(r"\bSelf\b").unwrap();
+    }
+
+    SELF_REGEX.replace_all(ty, struct_name).to_string()
+}
+
+/// Captures the type parameters of a generic type, e.g. of `Vec<T>` for `GenericCapture::new("Vec")`
+struct GenericCapture {
+    cls_name: String,
+}
+
+impl GenericCapture {
+    pub fn new(cls_name: &str) -> Self {
+        Self {
+            cls_name: cls_name.to_string(),
+        }
+    }
+
+    /// e.g. `List<Tom>` => `Some(["Tom"])`, or `Map<Vec<u8>,Option<String>>` => `Some(["Vec<u8>", "Option<String>"])`
+    pub fn captures_all(&self, s: &str) -> Option<Vec<String>> {
+        let (prefix, rest) = s.split_once('<')?;
+        if !prefix.ends_with(&self.cls_name) {
+            return None;
+        }
+        let inner = rest.strip_suffix('>')?;
+
+        // the last `>` must close the first `<`, unlike in e.g. `Vec<u8>,Vec<u8>`
+        let mut depth = 0;
+        for (idx, c) in inner.char_indices() {
+            match c {
+                // the arrow of e.g. `Fn(i32)->i32`
+                '>' if inner[..idx].ends_with('-') => {}
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if depth != 0 {
+            return None;
+        }
+
+        Some(
+            split_top_level_commas(inner)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    /// [Self::captures_all] if there are exactly [n] type parameters
+    pub fn captures_n(&self, s: &str, n: usize) -> Option<Vec<String>> {
+        self.captures_all(s).filter(|params| params.len() == n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_attribute_excludes_items() {
+        let api_file = parse_source(
+            "
+            pub struct Helper {
+                pub id: i32,
+            }
+
+            #[frb(skip)]
+            pub struct Internal {
+                pub id: i32,
+            }
+
+            impl Internal {
+                pub fn new() -> Result<Internal> {
+                    Ok(Internal { id: 0 })
+                }
+            }
+
+            pub fn bridged(helper: Helper) -> Result<i32> {
+                Ok(helper.id)
+            }
+
+            #[frb(skip)]
+            pub fn skipped(internal: Internal) -> Result<i32> {
+                Ok(internal.id)
+            }
+            ",
+        );
+
+        let func_names: Vec<_> = api_file.funcs.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(func_names, ["bridged"]);
+        assert!(api_file.struct_pool.contains_key("Helper"));
+        assert!(!api_file.struct_pool.contains_key("Internal"));
+    }
+
+    #[test]
+    fn name_attribute_renames_in_dart() {
+        let api_file = parse_source(
+            "
+            pub struct Point {
+                #[frb(name = \"dx\")]
+                pub x_offset: f64,
+                pub y_offset: f64,
+            }
+
+            #[frb(name = \"makePoint\")]
+            pub fn new_point(x: f64) -> Result<Point> {
+                Ok(Point { x_offset: x, y_offset: 0.0 })
+            }
+
+            #[frb(dart_name = \"origin\")]
+            pub fn zero() -> Result<Point> {
+                Ok(Point { x_offset: 0.0, y_offset: 0.0 })
+            }
+            ",
+        );
+
+        let dart_names: Vec<_> = api_file.funcs.iter().map(ApiFunc::dart_name).collect();
+        assert_eq!(dart_names, ["makePoint", "origin"]);
+        let point = &api_file.struct_pool["Point"];
+        let field_names: Vec<_> = point.fields.iter().map(ApiField::dart_name).collect();
+        assert_eq!(field_names, ["dx", "yOffset"]);
+        assert_eq!(point.fields[0].name.rust_style(), "x_offset");
+    }
+
+    #[test]
+    fn fn_inputs_are_callbacks() {
+        let api_file = parse_source(
+            "
+            pub type Adder = Box<dyn Fn(i32) -> i32 + Send + Sync>;
+
+            pub fn download(url: String, on_progress: impl Fn(u64, String)) -> Result<u64> {
+                Ok(0)
+            }
+
+            pub fn filter(keep: Option<Box<dyn Fn(i32) -> bool + Send>>) -> Result<i32> {
+                Ok(0)
+            }
+
+            pub fn apply_adder(adder: Adder, x: i32) -> Result<i32> {
+                Ok(adder(x))
+            }
+
+            pub fn apply(x: i32, f: impl Fn(i32) -> anyhow::Result<i32>) -> Result<i32> {
+                f(x)
+            }
+            ",
+        );
+
+        let input_types: Vec<_> = api_file
+            .funcs
+            .iter()
+            .map(|func| func.inputs.last().unwrap().ty.clone())
+            .collect();
+        match &input_types[..] {
+            [Callback(on_progress), Callback(keep), Primitive(_), _] => {
+                assert_eq!(on_progress.args.len(), 2);
+                assert!(on_progress.ret.is_none() && !on_progress.is_optional);
+                assert_eq!(
+                    on_progress.rust_api_type(),
+                    "Box<dyn Fn(u64, String) + Send + Sync>"
+                );
+                assert_eq!(on_progress.dart_api_type(), "void Function(int, String)");
+                assert!(matches!(keep.ret, Some(ApiTypePrimitive::Bool)));
+                assert_eq!(
+                    keep.rust_api_type(),
+                    "Option<Box<dyn Fn(i32) -> bool + Send>>"
+                );
+                assert_eq!(keep.dart_api_type(), "bool Function(int)?");
+            }
+            _ => panic!("unexpected input types {:?}", input_types),
+        }
+        assert!(matches!(
+            api_file.funcs[2].inputs[0].ty,
+            Opaque(ApiTypeOpaque { .. })
+        ));
+        match &input_types[3] {
+            Callback(f) => {
+                assert!(matches!(f.ret, Some(ApiTypePrimitive::I32)) && f.ret_is_result);
+                assert_eq!(
+                    f.rust_api_type(),
+                    "Box<dyn Fn(i32) -> anyhow::Result<i32> + Send + Sync>"
+                );
+                assert_eq!(f.dart_api_type(), "int Function(int)");
+            }
+            ty => panic!("unexpected input type {:?}", ty),
+        }
+    }
+
+    #[test]
+    fn discovers_items_of_inline_modules() {
+        let api_file = parse_source(
+            "
+            pub mod geometry {
+                pub struct Point {
+                    pub x: f64,
+                    pub y: f64,
+                }
+
+                pub fn norm(point: Point) -> Result<f64> {
+                    Ok(point.x.hypot(point.y))
+                }
+            }
+
+            mod internal {
+                pub fn hidden() -> Result<i32> {
+                    Ok(0)
+                }
+            }
+
+            pub fn origin() -> Result<geometry::Point> {
+                Ok(geometry::Point { x: 0.0, y: 0.0 })
+            }
+            ",
+        );
+
+        let call_paths: Vec<_> = api_file.funcs.iter().map(ApiFunc::rust_call_path).collect();
+        assert_eq!(call_paths, ["origin", "geometry::norm"]);
+        let point = &api_file.struct_pool["GeometryPoint"];
+        assert_eq!(point.rust_type(), "geometry::Point");
+        for ty in [&api_file.funcs[0].output, &api_file.funcs[1].inputs[0].ty] {
+            match ty {
+                StructRef(struct_ref) => assert_eq!(struct_ref.name, "GeometryPoint"),
+                _ => panic!("unexpected type {:?}", ty),
+            }
+        }
+    }
+
+    #[test]
+    fn follows_re_exports_within_the_file() {
+        let api_file = parse_source(
+            "
+            mod shapes {
+                pub struct Size {
+                    pub width: f64,
+                    pub height: f64,
+                }
+
+                pub struct Rect {
+                    pub size: Size,
+                }
+            }
+
+            pub mod geometry {
+                pub struct Point {
+                    pub x: f64,
+                    pub y: f64,
+                }
+            }
+
+            pub use geometry::Point as Location;
+            pub use shapes::*;
+
+            pub fn area(rect: Rect, at: Location) -> Result<f64> {
+                Ok(rect.size.width * rect.size.height)
+            }
+            ",
+        );
+
+        let mut names: Vec<_> = api_file.struct_pool.keys().collect();
+        names.sort();
+        assert_eq!(names, ["Location", "Rect", "Size"]);
+        assert_eq!(api_file.struct_pool["Location"].rust_type(), "Location");
+    }
+
+    #[test]
+    #[should_panic(expected = "Rgb is re-exported from image::Rgb, which is outside of this file")]
+    fn rejects_re_exports_of_other_crates() {
+        parse_source(
+            "
+            pub use image::Rgb;
+
+            pub fn paint(color: Rgb) -> Result<i32> {
+                Ok(0)
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn cow_with_or_without_lifetime() {
+        let api_file = parse_source(
+            "
+            pub fn greet(name: Cow<str>, data: std::borrow::Cow<'_, [u8]>) -> Result<Cow<'static, str>> {
+                Ok(name.into_owned().into())
+            }
+            ",
+        );
+
+        let func = &api_file.funcs[0];
+        let types: Vec<_> = func.inputs.iter().map(|input| &input.ty).collect();
+        assert!(matches!(
+            types[..],
+            [
+                Delegate(ApiTypeDelegate::CowStr),
+                Delegate(ApiTypeDelegate::CowBytes)
+            ]
+        ));
+        assert!(matches!(func.output, Delegate(ApiTypeDelegate::CowStr)));
+    }
+
+    #[test]
+    fn opaque_attribute_passes_structs_as_handles() {
+        let api_file = parse_source(
+            "
+            #[frb(opaque)]
+            pub struct Connection {
+                fd: RawFd,
+                pub label: String,
+            }
+
+            impl Connection {
+                pub fn label(&self) -> Result<String> {
+                    Ok(self.label.clone())
+                }
+            }
+
+            pub fn open(path: String) -> Result<Connection> {
+                todo!()
+            }
+            ",
+        );
+
+        let func_names: Vec<_> = api_file.funcs.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(func_names, ["open"]);
+        assert!(api_file.struct_pool.is_empty());
+        match &api_file.funcs[0].output {
+            Opaque(opaque) => {
+                assert_eq!(opaque.dart_api_type(), "Connection");
+                assert_eq!(opaque.rust_api_type(), "Connection");
+                assert_eq!(opaque.slab_name(), "OPAQUE_CONNECTION");
+            }
+            ty => panic!("unexpected type {:?}", ty),
+        }
+    }
+
+    #[test]
+    fn features_exclude_items_of_other_features() {
+        let source = "
+            #[cfg(feature = \"audio\")]
+            pub fn play() -> Result<i32> {
+                Ok(0)
+            }
+
+            #[cfg(all(feature = \"video\", not(feature = \"audio\")))]
+            pub fn record() -> Result<i32> {
+                Ok(0)
+            }
+
+            #[cfg(any(feature = \"audio\", test))]
+            pub fn mute() -> Result<i32> {
+                Ok(0)
+            }
+
+            #[cfg(test)]
+            mod tests {
+                use super::*;
+            }
+            ";
+
+        assert_eq!(parse_func_names(source, None), ["play", "record", "mute"]);
+        assert_eq!(
+            parse_func_names(source, Some(&["audio".to_string()])),
+            ["play", "mute"]
+        );
+        assert_eq!(
+            parse_func_names(source, Some(&["video".to_string()])),
+            ["record"]
+        );
+        assert!(parse_func_names(source, Some(&[])).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "target_os = \"android\"))]` with --features")]
+    fn features_reject_unknown_cfg_predicates() {
+        parse_func_names(
+            "
+            #[cfg(any(feature = \"audio\", target_os = \"android\"))]
+            pub fn mute() -> Result<i32> {
+                Ok(0)
+            }
+            ",
+            Some(&["video".to_string()]),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not supported with --features")]
+    fn features_reject_cfg_attr_of_cfg() {
+        parse_func_names(
+            "
+            #[cfg_attr(unix, cfg(feature = \"audio\"))]
+            pub fn play() -> Result<i32> {
+                Ok(0)
+            }
+            ",
+            Some(&["audio".to_string()]),
+        );
+    }
+
+    fn parse_func_names(source: &str, features: Option<&[String]>) -> Vec<String> {
+        parse(
+            source,
+            syn::parse_file(source).unwrap(),
+            false,
+            DartUuidStyle::Value,
+            IncludedVisibility::default(),
+            features,
+        )
+        .funcs
+        .into_iter()
+        .map(|f| f.name)
+        .collect()
+    }
+
+    #[test]
+    fn records_source_locations_of_functions() {
+        let api_file = parse_source(
+            "pub fn first() -> Result<i32> {
+    Ok(1)
+}
+
+pub struct Counter {
+    pub count: i32,
+}
+
+#[frb(methods)]
+impl Counter {
+    pub fn second(&self) -> Result<i32> {
+        Ok(self.count)
+    }
+}
+",
+        );
+
+        let locations: Vec<_> = api_file
+            .funcs
+            .iter()
+            .map(|func| func.source_location.as_str())
+            .collect();
+        assert_eq!(locations, ["1:8", "11:12"]);
+    }
+
+    #[test]
+    fn sync_attribute_marks_functions_sync() {
+        let api_file = parse_source(
+            "
+            #[frb(sync)]
+            pub fn version() -> Result<String> {
+                Ok(String::new())
+            }
+
+            pub fn load() -> Result<String> {
+                Ok(String::new())
+            }
+            ",
+        );
+
+        let modes: Vec<_> = api_file.funcs.iter().map(|func| &func.mode).collect();
+        assert_eq!(modes, [&ApiFuncMode::Sync, &ApiFuncMode::Normal]);
+        assert!(matches!(
+            api_file.funcs[0].output,
+            Delegate(ApiTypeDelegate::String)
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "#[frb(sync)] only supports returning primitives, String and Vec<u8>"
+    )]
+    fn sync_attribute_rejects_structs() {
+        parse_source(
+            "
+            pub struct Point {
+                pub x: i32,
+            }
+
+            #[frb(sync)]
+            pub fn origin() -> Result<Point> {
+                Ok(Point { x: 0 })
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn methods_attribute_bridges_methods() {
+        let api_file = parse_source(
+            "
+            pub struct Counter {
+                pub count: i32,
+            }
+
+            #[frb(methods)]
+            impl Counter {
+                pub fn get(&self) -> Result<i32> {
+                    Ok(self.count)
+                }
+
+                pub fn increment(&mut self) -> Result<()> {
+                    self.count += 1;
+                    Ok(())
+                }
+            }
+
+            pub struct Timer {
+                pub millis: i32,
+            }
+
+            impl Timer {
+                pub fn elapsed(&self) -> Result<i32> {
+                    Ok(self.millis)
+                }
+            }
+
+            pub fn start(timer: Timer) -> Result<Counter> {
+                Ok(Counter { count: timer.millis })
+            }
+            ",
+        );
+
+        let func_names: Vec<_> = api_file.funcs.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(func_names, ["counter_get", "start"]);
+        let method = api_file.funcs[0].method.as_ref().unwrap();
+        assert_eq!(method.struct_name, "Counter");
+        assert_eq!(method.method_name, "get");
+        assert!(matches!(method.receiver, Some(ApiMethodReceiver::Ref)));
+    }
+
+    #[test]
+    fn parses_optional_collections_as_nullable_pointers() {
+        let api_file = parse_source(
+            "
+            pub fn merge(
+                ids: Option<Vec<i32>>,
+                names: Option<HashMap<String, i32>>,
+                pair: Option<(i32, String)>,
+            ) -> Result<Option<Vec<String>>> {
+                Ok(None)
+            }
+            ",
+        );
+
+        let func = &api_file.funcs[0];
+        let inner_of = |ty: &ApiType| match ty {
+            Optional(opt) => (*opt.inner).clone(),
+            ty => panic!("unexpected type {:?}", ty),
+        };
+        assert!(matches!(inner_of(&func.inputs[0].ty), PrimitiveList(_)));
+        assert!(matches!(inner_of(&func.inputs[1].ty), Map(_)));
+        assert!(matches!(inner_of(&func.inputs[2].ty), Tuple(_)));
+        assert!(matches!(inner_of(&func.output), GeneralList(_)));
+    }
+
+    #[test]
+    fn parses_optional_dyn_errors() {
+        let api_file = parse_source(
+            "
+            pub fn last_error(clear: Option<Box<dyn Error + Send>>) -> Result<Option<Box<dyn Error + Send + Sync>>> {
+                Ok(None)
+            }
+            ",
+        );
+
+        let func = &api_file.funcs[0];
+        for ty in [&func.inputs[0].ty, &func.output] {
+            assert!(matches!(
+                ty,
+                Optional(opt) if matches!(&*opt.inner, Delegate(ApiTypeDelegate::DynError(_)))
+            ));
+        }
+    }
+
+    #[test]
+    fn parses_dyn_errors_without_send_as_outputs() {
+        let api_file = parse_source(
+            "
+            pub fn last_error() -> Result<Option<Box<dyn std::error::Error>>> {
+                Ok(None)
+            }
+            ",
+        );
+
+        assert!(matches!(
+            &api_file.funcs[0].output,
+            Optional(opt) if matches!(&*opt.inner, Delegate(ApiTypeDelegate::DynError(bounds)) if bounds.is_empty())
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "rate_limit should be a positive number of events per second, see ticks"
+    )]
+    fn rejects_zero_rate_limits() {
+        parse_source(
+            "
+            #[frb(rate_limit = 0)]
+            pub fn ticks(sink: StreamSink<u64>) -> Result<()> {
+                Ok(())
+            }
+            ",
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "throw_on_null_output can only be used on functions returning an Option, see find_user"
+    )]
+    fn rejects_throw_on_null_output_without_an_optional_output() {
+        parse_source(
+            "
+            #[frb(throw_on_null_output)]
+            pub fn find_user(id: u32) -> Result<String> {
+                Ok(String::new())
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn parses_nested_lists() {
+        let api_file = parse_source(
+            "
+            pub fn nested(
+                ints: Vec<Vec<i32>>,
+                strings: Vec<Vec<String>>,
+                optional_ints: Vec<Option<Vec<i32>>>,
+            ) -> Result<Vec<Vec<String>>> {
+                Ok(strings)
+            }
+            ",
+        );
+
+        let func = &api_file.funcs[0];
+        let inner_of = |ty: &ApiType| match ty {
+            GeneralList(list) => list.inner.clone(),
+            _ => panic!("not a list: {:?}", ty),
+        };
+        assert!(matches!(
+            inner_of(&func.inputs[0].ty),
+            PrimitiveList(ApiTypePrimitiveList {
+                primitive: ApiTypePrimitive::I32
+            })
+        ));
+        for ty in [&func.inputs[1].ty, &func.output] {
+            assert!(matches!(
+                inner_of(&inner_of(ty)),
+                Delegate(ApiTypeDelegate::String)
+            ));
+        }
+        assert!(matches!(
+            inner_of(&func.inputs[2].ty),
+            Optional(ApiTypeOptional { inner }) if matches!(*inner, PrimitiveList(_))
+        ));
+    }
+
+    #[test]
+    fn bridges_only_pub_fields() {
+        let api_file = parse_source(
+            "
+            pub struct Account {
+                pub name: String,
+                secret: String,
+            }
+
+            impl Default for Account {
+                fn default() -> Self {
+                    Self { name: String::new(), secret: String::new() }
+                }
+            }
+
+            pub fn rename(account: Account) -> Result<Account> {
+                Ok(account)
+            }
+            ",
+        );
+
+        let account = &api_file.struct_pool["Account"];
+        let names: Vec<_> = account.fields.iter().map(|f| f.name.rust_style()).collect();
+        assert_eq!(names, ["name"]);
+        assert_eq!(account.num_private_fields, 1);
+        assert!(account.implements_default);
+    }
+
+    #[test]
+    fn converts_float_literals_to_valid_dart() {
+        let api_file = parse_source(
+            "
+            pub struct Gain {
+                #[frb(default = 1.)]
+                pub level: f64,
+            }
+
+            impl Gain {
+                pub const UNIT: f64 = 1.;
+                pub const MIN: f32 = -2f32;
+                pub const LARGE: f64 = 1e3;
+                pub const SMALL: f64 = 2.5E-3;
+            }
+
+            pub fn amplify(gain: Gain) -> Result<Gain> {
+                Ok(gain)
+            }
+            ",
+        );
+
+        let gain = &api_file.struct_pool["Gain"];
+        assert_eq!(gain.fields[0].default.as_deref(), Some("1.0"));
+        let values: Vec<_> = gain
+            .associated_consts
+            .iter()
+            .map(|c| c.value.as_str())
+            .collect();
+        assert_eq!(values, ["1.0", "-2.0", "1e3", "2.5e-3"]);
+    }
+
+    #[test]
+    fn skips_integer_consts_beyond_dart_int() {
+        let api_file = parse_source(
+            "
+            pub struct Limits {
+                pub count: u64,
+            }
+
+            impl Limits {
+                pub const I64_MAX: u64 = 9223372036854775807;
+                pub const I64_MIN: i64 = -9223372036854775808;
+                pub const ABOVE_I64_MAX: u64 = 9223372036854775808;
+                pub const U64_MAX: u64 = 18446744073709551615u64;
+            }
+
+            pub fn limits() -> Result<Limits> {
+                Ok(Limits { count: 0 })
+            }
+            ",
+        );
+
+        let consts = &api_file.struct_pool["Limits"].associated_consts;
+        let names: Vec<_> = consts.iter().map(|c| c.name.rust_style()).collect();
+        assert_eq!(names, ["I64_MAX", "I64_MIN"]);
+        assert_eq!(consts[0].value, "9223372036854775807");
+        assert_eq!(consts[1].value, "-9223372036854775808");
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported default value of Limits.count")]
+    fn rejects_integer_defaults_beyond_dart_int() {
+        parse_source(
+            "
+            pub struct Limits {
+                #[frb(default = 18446744073709551615)]
+                pub count: u64,
+            }
+
+            pub fn limits(limits: Limits) -> Result<Limits> {
+                Ok(limits)
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn generic_capture_splits_nested_type_parameters() {
+        let map = GenericCapture::new("HashMap");
+        assert_eq!(
+            map.captures_all("std::collections::HashMap<Vec<u8>,Option<HashMap<String,(i32,u8)>>>"),
+            Some(vec![
+                "Vec<u8>".to_string(),
+                "Option<HashMap<String,(i32,u8)>>".to_string()
+            ])
+        );
+        let vec = GenericCapture::new("Vec");
+        assert_eq!(
+            vec.captures_all("Vec<Vec<[u8;4]>>"),
+            Some(vec!["Vec<[u8;4]>".to_string()])
+        );
+        assert_eq!(
+            GenericCapture::new("Box").captures_all("Box<dynFn(i32)->Vec<i32>>"),
+            Some(vec!["dynFn(i32)->Vec<i32>".to_string()])
+        );
+    }
+
+    #[test]
+    fn generic_capture_checks_the_number_of_type_parameters() {
+        let result = GenericCapture::new("Result");
+        assert_eq!(
+            result.captures_n("Result<Vec<u8>>", 1),
+            Some(vec!["Vec<u8>".to_string()])
+        );
+        assert_eq!(
+            result.captures_n("Result<String,MyError<u8>>", 2),
+            Some(vec!["String".to_string(), "MyError<u8>".to_string()])
+        );
+        assert_eq!(result.captures_n("Result<String,MyError<u8>>", 1), None);
+        assert_eq!(result.captures_n("Result<Vec<u8>>", 2), None);
+    }
+
+    #[test]
+    fn generic_capture_rejects_other_outer_types() {
+        let vec = GenericCapture::new("Vec");
+        // the parameter is a `Vec`, but not the type itself
+        assert_eq!(vec.captures_all("Option<Vec<u8>>"), None);
+        assert_eq!(vec.captures_all("VecDeque<u8>"), None);
+        assert_eq!(vec.captures_all("Vec"), None);
+        // two types rather than one, whose first `<` is not closed by the last `>`
+        assert_eq!(vec.captures_all("Vec<u8>,Vec<u8>"), None);
+        assert_eq!(vec.captures_all("Vec<u8>>"), None);
+        assert_eq!(
+            GenericCapture::new("Range").captures_all("RangeInclusive<i32>"),
+            None
+        );
     }
 }
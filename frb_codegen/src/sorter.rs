@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::api_types::ApiType::*;
+use crate::api_types::{ApiStruct, ApiStructPool, ApiType};
+
+/// Sorts the structs so that each one comes after the structs of its field types, and otherwise
+/// by name. A struct referring to itself, e.g. `left: Option<Box<BinaryTree>>` of `BinaryTree`,
+/// needs no order, but structs referring to each other are a [StructCycle].
+pub fn sort_structs(pool: &ApiStructPool) -> Result<Vec<&ApiStruct>, StructCycle> {
+    let mut names: Vec<_> = pool.keys().collect();
+    names.sort();
+
+    let mut sorter = Sorter {
+        pool,
+        visiting: Vec::new(),
+        done: HashSet::new(),
+        sorted: Vec::new(),
+    };
+    for name in names {
+        sorter.visit(name)?;
+    }
+    Ok(sorter.sorted)
+}
+
+struct Sorter<'a> {
+    pool: &'a ApiStructPool,
+    /// the path from the struct the search started at
+    visiting: Vec<&'a str>,
+    done: HashSet<&'a str>,
+    sorted: Vec<&'a ApiStruct>,
+}
+
+impl<'a> Sorter<'a> {
+    fn visit(&mut self, name: &'a str) -> Result<(), StructCycle> {
+        if self.done.contains(name) {
+            return Ok(());
+        }
+        if let Some(idx) = self.visiting.iter().position(|visiting| *visiting == name) {
+            let mut path: Vec<_> = self.visiting[idx..].iter().map(|s| s.to_string()).collect();
+            path.push(name.to_string());
+            return Err(StructCycle(path));
+        }
+
+        let (name, api_struct) = self.pool.get_key_value(name).unwrap();
+        self.visiting.push(name);
+        let mut dependencies = Vec::new();
+        for field in &api_struct.fields {
+            collect_struct_refs(&field.ty, &mut dependencies);
+        }
+        for dependency in dependencies {
+            if dependency != name.as_str() {
+                if let Some((dependency, _)) = self.pool.get_key_value(dependency) {
+                    self.visit(dependency)?;
+                }
+            }
+        }
+        self.visiting.pop();
+
+        self.done.insert(name);
+        self.sorted.push(api_struct);
+        Ok(())
+    }
+}
+
+/// The structs a value of [ty] contains, without going into the fields of those structs
+fn collect_struct_refs<'a>(ty: &'a ApiType, refs: &mut Vec<&'a str>) {
+    match ty {
+        StructRef(struct_ref) => refs.push(&struct_ref.name),
+        OpaqueWeak(weak) => refs.push(&weak.inner.name),
+        GeneralList(list) => collect_struct_refs(&list.inner, refs),
+        AssocList(list) => {
+            collect_struct_refs(&list.key, refs);
+            collect_struct_refs(&list.value, refs);
+        }
+        Map(map) => {
+            collect_struct_refs(&map.key, refs);
+            collect_struct_refs(&map.value, refs);
+        }
+        Tuple(tuple) => {
+            for element in &tuple.elements {
+                collect_struct_refs(element, refs);
+            }
+        }
+        Boxed(boxed) => collect_struct_refs(&boxed.inner, refs),
+        Optional(optional) => collect_struct_refs(&optional.inner, refs),
+        Primitive(_) | Delegate(_) | PrimitiveList(_) | EnumRef(_) | Opaque(_) | Callback(_) => {}
+    }
+}
+
+/// e.g. `["Parent", "Child", "Parent"]` of `struct Parent { child: Box<Child> }` and
+/// `struct Child { parent: Option<Box<Parent>> }`
+#[derive(Debug)]
+pub struct StructCycle(pub Vec<String>);
+
+impl fmt::Display for StructCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: Vec<_> = self.0.iter().map(|name| format!("`{}`", name)).collect();
+        write!(f, "structs refer to each other: {}", path.join(" -> "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn sorted_names(source: &str) -> Result<Vec<String>, StructCycle> {
+        let api_file = parser::parse_source(source);
+        let sorted = sort_structs(&api_file.struct_pool)?;
+        Ok(sorted.iter().map(|s| s.name.clone()).collect())
+    }
+
+    #[test]
+    fn sorts_diamond_dependencies() {
+        let names = sorted_names(
+            "
+            pub struct Apex {
+                pub left: Left,
+                pub right: Vec<Right>,
+            }
+
+            pub struct Left {
+                pub base: Base,
+            }
+
+            pub struct Right {
+                pub base: Option<Box<Base>>,
+                pub next: Option<Box<Right>>,
+            }
+
+            pub struct Base {
+                pub id: i32,
+            }
+
+            pub fn handle(apex: Apex) -> Result<i32> {
+                Ok(0)
+            }
+            ",
+        )
+        .unwrap();
+        assert_eq!(names, ["Base", "Left", "Right", "Apex"]);
+    }
+
+    #[test]
+    fn reports_mutual_references() {
+        let cycle = sorted_names(
+            "
+            pub struct Parent {
+                pub child: Box<Child>,
+            }
+
+            pub struct Child {
+                pub parent: Option<Box<Parent>>,
+            }
+
+            pub fn handle(parent: Parent) -> Result<i32> {
+                Ok(0)
+            }
+            ",
+        )
+        .unwrap_err();
+        assert_eq!(cycle.0, ["Child", "Parent", "Child"]);
+        assert_eq!(
+            cycle.to_string(),
+            "structs refer to each other: `Child` -> `Parent` -> `Child`"
+        );
+    }
+}
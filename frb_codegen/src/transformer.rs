@@ -1,9 +1,12 @@
+use std::collections::HashMap;
+
 use log::debug;
 
 use crate::api_types::ApiType::{Boxed, StructRef};
-use crate::api_types::{ApiField, ApiFile, ApiFunc, ApiTypeBoxed};
+use crate::api_types::{ApiField, ApiFile, ApiFunc, ApiStruct, ApiTypeBoxed};
 
-pub fn transform(src: ApiFile) -> ApiFile {
+/// [dedup_structs] is disabled by `--no-dedup`
+pub fn transform(src: ApiFile, dedup_structs: bool) -> ApiFile {
     let dst_funcs = src
         .funcs
         .into_iter()
@@ -17,12 +20,73 @@ pub fn transform(src: ApiFile) -> ApiFile {
         })
         .collect();
 
-    ApiFile {
+    let dst = ApiFile {
         funcs: dst_funcs,
         ..src
+    };
+    if dedup_structs {
+        transform_dedup_structs(dst)
+    } else {
+        dst
     }
 }
 
+/// Structs with the same fields, e.g. `Point2D` and `Vector2D` of `x: f64, y: f64`, share one
+/// Dart class: all but the first by name become a `typedef` of it, see
+/// [ApiFile::struct_dart_aliases]
+fn transform_dedup_structs(mut src: ApiFile) -> ApiFile {
+    let mut names: Vec<_> = src
+        .distinct_types(true, true)
+        .into_iter()
+        .filter_map(|ty| match ty {
+            StructRef(s) => Some(s.name),
+            _ => None,
+        })
+        .filter(|name| is_dedup_candidate(&src.struct_pool[name], &src))
+        .collect();
+    names.sort();
+
+    let mut first_of_shape: HashMap<_, String> = HashMap::new();
+    for name in names {
+        let shape = struct_shape(&src.struct_pool[&name]);
+        match first_of_shape.get(&shape) {
+            Some(first) => {
+                debug!("transform_dedup_structs alias {} to {}", name, first);
+                src.struct_dart_aliases.insert(name, first.clone());
+            }
+            None => {
+                first_of_shape.insert(shape, name);
+            }
+        }
+    }
+    src
+}
+
+/// Members other than the fields belong to one of the Rust types only
+fn is_dedup_candidate(s: &ApiStruct, api_file: &ApiFile) -> bool {
+    !s.notify_on_change
+        && s.associated_consts.is_empty()
+        && s.num_private_fields == 0
+        && !api_file
+            .funcs
+            .iter()
+            .any(|func| matches!(&func.method, Some(method) if method.struct_name == s.name))
+}
+
+fn struct_shape(s: &ApiStruct) -> Vec<(String, String, Option<String>, Option<String>)> {
+    s.fields
+        .iter()
+        .map(|field| {
+            (
+                field.dart_name(),
+                field.ty.safe_ident(),
+                field.default.clone(),
+                field.deprecated.clone(),
+            )
+        })
+        .collect()
+}
+
 fn transform_func_input_add_boxed(input: ApiField) -> ApiField {
     if let StructRef(_) = &input.ty {
         debug!(
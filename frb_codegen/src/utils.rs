@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::Path;
 
+use crate::config::LineEnding;
+
 pub fn mod_from_rust_path(code_path: &str, crate_path: &str) -> String {
     Path::new(code_path)
         .strip_prefix(Path::new(crate_path).join("src"))
@@ -12,6 +14,12 @@ pub fn mod_from_rust_path(code_path: &str, crate_path: &str) -> String {
         .replace('/', "::")
 }
 
+/// Write an output file with the configured line endings, see [crate::config::RawOpts::line_ending].
+/// `rustfmt` and `dart format` keep the line endings of the files they format.
+pub fn write_output<P: AsRef<Path>>(path: P, content: &str, line_ending: LineEnding) {
+    fs::write(path, line_ending.apply(content)).unwrap();
+}
+
 pub fn with_changed_file<F: FnOnce()>(path: &str, append_content: &str, f: F) {
     let content_original = fs::read_to_string(&path).unwrap();
     fs::write(&path, content_original.clone() + append_content).unwrap();
@@ -20,3 +28,15 @@ pub fn with_changed_file<F: FnOnce()>(path: &str, append_content: &str, f: F) {
 
     fs::write(&path, content_original).unwrap();
 }
+
+/// e.g. removes `#![allow(unused)]` at the beginning of [code]
+pub fn strip_inner_attributes(code: &str) -> String {
+    let mut rest = code.trim_start();
+    while rest.starts_with("#![") {
+        let end = rest
+            .find(")]")
+            .map_or_else(|| rest.find(']').unwrap(), |idx| idx + 1);
+        rest = rest[end + 1..].trim_start();
+    }
+    rest.to_string()
+}
@@ -0,0 +1,150 @@
+//! Generate again whenever the input changes, see [crate::config::RawOpts::watch]
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use syn::{Item, ItemMod};
+
+use crate::config::Opts;
+use crate::frb_codegen;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Changes within this window are handled by a single generation
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(200);
+
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Run the generation, then run it again on each change of the watched files until Ctrl-C.
+/// Fails only if the initial generation fails; later failures are logged and then waited out.
+pub fn watch(config: &Opts) -> Result<()> {
+    install_stop_handler();
+
+    if !try_frb_codegen(config) {
+        return Err(anyhow!("initial generation failed"));
+    }
+
+    let mut snapshot = take_snapshot(config);
+    info!(
+        "Watching {:?} for changes, press Ctrl-C to stop",
+        snapshot.iter().map(|(path, _)| path).collect::<Vec<_>>()
+    );
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        if STOP_REQUESTED.load(Ordering::SeqCst) {
+            info!("Stop watching");
+            return Ok(());
+        }
+        if take_snapshot(config) == snapshot {
+            continue;
+        }
+
+        loop {
+            thread::sleep(DEBOUNCE_WINDOW);
+            let next = take_snapshot(config);
+            if next == snapshot {
+                break;
+            }
+            snapshot = next;
+        }
+
+        info!("Change detected, generate again");
+        if try_frb_codegen(config) {
+            info!("Success! Waiting for changes");
+        } else {
+            error!("Generation failed, waiting for changes");
+        }
+        // generation may add `mod` declarations or reformat files, so do not treat it as a change
+        snapshot = take_snapshot(config);
+    }
+}
+
+/// The panic message is already printed by the panic hook
+fn try_frb_codegen(config: &Opts) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(|| frb_codegen(config))).is_ok()
+}
+
+type Snapshot = Vec<(PathBuf, Option<SystemTime>)>;
+
+fn take_snapshot(config: &Opts) -> Snapshot {
+    let mut paths = Vec::new();
+    for rust_input_path in &config.rust_input_paths {
+        collect_watched_files(Path::new(rust_input_path), &mut paths);
+    }
+    paths
+        .into_iter()
+        .filter(|path| {
+            path != Path::new(&config.rust_output_path)
+                && !(config.split_output && path.starts_with(config.rust_output_dir()))
+        })
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+/// [path] and the files of the modules it declares via `mod foo;`, recursively
+fn collect_watched_files(path: &Path, paths: &mut Vec<PathBuf>) {
+    if paths.iter().any(|existing| existing == path) {
+        return;
+    }
+    paths.push(path.to_path_buf());
+
+    let file = match fs::read_to_string(path)
+        .ok()
+        .and_then(|content| syn::parse_file(&content).ok())
+    {
+        Some(file) => file,
+        None => return,
+    };
+    let dir = module_dir(path);
+    for item in file.items {
+        if let Item::Mod(ItemMod {
+            ident,
+            content: None,
+            ..
+        }) = item
+        {
+            let candidates = vec![
+                dir.join(format!("{}.rs", ident)),
+                dir.join(ident.to_string()).join("mod.rs"),
+            ];
+            if let Some(child) = candidates.into_iter().find(|child| child.exists()) {
+                collect_watched_files(&child, paths);
+            }
+        }
+    }
+}
+
+/// e.g. modules of `src/lib.rs` are in `src/`, while modules of `src/api.rs` are in `src/api/`
+fn module_dir(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    match path.file_stem().and_then(|stem| stem.to_str()) {
+        Some("lib" | "main" | "mod") | None => parent.to_path_buf(),
+        Some(stem) => parent.join(stem),
+    }
+}
+
+#[cfg(unix)]
+fn install_stop_handler() {
+    extern "C" fn handle_sigint(_: libc::c_int) {
+        STOP_REQUESTED.store(true, Ordering::SeqCst);
+    }
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            handle_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+/// Ctrl-C terminates the process directly
+#[cfg(not(unix))]
+fn install_stop_handler() {}
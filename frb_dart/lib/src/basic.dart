@@ -1,11 +1,11 @@
 import 'dart:async';
 import 'dart:convert';
+import 'dart:developer' as developer;
 import 'dart:ffi' as ffi;
 import 'dart:ffi';
 import 'dart:isolate';
 import 'dart:typed_data';
 
-import 'package:flutter_rust_bridge/src/utils.dart';
 import 'package:meta/meta.dart';
 
 final _instances = <Type>{};
@@ -36,11 +36,13 @@ abstract class FlutterRustBridgeBase<T extends FlutterRustBridgeWireBase> {
 
   /// Execute a normal ffi call. Usually called by generated code instead of manually called.
   @protected
-  Future<S> executeNormal<S>(FlutterRustBridgeTask<S> task) {
-    final completer = Completer<dynamic>();
-    final sendPort = singleCompletePort(completer);
-    task.callFfi(sendPort.nativePort);
-    return completer.future.then((dynamic raw) => _transformRust2DartMessage(raw, task.parseSuccessData));
+  Future<S> executeNormal<S>(FlutterRustBridgeTask<S> task) async {
+    final receivePort = ReceivePort();
+    task.callFfi(receivePort.sendPort.nativePort);
+
+    // the port is closed once the only message arrives
+    final dynamic raw = await receivePort.first;
+    return _transformRust2DartMessage(raw, task);
   }
 
   /// Similar to [executeNormal], except that this will return synchronously
@@ -56,7 +58,7 @@ abstract class FlutterRustBridgeBase<T extends FlutterRustBridgeWireBase> {
     if (success) {
       return bytes;
     } else {
-      throw FfiException('EXECUTE_SYNC', utf8.decode(bytes), null);
+      throw FfiException.fromRust('EXECUTE_SYNC', utf8.decode(bytes), null);
     }
   }
 
@@ -68,22 +70,60 @@ abstract class FlutterRustBridgeBase<T extends FlutterRustBridgeWireBase> {
 
     await for (final raw in receivePort) {
       try {
-        yield _transformRust2DartMessage(raw, task.parseSuccessData);
+        yield _transformRust2DartMessage(raw, task);
       } on _CloseStreamException {
         receivePort.close();
       }
     }
   }
 
-  S _transformRust2DartMessage<S>(dynamic raw, S Function(dynamic) parseSuccessData) {
+  /// Similar to [executeStream], except that each event of the Rust `StreamSink<Result<T, E>>` is either
+  /// a value or an error, where errors are added via [StreamController.addError] without closing the stream.
+  @protected
+  Stream<S> executeResultStream<S>(FlutterRustBridgeTask<S> task) {
+    final parseStreamErrorData = task.parseStreamErrorData!;
+    final receivePort = ReceivePort();
+    final controller = StreamController<S>(onCancel: receivePort.close);
+
+    controller.onListen = () {
+      task.callFfi(receivePort.sendPort.nativePort);
+      receivePort.listen((dynamic raw) {
+        try {
+          if (raw[0] == _RUST2DART_ACTION_SUCCESS) {
+            final event = raw[1];
+            if (event[0] == _STREAM_EVENT_OK) {
+              controller.add(task.parseSuccessData(event[1]));
+            } else {
+              controller.addError(parseStreamErrorData(event[1]));
+            }
+          } else {
+            // only errors of the function itself and the end of the stream are left
+            _transformRust2DartMessage(raw, task);
+          }
+        } on _CloseStreamException {
+          receivePort.close();
+          controller.close();
+        } catch (e, s) {
+          controller.addError(e, s);
+          receivePort.close();
+          controller.close();
+        }
+      });
+    };
+    return controller.stream;
+  }
+
+  S _transformRust2DartMessage<S>(dynamic raw, FlutterRustBridgeTask<S> task) {
     final action = raw[0];
     switch (action) {
       case _RUST2DART_ACTION_SUCCESS:
         assert(raw.length == 2);
-        return parseSuccessData(raw[1]);
+        return task.parseSuccessData(raw[1]);
       case _RUST2DART_ACTION_ERROR:
         assert(raw.length == 4);
-        throw FfiException(raw[1], raw[2], raw[3]);
+        final parseErrorData = task.parseErrorData;
+        if (raw[1] == _CUSTOM_ERROR_CODE && parseErrorData != null) throw parseErrorData(raw[3]);
+        throw FfiException.fromRust(raw[1], raw[2], raw[3]);
       case _RUST2DART_ACTION_CLOSE_STREAM:
         assert(raw.length == 1);
         throw _CloseStreamException();
@@ -95,6 +135,46 @@ abstract class FlutterRustBridgeBase<T extends FlutterRustBridgeWireBase> {
   static const _RUST2DART_ACTION_SUCCESS = 0; // ignore: constant_identifier_names
   static const _RUST2DART_ACTION_ERROR = 1; // ignore: constant_identifier_names
   static const _RUST2DART_ACTION_CLOSE_STREAM = 2; // ignore: constant_identifier_names
+  static const _CUSTOM_ERROR_CODE = 'CUSTOM_ERROR'; // ignore: constant_identifier_names
+  static const _STREAM_EVENT_OK = 0; // ignore: constant_identifier_names
+}
+
+/// Base class of the wrappers generated with `--dart-isolate-safe`, which forward each call to the bridge in
+/// the [Zone] they are created in, so that futures complete and stream events arrive there. Using them from
+/// an isolate other than the one creating them throws a [StateError], instead of failing in obscure ways.
+abstract class FlutterRustBridgeIsolateSafe {
+  final Zone _zone = Zone.current;
+  final SendPort _controlPort = Isolate.current.controlPort;
+
+  void _checkIsolate() {
+    if (Isolate.current.controlPort != _controlPort) {
+      throw StateError('$runtimeType is used by an isolate other than the one creating it');
+    }
+  }
+
+  /// Call [body] in the zone of this wrapper. Usually called by generated code instead of manually called.
+  @protected
+  T run<T>(T Function() body) {
+    _checkIsolate();
+    return _zone.run(body);
+  }
+
+  /// Similar to [run], except that the stream is also listened to in the zone of this wrapper.
+  @protected
+  Stream<T> runStream<T>(Stream<T> Function() body) {
+    _checkIsolate();
+    late StreamSubscription<T> subscription;
+    final controller = StreamController<T>();
+    controller
+      ..onListen = () {
+        subscription = _zone.run(
+            () => body().listen(controller.add, onError: controller.addError, onDone: controller.close));
+      }
+      ..onPause = () => subscription.pause()
+      ..onResume = () => subscription.resume()
+      ..onCancel = () => subscription.cancel();
+    return controller.stream;
+  }
 }
 
 /// A task to call FFI function. Normally you do not manually create instances of this task, but instead
@@ -103,12 +183,21 @@ abstract class FlutterRustBridgeBase<T extends FlutterRustBridgeWireBase> {
 class FlutterRustBridgeTask<S> {
   final void Function(int port) callFfi;
   final S Function(dynamic) parseSuccessData;
+
+  /// Parses the error of a Rust function returning `Result<T, E>` with a user-defined `E`,
+  /// which is then thrown instead of a [FfiException]
+  final Object Function(dynamic)? parseErrorData;
+
+  /// Parses the `Err` events of a Rust `StreamSink<Result<T, E>>`, see [FlutterRustBridgeBase.executeResultStream]
+  final Object Function(dynamic)? parseStreamErrorData;
   final String debugName;
   final dynamic hint;
 
   FlutterRustBridgeTask({
     required this.callFfi,
     required this.parseSuccessData,
+    this.parseErrorData,
+    this.parseStreamErrorData,
     required this.debugName,
     this.hint,
   });
@@ -134,10 +223,286 @@ class FfiException {
   final String message;
   final Object? details;
 
-  FfiException(this.code, this.message, this.details);
+  /// The location of the Rust function which panicked, e.g. `src/api.rs:42:8`, if the bindings are
+  /// generated with `--source-locations`.
+  final String? rustLocation;
+
+  FfiException(this.code, this.message, this.details, [this.rustLocation]);
+
+  /// Moves the ` [rust: src/api.rs:42:8]` suffix of [message] into [rustLocation]. The location is
+  /// dropped in release builds, or as set by `--dart-define=frb.strip_rust_locations=true|false`.
+  factory FfiException.fromRust(String code, String message, Object? details) {
+    final match = _rustLocationSuffix.firstMatch(message);
+    if (match == null) return FfiException(code, message, details);
+    return FfiException(
+        code, message.substring(0, match.start), details, _stripRustLocations ? null : match.group(1));
+  }
+
+  @override
+  String toString() =>
+      'FfiException($code, $message, $details)${rustLocation == null ? '' : ' [rust: $rustLocation]'}';
+}
+
+final _rustLocationSuffix = RegExp(r' \[rust: ([^\]]+)\]$');
+
+const _stripRustLocations =
+    bool.fromEnvironment('frb.strip_rust_locations', defaultValue: bool.fromEnvironment('dart.vm.product'));
+
+/// Thrown when a function marked with `#[frb(throw_on_null_output)]` returns `None`.
+class FrbNullException implements Exception {
+  final String message;
+
+  const FrbNullException(this.message);
+
+  @override
+  String toString() => 'FrbNullException($message)';
+}
+
+/// A Rust `Range<T>` (when [inclusive] is false) or `RangeInclusive<T>` (when [inclusive] is true).
+@immutable
+class FrbRange<T extends num> {
+  final T start;
+  final T end;
+  final bool inclusive;
+
+  const FrbRange(this.start, this.end, {this.inclusive = false});
+
+  @override
+  bool operator ==(Object other) =>
+      other is FrbRange<T> && other.start == start && other.end == end && other.inclusive == inclusive;
+
+  @override
+  int get hashCode => Object.hash(start, end, inclusive);
+
+  @override
+  String toString() => inclusive ? '$start..=$end' : '$start..$end';
+}
+
+/// A Rust tuple `(A, B)`.
+@immutable
+class FrbTuple2<A, B> {
+  final A field0;
+  final B field1;
+
+  const FrbTuple2(this.field0, this.field1);
+
+  @override
+  bool operator ==(Object other) =>
+      other is FrbTuple2<A, B> && other.field0 == field0 && other.field1 == field1;
+
+  @override
+  int get hashCode => Object.hash(field0, field1);
+
+  @override
+  String toString() => '($field0, $field1)';
+}
+
+/// A Rust tuple `(A, B, C)`.
+@immutable
+class FrbTuple3<A, B, C> {
+  final A field0;
+  final B field1;
+  final C field2;
+
+  const FrbTuple3(this.field0, this.field1, this.field2);
+
+  @override
+  bool operator ==(Object other) =>
+      other is FrbTuple3<A, B, C> && other.field0 == field0 && other.field1 == field1 && other.field2 == field2;
 
   @override
-  String toString() => 'FfiException($code, $message, $details)';
+  int get hashCode => Object.hash(field0, field1, field2);
+
+  @override
+  String toString() => '($field0, $field1, $field2)';
+}
+
+final _mask64 = (BigInt.one << 64) - BigInt.one;
+
+/// A Rust `i128`, as its [high] and [low] 64-bit halves. [low] holds the bits of the unsigned
+/// lower half, so it is negative when the top bit of that half is set.
+@immutable
+class Int128 {
+  final int high;
+  final int low;
+
+  const Int128(this.high, this.low);
+
+  Int128.fromBigInt(BigInt value)
+      : high = (value >> 64).toSigned(64).toInt(),
+        low = (value & _mask64).toSigned(64).toInt();
+
+  BigInt toBigInt() => (BigInt.from(high) << 64) | (BigInt.from(low) & _mask64);
+
+  @override
+  bool operator ==(Object other) => other is Int128 && other.high == high && other.low == low;
+
+  @override
+  int get hashCode => Object.hash(high, low);
+
+  @override
+  String toString() => toBigInt().toString();
+}
+
+/// A Rust `u128`, as its [high] and [low] 64-bit halves. Both hold the bits of unsigned halves,
+/// so they are negative when the top bit of their half is set.
+@immutable
+class Uint128 {
+  final int high;
+  final int low;
+
+  const Uint128(this.high, this.low);
+
+  Uint128.fromBigInt(BigInt value)
+      : high = ((value >> 64) & _mask64).toSigned(64).toInt(),
+        low = (value & _mask64).toSigned(64).toInt();
+
+  BigInt toBigInt() => ((BigInt.from(high) & _mask64) << 64) | (BigInt.from(low) & _mask64);
+
+  @override
+  bool operator ==(Object other) => other is Uint128 && other.high == high && other.low == low;
+
+  @override
+  int get hashCode => Object.hash(high, low);
+
+  @override
+  String toString() => toBigInt().toString();
+}
+
+/// The value of a `#[non_exhaustive]` Rust enum at [index] of [values], or [unknown] for variants added to Rust after
+/// the bindings were generated.
+T frbNonExhaustiveEnumValue<T>(List<T> values, int index, T unknown) =>
+    index >= 0 && index < values.length ? values[index] : unknown;
+
+/// Whether [a] and [b] have equal elements in the same order, comparing nested lists likewise, for the `==` of the
+/// classes generated from Rust structs. Unlike `listEquals` of Flutter, it needs no Flutter, e.g. in pure Dart.
+bool frbListEquals<T>(List<T>? a, List<T>? b) {
+  if (identical(a, b)) return true;
+  if (a == null || b == null || a.length != b.length) return false;
+  for (var i = 0; i < a.length; i++) {
+    final x = a[i], y = b[i];
+    if (x is List && y is List ? !frbListEquals(x, y) : x != y) return false;
+  }
+  return true;
+}
+
+/// A hash code of the elements of [list], consistent with [frbListEquals].
+int frbListHash<T>(List<T>? list) {
+  if (list == null) return null.hashCode;
+  return Object.hashAll(list.map((element) => element is List ? frbListHash(element) : element));
+}
+
+/// Converts a UUID string such as `67e55044-10b1-426f-9247-bb680e5fe0c8` to the 16 bytes of a Rust `uuid::Uuid`.
+Uint8List frbUuidToBytes(String uuid) {
+  final hex = uuid.replaceAll('-', '');
+  if (hex.length != 32) throw FormatException('invalid UUID', uuid);
+  return Uint8List.fromList([for (var i = 0; i < 32; i += 2) int.parse(hex.substring(i, i + 2), radix: 16)]);
+}
+
+/// Converts the 16 bytes of a Rust `uuid::Uuid` to its lowercase hyphenated string, as Rust formats it.
+String frbUuidFromBytes(Uint8List bytes) {
+  final hex = bytes.map((byte) => byte.toRadixString(16).padLeft(2, '0')).join();
+  return '${hex.substring(0, 8)}-${hex.substring(8, 12)}-${hex.substring(12, 16)}-${hex.substring(16, 20)}-${hex.substring(20)}';
+}
+
+/// Splits the concatenated frames of a Rust `ZeroCopyBuffer<Vec<Vec<T>>>` into views created by [view], e.g.
+/// [Uint8List.sublistView], so that the frames share the memory received from Rust without copying.
+List<L> frbSplitZeroCopyFrames<L extends TypedData>(
+    Uint64List lengths, L data, L Function(L data, int start, int end) view) {
+  var start = 0;
+  return [
+    for (final length in lengths) view(data, start, start += length),
+  ];
+}
+
+/// Listens to the calls of a Rust closure wrapping [callback], and returns the native port that Rust posts them to,
+/// see `DartCallback` of the Rust side. Each call is `[callId, ...args]`, and `[]` closes the port once Rust drops
+/// the closure. Calls with a non-zero `callId` wait for the result, which is sent by [reply], or by [reject] if
+/// [callback] throws.
+int frbListenCallback<R>(
+  R Function(List<dynamic> args) callback, {
+  void Function(int callId, R result)? reply,
+  void Function(int callId)? reject,
+}) {
+  final port = ReceivePort();
+  port.listen((message) {
+    final call = message as List<dynamic>;
+    if (call.isEmpty) {
+      port.close();
+      return;
+    }
+    final callId = call[0] as int;
+    if (callId == 0) {
+      callback(call.sublist(1));
+      return;
+    }
+    final R result;
+    try {
+      result = callback(call.sublist(1));
+    } catch (e) {
+      developer.log('callback threw, which fails the Rust call: $e', name: 'flutter_rust_bridge');
+      reject!(callId);
+      return;
+    }
+    reply!(callId, result);
+  });
+  return port.sendPort.nativePort;
+}
+
+/// A non-owning reference to a value held by a Rust `Arc<T>`, received from a Rust `Weak<T>`.
+/// Call [dispose] when it is no longer needed, otherwise the Rust `Weak<T>` is leaked.
+class WeakRef<T> {
+  final int _address;
+  final Future<T?> Function(int address) _upgrade;
+  final void Function(int address) _dispose;
+  bool _disposed = false;
+
+  WeakRef(
+    this._address, {
+    required Future<T?> Function(int address) upgrade,
+    required void Function(int address) dispose,
+  })  : _upgrade = upgrade,
+        _dispose = dispose;
+
+  /// Address of the Rust `Weak<T>`. Only for internal usage.
+  int get address {
+    if (_disposed) throw StateError('WeakRef is already disposed');
+    return _address;
+  }
+
+  /// A copy of the value, or null if all strong references in Rust have been dropped.
+  Future<T?> upgrade() => _upgrade(address);
+
+  /// Release the Rust `Weak<T>`. The value itself is not affected.
+  void dispose() {
+    _dispose(address);
+    _disposed = true;
+  }
+}
+
+/// A handle to a Rust object, such as a `Box<dyn Fn(i32) -> i32 + Send>`, which Dart can hold and
+/// pass back to Rust without bridging its internals. Passing it to Rust moves the object there,
+/// after which the handle can no longer be used. Otherwise, call [dispose] when it is no longer
+/// needed, or the Rust object is leaked.
+class OpaqueHandle {
+  final int _handle;
+  final void Function(int handle) _dispose;
+  bool _disposed = false;
+
+  OpaqueHandle(this._handle, {required void Function(int handle) dispose}) : _dispose = dispose;
+
+  /// Whether the handle is disposed or moved to Rust.
+  bool get isDisposed => _disposed;
+
+  /// Give up the handle, to move the object to Rust. Only for internal usage.
+  int move() {
+    if (_disposed) throw StateError('$runtimeType is already disposed or moved to Rust');
+    _disposed = true;
+    return _handle;
+  }
+
+  /// Release the Rust object.
+  void dispose() => _dispose(move());
 }
 
 /// This class, together with its subclasses, are only for internal usage.
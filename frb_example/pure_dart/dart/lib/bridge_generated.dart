@@ -1,16 +1,22 @@
 // AUTO GENERATED FILE, DO NOT EDIT.
-// Generated by `flutter_rust_bridge`.
+// Generated by `flutter_rust_bridge` 1.5.0.
+
+// ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments, camel_case_types
+library flutter_rust_bridge_example;
 
-// ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments
 import 'dart:convert';
+import 'dart:ffi' as ffi;
 import 'dart:typed_data';
 
 import 'package:flutter_rust_bridge/flutter_rust_bridge.dart';
-import 'dart:ffi' as ffi;
+import 'package:uuid/uuid.dart';
 
 abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRustBridgeExampleWire> {
-  factory FlutterRustBridgeExample(ffi.DynamicLibrary dylib) =>
-      FlutterRustBridgeExampleImpl.raw(FlutterRustBridgeExampleWire(dylib));
+  factory FlutterRustBridgeExample(ffi.DynamicLibrary dylib) {
+    final bridge = FlutterRustBridgeExampleImpl.raw(FlutterRustBridgeExampleWire(dylib));
+    FlutterRustBridgeExampleRegistry.instance.register(FlutterRustBridgeExampleRegistry.defaultName, bridge);
+    return bridge;
+  }
 
   FlutterRustBridgeExample.raw(FlutterRustBridgeExampleWire inner) : super(inner);
 
@@ -34,6 +40,11 @@ abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRus
 
   Future<ZeroCopyVecOfPrimitivePack> handleZeroCopyVecOfPrimitive({required int n, dynamic hint});
 
+  /// Row `i` has `i` elements of value `i`
+  Future<List<Uint8List>> handleZeroCopyRows({required int rows, dynamic hint});
+
+  Future<List<String>> handleZeroCopyStrings({required List<String> strings, dynamic hint});
+
   Future<MySize> handleStruct({required MySize arg, required MySize boxed, dynamic hint});
 
   Future<NewTypeInt> handleNewtype({required NewTypeInt arg, dynamic hint});
@@ -44,10 +55,18 @@ abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRus
 
   Uint8List handleSyncReturn({required String mode, dynamic hint});
 
+  String handleSyncReturnString({required String name, dynamic hint});
+
   Stream<String> handleStream({required String arg, dynamic hint});
 
+  /// Parses each comma-separated number of [input], where invalid ones become errors of the stream
+  /// without closing it.
+  Stream<int> parseNumbers({required String input, dynamic hint});
+
   Future<int> returnErr({dynamic hint});
 
+  Future<int> returnErrWithContext({dynamic hint});
+
   Future<int> returnPanic({dynamic hint});
 
   Future<double?> handleOptionalReturn({required double left, required double right, dynamic hint});
@@ -67,6 +86,78 @@ abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRus
       bool? boolbox,
       ExoticOptionals? structbox,
       dynamic hint});
+
+  Future<BinaryTree> handleBinaryTree({required BinaryTree tree, dynamic hint});
+
+  Future<int> handlePlatformWidth({required int len, required int offset, dynamic hint});
+
+  Future<Uint8List> handleRgba({required Uint8List rgba, dynamic hint});
+
+  Future<Float64List> clampValues({required Float64List data, required FrbRange<double> range, dynamic hint});
+
+  Future<Uint8List> handleCowBytes({required bool borrowed, dynamic hint});
+
+  Future<String> handleCowStr({required String text, required bool borrowed, dynamic hint});
+
+  Future<Map<String, int>> wordCounts({required String text, dynamic hint});
+
+  /// Takes the counts of `word_counts`, which Dart passes as a `Map<String, int>`
+  Future<int> totalWords({required Map<String, int> counts, dynamic hint});
+
+  Future<String?> describeError({String? error, dynamic hint});
+
+  Future<DateTime> handleDateTime({required DateTime at, dynamic hint});
+
+  Future<UuidValue> newUuid({dynamic hint});
+
+  Future<String> handleUuid({required UuidValue id, dynamic hint});
+
+  Future<Set<int>> handleSet({required Set<int> nums, dynamic hint});
+
+  Future<Uint128> nextNonce({required Uint128 nonce, dynamic hint});
+
+  Future<Adder> makeAdder({required int n, dynamic hint});
+
+  Future<int> applyAdder({required Adder adder, required int x, dynamic hint});
+
+  Future<int> sumWithProgress({required Int32List nums, required void Function(int, int) onProgress, dynamic hint});
+
+  Future<int> applyTwice({required int x, int Function(int)? f, dynamic hint});
+
+  /// Takes any Dart object as the name
+  Future<String> greetAnything({required Object name, dynamic hint});
+
+  Future<Point2D> translate({required Point2D p, required Vector2D v, dynamic hint});
+
+  Future<Vector2D> vectorBetween({required Point2D from, required Point2D to, dynamic hint});
+
+  Future<int> lookupSize({required String name, dynamic hint});
+
+  /// Leaks the value and gives its address, which `unboxValue` frees
+  Future<int> boxValue({required int value, dynamic hint});
+
+  Future<int> unboxValue({required int ptr, dynamic hint});
+}
+
+/// Look up instances of [FlutterRustBridgeExample] by name, e.g. when an app uses multiple bridges.
+/// Instances created by the [FlutterRustBridgeExample] factory are registered as [defaultName].
+class FlutterRustBridgeExampleRegistry {
+  static const defaultName = 'default';
+  static final instance = FlutterRustBridgeExampleRegistry._();
+
+  final _bridges = <String, Object>{};
+
+  FlutterRustBridgeExampleRegistry._();
+
+  void register(String name, FlutterRustBridgeExample bridge) => _bridges[name] = bridge;
+
+  T? lookup<T extends FlutterRustBridgeExample>(String name) {
+    final bridge = _bridges[name];
+    return bridge is T ? bridge : null;
+  }
+
+  /// Drop all registered instances
+  void dispose() => _bridges.clear();
 }
 
 class Attribute {
@@ -78,6 +169,39 @@ class Attribute {
     required this.key,
     required this.value,
   });
+
+  @override
+  bool operator ==(Object other) => other is Attribute && other.key == key && other.value == value;
+
+  @override
+  int get hashCode => Object.hashAll([key, value]);
+
+  @override
+  String toString() => 'Attribute(key: $key, value: $value)';
+}
+
+class BinaryTree {
+  final int value;
+
+  final BinaryTree? left;
+
+  final BinaryTree? right;
+
+  BinaryTree({
+    required this.value,
+    this.left,
+    this.right,
+  });
+
+  @override
+  bool operator ==(Object other) =>
+      other is BinaryTree && other.value == value && other.left == left && other.right == right;
+
+  @override
+  int get hashCode => Object.hashAll([value, left, right]);
+
+  @override
+  String toString() => 'BinaryTree(value: $value, left: $left, right: $right)';
 }
 
 class Element {
@@ -95,6 +219,37 @@ class Element {
     this.attributes,
     this.children,
   });
+
+  @override
+  bool operator ==(Object other) =>
+      other is Element &&
+      other.tag == tag &&
+      other.text == text &&
+      frbListEquals(other.attributes, attributes) &&
+      frbListEquals(other.children, children);
+
+  @override
+  int get hashCode => Object.hashAll([tag, text, frbListHash(attributes), frbListHash(children)]);
+
+  @override
+  String toString() => 'Element(tag: $tag, text: $text, attributes: $attributes, children: $children)';
+}
+
+class NewTypeInt {
+  final int field0;
+
+  NewTypeInt({
+    required this.field0,
+  });
+
+  @override
+  bool operator ==(Object other) => other is NewTypeInt && other.field0 == field0;
+
+  @override
+  int get hashCode => Object.hashAll([field0]);
+
+  @override
+  String toString() => 'NewTypeInt(field0: $field0)';
 }
 
 class ExoticOptionals {
@@ -145,6 +300,48 @@ class ExoticOptionals {
     this.nullableAttributes,
     this.newtypeint,
   });
+
+  @override
+  bool operator ==(Object other) =>
+      other is ExoticOptionals &&
+      other.int32 == int32 &&
+      other.int64 == int64 &&
+      other.float64 == float64 &&
+      other.boolean == boolean &&
+      frbListEquals(other.zerocopy, zerocopy) &&
+      frbListEquals(other.int8List, int8List) &&
+      frbListEquals(other.uint8List, uint8List) &&
+      frbListEquals(other.int32List, int32List) &&
+      frbListEquals(other.int64List, int64List) &&
+      frbListEquals(other.float32List, float32List) &&
+      frbListEquals(other.float64List, float64List) &&
+      frbListEquals(other.attributes, attributes) &&
+      frbListEquals(other.attributesNullable, attributesNullable) &&
+      frbListEquals(other.nullableAttributes, nullableAttributes) &&
+      other.newtypeint == newtypeint;
+
+  @override
+  int get hashCode => Object.hashAll([
+        int32,
+        int64,
+        float64,
+        boolean,
+        frbListHash(zerocopy),
+        frbListHash(int8List),
+        frbListHash(uint8List),
+        frbListHash(int32List),
+        frbListHash(int64List),
+        frbListHash(float32List),
+        frbListHash(float64List),
+        frbListHash(attributes),
+        frbListHash(attributesNullable),
+        frbListHash(nullableAttributes),
+        newtypeint
+      ]);
+
+  @override
+  String toString() =>
+      'ExoticOptionals(int32: $int32, int64: $int64, float64: $float64, boolean: $boolean, zerocopy: $zerocopy, int8List: $int8List, uint8List: $uint8List, int32List: $int32List, int64List: $int64List, float32List: $float32List, ...)';
 }
 
 class MySize {
@@ -156,6 +353,15 @@ class MySize {
     required this.width,
     required this.height,
   });
+
+  @override
+  bool operator ==(Object other) => other is MySize && other.width == width && other.height == height;
+
+  @override
+  int get hashCode => Object.hashAll([width, height]);
+
+  @override
+  String toString() => 'MySize(width: $width, height: $height)';
 }
 
 class MyTreeNode {
@@ -170,14 +376,39 @@ class MyTreeNode {
     required this.valueVecU8,
     required this.children,
   });
+
+  @override
+  bool operator ==(Object other) =>
+      other is MyTreeNode &&
+      other.valueI32 == valueI32 &&
+      frbListEquals(other.valueVecU8, valueVecU8) &&
+      frbListEquals(other.children, children);
+
+  @override
+  int get hashCode => Object.hashAll([valueI32, frbListHash(valueVecU8), frbListHash(children)]);
+
+  @override
+  String toString() => 'MyTreeNode(valueI32: $valueI32, valueVecU8: $valueVecU8, children: $children)';
 }
 
-class NewTypeInt {
-  final int field0;
+class Point2D {
+  final double x;
 
-  NewTypeInt({
-    required this.field0,
+  final double y;
+
+  Point2D({
+    required this.x,
+    required this.y,
   });
+
+  @override
+  bool operator ==(Object other) => other is Point2D && other.x == x && other.y == y;
+
+  @override
+  int get hashCode => Object.hashAll([x, y]);
+
+  @override
+  String toString() => 'Point2D(x: $x, y: $y)';
 }
 
 class VecOfPrimitivePack {
@@ -213,7 +444,42 @@ class VecOfPrimitivePack {
     required this.float32List,
     required this.float64List,
   });
-}
+
+  @override
+  bool operator ==(Object other) =>
+      other is VecOfPrimitivePack &&
+      frbListEquals(other.int8List, int8List) &&
+      frbListEquals(other.uint8List, uint8List) &&
+      frbListEquals(other.int16List, int16List) &&
+      frbListEquals(other.uint16List, uint16List) &&
+      frbListEquals(other.uint32List, uint32List) &&
+      frbListEquals(other.int32List, int32List) &&
+      frbListEquals(other.uint64List, uint64List) &&
+      frbListEquals(other.int64List, int64List) &&
+      frbListEquals(other.float32List, float32List) &&
+      frbListEquals(other.float64List, float64List);
+
+  @override
+  int get hashCode => Object.hashAll([
+        frbListHash(int8List),
+        frbListHash(uint8List),
+        frbListHash(int16List),
+        frbListHash(uint16List),
+        frbListHash(uint32List),
+        frbListHash(int32List),
+        frbListHash(uint64List),
+        frbListHash(int64List),
+        frbListHash(float32List),
+        frbListHash(float64List)
+      ]);
+
+  @override
+  String toString() =>
+      'VecOfPrimitivePack(int8List: $int8List, uint8List: $uint8List, int16List: $int16List, uint16List: $uint16List, uint32List: $uint32List, int32List: $int32List, uint64List: $uint64List, int64List: $int64List, float32List: $float32List, float64List: $float64List)';
+}
+
+/// A `typedef` of [Point2D] in Dart, since it has the same fields
+typedef Vector2D = Point2D;
 
 class ZeroCopyVecOfPrimitivePack {
   final Int8List int8List;
@@ -248,6 +514,79 @@ class ZeroCopyVecOfPrimitivePack {
     required this.float32List,
     required this.float64List,
   });
+
+  @override
+  bool operator ==(Object other) =>
+      other is ZeroCopyVecOfPrimitivePack &&
+      frbListEquals(other.int8List, int8List) &&
+      frbListEquals(other.uint8List, uint8List) &&
+      frbListEquals(other.int16List, int16List) &&
+      frbListEquals(other.uint16List, uint16List) &&
+      frbListEquals(other.uint32List, uint32List) &&
+      frbListEquals(other.int32List, int32List) &&
+      frbListEquals(other.uint64List, uint64List) &&
+      frbListEquals(other.int64List, int64List) &&
+      frbListEquals(other.float32List, float32List) &&
+      frbListEquals(other.float64List, float64List);
+
+  @override
+  int get hashCode => Object.hashAll([
+        frbListHash(int8List),
+        frbListHash(uint8List),
+        frbListHash(int16List),
+        frbListHash(uint16List),
+        frbListHash(uint32List),
+        frbListHash(int32List),
+        frbListHash(uint64List),
+        frbListHash(int64List),
+        frbListHash(float32List),
+        frbListHash(float64List)
+      ]);
+
+  @override
+  String toString() =>
+      'ZeroCopyVecOfPrimitivePack(int8List: $int8List, uint8List: $uint8List, int16List: $int16List, uint16List: $uint16List, uint32List: $uint32List, int32List: $int32List, uint64List: $uint64List, int64List: $int64List, float32List: $float32List, float64List: $float64List)';
+}
+
+abstract class CustomError implements Exception {
+  CustomError._();
+}
+
+class CustomError_NotFound extends CustomError {
+  final String name;
+
+  CustomError_NotFound({
+    required this.name,
+  }) : super._();
+}
+
+class CustomError_TooLarge extends CustomError {
+  final int size;
+
+  final int limit;
+
+  CustomError_TooLarge({
+    required this.size,
+    required this.limit,
+  }) : super._();
+}
+
+/// An opaque handle to a Rust `Box<dyn Fn(i32) -> i32 + Send + Sync>`, see [OpaqueHandle]. The Rust object is dropped
+/// once the handle is garbage collected, unless it is disposed or moved to Rust before.
+class Adder extends OpaqueHandle implements ffi.Finalizable {
+  final ffi.NativeFinalizer _finalizer;
+
+  Adder._(int handle, {required void Function(int handle) dispose, required ffi.NativeFinalizer finalizer})
+      : _finalizer = finalizer,
+        super(handle, dispose: dispose) {
+    _finalizer.attach(this, ffi.Pointer.fromAddress(handle), detach: this);
+  }
+
+  @override
+  int move() {
+    _finalizer.detach(this);
+    return super.move();
+  }
 }
 
 // ------------------------- Implementation Details -------------------------
@@ -299,6 +638,20 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
           parseSuccessData: _wire2api_zero_copy_vec_of_primitive_pack,
           hint: hint));
 
+  Future<List<Uint8List>> handleZeroCopyRows({required int rows, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'handle_zero_copy_rows',
+          callFfi: (port) => inner.wire_handle_zero_copy_rows(port, _api2wire_u8(rows)),
+          parseSuccessData: _wire2api_ZeroCopyBuffer_list_uint_8_list,
+          hint: hint));
+
+  Future<List<String>> handleZeroCopyStrings({required List<String> strings, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'handle_zero_copy_strings',
+          callFfi: (port) => inner.wire_handle_zero_copy_strings(port, _api2wire_ZeroCopyBuffer_list_String(strings)),
+          parseSuccessData: _wire2api_ZeroCopyBuffer_list_String,
+          hint: hint));
+
   Future<MySize> handleStruct({required MySize arg, required MySize boxed, dynamic hint}) =>
       executeNormal(FlutterRustBridgeTask(
           debugName: 'handle_struct',
@@ -331,18 +684,37 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
       callFfi: () => inner.wire_handle_sync_return(_api2wire_String(mode)),
       hint: hint));
 
+  String handleSyncReturnString({required String name, dynamic hint}) => utf8.decode(executeSync(
+      FlutterRustBridgeSyncTask(
+          debugName: 'handle_sync_return_string',
+          callFfi: () => inner.wire_handle_sync_return_string(_api2wire_String(name)),
+          hint: hint)));
+
   Stream<String> handleStream({required String arg, dynamic hint}) => executeStream(FlutterRustBridgeTask(
       debugName: 'handle_stream',
       callFfi: (port) => inner.wire_handle_stream(port, _api2wire_String(arg)),
       parseSuccessData: _wire2api_String,
       hint: hint));
 
+  Stream<int> parseNumbers({required String input, dynamic hint}) => executeResultStream(FlutterRustBridgeTask(
+      debugName: 'parse_numbers',
+      callFfi: (port) => inner.wire_parse_numbers(port, _api2wire_String(input)),
+      parseSuccessData: _wire2api_i32,
+      parseStreamErrorData: _wire2api_String,
+      hint: hint));
+
   Future<int> returnErr({dynamic hint}) => executeNormal(FlutterRustBridgeTask(
       debugName: 'return_err',
       callFfi: (port) => inner.wire_return_err(port),
       parseSuccessData: _wire2api_i32,
       hint: hint));
 
+  Future<int> returnErrWithContext({dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'return_err_with_context',
+      callFfi: (port) => inner.wire_return_err_with_context(port),
+      parseSuccessData: _wire2api_i32,
+      hint: hint));
+
   Future<int> returnPanic({dynamic hint}) => executeNormal(FlutterRustBridgeTask(
       debugName: 'return_panic',
       callFfi: (port) => inner.wire_return_panic(port),
@@ -399,15 +771,219 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
           parseSuccessData: _wire2api_String,
           hint: hint));
 
+  Future<BinaryTree> handleBinaryTree({required BinaryTree tree, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_binary_tree',
+      callFfi: (port) => inner.wire_handle_binary_tree(port, _api2wire_box_autoadd_binary_tree(tree)),
+      parseSuccessData: _wire2api_binary_tree,
+      hint: hint));
+
+  Future<int> handlePlatformWidth({required int len, required int offset, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'handle_platform_width',
+          callFfi: (port) => inner.wire_handle_platform_width(port, _api2wire_usize(len), _api2wire_isize(offset)),
+          parseSuccessData: _wire2api_isize,
+          hint: hint));
+
+  Future<Uint8List> handleRgba({required Uint8List rgba, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_rgba',
+      callFfi: (port) => inner.wire_handle_rgba(port, _api2wire_array_u8_4(rgba)),
+      parseSuccessData: _wire2api_array_u8_4,
+      hint: hint));
+
+  Future<Float64List> clampValues({required Float64List data, required FrbRange<double> range, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'clamp_values',
+          callFfi: (port) => inner.wire_clamp_values(port, _api2wire_float_64_list(data), _api2wire_Range_f64(range)),
+          parseSuccessData: _wire2api_float_64_list,
+          hint: hint));
+
+  Future<Uint8List> handleCowBytes({required bool borrowed, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_cow_bytes',
+      callFfi: (port) => inner.wire_handle_cow_bytes(port, _api2wire_bool(borrowed)),
+      parseSuccessData: _wire2api_CowBytes,
+      hint: hint));
+
+  Future<String> handleCowStr({required String text, required bool borrowed, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'handle_cow_str',
+          callFfi: (port) => inner.wire_handle_cow_str(port, _api2wire_CowStr(text), _api2wire_bool(borrowed)),
+          parseSuccessData: _wire2api_CowStr,
+          hint: hint));
+
+  Future<Map<String, int>> wordCounts({required String text, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'word_counts',
+      callFfi: (port) => inner.wire_word_counts(port, _api2wire_String(text)),
+      parseSuccessData: _wire2api_assoc_list_String_u32,
+      hint: hint));
+
+  Future<int> totalWords({required Map<String, int> counts, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'total_words',
+      callFfi: (port) => inner.wire_total_words(port, _api2wire_assoc_list_String_u32(counts)),
+      parseSuccessData: _wire2api_u32,
+      hint: hint));
+
+  Future<String?> describeError({String? error, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'describe_error',
+      callFfi: (port) => inner.wire_describe_error(port, _api2wire_opt_DynError(error)),
+      parseSuccessData: _wire2api_opt_DynError,
+      hint: hint));
+
+  Future<DateTime> handleDateTime({required DateTime at, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_date_time',
+      callFfi: (port) => inner.wire_handle_date_time(port, _api2wire_Chrono_Utc(at)),
+      parseSuccessData: _wire2api_Chrono_NaiveDate,
+      hint: hint));
+
+  Future<UuidValue> newUuid({dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'new_uuid',
+      callFfi: (port) => inner.wire_new_uuid(port),
+      parseSuccessData: _wire2api_Uuid,
+      hint: hint));
+
+  Future<String> handleUuid({required UuidValue id, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_uuid',
+      callFfi: (port) => inner.wire_handle_uuid(port, _api2wire_Uuid(id)),
+      parseSuccessData: _wire2api_String,
+      hint: hint));
+
+  Future<Set<int>> handleSet({required Set<int> nums, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'handle_set',
+      callFfi: (port) => inner.wire_handle_set(port, _api2wire_hash_set_i32(nums)),
+      parseSuccessData: _wire2api_b_tree_set_i32,
+      hint: hint));
+
+  Future<Uint128> nextNonce({required Uint128 nonce, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'next_nonce',
+      callFfi: (port) => inner.wire_next_nonce(port, _api2wire_u128(nonce)),
+      parseSuccessData: _wire2api_u128,
+      hint: hint));
+
+  Future<Adder> makeAdder({required int n, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'make_adder',
+      callFfi: (port) => inner.wire_make_adder(port, _api2wire_i32(n)),
+      parseSuccessData: _wire2api_opaque_adder,
+      hint: hint));
+
+  Future<int> applyAdder({required Adder adder, required int x, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'apply_adder',
+      callFfi: (port) => inner.wire_apply_adder(port, _api2wire_opaque_adder(adder), _api2wire_i32(x)),
+      parseSuccessData: _wire2api_i32,
+      hint: hint));
+
+  Future<int> sumWithProgress({required Int32List nums, required void Function(int, int) onProgress, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'sum_with_progress',
+          callFfi: (port) => inner.wire_sum_with_progress(
+              port, _api2wire_int_32_list(nums), _api2wire_callback_box_dyn_fn_u_32_i_32_send_sync(onProgress)),
+          parseSuccessData: _wire2api_i32,
+          hint: hint));
+
+  Future<int> applyTwice({required int x, int Function(int)? f, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'apply_twice',
+      callFfi: (port) => inner.wire_apply_twice(
+          port, _api2wire_i32(x), _api2wire_callback_option_box_dyn_fn_i_32_anyhow_result_i_32(f)),
+      parseSuccessData: _wire2api_i32,
+      hint: hint));
+
+  Future<String> greetAnything({required Object name, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'greet_anything',
+      callFfi: (port) => inner.wire_greet_anything(port, _api2wire_String(name.toString())),
+      parseSuccessData: _wire2api_String,
+      hint: hint));
+
+  Future<Point2D> translate({required Point2D p, required Vector2D v, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'translate',
+          callFfi: (port) =>
+              inner.wire_translate(port, _api2wire_box_autoadd_point_2_d(p), _api2wire_box_autoadd_vector_2_d(v)),
+          parseSuccessData: _wire2api_point_2_d,
+          hint: hint));
+
+  Future<Vector2D> vectorBetween({required Point2D from, required Point2D to, dynamic hint}) =>
+      executeNormal(FlutterRustBridgeTask(
+          debugName: 'vector_between',
+          callFfi: (port) => inner.wire_vector_between(
+              port, _api2wire_box_autoadd_point_2_d(from), _api2wire_box_autoadd_point_2_d(to)),
+          parseSuccessData: _wire2api_vector_2_d,
+          hint: hint));
+
+  Future<int> lookupSize({required String name, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'lookup_size',
+      callFfi: (port) => inner.wire_lookup_size(port, _api2wire_String(name)),
+      parseSuccessData: _wire2api_u32,
+      parseErrorData: _wire2api_custom_error,
+      hint: hint));
+
+  Future<int> boxValue({required int value, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'box_value',
+      callFfi: (port) => inner.wire_box_value(port, _api2wire_i32(value)),
+      parseSuccessData: _wire2api_NonNull_i32,
+      hint: hint));
+
+  Future<int> unboxValue({required int ptr, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'unbox_value',
+      callFfi: (port) => inner.wire_unbox_value(port, _api2wire_NonNull_i32(ptr)),
+      parseSuccessData: _wire2api_i32,
+      hint: hint));
+
   // Section: api2wire
+  int _api2wire_Chrono_Utc(DateTime raw) {
+    return raw.millisecondsSinceEpoch;
+  }
+
+  ffi.Pointer<wire_uint_8_list> _api2wire_CowStr(String raw) {
+    return _api2wire_uint_8_list(utf8.encoder.convert(raw));
+  }
+
+  ffi.Pointer<wire_uint_8_list> _api2wire_DynError(String raw) {
+    return _api2wire_uint_8_list(utf8.encoder.convert(raw));
+  }
+
+  int _api2wire_NonNull_i32(int raw) {
+    return raw;
+  }
+
+  ffi.Pointer<wire_float_64_list> _api2wire_Range_f64(FrbRange<double> raw) {
+    return _api2wire_float_64_list(Float64List.fromList([raw.start, raw.end]));
+  }
+
   ffi.Pointer<wire_uint_8_list> _api2wire_String(String raw) {
     return _api2wire_uint_8_list(utf8.encoder.convert(raw));
   }
 
+  ffi.Pointer<wire_uint_8_list> _api2wire_Uuid(UuidValue raw) {
+    return _api2wire_array_u8_16(raw.toBytes());
+  }
+
   ffi.Pointer<wire_uint_8_list> _api2wire_ZeroCopyBuffer_Uint8List(Uint8List raw) {
     return _api2wire_uint_8_list(raw);
   }
 
+  ffi.Pointer<wire_list_String> _api2wire_ZeroCopyBuffer_list_String(List<String> raw) {
+    return _api2wire_list_String(raw);
+  }
+
+  ffi.Pointer<wire_uint_8_list> _api2wire_array_u8_16(Uint8List raw) {
+    if (raw.length != 16) throw ArgumentError('unexpected arr length: expect 16 but see ${raw.length}');
+    return _api2wire_uint_8_list(raw);
+  }
+
+  ffi.Pointer<wire_uint_8_list> _api2wire_array_u8_4(Uint8List raw) {
+    if (raw.length != 4) throw ArgumentError('unexpected arr length: expect 4 but see ${raw.length}');
+    return _api2wire_uint_8_list(raw);
+  }
+
+  ffi.Pointer<wire_assoc_list_String_u32> _api2wire_assoc_list_String_u32(Map<String, int> raw) {
+    final ans = inner.new_assoc_list_String_u32(raw.length);
+    var i = 0;
+    for (final entry in raw.entries) {
+      ans.ref.keys[i] = _api2wire_String(entry.key);
+      ans.ref.values[i] = _api2wire_u32(entry.value);
+      ++i;
+    }
+    return ans;
+  }
+
   bool _api2wire_bool(bool raw) {
     return raw;
   }
@@ -418,6 +994,12 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return ptr;
   }
 
+  ffi.Pointer<wire_BinaryTree> _api2wire_box_autoadd_binary_tree(BinaryTree raw) {
+    final ptr = inner.new_box_autoadd_binary_tree();
+    _api_fill_to_wire_binary_tree(raw, ptr.ref);
+    return ptr;
+  }
+
   ffi.Pointer<ffi.Uint8> _api2wire_box_autoadd_bool(bool raw) {
     return inner.new_box_autoadd_bool(raw);
   }
@@ -458,6 +1040,24 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return ptr;
   }
 
+  ffi.Pointer<wire_Point2D> _api2wire_box_autoadd_point_2_d(Point2D raw) {
+    final ptr = inner.new_box_autoadd_point_2_d();
+    _api_fill_to_wire_point_2_d(raw, ptr.ref);
+    return ptr;
+  }
+
+  ffi.Pointer<wire_Vector2D> _api2wire_box_autoadd_vector_2_d(Vector2D raw) {
+    final ptr = inner.new_box_autoadd_vector_2_d();
+    _api_fill_to_wire_vector_2_d(raw, ptr.ref);
+    return ptr;
+  }
+
+  ffi.Pointer<wire_BinaryTree> _api2wire_box_binary_tree(BinaryTree raw) {
+    final ptr = inner.new_box_binary_tree();
+    _api_fill_to_wire_binary_tree(raw, ptr.ref);
+    return ptr;
+  }
+
   ffi.Pointer<ffi.Uint8> _api2wire_box_bool(bool raw) {
     return inner.new_box_bool(raw);
   }
@@ -494,6 +1094,16 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return inner.new_box_u8(raw);
   }
 
+  int _api2wire_callback_box_dyn_fn_u_32_i_32_send_sync(void Function(int, int) raw) {
+    return frbListenCallback((args) => raw(_wire2api_u32(args[0]), _wire2api_i32(args[1])));
+  }
+
+  int _api2wire_callback_option_box_dyn_fn_i_32_anyhow_result_i_32(int Function(int)? raw) {
+    if (raw == null) return 0;
+    return frbListenCallback((args) => raw(_wire2api_i32(args[0])),
+        reply: inner.wire_reply_callback_i32, reject: inner.wire_reject_callback);
+  }
+
   double _api2wire_f32(double raw) {
     return raw;
   }
@@ -514,6 +1124,10 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return ans;
   }
 
+  ffi.Pointer<wire_int_32_list> _api2wire_hash_set_i32(Set<int> raw) {
+    return _api2wire_int_32_list(Int32List.fromList(raw.toList()));
+  }
+
   int _api2wire_i32(int raw) {
     return raw;
   }
@@ -544,6 +1158,18 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return ans;
   }
 
+  int _api2wire_isize(int raw) {
+    return raw;
+  }
+
+  ffi.Pointer<wire_list_String> _api2wire_list_String(List<String> raw) {
+    final ans = inner.new_list_String(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      ans.ref.ptr[i] = _api2wire_String(raw[i]);
+    }
+    return ans;
+  }
+
   ffi.Pointer<wire_list_attribute> _api2wire_list_attribute(List<Attribute> raw) {
     final ans = inner.new_list_attribute(raw.length);
     for (var i = 0; i < raw.length; ++i) {
@@ -571,11 +1197,19 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
   ffi.Pointer<wire_list_opt_box_autoadd_attribute> _api2wire_list_opt_box_autoadd_attribute(List<Attribute?> raw) {
     final ans = inner.new_list_opt_box_autoadd_attribute(raw.length);
     for (var i = 0; i < raw.length; ++i) {
-      _api_fill_to_wire_opt_box_autoadd_attribute(raw[i], ans.ref.ptr[i]);
+      ans.ref.ptr[i] = _api2wire_opt_box_autoadd_attribute(raw[i]);
     }
     return ans;
   }
 
+  int _api2wire_opaque_adder(Adder raw) {
+    return raw.move();
+  }
+
+  ffi.Pointer<wire_uint_8_list> _api2wire_opt_DynError(String? raw) {
+    return raw == null ? ffi.nullptr : _api2wire_DynError(raw);
+  }
+
   ffi.Pointer<wire_uint_8_list> _api2wire_opt_String(String? raw) {
     return raw == null ? ffi.nullptr : _api2wire_String(raw);
   }
@@ -612,6 +1246,10 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return raw == null ? ffi.nullptr : _api2wire_box_autoadd_new_type_int(raw);
   }
 
+  ffi.Pointer<wire_BinaryTree> _api2wire_opt_box_binary_tree(BinaryTree? raw) {
+    return raw == null ? ffi.nullptr : _api2wire_box_binary_tree(raw);
+  }
+
   ffi.Pointer<ffi.Uint8> _api2wire_opt_box_bool(bool? raw) {
     return raw == null ? ffi.nullptr : _api2wire_box_bool(raw);
   }
@@ -672,6 +1310,14 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return raw == null ? ffi.nullptr : _api2wire_uint_8_list(raw);
   }
 
+  ffi.Pointer<wire_int_64_list> _api2wire_u128(Uint128 raw) {
+    return _api2wire_int_64_list(Int64List.fromList([raw.high, raw.low]));
+  }
+
+  int _api2wire_u32(int raw) {
+    return raw;
+  }
+
   int _api2wire_u8(int raw) {
     return raw;
   }
@@ -682,6 +1328,10 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     return ans;
   }
 
+  int _api2wire_usize(int raw) {
+    return raw;
+  }
+
   // Section: api_fill_to_wire
 
   void _api_fill_to_wire_attribute(Attribute apiObj, wire_Attribute wireObj) {
@@ -689,10 +1339,20 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     wireObj.value = _api2wire_String(apiObj.value);
   }
 
+  void _api_fill_to_wire_binary_tree(BinaryTree apiObj, wire_BinaryTree wireObj) {
+    wireObj.value = _api2wire_i32(apiObj.value);
+    wireObj.left = _api2wire_opt_box_binary_tree(apiObj.left);
+    wireObj.right = _api2wire_opt_box_binary_tree(apiObj.right);
+  }
+
   void _api_fill_to_wire_box_autoadd_attribute(Attribute apiObj, ffi.Pointer<wire_Attribute> wireObj) {
     _api_fill_to_wire_attribute(apiObj, wireObj.ref);
   }
 
+  void _api_fill_to_wire_box_autoadd_binary_tree(BinaryTree apiObj, ffi.Pointer<wire_BinaryTree> wireObj) {
+    _api_fill_to_wire_binary_tree(apiObj, wireObj.ref);
+  }
+
   void _api_fill_to_wire_box_autoadd_exotic_optionals(
       ExoticOptionals apiObj, ffi.Pointer<wire_ExoticOptionals> wireObj) {
     _api_fill_to_wire_exotic_optionals(apiObj, wireObj.ref);
@@ -710,6 +1370,18 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     _api_fill_to_wire_new_type_int(apiObj, wireObj.ref);
   }
 
+  void _api_fill_to_wire_box_autoadd_point_2_d(Point2D apiObj, ffi.Pointer<wire_Point2D> wireObj) {
+    _api_fill_to_wire_point_2_d(apiObj, wireObj.ref);
+  }
+
+  void _api_fill_to_wire_box_autoadd_vector_2_d(Vector2D apiObj, ffi.Pointer<wire_Vector2D> wireObj) {
+    _api_fill_to_wire_vector_2_d(apiObj, wireObj.ref);
+  }
+
+  void _api_fill_to_wire_box_binary_tree(BinaryTree apiObj, ffi.Pointer<wire_BinaryTree> wireObj) {
+    _api_fill_to_wire_binary_tree(apiObj, wireObj.ref);
+  }
+
   void _api_fill_to_wire_box_exotic_optionals(ExoticOptionals apiObj, ffi.Pointer<wire_ExoticOptionals> wireObj) {
     _api_fill_to_wire_exotic_optionals(apiObj, wireObj.ref);
   }
@@ -764,20 +1436,70 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     if (apiObj != null) _api_fill_to_wire_box_autoadd_new_type_int(apiObj, wireObj);
   }
 
+  void _api_fill_to_wire_opt_box_binary_tree(BinaryTree? apiObj, ffi.Pointer<wire_BinaryTree> wireObj) {
+    if (apiObj != null) _api_fill_to_wire_box_binary_tree(apiObj, wireObj);
+  }
+
   void _api_fill_to_wire_opt_box_exotic_optionals(ExoticOptionals? apiObj, ffi.Pointer<wire_ExoticOptionals> wireObj) {
     if (apiObj != null) _api_fill_to_wire_box_exotic_optionals(apiObj, wireObj);
   }
+
+  void _api_fill_to_wire_point_2_d(Point2D apiObj, wire_Point2D wireObj) {
+    wireObj.x = _api2wire_f64(apiObj.x);
+    wireObj.y = _api2wire_f64(apiObj.y);
+  }
+
+  void _api_fill_to_wire_vector_2_d(Vector2D apiObj, wire_Vector2D wireObj) {
+    wireObj.x = _api2wire_f64(apiObj.x);
+    wireObj.y = _api2wire_f64(apiObj.y);
+  }
+
+  // Section: weak references
+  late final _finalizer_opaque_adder =
+      ffi.NativeFinalizer(inner._lookup<ffi.NativeFinalizerFunction>('frb_dispose_opaque_adder'));
+
+  Adder _wire2api_opaque_adder(dynamic raw) {
+    return Adder._(raw as int, dispose: inner.drop_opaque_adder, finalizer: _finalizer_opaque_adder);
+  }
 }
 
 // Section: wire2api
+DateTime _wire2api_Chrono_NaiveDate(dynamic raw) {
+  return DateTime.fromMillisecondsSinceEpoch(raw as int, isUtc: true);
+}
+
+Uint8List _wire2api_CowBytes(dynamic raw) {
+  return raw as Uint8List;
+}
+
+String _wire2api_CowStr(dynamic raw) {
+  return raw as String;
+}
+
+String _wire2api_DynError(dynamic raw) {
+  return raw as String;
+}
+
+int _wire2api_NonNull_i32(dynamic raw) {
+  return raw as int;
+}
+
 String _wire2api_String(dynamic raw) {
   return raw as String;
 }
 
+String _wire2api_SyncReturnString(dynamic raw) {
+  return raw as String;
+}
+
 Uint8List _wire2api_SyncReturnVecU8(dynamic raw) {
   return raw as Uint8List;
 }
 
+UuidValue _wire2api_Uuid(dynamic raw) {
+  return UuidValue.fromByteList(_wire2api_array_u8_16(raw));
+}
+
 Float32List _wire2api_ZeroCopyBuffer_Float32List(dynamic raw) {
   return raw as Float32List;
 }
@@ -818,6 +1540,37 @@ Uint8List _wire2api_ZeroCopyBuffer_Uint8List(dynamic raw) {
   return raw as Uint8List;
 }
 
+List<String> _wire2api_ZeroCopyBuffer_list_String(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  return frbSplitZeroCopyFrames(arr[0] as Uint64List, arr[1] as Uint8List, Uint8List.sublistView)
+      .map(utf8.decode)
+      .toList();
+}
+
+List<Uint8List> _wire2api_ZeroCopyBuffer_list_uint_8_list(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  return frbSplitZeroCopyFrames(arr[0] as Uint64List, arr[1] as Uint8List, Uint8List.sublistView);
+}
+
+Uint8List _wire2api_array_u8_16(dynamic raw) {
+  final arr = _wire2api_uint_8_list(raw);
+  if (arr.length != 16) throw Exception('unexpected arr length: expect 16 but see ${arr.length}');
+  return arr;
+}
+
+Uint8List _wire2api_array_u8_4(dynamic raw) {
+  final arr = _wire2api_uint_8_list(raw);
+  if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+  return arr;
+}
+
+Map<String, int> _wire2api_assoc_list_String_u32(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  return Map.fromEntries([
+    for (var i = 0; i < arr.length; i += 2) MapEntry(_wire2api_String(arr[i]), _wire2api_u32(arr[i + 1])),
+  ]);
+}
+
 Attribute _wire2api_attribute(dynamic raw) {
   final arr = raw as List<dynamic>;
   if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
@@ -827,6 +1580,20 @@ Attribute _wire2api_attribute(dynamic raw) {
   );
 }
 
+Set<int> _wire2api_b_tree_set_i32(dynamic raw) {
+  return _wire2api_int_32_list(raw).toSet();
+}
+
+BinaryTree _wire2api_binary_tree(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+  return BinaryTree(
+    value: _wire2api_i32(arr[0]),
+    left: _wire2api_opt_box_binary_tree(arr[1]),
+    right: _wire2api_opt_box_binary_tree(arr[2]),
+  );
+}
+
 bool _wire2api_bool(dynamic raw) {
   return raw as bool;
 }
@@ -863,6 +1630,27 @@ NewTypeInt _wire2api_box_autoadd_new_type_int(dynamic raw) {
   return _wire2api_new_type_int(raw);
 }
 
+BinaryTree _wire2api_box_binary_tree(dynamic raw) {
+  return _wire2api_binary_tree(raw);
+}
+
+CustomError _wire2api_custom_error(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  switch (arr[0]) {
+    case 0:
+      return CustomError_NotFound(
+        name: _wire2api_String(arr[1]),
+      );
+    case 1:
+      return CustomError_TooLarge(
+        size: _wire2api_u32(arr[1]),
+        limit: _wire2api_u32(arr[2]),
+      );
+    default:
+      throw Exception('unexpected variant of CustomError: ${arr[0]}');
+  }
+}
+
 Element _wire2api_element(dynamic raw) {
   final arr = raw as List<dynamic>;
   if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
@@ -944,6 +1732,14 @@ Int8List _wire2api_int_8_list(dynamic raw) {
   return raw as Int8List;
 }
 
+int _wire2api_isize(dynamic raw) {
+  return raw as int;
+}
+
+List<String> _wire2api_list_String(dynamic raw) {
+  return (raw as List<dynamic>).map(_wire2api_String).toList();
+}
+
 List<Attribute> _wire2api_list_attribute(dynamic raw) {
   return (raw as List<dynamic>).map(_wire2api_attribute).toList();
 }
@@ -964,6 +1760,10 @@ List<Attribute?> _wire2api_list_opt_box_autoadd_attribute(dynamic raw) {
   return (raw as List<dynamic>).map(_wire2api_opt_box_autoadd_attribute).toList();
 }
 
+List<Uint8List> _wire2api_list_uint_8_list(dynamic raw) {
+  return (raw as List<dynamic>).map(_wire2api_uint_8_list).toList();
+}
+
 MySize _wire2api_my_size(dynamic raw) {
   final arr = raw as List<dynamic>;
   if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
@@ -991,6 +1791,10 @@ NewTypeInt _wire2api_new_type_int(dynamic raw) {
   );
 }
 
+String? _wire2api_opt_DynError(dynamic raw) {
+  return raw == null ? null : _wire2api_DynError(raw);
+}
+
 String? _wire2api_opt_String(dynamic raw) {
   return raw == null ? null : _wire2api_String(raw);
 }
@@ -1031,6 +1835,10 @@ NewTypeInt? _wire2api_opt_box_autoadd_new_type_int(dynamic raw) {
   return raw == null ? null : _wire2api_box_autoadd_new_type_int(raw);
 }
 
+BinaryTree? _wire2api_opt_box_binary_tree(dynamic raw) {
+  return raw == null ? null : _wire2api_box_binary_tree(raw);
+}
+
 Float32List? _wire2api_opt_float_32_list(dynamic raw) {
   return raw == null ? null : _wire2api_float_32_list(raw);
 }
@@ -1067,6 +1875,20 @@ Uint8List? _wire2api_opt_uint_8_list(dynamic raw) {
   return raw == null ? null : _wire2api_uint_8_list(raw);
 }
 
+Point2D _wire2api_point_2_d(dynamic raw) {
+  final arr = raw as List<dynamic>;
+  if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+  return Point2D(
+    x: _wire2api_f64(arr[0]),
+    y: _wire2api_f64(arr[1]),
+  );
+}
+
+Uint128 _wire2api_u128(dynamic raw) {
+  final arr = raw as Int64List;
+  return Uint128(arr[0], arr[1]);
+}
+
 int _wire2api_u16(dynamic raw) {
   return raw as int;
 }
@@ -1116,6 +1938,10 @@ VecOfPrimitivePack _wire2api_vec_of_primitive_pack(dynamic raw) {
   );
 }
 
+Vector2D _wire2api_vector_2_d(dynamic raw) {
+  return _wire2api_point_2_d(raw);
+}
+
 ZeroCopyVecOfPrimitivePack _wire2api_zero_copy_vec_of_primitive_pack(dynamic raw) {
   final arr = raw as List<dynamic>;
   if (arr.length != 10) throw Exception('unexpected arr length: expect 10 but see ${arr.length}');
@@ -1247,6 +2073,36 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_handle_zero_copy_vec_of_primitive =
       _wire_handle_zero_copy_vec_of_primitivePtr.asFunction<void Function(int, int)>();
 
+  void wire_handle_zero_copy_rows(
+    int port,
+    int rows,
+  ) {
+    return _wire_handle_zero_copy_rows(
+      port,
+      rows,
+    );
+  }
+
+  late final _wire_handle_zero_copy_rowsPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Uint8)>>('wire_handle_zero_copy_rows');
+  late final _wire_handle_zero_copy_rows = _wire_handle_zero_copy_rowsPtr.asFunction<void Function(int, int)>();
+
+  void wire_handle_zero_copy_strings(
+    int port,
+    ffi.Pointer<wire_list_String> strings,
+  ) {
+    return _wire_handle_zero_copy_strings(
+      port,
+      strings,
+    );
+  }
+
+  late final _wire_handle_zero_copy_stringsPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_list_String>)>>(
+          'wire_handle_zero_copy_strings');
+  late final _wire_handle_zero_copy_strings =
+      _wire_handle_zero_copy_stringsPtr.asFunction<void Function(int, ffi.Pointer<wire_list_String>)>();
+
   void wire_handle_struct(
     int port,
     ffi.Pointer<wire_MySize> arg,
@@ -1326,6 +2182,20 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_handle_sync_return =
       _wire_handle_sync_returnPtr.asFunction<WireSyncReturnStruct Function(ffi.Pointer<wire_uint_8_list>)>();
 
+  WireSyncReturnStruct wire_handle_sync_return_string(
+    ffi.Pointer<wire_uint_8_list> name,
+  ) {
+    return _wire_handle_sync_return_string(
+      name,
+    );
+  }
+
+  late final _wire_handle_sync_return_stringPtr =
+      _lookup<ffi.NativeFunction<WireSyncReturnStruct Function(ffi.Pointer<wire_uint_8_list>)>>(
+          'wire_handle_sync_return_string');
+  late final _wire_handle_sync_return_string =
+      _wire_handle_sync_return_stringPtr.asFunction<WireSyncReturnStruct Function(ffi.Pointer<wire_uint_8_list>)>();
+
   void wire_handle_stream(
     int port,
     ffi.Pointer<wire_uint_8_list> arg,
@@ -1341,6 +2211,21 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_handle_stream =
       _wire_handle_streamPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
 
+  void wire_parse_numbers(
+    int port,
+    ffi.Pointer<wire_uint_8_list> input,
+  ) {
+    return _wire_parse_numbers(
+      port,
+      input,
+    );
+  }
+
+  late final _wire_parse_numbersPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_parse_numbers');
+  late final _wire_parse_numbers =
+      _wire_parse_numbersPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
   void wire_return_err(
     int port,
   ) {
@@ -1352,6 +2237,18 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_return_errPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('wire_return_err');
   late final _wire_return_err = _wire_return_errPtr.asFunction<void Function(int)>();
 
+  void wire_return_err_with_context(
+    int port,
+  ) {
+    return _wire_return_err_with_context(
+      port,
+    );
+  }
+
+  late final _wire_return_err_with_contextPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('wire_return_err_with_context');
+  late final _wire_return_err_with_context = _wire_return_err_with_contextPtr.asFunction<void Function(int)>();
+
   void wire_return_panic(
     int port,
   ) {
@@ -1465,6 +2362,436 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
       void Function(int, ffi.Pointer<ffi.Int8>, ffi.Pointer<ffi.Uint8>, ffi.Pointer<ffi.Int32>, ffi.Pointer<ffi.Int64>,
           ffi.Pointer<ffi.Double>, ffi.Pointer<ffi.Uint8>, ffi.Pointer<wire_ExoticOptionals>)>();
 
+  void wire_handle_binary_tree(
+    int port,
+    ffi.Pointer<wire_BinaryTree> tree,
+  ) {
+    return _wire_handle_binary_tree(
+      port,
+      tree,
+    );
+  }
+
+  late final _wire_handle_binary_treePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_BinaryTree>)>>(
+          'wire_handle_binary_tree');
+  late final _wire_handle_binary_tree =
+      _wire_handle_binary_treePtr.asFunction<void Function(int, ffi.Pointer<wire_BinaryTree>)>();
+
+  void wire_handle_platform_width(
+    int port,
+    int len,
+    int offset,
+  ) {
+    return _wire_handle_platform_width(
+      port,
+      len,
+      offset,
+    );
+  }
+
+  late final _wire_handle_platform_widthPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Uint64, ffi.Int64)>>('wire_handle_platform_width');
+  late final _wire_handle_platform_width = _wire_handle_platform_widthPtr.asFunction<void Function(int, int, int)>();
+
+  void wire_handle_rgba(
+    int port,
+    ffi.Pointer<wire_uint_8_list> rgba,
+  ) {
+    return _wire_handle_rgba(
+      port,
+      rgba,
+    );
+  }
+
+  late final _wire_handle_rgbaPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_handle_rgba');
+  late final _wire_handle_rgba = _wire_handle_rgbaPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_clamp_values(
+    int port,
+    ffi.Pointer<wire_float_64_list> data,
+    ffi.Pointer<wire_float_64_list> range,
+  ) {
+    return _wire_clamp_values(
+      port,
+      data,
+      range,
+    );
+  }
+
+  late final _wire_clamp_valuesPtr = _lookup<
+      ffi.NativeFunction<
+          ffi.Void Function(ffi.Int64, ffi.Pointer<wire_float_64_list>,
+              ffi.Pointer<wire_float_64_list>)>>('wire_clamp_values');
+  late final _wire_clamp_values = _wire_clamp_valuesPtr.asFunction<
+      void Function(int, ffi.Pointer<wire_float_64_list>, ffi.Pointer<wire_float_64_list>)>();
+
+  void wire_handle_cow_bytes(
+    int port,
+    bool borrowed,
+  ) {
+    return _wire_handle_cow_bytes(
+      port,
+      borrowed ? 1 : 0,
+    );
+  }
+
+  late final _wire_handle_cow_bytesPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Uint8)>>('wire_handle_cow_bytes');
+  late final _wire_handle_cow_bytes = _wire_handle_cow_bytesPtr.asFunction<void Function(int, int)>();
+
+  void wire_handle_cow_str(
+    int port,
+    ffi.Pointer<wire_uint_8_list> text,
+    bool borrowed,
+  ) {
+    return _wire_handle_cow_str(
+      port,
+      text,
+      borrowed ? 1 : 0,
+    );
+  }
+
+  late final _wire_handle_cow_strPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>, ffi.Uint8)>>(
+          'wire_handle_cow_str');
+  late final _wire_handle_cow_str =
+      _wire_handle_cow_strPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>, int)>();
+
+  void wire_word_counts(
+    int port,
+    ffi.Pointer<wire_uint_8_list> text,
+  ) {
+    return _wire_word_counts(
+      port,
+      text,
+    );
+  }
+
+  late final _wire_word_countsPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_word_counts');
+  late final _wire_word_counts = _wire_word_countsPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_total_words(
+    int port,
+    ffi.Pointer<wire_assoc_list_String_u32> counts,
+  ) {
+    return _wire_total_words(
+      port,
+      counts,
+    );
+  }
+
+  late final _wire_total_wordsPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_assoc_list_String_u32>)>>(
+          'wire_total_words');
+  late final _wire_total_words =
+      _wire_total_wordsPtr.asFunction<void Function(int, ffi.Pointer<wire_assoc_list_String_u32>)>();
+
+  void wire_describe_error(
+    int port,
+    ffi.Pointer<wire_uint_8_list> error,
+  ) {
+    return _wire_describe_error(
+      port,
+      error,
+    );
+  }
+
+  late final _wire_describe_errorPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_describe_error');
+  late final _wire_describe_error =
+      _wire_describe_errorPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_handle_date_time(
+    int port,
+    int at,
+  ) {
+    return _wire_handle_date_time(
+      port,
+      at,
+    );
+  }
+
+  late final _wire_handle_date_timePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int64)>>('wire_handle_date_time');
+  late final _wire_handle_date_time = _wire_handle_date_timePtr.asFunction<void Function(int, int)>();
+
+  void wire_new_uuid(
+    int port,
+  ) {
+    return _wire_new_uuid(
+      port,
+    );
+  }
+
+  late final _wire_new_uuidPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('wire_new_uuid');
+  late final _wire_new_uuid = _wire_new_uuidPtr.asFunction<void Function(int)>();
+
+  void wire_handle_uuid(
+    int port,
+    ffi.Pointer<wire_uint_8_list> id,
+  ) {
+    return _wire_handle_uuid(
+      port,
+      id,
+    );
+  }
+
+  late final _wire_handle_uuidPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_handle_uuid');
+  late final _wire_handle_uuid = _wire_handle_uuidPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_handle_set(
+    int port,
+    ffi.Pointer<wire_int_32_list> nums,
+  ) {
+    return _wire_handle_set(
+      port,
+      nums,
+    );
+  }
+
+  late final _wire_handle_setPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_int_32_list>)>>('wire_handle_set');
+  late final _wire_handle_set = _wire_handle_setPtr.asFunction<void Function(int, ffi.Pointer<wire_int_32_list>)>();
+
+  void wire_next_nonce(
+    int port,
+    ffi.Pointer<wire_int_64_list> nonce,
+  ) {
+    return _wire_next_nonce(
+      port,
+      nonce,
+    );
+  }
+
+  late final _wire_next_noncePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_int_64_list>)>>('wire_next_nonce');
+  late final _wire_next_nonce = _wire_next_noncePtr.asFunction<void Function(int, ffi.Pointer<wire_int_64_list>)>();
+
+  void wire_make_adder(
+    int port,
+    int n,
+  ) {
+    return _wire_make_adder(
+      port,
+      n,
+    );
+  }
+
+  late final _wire_make_adderPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32)>>('wire_make_adder');
+  late final _wire_make_adder = _wire_make_adderPtr.asFunction<void Function(int, int)>();
+
+  void wire_apply_adder(
+    int port,
+    int adder,
+    int x,
+  ) {
+    return _wire_apply_adder(
+      port,
+      adder,
+      x,
+    );
+  }
+
+  late final _wire_apply_adderPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int64, ffi.Int32)>>('wire_apply_adder');
+  late final _wire_apply_adder = _wire_apply_adderPtr.asFunction<void Function(int, int, int)>();
+
+  void wire_sum_with_progress(
+    int port,
+    ffi.Pointer<wire_int_32_list> nums,
+    int on_progress,
+  ) {
+    return _wire_sum_with_progress(
+      port,
+      nums,
+      on_progress,
+    );
+  }
+
+  late final _wire_sum_with_progressPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_int_32_list>, ffi.Int64)>>(
+          'wire_sum_with_progress');
+  late final _wire_sum_with_progress =
+      _wire_sum_with_progressPtr.asFunction<void Function(int, ffi.Pointer<wire_int_32_list>, int)>();
+
+  void wire_apply_twice(
+    int port,
+    int x,
+    int f,
+  ) {
+    return _wire_apply_twice(
+      port,
+      x,
+      f,
+    );
+  }
+
+  late final _wire_apply_twicePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32, ffi.Int64)>>('wire_apply_twice');
+  late final _wire_apply_twice = _wire_apply_twicePtr.asFunction<void Function(int, int, int)>();
+
+  void wire_greet_anything(
+    int port,
+    ffi.Pointer<wire_uint_8_list> name,
+  ) {
+    return _wire_greet_anything(
+      port,
+      name,
+    );
+  }
+
+  late final _wire_greet_anythingPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_greet_anything');
+  late final _wire_greet_anything =
+      _wire_greet_anythingPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_translate(
+    int port,
+    ffi.Pointer<wire_Point2D> p,
+    ffi.Pointer<wire_Vector2D> v,
+  ) {
+    return _wire_translate(
+      port,
+      p,
+      v,
+    );
+  }
+
+  late final _wire_translatePtr = _lookup<
+          ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_Point2D>, ffi.Pointer<wire_Vector2D>)>>(
+      'wire_translate');
+  late final _wire_translate =
+      _wire_translatePtr.asFunction<void Function(int, ffi.Pointer<wire_Point2D>, ffi.Pointer<wire_Vector2D>)>();
+
+  void wire_vector_between(
+    int port,
+    ffi.Pointer<wire_Point2D> from,
+    ffi.Pointer<wire_Point2D> to,
+  ) {
+    return _wire_vector_between(
+      port,
+      from,
+      to,
+    );
+  }
+
+  late final _wire_vector_betweenPtr = _lookup<
+          ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_Point2D>, ffi.Pointer<wire_Point2D>)>>(
+      'wire_vector_between');
+  late final _wire_vector_between =
+      _wire_vector_betweenPtr.asFunction<void Function(int, ffi.Pointer<wire_Point2D>, ffi.Pointer<wire_Point2D>)>();
+
+  void wire_lookup_size(
+    int port,
+    ffi.Pointer<wire_uint_8_list> name,
+  ) {
+    return _wire_lookup_size(
+      port,
+      name,
+    );
+  }
+
+  late final _wire_lookup_sizePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>('wire_lookup_size');
+  late final _wire_lookup_size = _wire_lookup_sizePtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_box_value(
+    int port,
+    int value,
+  ) {
+    return _wire_box_value(
+      port,
+      value,
+    );
+  }
+
+  late final _wire_box_valuePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32)>>('wire_box_value');
+  late final _wire_box_value = _wire_box_valuePtr.asFunction<void Function(int, int)>();
+
+  void wire_unbox_value(
+    int port,
+    int ptr,
+  ) {
+    return _wire_unbox_value(
+      port,
+      ptr,
+    );
+  }
+
+  late final _wire_unbox_valuePtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int64)>>('wire_unbox_value');
+  late final _wire_unbox_value = _wire_unbox_valuePtr.asFunction<void Function(int, int)>();
+
+  void drop_opaque_adder(
+    int handle,
+  ) {
+    return _drop_opaque_adder(
+      handle,
+    );
+  }
+
+  late final _drop_opaque_adderPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('drop_opaque_adder');
+  late final _drop_opaque_adder = _drop_opaque_adderPtr.asFunction<void Function(int)>();
+
+  void frb_dispose_opaque_adder(
+    ffi.Pointer<ffi.Void> handle,
+  ) {
+    return _frb_dispose_opaque_adder(
+      handle,
+    );
+  }
+
+  late final _frb_dispose_opaque_adderPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>('frb_dispose_opaque_adder');
+  late final _frb_dispose_opaque_adder =
+      _frb_dispose_opaque_adderPtr.asFunction<void Function(ffi.Pointer<ffi.Void>)>();
+
+  void wire_reply_callback_i32(
+    int call_id,
+    int result,
+  ) {
+    return _wire_reply_callback_i32(
+      call_id,
+      result,
+    );
+  }
+
+  late final _wire_reply_callback_i32Ptr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32)>>('wire_reply_callback_i32');
+  late final _wire_reply_callback_i32 = _wire_reply_callback_i32Ptr.asFunction<void Function(int, int)>();
+
+  void wire_reject_callback(
+    int call_id,
+  ) {
+    return _wire_reject_callback(
+      call_id,
+    );
+  }
+
+  late final _wire_reject_callbackPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('wire_reject_callback');
+  late final _wire_reject_callback = _wire_reject_callbackPtr.asFunction<void Function(int)>();
+
+  ffi.Pointer<wire_assoc_list_String_u32> new_assoc_list_String_u32(
+    int len,
+  ) {
+    return _new_assoc_list_String_u32(
+      len,
+    );
+  }
+
+  late final _new_assoc_list_String_u32Ptr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_assoc_list_String_u32> Function(ffi.Int32)>>(
+          'new_assoc_list_String_u32');
+  late final _new_assoc_list_String_u32 =
+      _new_assoc_list_String_u32Ptr.asFunction<ffi.Pointer<wire_assoc_list_String_u32> Function(int)>();
+
   ffi.Pointer<wire_Attribute> new_box_autoadd_attribute() {
     return _new_box_autoadd_attribute();
   }
@@ -1474,6 +2801,15 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _new_box_autoadd_attribute =
       _new_box_autoadd_attributePtr.asFunction<ffi.Pointer<wire_Attribute> Function()>();
 
+  ffi.Pointer<wire_BinaryTree> new_box_autoadd_binary_tree() {
+    return _new_box_autoadd_binary_tree();
+  }
+
+  late final _new_box_autoadd_binary_treePtr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_BinaryTree> Function()>>('new_box_autoadd_binary_tree');
+  late final _new_box_autoadd_binary_tree =
+      _new_box_autoadd_binary_treePtr.asFunction<ffi.Pointer<wire_BinaryTree> Function()>();
+
   ffi.Pointer<ffi.Uint8> new_box_autoadd_bool(
     bool value,
   ) {
@@ -1557,6 +2893,32 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _new_box_autoadd_new_type_int =
       _new_box_autoadd_new_type_intPtr.asFunction<ffi.Pointer<wire_NewTypeInt> Function()>();
 
+  ffi.Pointer<wire_Point2D> new_box_autoadd_point_2_d() {
+    return _new_box_autoadd_point_2_d();
+  }
+
+  late final _new_box_autoadd_point_2_dPtr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_Point2D> Function()>>('new_box_autoadd_point_2_d');
+  late final _new_box_autoadd_point_2_d =
+      _new_box_autoadd_point_2_dPtr.asFunction<ffi.Pointer<wire_Point2D> Function()>();
+
+  ffi.Pointer<wire_Vector2D> new_box_autoadd_vector_2_d() {
+    return _new_box_autoadd_vector_2_d();
+  }
+
+  late final _new_box_autoadd_vector_2_dPtr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_Vector2D> Function()>>('new_box_autoadd_vector_2_d');
+  late final _new_box_autoadd_vector_2_d =
+      _new_box_autoadd_vector_2_dPtr.asFunction<ffi.Pointer<wire_Vector2D> Function()>();
+
+  ffi.Pointer<wire_BinaryTree> new_box_binary_tree() {
+    return _new_box_binary_tree();
+  }
+
+  late final _new_box_binary_treePtr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_BinaryTree> Function()>>('new_box_binary_tree');
+  late final _new_box_binary_tree = _new_box_binary_treePtr.asFunction<ffi.Pointer<wire_BinaryTree> Function()>();
+
   ffi.Pointer<ffi.Uint8> new_box_bool(
     bool value,
   ) {
@@ -1699,6 +3061,18 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
       _lookup<ffi.NativeFunction<ffi.Pointer<wire_int_8_list> Function(ffi.Int32)>>('new_int_8_list');
   late final _new_int_8_list = _new_int_8_listPtr.asFunction<ffi.Pointer<wire_int_8_list> Function(int)>();
 
+  ffi.Pointer<wire_list_String> new_list_String(
+    int len,
+  ) {
+    return _new_list_String(
+      len,
+    );
+  }
+
+  late final _new_list_StringPtr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_list_String> Function(ffi.Int32)>>('new_list_String');
+  late final _new_list_String = _new_list_StringPtr.asFunction<ffi.Pointer<wire_list_String> Function(int)>();
+
   ffi.Pointer<wire_list_attribute> new_list_attribute(
     int len,
   ) {
@@ -1795,6 +3169,13 @@ class wire_uint_8_list extends ffi.Struct {
   external int len;
 }
 
+class wire_list_String extends ffi.Struct {
+  external ffi.Pointer<ffi.Pointer<wire_uint_8_list>> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
 class wire_MySize extends ffi.Struct {
   @ffi.Int32()
   external int width;
@@ -1918,5 +3299,39 @@ class wire_ExoticOptionals extends ffi.Struct {
   external ffi.Pointer<wire_NewTypeInt> newtypeint;
 }
 
+class wire_BinaryTree extends ffi.Struct {
+  @ffi.Int32()
+  external int value;
+
+  external ffi.Pointer<wire_BinaryTree> left;
+
+  external ffi.Pointer<wire_BinaryTree> right;
+}
+
+class wire_assoc_list_String_u32 extends ffi.Struct {
+  external ffi.Pointer<ffi.Pointer<wire_uint_8_list>> keys;
+
+  external ffi.Pointer<ffi.Uint32> values;
+
+  @ffi.Int32()
+  external int len;
+}
+
+class wire_Point2D extends ffi.Struct {
+  @ffi.Double()
+  external double x;
+
+  @ffi.Double()
+  external double y;
+}
+
+class wire_Vector2D extends ffi.Struct {
+  @ffi.Double()
+  external double x;
+
+  @ffi.Double()
+  external double y;
+}
+
 typedef DartPostCObjectFnType = ffi.Pointer<ffi.NativeFunction<ffi.Uint8 Function(DartPort, ffi.Pointer<ffi.Void>)>>;
 typedef DartPort = ffi.Int64;
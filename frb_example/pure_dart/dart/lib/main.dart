@@ -46,7 +46,7 @@ void main(List<String> args) async {
       final resp = await api.handleVecOfPrimitive(n: n);
       expect(resp.uint8List, Uint8List.fromList(List.filled(n, 42)));
       expect(resp.int8List, Int8List.fromList(List.filled(n, 42)));
-      expect(resp.uint16List, Uint16List.fromList(List.filled(n, 42)));
+      expect(resp.uint16List, Uint16List.fromList(List.generate(n, (i) => [0, 1, 65534, 65535][i % 4])));
       expect(resp.int16List, Int16List.fromList(List.filled(n, 42)));
       expect(resp.uint32List, Uint32List.fromList(List.filled(n, 42)));
       expect(resp.int32List, Int32List.fromList(List.filled(n, 42)));
@@ -62,7 +62,7 @@ void main(List<String> args) async {
       final resp = await api.handleZeroCopyVecOfPrimitive(n: n);
       expect(resp.uint8List, Uint8List.fromList(List.filled(n, 42)));
       expect(resp.int8List, Int8List.fromList(List.filled(n, 42)));
-      expect(resp.uint16List, Uint16List.fromList(List.filled(n, 42)));
+      expect(resp.uint16List, Uint16List.fromList(List.generate(n, (i) => [0, 1, 65534, 65535][i % 4])));
       expect(resp.int16List, Int16List.fromList(List.filled(n, 42)));
       expect(resp.uint32List, Uint32List.fromList(List.filled(n, 42)));
       expect(resp.int32List, Int32List.fromList(List.filled(n, 42)));
@@ -72,6 +72,20 @@ void main(List<String> args) async {
       expect(resp.float64List, Float64List.fromList(List.filled(n, 42)));
     }
 
+    print('dart call handleZeroCopyRows');
+    {
+      final rows = await api.handleZeroCopyRows(rows: 4);
+      expect(rows, [for (var i = 0; i < 4; ++i) Uint8List.fromList(List.filled(i, i))]);
+      // views into the same buffer received from Rust
+      expect(rows[3].buffer, rows[2].buffer);
+    }
+
+    print('dart call handleZeroCopyStrings');
+    {
+      expect(await api.handleZeroCopyStrings(strings: ['a', '', 'héllo']), ['héllo', '', 'a']);
+      expect(await api.handleZeroCopyStrings(strings: []), isEmpty);
+    }
+
     print('dart call handleStruct');
     {
       final structResp =
@@ -100,7 +114,10 @@ void main(List<String> args) async {
     print('dart call handleComplexStruct');
     {
       final arrLen = 5;
-      final complexStructResp = await api.handleComplexStruct(s: _createMyTreeNode(arrLen: arrLen));
+      final complexStruct = _createMyTreeNode(arrLen: arrLen);
+      final complexStructResp = await api.handleComplexStruct(s: complexStruct);
+      // the nested structs are passed to Rust and back unchanged
+      expect(complexStructResp, complexStruct);
       expect(complexStructResp.valueI32, 100);
       expect(complexStructResp.valueVecU8, List.filled(arrLen, 100));
       expect(complexStructResp.children[0].valueVecU8, List.filled(arrLen, 110));
@@ -108,6 +125,183 @@ void main(List<String> args) async {
       expect(complexStructResp.children[1].valueVecU8, List.filled(arrLen, 120));
     }
 
+    print('dart call handleBinaryTree');
+    {
+      final tree = BinaryTree(
+        value: 1,
+        left: BinaryTree(value: 2, left: BinaryTree(value: 4)),
+        right: BinaryTree(value: 3),
+      );
+      final mirrored = await api.handleBinaryTree(tree: tree);
+      expect(mirrored.value, 1);
+      expect(mirrored.left!.value, 3);
+      expect(mirrored.left!.left, null);
+      expect(mirrored.left!.right, null);
+      expect(mirrored.right!.value, 2);
+      expect(mirrored.right!.left, null);
+      expect(mirrored.right!.right!.value, 4);
+    }
+
+    print('dart call handlePlatformWidth');
+    {
+      expect(await api.handlePlatformWidth(len: 42, offset: -50), -8);
+      // values beyond 32 bits are only kept where `usize` is 64-bit
+      if (sizeOf<IntPtr>() == 8) {
+        expect(await api.handlePlatformWidth(len: 1 << 40, offset: -1), (1 << 40) - 1);
+      }
+    }
+
+    print('dart call handleRgba');
+    {
+      expect(await api.handleRgba(rgba: Uint8List.fromList([1, 2, 3, 4])), Uint8List.fromList([4, 3, 2, 1]));
+      try {
+        await api.handleRgba(rgba: Uint8List.fromList([1, 2, 3]));
+        fail("exception not thrown");
+      } catch (e) {
+        print('dart catch e: $e');
+        expect(e, isA<ArgumentError>());
+      }
+    }
+
+    print('dart call clampValues');
+    {
+      expect(
+          await api.clampValues(data: Float64List.fromList([-1.5, 0.5, 2.5]), range: FrbRange(0.0, 1.0)),
+          Float64List.fromList([0.0, 0.5, 1.0]));
+      expect(FrbRange(0.0, 1.0), FrbRange(0.0, 1.0));
+      expect(FrbRange(0.0, 1.0).hashCode, FrbRange(0.0, 1.0).hashCode);
+      expect(FrbRange(0.0, 1.0), isNot(FrbRange(0.0, 1.0, inclusive: true)));
+    }
+
+    print('dart call handleCowBytes');
+    {
+      expect(await api.handleCowBytes(borrowed: true), Uint8List.fromList([1, 2, 3]));
+      expect(await api.handleCowBytes(borrowed: false), Uint8List.fromList([2, 4, 6]));
+    }
+
+    print('dart call handleCowStr');
+    {
+      expect(await api.handleCowStr(text: 'hello', borrowed: true), 'borrowed');
+      expect(await api.handleCowStr(text: 'hello', borrowed: false), 'hello, owned');
+    }
+
+    print('dart call wordCounts');
+    {
+      expect(await api.wordCounts(text: 'the cat saw the dog'), {'the': 2, 'cat': 1, 'saw': 1, 'dog': 1});
+      expect(await api.wordCounts(text: ''), <String, int>{});
+    }
+
+    print('dart call totalWords');
+    {
+      expect(await api.totalWords(counts: {'the': 2, 'cat': 1}), 3);
+      expect(await api.totalWords(counts: {}), 0);
+    }
+
+    print('dart call describeError');
+    {
+      expect(await api.describeError(error: 'disk full'), 'described: disk full');
+      expect(await api.describeError(), null);
+    }
+
+    print('dart call handleDateTime');
+    {
+      expect(await api.handleDateTime(at: DateTime.utc(2022, 3, 4, 23, 59)), DateTime.utc(2022, 3, 4));
+    }
+
+    print('dart call handleUuid');
+    {
+      // a random `Uuid::new_v4()` of Rust, sent back and formatted there
+      final id = await api.newUuid();
+      expect(await api.handleUuid(id: id), id.toString());
+    }
+
+    print('dart call handleSet');
+    {
+      expect(await api.handleSet(nums: {1, 2, 3, 4}), {0, 1, 2});
+    }
+
+    print('dart call nextNonce');
+    {
+      // the increment carries over from the low half to the high half
+      final nonce = Uint128.fromBigInt(BigInt.parse('ffffffffffffffff', radix: 16));
+      expect(nonce, const Uint128(0, -1));
+      final next = await api.nextNonce(nonce: nonce);
+      expect(next, const Uint128(1, 0));
+      expect(next.toString(), '18446744073709551616');
+      expect(await api.nextNonce(nonce: const Uint128(-1, -1)), const Uint128(0, 0));
+    }
+
+    print('dart call makeAdder, applyAdder');
+    {
+      final adder = await api.makeAdder(n: 3);
+      expect(await api.applyAdder(adder: adder, x: 4), 7);
+      // moved to Rust by the call above
+      expect(adder.isDisposed, true);
+      expect(() => api.applyAdder(adder: adder, x: 4), throwsStateError);
+
+      final unused = await api.makeAdder(n: 1);
+      unused.dispose();
+      expect(unused.isDisposed, true);
+    }
+
+    print('dart call sumWithProgress');
+    {
+      final progress = <List<int>>[];
+      final sum = await api.sumWithProgress(
+          nums: Int32List.fromList([1, 2, 3]), onProgress: (done, partial) => progress.add([done, partial]));
+      expect(sum, 6);
+      // fire-and-forget calls may still be in flight when the future completes
+      await Future.delayed(const Duration(milliseconds: 100));
+      expect(progress, [
+        [1, 1],
+        [2, 3],
+        [3, 6],
+      ]);
+    }
+
+    print('dart call applyTwice');
+    {
+      expect(await api.applyTwice(x: 2, f: (x) => x * 3), 18);
+      expect(await api.applyTwice(x: 2), 2);
+      try {
+        await api.applyTwice(x: 2, f: (x) => throw StateError('no'));
+        fail("exception not thrown");
+      } on FfiException catch (e) {
+        expect(e.code, 'RESULT_ERROR');
+        expect(e.message, 'the Dart callback threw');
+      }
+    }
+
+    print('dart call translate, vectorBetween');
+    {
+      final p = await api.translate(p: Point2D(x: 1, y: 2), v: Vector2D(x: 3, y: 4));
+      expect([p.x, p.y], [4, 6]);
+      // `Vector2D` is a `typedef` of `Point2D` since they have the same fields
+      final Point2D v = await api.vectorBetween(from: Point2D(x: 1, y: 2), to: p);
+      expect([v.x, v.y], [3, 4]);
+      expect(v, Point2D(x: 3, y: 4));
+      expect(v.hashCode, Point2D(x: 3, y: 4).hashCode);
+      expect(v.toString(), 'Point2D(x: 3.0, y: 4.0)');
+    }
+
+    print('dart call lookupSize');
+    {
+      expect(await api.lookupSize(name: 'small'), 4);
+      try {
+        await api.lookupSize(name: 'large');
+        fail("exception not thrown");
+      } on CustomError_TooLarge catch (e) {
+        expect(e.size, 64);
+        expect(e.limit, 16);
+      }
+      try {
+        await api.lookupSize(name: 'huge');
+        fail("exception not thrown");
+      } on CustomError_NotFound catch (e) {
+        expect(e.name, 'huge');
+      }
+    }
+
     print('dart call handle_sync_return');
     {
       expect(api.handleSyncReturn(mode: 'NORMAL'), List.filled(100, 42));
@@ -121,6 +315,8 @@ void main(List<String> args) async {
           expect(e, isA<FfiException>());
         }
       }
+
+      expect(api.handleSyncReturnString(name: 'Zoë'), 'Hello, Zoë! (4 bytes)');
     }
 
     print('dart call handle_stream');
@@ -134,6 +330,16 @@ void main(List<String> args) async {
       expect(cnt, 10);
     }
 
+    print('dart call parseNumbers');
+    {
+      final values = <int>[];
+      final errors = <Object>[];
+      await api.parseNumbers(input: '1,x,3').handleError(errors.add).forEach(values.add);
+      expect(values, [1, 3]);
+      expect(errors, hasLength(1));
+      expect(errors.single, contains('invalid number "x"'));
+    }
+
     print('dart call returnErr');
     {
       try {
@@ -145,6 +351,17 @@ void main(List<String> args) async {
       }
     }
 
+    print('dart call returnErrWithContext');
+    {
+      try {
+        await api.returnErrWithContext();
+        fail("exception not thrown");
+      } on FfiException catch (e) {
+        expect(e.code, 'RESULT_ERROR');
+        expect(e.message, 'outer: inner');
+      }
+    }
+
     print('dart call returnPanic');
     {
       try {
@@ -249,8 +466,17 @@ void main(List<String> args) async {
 
     print('flutter_rust_bridge example program end');
   });
+
+  test('unrecognised values of non-exhaustive enums decode to unknown', () {
+    expect(frbNonExhaustiveEnumValue(_Mood.values, 1, _Mood.unknown), _Mood.sad);
+    expect(frbNonExhaustiveEnumValue(_Mood.values, 999, _Mood.unknown), _Mood.unknown);
+    expect(frbNonExhaustiveEnumValue(_Mood.values, -1, _Mood.unknown), _Mood.unknown);
+  });
 }
 
+/// Like the Dart enum of a `#[non_exhaustive]` Rust enum with the variants `Happy` and `Sad`
+enum _Mood { happy, sad, unknown }
+
 int _createGarbage() {
   print('dart create garbage (thus make it more possible to GC)');
   var cum = 0;
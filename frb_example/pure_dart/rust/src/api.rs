@@ -1,13 +1,19 @@
 #![allow(unused_variables)]
 
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::Range;
+use std::ptr::NonNull;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
 
-use flutter_rust_bridge::{StreamSink, SyncReturn, ZeroCopyBuffer};
+use flutter_rust_bridge::{frb, StreamSink, SyncReturn, ZeroCopyBuffer};
 
 /// Documentation on a simple adder function.
 pub fn simple_adder(a: i32, b: i32) -> Result<i32> {
@@ -58,7 +64,7 @@ pub fn handle_vec_of_primitive(n: i32) -> Result<VecOfPrimitivePack> {
         int8list: vec![42i8; n as usize],
         uint8list: vec![42u8; n as usize],
         int16list: vec![42i16; n as usize],
-        uint16list: vec![42u16; n as usize],
+        uint16list: uint16_boundary_values(n),
         int32list: vec![42i32; n as usize],
         uint32list: vec![42u32; n as usize],
         int64list: vec![42i64; n as usize],
@@ -68,6 +74,13 @@ pub fn handle_vec_of_primitive(n: i32) -> Result<VecOfPrimitivePack> {
     })
 }
 
+// values near both ends of the range, to check nothing is truncated or sign-extended
+fn uint16_boundary_values(n: i32) -> Vec<u16> {
+    (0..n as usize)
+        .map(|i| [0, 1, u16::MAX - 1, u16::MAX][i % 4])
+        .collect()
+}
+
 pub struct ZeroCopyVecOfPrimitivePack {
     pub int8list: ZeroCopyBuffer<Vec<i8>>,
     pub uint8list: ZeroCopyBuffer<Vec<u8>>,
@@ -86,7 +99,7 @@ pub fn handle_zero_copy_vec_of_primitive(n: i32) -> Result<ZeroCopyVecOfPrimitiv
         int8list: ZeroCopyBuffer(vec![42i8; n as usize]),
         uint8list: ZeroCopyBuffer(vec![42u8; n as usize]),
         int16list: ZeroCopyBuffer(vec![42i16; n as usize]),
-        uint16list: ZeroCopyBuffer(vec![42u16; n as usize]),
+        uint16list: ZeroCopyBuffer(uint16_boundary_values(n)),
         int32list: ZeroCopyBuffer(vec![42i32; n as usize]),
         uint32list: ZeroCopyBuffer(vec![42u32; n as usize]),
         int64list: ZeroCopyBuffer(vec![42i64; n as usize]),
@@ -96,6 +109,19 @@ pub fn handle_zero_copy_vec_of_primitive(n: i32) -> Result<ZeroCopyVecOfPrimitiv
     })
 }
 
+/// Row `i` has `i` elements of value `i`
+pub fn handle_zero_copy_rows(rows: u8) -> Result<ZeroCopyBuffer<Vec<Vec<u8>>>> {
+    Ok(ZeroCopyBuffer(
+        (0..rows).map(|row| vec![row; row as usize]).collect(),
+    ))
+}
+
+pub fn handle_zero_copy_strings(
+    strings: ZeroCopyBuffer<Vec<String>>,
+) -> Result<ZeroCopyBuffer<Vec<String>>> {
+    Ok(ZeroCopyBuffer(strings.0.into_iter().rev().collect()))
+}
+
 #[derive(Debug, Clone)]
 pub struct MySize {
     pub width: i32,
@@ -147,6 +173,10 @@ pub fn handle_sync_return(mode: String) -> Result<SyncReturn<Vec<u8>>> {
     }
 }
 
+pub fn handle_sync_return_string(name: String) -> Result<SyncReturn<String>> {
+    Ok(SyncReturn(format!("Hello, {}! ({} bytes)", name, name.len())))
+}
+
 pub fn handle_stream(sink: StreamSink<String>, arg: String) -> Result<()> {
     println!("handle_stream arg={}", arg);
 
@@ -177,12 +207,31 @@ pub fn handle_stream(sink: StreamSink<String>, arg: String) -> Result<()> {
     Ok(())
 }
 
+/// Parses each comma-separated number of [input], where invalid ones become errors of the stream
+/// without closing it.
+pub fn parse_numbers(sink: StreamSink<Result<i32, String>>, input: String) -> Result<()> {
+    for number in input.split(',') {
+        sink.add(
+            number
+                .parse()
+                .map_err(|err| format!("invalid number {:?}: {}", number, err)),
+        );
+    }
+    sink.close();
+    Ok(())
+}
+
 pub fn return_err() -> Result<i32> {
     Err(anyhow!(
         "return_err() is called, thus deliberately return Err"
     ))
 }
 
+// reported with the messages of the causes, i.e. `outer: inner`
+pub fn return_err_with_context() -> Result<i32> {
+    Err(anyhow!("inner")).context("outer")
+}
+
 pub fn return_panic() -> Result<i32> {
     panic!("return_panic() is called, thus deliberately panic")
 }
@@ -318,3 +367,199 @@ pub fn handle_option_box_arguments(
         (i8box, u8box, i32box, i64box, f64box, boolbox, structbox)
     ))
 }
+
+// recursive via `Box`, which is passed to Dart as a nullable field
+#[derive(Debug, Clone)]
+pub struct BinaryTree {
+    pub value: i32,
+    pub left: Option<Box<BinaryTree>>,
+    pub right: Option<Box<Self>>,
+}
+
+pub fn handle_binary_tree(tree: BinaryTree) -> Result<BinaryTree> {
+    println!("handle_binary_tree({:?})", &tree);
+    Ok(mirror_binary_tree(tree))
+}
+
+fn mirror_binary_tree(tree: BinaryTree) -> BinaryTree {
+    BinaryTree {
+        value: tree.value,
+        left: tree.right.map(|t| Box::new(mirror_binary_tree(*t))),
+        right: tree.left.map(|t| Box::new(mirror_binary_tree(*t))),
+    }
+}
+
+// `usize` and `isize` are passed as 64-bit integers whatever the target
+pub fn handle_platform_width(len: usize, offset: isize) -> Result<isize> {
+    Ok(len as isize + offset)
+}
+
+// fixed-size arrays are passed like `Vec<T>`, with their length checked on both sides, and arrays
+// of numbers are returned to Dart without a copy
+pub fn handle_rgba(rgba: [u8; 4]) -> Result<[u8; 4]> {
+    let [r, g, b, a] = rgba;
+    Ok([a, b, g, r])
+}
+
+// ranges are passed as their start and end, and received in Dart as `FrbRange<T>`
+pub fn clamp_values(data: Vec<f64>, range: Range<f64>) -> Result<Vec<f64>> {
+    Ok(data
+        .into_iter()
+        .map(|value| value.max(range.start).min(range.end))
+        .collect())
+}
+
+// `Cow<'_, [u8]>` and `Cow<'_, str>` arrive in Dart as `Uint8List` and `String`, whether borrowed or owned
+pub fn handle_cow_bytes(borrowed: bool) -> Result<Cow<'static, [u8]>> {
+    const BYTES: &[u8] = &[1, 2, 3];
+    Ok(if borrowed {
+        Cow::Borrowed(BYTES)
+    } else {
+        Cow::Owned(BYTES.iter().map(|byte| byte * 2).collect())
+    })
+}
+
+pub fn handle_cow_str(text: Cow<'static, str>, borrowed: bool) -> Result<Cow<'static, str>> {
+    Ok(if borrowed {
+        Cow::Borrowed("borrowed")
+    } else {
+        Cow::Owned(format!("{}, owned", text))
+    })
+}
+
+// `Vec<(K, V)>` is received in Dart as `Map<K, V>`
+pub fn word_counts(text: String) -> Result<Vec<(String, u32)>> {
+    let mut counts: Vec<(String, u32)> = Vec::new();
+    for word in text.split_whitespace() {
+        match counts.iter_mut().find(|(known, _)| known == word) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((word.to_string(), 1)),
+        }
+    }
+    Ok(counts)
+}
+
+/// Takes the counts of `word_counts`, which Dart passes as a `Map<String, int>`
+pub fn total_words(counts: Vec<(String, u32)>) -> Result<u32> {
+    Ok(counts.iter().map(|(_, count)| count).sum())
+}
+
+// `Box<dyn Error>` is passed as its message, and needs to be `Send` as an input
+pub fn describe_error(
+    error: Option<Box<dyn std::error::Error + Send>>,
+) -> Result<Option<Box<dyn std::error::Error>>> {
+    Ok(error.map(|error| format!("described: {}", error).into()))
+}
+
+// `chrono` types are passed as Dart `DateTime`, with the `chrono` feature of `flutter_rust_bridge`
+pub fn handle_date_time(at: DateTime<Utc>) -> Result<NaiveDate> {
+    Ok(at.date_naive())
+}
+
+// `uuid::Uuid` is passed as its 16 bytes, with the `uuid` feature of `flutter_rust_bridge`
+pub fn new_uuid() -> Result<Uuid> {
+    Ok(Uuid::new_v4())
+}
+
+pub fn handle_uuid(id: Uuid) -> Result<String> {
+    Ok(id.to_string())
+}
+
+// sets are passed like `Vec<T>` in no particular order, and are `Set<T>` in Dart
+pub fn handle_set(nums: HashSet<i32>) -> Result<BTreeSet<i32>> {
+    Ok(nums.into_iter().map(|n| n / 2).collect())
+}
+
+// `u128` (and `i128`) is passed as its high and low 64-bit halves, e.g. for a 128-bit nonce
+pub fn next_nonce(nonce: u128) -> Result<u128> {
+    Ok(nonce.wrapping_add(1))
+}
+
+// trait objects are opaque handles in Dart, which keep the object in Rust until passed back
+pub type Adder = Box<dyn Fn(i32) -> i32 + Send + Sync>;
+
+pub fn make_adder(n: i32) -> Result<Adder> {
+    Ok(Box::new(move |x| x + n))
+}
+
+pub fn apply_adder(adder: Adder, x: i32) -> Result<i32> {
+    Ok(adder(x))
+}
+
+// Dart functions are passed as callbacks, which Rust calls through the port they listen to
+pub fn sum_with_progress(nums: Vec<i32>, on_progress: impl Fn(u32, i32)) -> Result<i32> {
+    let mut sum = 0;
+    for (idx, num) in nums.into_iter().enumerate() {
+        sum += num;
+        on_progress(idx as u32 + 1, sum);
+    }
+    Ok(sum)
+}
+
+// a callback returning `Result` gets an error instead of panicking when the Dart function throws
+pub fn apply_twice(x: i32, f: Option<Box<dyn Fn(i32) -> Result<i32>>>) -> Result<i32> {
+    Ok(match f {
+        Some(f) => f(f(x)?)?,
+        None => x,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Point2D {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A `typedef` of [Point2D] in Dart, since it has the same fields
+#[derive(Debug, Clone)]
+pub struct Vector2D {
+    pub x: f64,
+    pub y: f64,
+}
+
+// `#[frb(...)]` on the function removes those of its parameters, which Rust rejects otherwise
+/// Takes any Dart object as the name
+#[frb]
+pub fn greet_anything(#[frb(into)] name: String) -> Result<String> {
+    Ok(format!("Hello, {}!", name))
+}
+
+pub fn translate(p: Point2D, v: Vector2D) -> Result<Point2D> {
+    Ok(Point2D {
+        x: p.x + v.x,
+        y: p.y + v.y,
+    })
+}
+
+pub fn vector_between(from: Point2D, to: Point2D) -> Result<Vector2D> {
+    Ok(Vector2D {
+        x: to.x - from.x,
+        y: to.y - from.y,
+    })
+}
+
+// errors of user-defined enums are thrown in Dart as is, instead of as a generic `FfiException`
+pub enum CustomError {
+    NotFound { name: String },
+    TooLarge { size: u32, limit: u32 },
+}
+
+pub fn lookup_size(name: String) -> std::result::Result<u32, CustomError> {
+    match name.as_str() {
+        "small" => Ok(4),
+        "large" => Err(CustomError::TooLarge { size: 64, limit: 16 }),
+        _ => Err(CustomError::NotFound { name }),
+    }
+}
+
+/// Leaks the value and gives its address, which `unboxValue` frees
+#[frb(unsafe_ptr)]
+pub fn box_value(value: i32) -> Result<NonNull<i32>> {
+    Ok(NonNull::from(Box::leak(Box::new(value))))
+}
+
+#[frb(unsafe_ptr)]
+pub fn unbox_value(ptr: NonNull<i32>) -> Result<i32> {
+    // SAFETY: the address comes from `box_value` and is only unboxed once
+    Ok(*unsafe { Box::from_raw(ptr.as_ptr()) })
+}
@@ -1,11 +1,6 @@
-#![allow(
-    non_camel_case_types,
-    unused,
-    clippy::redundant_closure,
-    clippy::useless_conversion
-)]
+#![allow(non_camel_case_types, unused, clippy::all)]
 // AUTO GENERATED FILE, DO NOT EDIT.
-// Generated by `flutter_rust_bridge`.
+// Generated by `flutter_rust_bridge` 1.5.0.
 
 use crate::api::*;
 use flutter_rust_bridge::*;
@@ -23,7 +18,7 @@ pub extern "C" fn wire_simple_adder(port: i64, a: i32, b: i32) {
         move || {
             let api_a = a.wire2api();
             let api_b = b.wire2api();
-            move |task_callback| simple_adder(api_a, api_b)
+            move |task_callback| simple_adder(api_a, api_b).map_err(support::string_error)
         },
     )
 }
@@ -47,7 +42,10 @@ pub extern "C" fn wire_primitive_types(
             let api_my_i64 = my_i64.wire2api();
             let api_my_f64 = my_f64.wire2api();
             let api_my_bool = my_bool.wire2api();
-            move |task_callback| primitive_types(api_my_i32, api_my_i64, api_my_f64, api_my_bool)
+            move |task_callback| {
+                primitive_types(api_my_i32, api_my_i64, api_my_f64, api_my_bool)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -62,7 +60,7 @@ pub extern "C" fn wire_handle_string(port: i64, s: *mut wire_uint_8_list) {
         },
         move || {
             let api_s = s.wire2api();
-            move |task_callback| handle_string(api_s)
+            move |task_callback| handle_string(api_s).map_err(support::string_error)
         },
     )
 }
@@ -77,7 +75,11 @@ pub extern "C" fn wire_handle_vec_u8(port: i64, v: *mut wire_uint_8_list) {
         },
         move || {
             let api_v = v.wire2api();
-            move |task_callback| handle_vec_u8(api_v)
+            move |task_callback| {
+                handle_vec_u8(api_v)
+                    .map(ZeroCopyBuffer)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -92,7 +94,7 @@ pub extern "C" fn wire_handle_vec_of_primitive(port: i64, n: i32) {
         },
         move || {
             let api_n = n.wire2api();
-            move |task_callback| handle_vec_of_primitive(api_n)
+            move |task_callback| handle_vec_of_primitive(api_n).map_err(support::string_error)
         },
     )
 }
@@ -107,7 +109,47 @@ pub extern "C" fn wire_handle_zero_copy_vec_of_primitive(port: i64, n: i32) {
         },
         move || {
             let api_n = n.wire2api();
-            move |task_callback| handle_zero_copy_vec_of_primitive(api_n)
+            move |task_callback| {
+                handle_zero_copy_vec_of_primitive(api_n).map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_zero_copy_rows(port: i64, rows: u8) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_zero_copy_rows",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_rows = rows.wire2api();
+            move |task_callback| {
+                handle_zero_copy_rows(api_rows)
+                    .map(|ans| support::zero_copy_frames(ans.0))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_zero_copy_strings(port: i64, strings: *mut wire_list_String) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_zero_copy_strings",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_strings = strings.wire2api();
+            move |task_callback| {
+                handle_zero_copy_strings(api_strings)
+                    .map(|ans| support::zero_copy_strings(ans.0))
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -123,7 +165,7 @@ pub extern "C" fn wire_handle_struct(port: i64, arg: *mut wire_MySize, boxed: *m
         move || {
             let api_arg = arg.wire2api();
             let api_boxed = boxed.wire2api();
-            move |task_callback| handle_struct(api_arg, api_boxed)
+            move |task_callback| handle_struct(api_arg, api_boxed).map_err(support::string_error)
         },
     )
 }
@@ -138,7 +180,7 @@ pub extern "C" fn wire_handle_newtype(port: i64, arg: *mut wire_NewTypeInt) {
         },
         move || {
             let api_arg = arg.wire2api();
-            move |task_callback| handle_newtype(api_arg)
+            move |task_callback| handle_newtype(api_arg).map_err(support::string_error)
         },
     )
 }
@@ -153,7 +195,7 @@ pub extern "C" fn wire_handle_list_of_struct(port: i64, l: *mut wire_list_my_siz
         },
         move || {
             let api_l = l.wire2api();
-            move |task_callback| handle_list_of_struct(api_l)
+            move |task_callback| handle_list_of_struct(api_l).map_err(support::string_error)
         },
     )
 }
@@ -168,7 +210,7 @@ pub extern "C" fn wire_handle_complex_struct(port: i64, s: *mut wire_MyTreeNode)
         },
         move || {
             let api_s = s.wire2api();
-            move |task_callback| handle_complex_struct(api_s)
+            move |task_callback| handle_complex_struct(api_s).map_err(support::string_error)
         },
     )
 }
@@ -185,7 +227,26 @@ pub extern "C" fn wire_handle_sync_return(
         },
         move || {
             let api_mode = mode.wire2api();
-            handle_sync_return(api_mode)
+            handle_sync_return(api_mode).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_sync_return_string(
+    name: *mut wire_uint_8_list,
+) -> support::WireSyncReturnStruct {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap_sync(
+        WrapInfo {
+            debug_name: "handle_sync_return_string",
+            port: None,
+            mode: FfiCallMode::Sync,
+        },
+        move || {
+            let api_name = name.wire2api();
+            handle_sync_return_string(api_name)
+                .map(|ans| SyncReturn(ans.0.into_bytes()))
+                .map_err(support::string_error)
         },
     )
 }
@@ -200,7 +261,27 @@ pub extern "C" fn wire_handle_stream(port: i64, arg: *mut wire_uint_8_list) {
         },
         move || {
             let api_arg = arg.wire2api();
-            move |task_callback| handle_stream(task_callback.stream_sink(), api_arg)
+            move |task_callback| {
+                handle_stream(task_callback.stream_sink(), api_arg).map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_parse_numbers(port: i64, input: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "parse_numbers",
+            port: Some(port),
+            mode: FfiCallMode::Stream,
+        },
+        move || {
+            let api_input = input.wire2api();
+            move |task_callback| {
+                parse_numbers(task_callback.stream_sink_of_results(), api_input)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -213,125 +294,628 @@ pub extern "C" fn wire_return_err(port: i64) {
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| return_err(),
+        move || move |task_callback| return_err().map_err(support::string_error),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_return_err_with_context(port: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "return_err_with_context",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || move |task_callback| return_err_with_context().map_err(support::string_error),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_return_panic(port: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "return_panic",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || move |task_callback| return_panic().map_err(support::string_error),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_optional_return(port: i64, left: f64, right: f64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_optional_return",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_left = left.wire2api();
+            let api_right = right.wire2api();
+            move |task_callback| {
+                handle_optional_return(api_left, api_right).map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_optional_struct(port: i64, document: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_optional_struct",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_document = document.wire2api();
+            move |task_callback| handle_optional_struct(api_document).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_optional_increment(port: i64, opt: *mut wire_ExoticOptionals) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_optional_increment",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_opt = opt.wire2api();
+            move |task_callback| handle_optional_increment(api_opt).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_increment_boxed_optional(port: i64, opt: *mut f64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_increment_boxed_optional",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_opt = opt.wire2api();
+            move |task_callback| {
+                handle_increment_boxed_optional(api_opt).map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_option_box_arguments(
+    port: i64,
+    i8box: *mut i8,
+    u8box: *mut u8,
+    i32box: *mut i32,
+    i64box: *mut i64,
+    f64box: *mut f64,
+    boolbox: *mut bool,
+    structbox: *mut wire_ExoticOptionals,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_option_box_arguments",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_i8box = i8box.wire2api();
+            let api_u8box = u8box.wire2api();
+            let api_i32box = i32box.wire2api();
+            let api_i64box = i64box.wire2api();
+            let api_f64box = f64box.wire2api();
+            let api_boolbox = boolbox.wire2api();
+            let api_structbox = structbox.wire2api();
+            move |task_callback| {
+                handle_option_box_arguments(
+                    api_i8box,
+                    api_u8box,
+                    api_i32box,
+                    api_i64box,
+                    api_f64box,
+                    api_boolbox,
+                    api_structbox,
+                )
+                .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_binary_tree(port: i64, tree: *mut wire_BinaryTree) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_binary_tree",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_tree = tree.wire2api();
+            move |task_callback| handle_binary_tree(api_tree).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_platform_width(port: i64, len: u64, offset: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_platform_width",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_len = len.wire2api();
+            let api_offset = offset.wire2api();
+            move |task_callback| {
+                handle_platform_width(api_len, api_offset)
+                    .map(|ans| (ans as i64))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_rgba(port: i64, rgba: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_rgba",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_rgba = rgba.wire2api();
+            move |task_callback| {
+                handle_rgba(api_rgba)
+                    .map(|ans| ZeroCopyBuffer(Vec::from(ans)))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_clamp_values(
+    port: i64,
+    data: *mut wire_float_64_list,
+    range: *mut wire_float_64_list,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "clamp_values",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_data = data.wire2api();
+            let api_range = range.wire2api();
+            move |task_callback| clamp_values(api_data, api_range).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_cow_bytes(port: i64, borrowed: bool) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_cow_bytes",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_borrowed = borrowed.wire2api();
+            move |task_callback| {
+                handle_cow_bytes(api_borrowed)
+                    .map(|ans| ZeroCopyBuffer(ans.into_owned()))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_cow_str(port: i64, text: *mut wire_uint_8_list, borrowed: bool) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_cow_str",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_text = text.wire2api();
+            let api_borrowed = borrowed.wire2api();
+            move |task_callback| {
+                handle_cow_str(api_text, api_borrowed)
+                    .map(|ans| ans.into_owned())
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_word_counts(port: i64, text: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "word_counts",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_text = text.wire2api();
+            move |task_callback| {
+                word_counts(api_text)
+                    .map(|ans| {
+                        ans.into_iter()
+                            .flat_map(|(key, value)| {
+                                vec![
+                                    support::IntoDart::into_dart(key),
+                                    support::IntoDart::into_dart(value),
+                                ]
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_total_words(port: i64, counts: *mut wire_assoc_list_String_u32) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "total_words",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_counts = counts.wire2api();
+            move |task_callback| total_words(api_counts).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_describe_error(port: i64, error: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "describe_error",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_error = std::panic::AssertUnwindSafe(error.wire2api());
+            move |task_callback| {
+                describe_error({ api_error }.0)
+                    .map(|ans| ans.map(|inner| inner.to_string()))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_date_time(port: i64, at: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_date_time",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_at = at.wire2api();
+            move |task_callback| {
+                handle_date_time(api_at)
+                    .map(|ans| support::naive_timestamp_millis(ans))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_new_uuid(port: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "new_uuid",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            move |task_callback| {
+                new_uuid()
+                    .map(|ans| support::uuid_to_bytes(ans))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_uuid(port: i64, id: *mut wire_uint_8_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_uuid",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_id = id.wire2api();
+            move |task_callback| handle_uuid(api_id).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_handle_set(port: i64, nums: *mut wire_int_32_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "handle_set",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_nums = nums.wire2api();
+            move |task_callback| {
+                handle_set(api_nums)
+                    .map(|ans| {
+                        const _: fn() = support::assert_b_tree_set_element::<i32>;
+                        ans.into_iter().collect::<Vec<_>>()
+                    })
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_next_nonce(port: i64, nonce: *mut wire_int_64_list) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "next_nonce",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_nonce = nonce.wire2api();
+            move |task_callback| {
+                next_nonce(api_nonce)
+                    .map(|ans| {
+                        let value = ans;
+                        vec![(value >> 64) as i64, value as i64]
+                    })
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_make_adder(port: i64, n: i32) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "make_adder",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_n = n.wire2api();
+            move |task_callback| {
+                make_adder(api_n)
+                    .map(|ans| OPAQUE_ADDER.insert(ans))
+                    .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_apply_adder(port: i64, adder: i64, x: i32) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "apply_adder",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_adder = std::panic::AssertUnwindSafe(adder.wire2api());
+            let api_x = x.wire2api();
+            move |task_callback| apply_adder({ api_adder }.0, api_x).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_sum_with_progress(port: i64, nums: *mut wire_int_32_list, on_progress: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "sum_with_progress",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_nums = nums.wire2api();
+            let api_on_progress = on_progress;
+            move |task_callback| {
+                sum_with_progress(
+                    api_nums,
+                    wire2api_callback_box_dyn_fn_u_32_i_32_send_sync(api_on_progress),
+                )
+                .map_err(support::string_error)
+            }
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_apply_twice(port: i64, x: i32, f: i64) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "apply_twice",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_x = x.wire2api();
+            let api_f = f;
+            move |task_callback| {
+                apply_twice(
+                    api_x,
+                    wire2api_callback_option_box_dyn_fn_i_32_anyhow_result_i_32(api_f),
+                )
+                .map_err(support::string_error)
+            }
+        },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_return_panic(port: i64) {
+pub extern "C" fn wire_greet_anything(port: i64, name: *mut wire_uint_8_list) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "return_panic",
+            debug_name: "greet_anything",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| return_panic(),
+        move || {
+            let api_name: String = name.wire2api();
+            move |task_callback| greet_anything(api_name).map_err(support::string_error)
+        },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_handle_optional_return(port: i64, left: f64, right: f64) {
+pub extern "C" fn wire_translate(port: i64, p: *mut wire_Point2D, v: *mut wire_Vector2D) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "handle_optional_return",
+            debug_name: "translate",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
         move || {
-            let api_left = left.wire2api();
-            let api_right = right.wire2api();
-            move |task_callback| handle_optional_return(api_left, api_right)
+            let api_p = p.wire2api();
+            let api_v = v.wire2api();
+            move |task_callback| translate(api_p, api_v).map_err(support::string_error)
         },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_handle_optional_struct(port: i64, document: *mut wire_uint_8_list) {
+pub extern "C" fn wire_vector_between(port: i64, from: *mut wire_Point2D, to: *mut wire_Point2D) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "handle_optional_struct",
+            debug_name: "vector_between",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
         move || {
-            let api_document = document.wire2api();
-            move |task_callback| handle_optional_struct(api_document)
+            let api_from = from.wire2api();
+            let api_to = to.wire2api();
+            move |task_callback| vector_between(api_from, api_to).map_err(support::string_error)
         },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_handle_optional_increment(port: i64, opt: *mut wire_ExoticOptionals) {
+pub extern "C" fn wire_lookup_size(port: i64, name: *mut wire_uint_8_list) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "handle_optional_increment",
+            debug_name: "lookup_size",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
         move || {
-            let api_opt = opt.wire2api();
-            move |task_callback| handle_optional_increment(api_opt)
+            let api_name = name.wire2api();
+            move |task_callback| lookup_size(api_name).map_err(support::custom_error)
         },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_handle_increment_boxed_optional(port: i64, opt: *mut f64) {
+pub extern "C" fn wire_box_value(port: i64, value: i32) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "handle_increment_boxed_optional",
+            debug_name: "box_value",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
         move || {
-            let api_opt = opt.wire2api();
-            move |task_callback| handle_increment_boxed_optional(api_opt)
+            let api_value = value.wire2api();
+            move |task_callback| {
+                box_value(api_value)
+                    .map(|ans| /* SAFETY: caller guarantees pointer validity */ ans.as_ptr() as i64)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
 
 #[no_mangle]
-pub extern "C" fn wire_handle_option_box_arguments(
-    port: i64,
-    i8box: *mut i8,
-    u8box: *mut u8,
-    i32box: *mut i32,
-    i64box: *mut i64,
-    f64box: *mut f64,
-    boolbox: *mut bool,
-    structbox: *mut wire_ExoticOptionals,
-) {
+pub extern "C" fn wire_unbox_value(port: i64, ptr: i64) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap(
         WrapInfo {
-            debug_name: "handle_option_box_arguments",
+            debug_name: "unbox_value",
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
         move || {
-            let api_i8box = i8box.wire2api();
-            let api_u8box = u8box.wire2api();
-            let api_i32box = i32box.wire2api();
-            let api_i64box = i64box.wire2api();
-            let api_f64box = f64box.wire2api();
-            let api_boolbox = boolbox.wire2api();
-            let api_structbox = structbox.wire2api();
-            move |task_callback| {
-                handle_option_box_arguments(
-                    api_i8box,
-                    api_u8box,
-                    api_i32box,
-                    api_i64box,
-                    api_f64box,
-                    api_boolbox,
-                    api_structbox,
-                )
-            }
+            let api_ptr = ptr;
+            move |task_callback| unbox_value(api_ptr.wire2api()).map_err(support::string_error)
         },
     )
 }
 
+// Section: weak references
+
+// Section: opaque handles
+
+support::lazy_static! {
+    static ref OPAQUE_ADDER: support::OpaqueSlab<Box<dyn Fn(i32) -> i32 + Send + Sync>> = Default::default();
+}
+
+#[no_mangle]
+pub extern "C" fn drop_opaque_adder(handle: i64) {
+    OPAQUE_ADDER.remove(handle);
+}
+
+#[no_mangle]
+pub extern "C" fn frb_dispose_opaque_adder(handle: *mut std::ffi::c_void) {
+    OPAQUE_ADDER.remove(handle as i64);
+}
+
+// Section: change notifiers
+
+// Section: callbacks
+
+#[no_mangle]
+pub extern "C" fn wire_reply_callback_i32(call_id: i64, result: i32) {
+    DartCallback::reply(call_id, result);
+}
+
+#[no_mangle]
+pub extern "C" fn wire_reject_callback(call_id: i64) {
+    DartCallback::reject(call_id);
+}
+
 // Section: wire structs
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_assoc_list_String_u32 {
+    keys: *mut *mut wire_uint_8_list,
+    values: *mut u32,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_Attribute {
@@ -339,6 +923,14 @@ pub struct wire_Attribute {
     value: *mut wire_uint_8_list,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_BinaryTree {
+    value: i32,
+    left: *mut wire_BinaryTree,
+    right: *mut wire_BinaryTree,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_ExoticOptionals {
@@ -394,6 +986,13 @@ pub struct wire_int_8_list {
     len: i32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_String {
+    ptr: *mut *mut wire_uint_8_list,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_list_attribute {
@@ -443,6 +1042,13 @@ pub struct wire_NewTypeInt {
     field0: i64,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_Point2D {
+    x: f64,
+    y: f64,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_uint_8_list {
@@ -450,13 +1056,35 @@ pub struct wire_uint_8_list {
     len: i32,
 }
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_Vector2D {
+    x: f64,
+    y: f64,
+}
+
 // Section: allocate functions
 
+#[no_mangle]
+pub extern "C" fn new_assoc_list_String_u32(len: i32) -> *mut wire_assoc_list_String_u32 {
+    let wrap = wire_assoc_list_String_u32 {
+        keys: support::new_leak_vec_ptr(<*mut wire_uint_8_list>::new_with_null_ptr(), len),
+        values: support::new_leak_vec_ptr(Default::default(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_attribute() -> *mut wire_Attribute {
     support::new_leak_box_ptr(wire_Attribute::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_binary_tree() -> *mut wire_BinaryTree {
+    support::new_leak_box_ptr(wire_BinaryTree::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_autoadd_bool(value: bool) -> *mut bool {
     support::new_leak_box_ptr(value)
@@ -497,6 +1125,21 @@ pub extern "C" fn new_box_autoadd_new_type_int() -> *mut wire_NewTypeInt {
     support::new_leak_box_ptr(wire_NewTypeInt::new_with_null_ptr())
 }
 
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_point_2_d() -> *mut wire_Point2D {
+    support::new_leak_box_ptr(wire_Point2D::new_with_null_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_autoadd_vector_2_d() -> *mut wire_Vector2D {
+    support::new_leak_box_ptr(wire_Vector2D::new_with_null_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn new_box_binary_tree() -> *mut wire_BinaryTree {
+    support::new_leak_box_ptr(wire_BinaryTree::new_with_null_ptr())
+}
+
 #[no_mangle]
 pub extern "C" fn new_box_bool(value: bool) -> *mut bool {
     support::new_leak_box_ptr(value)
@@ -582,6 +1225,15 @@ pub extern "C" fn new_int_8_list(len: i32) -> *mut wire_int_8_list {
     support::new_leak_box_ptr(ans)
 }
 
+#[no_mangle]
+pub extern "C" fn new_list_String(len: i32) -> *mut wire_list_String {
+    let wrap = wire_list_String {
+        ptr: support::new_leak_vec_ptr(<*mut wire_uint_8_list>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
 #[no_mangle]
 pub extern "C" fn new_list_attribute(len: i32) -> *mut wire_list_attribute {
     let wrap = wire_list_attribute {
@@ -648,6 +1300,40 @@ where
     }
 }
 
+impl Wire2Api<chrono::DateTime<chrono::Utc>> for i64 {
+    fn wire2api(self) -> chrono::DateTime<chrono::Utc> {
+        support::timestamp_from_millis(self)
+    }
+}
+
+impl Wire2Api<std::borrow::Cow<'static, str>> for *mut wire_uint_8_list {
+    fn wire2api(self) -> std::borrow::Cow<'static, str> {
+        let vec: Vec<u8> = self.wire2api();
+        std::borrow::Cow::Owned(String::from_utf8_lossy(&vec).into_owned())
+    }
+}
+
+impl Wire2Api<Box<dyn std::error::Error + Send>> for *mut wire_uint_8_list {
+    fn wire2api(self) -> Box<dyn std::error::Error + Send> {
+        let vec: Vec<u8> = self.wire2api();
+        Box::<dyn std::error::Error + Send + Sync>::from(String::from_utf8_lossy(&vec).into_owned())
+    }
+}
+
+impl Wire2Api<std::ptr::NonNull<i32>> for i64 {
+    fn wire2api(self) -> std::ptr::NonNull<i32> {
+        // SAFETY: caller guarantees pointer validity
+        std::ptr::NonNull::new(self as *mut i32).expect("received a null pointer for NonNull")
+    }
+}
+
+impl Wire2Api<std::ops::Range<f64>> for *mut wire_float_64_list {
+    fn wire2api(self) -> std::ops::Range<f64> {
+        let vec: Vec<_> = self.wire2api();
+        vec[0]..vec[1]
+    }
+}
+
 impl Wire2Api<String> for *mut wire_uint_8_list {
     fn wire2api(self) -> String {
         let vec: Vec<u8> = self.wire2api();
@@ -655,12 +1341,59 @@ impl Wire2Api<String> for *mut wire_uint_8_list {
     }
 }
 
+impl Wire2Api<uuid::Uuid> for *mut wire_uint_8_list {
+    fn wire2api(self) -> uuid::Uuid {
+        let bytes: [u8; 16] = self.wire2api();
+        support::uuid_from_bytes(bytes)
+    }
+}
+
 impl Wire2Api<ZeroCopyBuffer<Vec<u8>>> for *mut wire_uint_8_list {
     fn wire2api(self) -> ZeroCopyBuffer<Vec<u8>> {
         ZeroCopyBuffer(self.wire2api())
     }
 }
 
+impl Wire2Api<ZeroCopyBuffer<Vec<String>>> for *mut wire_list_String {
+    fn wire2api(self) -> ZeroCopyBuffer<Vec<String>> {
+        ZeroCopyBuffer(self.wire2api())
+    }
+}
+
+impl Wire2Api<[u8; 16]> for *mut wire_uint_8_list {
+    fn wire2api(self) -> [u8; 16] {
+        let vec: Vec<u8> = self.wire2api();
+        std::convert::TryInto::try_into(vec).unwrap_or_else(|vec: Vec<_>| {
+            panic!("unexpected arr length: expect 16 but see {}", vec.len())
+        })
+    }
+}
+
+impl Wire2Api<[u8; 4]> for *mut wire_uint_8_list {
+    fn wire2api(self) -> [u8; 4] {
+        let vec: Vec<u8> = self.wire2api();
+        std::convert::TryInto::try_into(vec).unwrap_or_else(|vec: Vec<_>| {
+            panic!("unexpected arr length: expect 4 but see {}", vec.len())
+        })
+    }
+}
+
+impl Wire2Api<Vec<(String, u32)>> for *mut wire_assoc_list_String_u32 {
+    fn wire2api(self) -> Vec<(String, u32)> {
+        let (keys, values) = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            (
+                support::vec_from_leak_ptr(wrap.keys, wrap.len),
+                support::vec_from_leak_ptr(wrap.values, wrap.len),
+            )
+        };
+        keys.into_iter()
+            .map(Wire2Api::wire2api)
+            .zip(values.into_iter().map(Wire2Api::wire2api))
+            .collect()
+    }
+}
+
 impl Wire2Api<Attribute> for wire_Attribute {
     fn wire2api(self) -> Attribute {
         Attribute {
@@ -670,6 +1403,16 @@ impl Wire2Api<Attribute> for wire_Attribute {
     }
 }
 
+impl Wire2Api<BinaryTree> for wire_BinaryTree {
+    fn wire2api(self) -> BinaryTree {
+        BinaryTree {
+            value: self.value.wire2api(),
+            left: self.left.wire2api(),
+            right: self.right.wire2api(),
+        }
+    }
+}
+
 impl Wire2Api<bool> for bool {
     fn wire2api(self) -> bool {
         self
@@ -679,120 +1422,173 @@ impl Wire2Api<bool> for bool {
 impl Wire2Api<Attribute> for *mut wire_Attribute {
     fn wire2api(self) -> Attribute {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<Attribute>::wire2api(*wrap).into()
+    }
+}
+
+impl Wire2Api<BinaryTree> for *mut wire_BinaryTree {
+    fn wire2api(self) -> BinaryTree {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<BinaryTree>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<bool> for *mut bool {
     fn wire2api(self) -> bool {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<bool>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<ExoticOptionals> for *mut wire_ExoticOptionals {
     fn wire2api(self) -> ExoticOptionals {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<ExoticOptionals>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<f64> for *mut f64 {
     fn wire2api(self) -> f64 {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<f64>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<i32> for *mut i32 {
     fn wire2api(self) -> i32 {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<i32>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<i64> for *mut i64 {
     fn wire2api(self) -> i64 {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<i64>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<MySize> for *mut wire_MySize {
     fn wire2api(self) -> MySize {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<MySize>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<MyTreeNode> for *mut wire_MyTreeNode {
     fn wire2api(self) -> MyTreeNode {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<MyTreeNode>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<NewTypeInt> for *mut wire_NewTypeInt {
     fn wire2api(self) -> NewTypeInt {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<NewTypeInt>::wire2api(*wrap).into()
+    }
+}
+
+impl Wire2Api<Point2D> for *mut wire_Point2D {
+    fn wire2api(self) -> Point2D {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<Point2D>::wire2api(*wrap).into()
+    }
+}
+
+impl Wire2Api<Vector2D> for *mut wire_Vector2D {
+    fn wire2api(self) -> Vector2D {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<Vector2D>::wire2api(*wrap).into()
+    }
+}
+
+impl Wire2Api<Box<BinaryTree>> for *mut wire_BinaryTree {
+    fn wire2api(self) -> Box<BinaryTree> {
+        let wrap = unsafe { support::box_from_leak_ptr(self) };
+        Wire2Api::<BinaryTree>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<bool>> for *mut bool {
     fn wire2api(self) -> Box<bool> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<bool>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<ExoticOptionals>> for *mut wire_ExoticOptionals {
     fn wire2api(self) -> Box<ExoticOptionals> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<ExoticOptionals>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<f64>> for *mut f64 {
     fn wire2api(self) -> Box<f64> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<f64>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<i32>> for *mut i32 {
     fn wire2api(self) -> Box<i32> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<i32>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<i64>> for *mut i64 {
     fn wire2api(self) -> Box<i64> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<i64>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<i8>> for *mut i8 {
     fn wire2api(self) -> Box<i8> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<i8>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<MySize>> for *mut wire_MySize {
     fn wire2api(self) -> Box<MySize> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<MySize>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Box<u8>> for *mut u8 {
     fn wire2api(self) -> Box<u8> {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<u8>::wire2api(*wrap).into()
+    }
+}
+
+fn wire2api_callback_box_dyn_fn_u_32_i_32_send_sync(
+    port: i64,
+) -> Box<dyn Fn(u32, i32) + Send + Sync> {
+    let callback = DartCallback::new(port);
+    let func: Box<dyn Fn(u32, i32) + Send + Sync> = Box::new(move |arg0: u32, arg1: i32| {
+        callback.post(vec![
+            support::IntoDart::into_dart(arg0),
+            support::IntoDart::into_dart(arg1),
+        ]);
+    });
+    func
+}
+
+fn wire2api_callback_option_box_dyn_fn_i_32_anyhow_result_i_32(
+    port: i64,
+) -> Option<Box<dyn Fn(i32) -> anyhow::Result<i32>>> {
+    if port == 0 {
+        return None;
     }
+    let callback = DartCallback::new(port);
+    let func: Box<dyn Fn(i32) -> anyhow::Result<i32>> =
+        Box::new(move |arg0: i32| callback.call::<i32>(vec![support::IntoDart::into_dart(arg0)]));
+    Some(func)
 }
 
 impl Wire2Api<ExoticOptionals> for wire_ExoticOptionals {
@@ -847,6 +1643,14 @@ impl Wire2Api<Vec<f64>> for *mut wire_float_64_list {
     }
 }
 
+impl Wire2Api<std::collections::HashSet<i32>> for *mut wire_int_32_list {
+    fn wire2api(self) -> std::collections::HashSet<i32> {
+        const _: fn() = support::assert_hash_set_element::<i32>;
+        let vec: Vec<i32> = self.wire2api();
+        vec.into_iter().collect()
+    }
+}
+
 impl Wire2Api<i32> for i32 {
     fn wire2api(self) -> i32 {
         self
@@ -892,6 +1696,22 @@ impl Wire2Api<Vec<i8>> for *mut wire_int_8_list {
     }
 }
 
+impl Wire2Api<isize> for i64 {
+    fn wire2api(self) -> isize {
+        self as isize
+    }
+}
+
+impl Wire2Api<Vec<String>> for *mut wire_list_String {
+    fn wire2api(self) -> Vec<String> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
+
 impl Wire2Api<Vec<Attribute>> for *mut wire_list_attribute {
     fn wire2api(self) -> Vec<Attribute> {
         let vec = unsafe {
@@ -957,6 +1777,36 @@ impl Wire2Api<NewTypeInt> for wire_NewTypeInt {
     }
 }
 
+impl Wire2Api<Box<dyn Fn(i32) -> i32 + Send + Sync>> for i64 {
+    fn wire2api(self) -> Box<dyn Fn(i32) -> i32 + Send + Sync> {
+        OPAQUE_ADDER
+            .remove(self)
+            .unwrap_or_else(|| panic!("opaque handle {} is already released", self))
+    }
+}
+
+impl Wire2Api<Point2D> for wire_Point2D {
+    fn wire2api(self) -> Point2D {
+        Point2D {
+            x: self.x.wire2api(),
+            y: self.y.wire2api(),
+        }
+    }
+}
+
+impl Wire2Api<u128> for *mut wire_int_64_list {
+    fn wire2api(self) -> u128 {
+        let vec: Vec<i64> = self.wire2api();
+        ((vec[0] as u64 as u128) << 64) | (vec[1] as u64 as u128)
+    }
+}
+
+impl Wire2Api<u32> for u32 {
+    fn wire2api(self) -> u32 {
+        self
+    }
+}
+
 impl Wire2Api<u8> for u8 {
     fn wire2api(self) -> u8 {
         self
@@ -972,6 +1822,21 @@ impl Wire2Api<Vec<u8>> for *mut wire_uint_8_list {
     }
 }
 
+impl Wire2Api<usize> for u64 {
+    fn wire2api(self) -> usize {
+        self as usize
+    }
+}
+
+impl Wire2Api<Vector2D> for wire_Vector2D {
+    fn wire2api(self) -> Vector2D {
+        Vector2D {
+            x: self.x.wire2api(),
+            y: self.y.wire2api(),
+        }
+    }
+}
+
 // Section: impl NewWithNullPtr
 
 pub trait NewWithNullPtr {
@@ -993,6 +1858,16 @@ impl NewWithNullPtr for wire_Attribute {
     }
 }
 
+impl NewWithNullPtr for wire_BinaryTree {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            value: Default::default(),
+            left: std::ptr::null_mut(),
+            right: std::ptr::null_mut(),
+        }
+    }
+}
+
 impl NewWithNullPtr for wire_ExoticOptionals {
     fn new_with_null_ptr() -> Self {
         Self {
@@ -1042,6 +1917,24 @@ impl NewWithNullPtr for wire_NewTypeInt {
     }
 }
 
+impl NewWithNullPtr for wire_Point2D {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            x: Default::default(),
+            y: Default::default(),
+        }
+    }
+}
+
+impl NewWithNullPtr for wire_Vector2D {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            x: Default::default(),
+            y: Default::default(),
+        }
+    }
+}
+
 // Section: impl IntoDart
 
 impl support::IntoDart for Attribute {
@@ -1051,6 +1944,31 @@ impl support::IntoDart for Attribute {
 }
 impl support::IntoDartExceptPrimitive for Attribute {}
 
+impl support::IntoDart for BinaryTree {
+    fn into_dart(self) -> support::DartCObject {
+        vec![
+            self.value.into_dart(),
+            self.left.map(|inner| (*inner)).into_dart(),
+            self.right.map(|inner| (*inner)).into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for BinaryTree {}
+
+impl support::IntoDart for CustomError {
+    fn into_dart(self) -> support::DartCObject {
+        match self {
+            Self::NotFound { name } => vec![0.into_dart(), name.into_dart()],
+            Self::TooLarge { size, limit } => {
+                vec![1.into_dart(), size.into_dart(), limit.into_dart()]
+            }
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for CustomError {}
+
 impl support::IntoDart for Element {
     fn into_dart(self) -> support::DartCObject {
         vec![
@@ -1114,6 +2032,13 @@ impl support::IntoDart for NewTypeInt {
 }
 impl support::IntoDartExceptPrimitive for NewTypeInt {}
 
+impl support::IntoDart for Point2D {
+    fn into_dart(self) -> support::DartCObject {
+        vec![self.x.into_dart(), self.y.into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for Point2D {}
+
 impl support::IntoDart for VecOfPrimitivePack {
     fn into_dart(self) -> support::DartCObject {
         vec![
@@ -1133,6 +2058,13 @@ impl support::IntoDart for VecOfPrimitivePack {
 }
 impl support::IntoDartExceptPrimitive for VecOfPrimitivePack {}
 
+impl support::IntoDart for Vector2D {
+    fn into_dart(self) -> support::DartCObject {
+        vec![self.x.into_dart(), self.y.into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for Vector2D {}
+
 impl support::IntoDart for ZeroCopyVecOfPrimitivePack {
     fn into_dart(self) -> support::DartCObject {
         vec![
@@ -1152,6 +2084,8 @@ impl support::IntoDart for ZeroCopyVecOfPrimitivePack {
 }
 impl support::IntoDartExceptPrimitive for ZeroCopyVecOfPrimitivePack {}
 
+// Section: mirror conversions
+
 // Section: executor
 support::lazy_static! {
     pub static ref FLUTTER_RUST_BRIDGE_HANDLER: support::DefaultHandler = Default::default();
@@ -64,6 +64,19 @@ void main() {
       }
     });
 
+    testWidgets('rebuild widgets listening to a Counter when its count is set', (WidgetTester tester) async {
+      final counter = await app.api.createCounter(count: 41);
+      await tester.pumpWidget(AnimatedBuilder(
+          animation: counter, builder: (context, _) => Text('${counter.count}', textDirection: TextDirection.ltr)));
+      expect(find.text('41'), findsOneWidget);
+
+      counter.count = 42;
+      await tester.pump();
+      expect(find.text('42'), findsOneWidget);
+
+      counter.dispose();
+    });
+
     testWidgets('repeat call to offTopicMemoryTestInputComplexStruct', (WidgetTester tester) async {
       await _testMemoryProblemForSingleTypeOfMethod(
           tester,
@@ -50,6 +50,8 @@ void wire_draw_mandelbrot(int64_t port,
 
 void wire_passing_complex_structs(int64_t port, struct wire_TreeNode *root);
 
+void wire_create_counter(int64_t port, int32_t count);
+
 void wire_off_topic_memory_test_input_array(int64_t port, struct wire_uint_8_list *input);
 
 void wire_off_topic_memory_test_output_zero_copy_buffer(int64_t port, int32_t len);
@@ -68,6 +70,10 @@ void wire_off_topic_deliberately_return_error(int64_t port);
 
 void wire_off_topic_deliberately_panic(int64_t port);
 
+void wire_set_counter_count(int64_t handle, int32_t value);
+
+void drop_on_change_counter(int64_t handle);
+
 struct wire_Point *new_box_autoadd_point(void);
 
 struct wire_Size *new_box_autoadd_size(void);
@@ -88,6 +94,7 @@ static int64_t dummy_method_to_enforce_bundling(void) {
     int64_t dummy_var = 0;
     dummy_var ^= ((int64_t) (void*) wire_draw_mandelbrot);
     dummy_var ^= ((int64_t) (void*) wire_passing_complex_structs);
+    dummy_var ^= ((int64_t) (void*) wire_create_counter);
     dummy_var ^= ((int64_t) (void*) wire_off_topic_memory_test_input_array);
     dummy_var ^= ((int64_t) (void*) wire_off_topic_memory_test_output_zero_copy_buffer);
     dummy_var ^= ((int64_t) (void*) wire_off_topic_memory_test_output_vec_u8);
@@ -97,6 +104,8 @@ static int64_t dummy_method_to_enforce_bundling(void) {
     dummy_var ^= ((int64_t) (void*) wire_off_topic_memory_test_output_complex_struct);
     dummy_var ^= ((int64_t) (void*) wire_off_topic_deliberately_return_error);
     dummy_var ^= ((int64_t) (void*) wire_off_topic_deliberately_panic);
+    dummy_var ^= ((int64_t) (void*) wire_set_counter_count);
+    dummy_var ^= ((int64_t) (void*) drop_on_change_counter);
     dummy_var ^= ((int64_t) (void*) new_box_autoadd_point);
     dummy_var ^= ((int64_t) (void*) new_box_autoadd_size);
     dummy_var ^= ((int64_t) (void*) new_box_autoadd_tree_node);
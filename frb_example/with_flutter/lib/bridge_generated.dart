@@ -1,16 +1,22 @@
 // AUTO GENERATED FILE, DO NOT EDIT.
-// Generated by `flutter_rust_bridge`.
+// Generated by `flutter_rust_bridge` 1.5.0.
+
+// ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments, camel_case_types
+library flutter_rust_bridge_example;
 
-// ignore_for_file: non_constant_identifier_names, unused_element, duplicate_ignore, directives_ordering, curly_braces_in_flow_control_structures, unnecessary_lambdas, slash_for_doc_comments
 import 'dart:convert';
+import 'dart:ffi' as ffi;
 import 'dart:typed_data';
 
+import 'package:flutter/foundation.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge.dart';
-import 'dart:ffi' as ffi;
 
 abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRustBridgeExampleWire> {
-  factory FlutterRustBridgeExample(ffi.DynamicLibrary dylib) =>
-      FlutterRustBridgeExampleImpl.raw(FlutterRustBridgeExampleWire(dylib));
+  factory FlutterRustBridgeExample(ffi.DynamicLibrary dylib) {
+    final bridge = FlutterRustBridgeExampleImpl.raw(FlutterRustBridgeExampleWire(dylib));
+    FlutterRustBridgeExampleRegistry.instance.register(FlutterRustBridgeExampleRegistry.defaultName, bridge);
+    return bridge;
+  }
 
   FlutterRustBridgeExample.raw(FlutterRustBridgeExampleWire inner) : super(inner);
 
@@ -23,6 +29,8 @@ abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRus
 
   Future<String> passingComplexStructs({required TreeNode root, dynamic hint});
 
+  Future<Counter> createCounter({required int count, dynamic hint});
+
   Future<int> offTopicMemoryTestInputArray({required Uint8List input, dynamic hint});
 
   Future<Uint8List> offTopicMemoryTestOutputZeroCopyBuffer({required int len, dynamic hint});
@@ -42,6 +50,65 @@ abstract class FlutterRustBridgeExample extends FlutterRustBridgeBase<FlutterRus
   Future<int> offTopicDeliberatelyPanic({dynamic hint});
 }
 
+/// Look up instances of [FlutterRustBridgeExample] by name, e.g. when an app uses multiple bridges.
+/// Instances created by the [FlutterRustBridgeExample] factory are registered as [defaultName].
+class FlutterRustBridgeExampleRegistry {
+  static const defaultName = 'default';
+  static final instance = FlutterRustBridgeExampleRegistry._();
+
+  final _bridges = <String, Object>{};
+
+  FlutterRustBridgeExampleRegistry._();
+
+  void register(String name, FlutterRustBridgeExample bridge) => _bridges[name] = bridge;
+
+  T? lookup<T extends FlutterRustBridgeExample>(String name) {
+    final bridge = _bridges[name];
+    return bridge is T ? bridge : null;
+  }
+
+  /// Drop all registered instances
+  void dispose() => _bridges.clear();
+}
+
+/// A Flutter `ChangeNotifier`: setting `count` notifies listeners and updates the Rust copy
+class Counter extends ChangeNotifier {
+  final FlutterRustBridgeExampleImpl? _rustBridge;
+  int? _rustHandle;
+
+  int _count;
+  int get count => _count;
+  set count(int value) {
+    _count = value;
+    final handle = _rustHandle;
+    if (handle != null) _rustBridge!.inner.wire_set_counter_count(handle, _rustBridge!._api2wire_i32(value));
+    notifyListeners();
+  }
+
+  Counter({
+    required int count,
+  })  : _rustBridge = null,
+        _rustHandle = null,
+        _count = count;
+
+  Counter._fromRust(
+    FlutterRustBridgeExampleImpl rustBridge,
+    int rustHandle, {
+    required int count,
+  })  : _rustBridge = rustBridge,
+        _rustHandle = rustHandle,
+        _count = count;
+
+  /// Also drops the Rust copy of an object received from Rust.
+  @override
+  void dispose() {
+    final handle = _rustHandle;
+    _rustHandle = null;
+    if (handle != null) _rustBridge!.inner.drop_on_change_counter(handle);
+    super.dispose();
+  }
+}
+
 class Point {
   final double x;
 
@@ -51,6 +118,15 @@ class Point {
     required this.x,
     required this.y,
   });
+
+  @override
+  bool operator ==(Object other) => other is Point && other.x == x && other.y == y;
+
+  @override
+  int get hashCode => Object.hashAll([x, y]);
+
+  @override
+  String toString() => 'Point(x: $x, y: $y)';
 }
 
 class Size {
@@ -62,6 +138,15 @@ class Size {
     required this.width,
     required this.height,
   });
+
+  @override
+  bool operator ==(Object other) => other is Size && other.width == width && other.height == height;
+
+  @override
+  int get hashCode => Object.hashAll([width, height]);
+
+  @override
+  String toString() => 'Size(width: $width, height: $height)';
 }
 
 class TreeNode {
@@ -73,6 +158,15 @@ class TreeNode {
     required this.name,
     required this.children,
   });
+
+  @override
+  bool operator ==(Object other) => other is TreeNode && other.name == name && frbListEquals(other.children, children);
+
+  @override
+  int get hashCode => Object.hashAll([name, frbListHash(children)]);
+
+  @override
+  String toString() => 'TreeNode(name: $name, children: $children)';
 }
 
 // ------------------------- Implementation Details -------------------------
@@ -102,6 +196,12 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
       parseSuccessData: _wire2api_String,
       hint: hint));
 
+  Future<Counter> createCounter({required int count, dynamic hint}) => executeNormal(FlutterRustBridgeTask(
+      debugName: 'create_counter',
+      callFfi: (port) => inner.wire_create_counter(port, _api2wire_i32(count)),
+      parseSuccessData: _wire2api_counter,
+      hint: hint));
+
   Future<int> offTopicMemoryTestInputArray({required Uint8List input, dynamic hint}) =>
       executeNormal(FlutterRustBridgeTask(
           debugName: 'off_topic_memory_test_input_array',
@@ -249,6 +349,17 @@ class FlutterRustBridgeExampleImpl extends FlutterRustBridgeExample {
     wireObj.name = _api2wire_String(apiObj.name);
     wireObj.children = _api2wire_list_tree_node(apiObj.children);
   }
+
+  // Section: weak references
+  Counter _wire2api_counter(dynamic raw) {
+    final arr = raw as List<dynamic>;
+    if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+    return Counter._fromRust(
+      this,
+      arr[0] as int,
+      count: _wire2api_i32(arr[1]),
+    );
+  }
 }
 
 // Section: wire2api
@@ -355,6 +466,20 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_passing_complex_structs =
       _wire_passing_complex_structsPtr.asFunction<void Function(int, ffi.Pointer<wire_TreeNode>)>();
 
+  void wire_create_counter(
+    int port,
+    int count,
+  ) {
+    return _wire_create_counter(
+      port,
+      count,
+    );
+  }
+
+  late final _wire_create_counterPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32)>>('wire_create_counter');
+  late final _wire_create_counter = _wire_create_counterPtr.asFunction<void Function(int, int)>();
+
   void wire_off_topic_memory_test_input_array(
     int port,
     ffi.Pointer<wire_uint_8_list> input,
@@ -492,6 +617,32 @@ class FlutterRustBridgeExampleWire implements FlutterRustBridgeWireBase {
   late final _wire_off_topic_deliberately_panic =
       _wire_off_topic_deliberately_panicPtr.asFunction<void Function(int)>();
 
+  void wire_set_counter_count(
+    int handle,
+    int value,
+  ) {
+    return _wire_set_counter_count(
+      handle,
+      value,
+    );
+  }
+
+  late final _wire_set_counter_countPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Int32)>>('wire_set_counter_count');
+  late final _wire_set_counter_count = _wire_set_counter_countPtr.asFunction<void Function(int, int)>();
+
+  void drop_on_change_counter(
+    int handle,
+  ) {
+    return _drop_on_change_counter(
+      handle,
+    );
+  }
+
+  late final _drop_on_change_counterPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('drop_on_change_counter');
+  late final _drop_on_change_counter = _drop_on_change_counterPtr.asFunction<void Function(int)>();
+
   ffi.Pointer<wire_Point> new_box_autoadd_point() {
     return _new_box_autoadd_point();
   }
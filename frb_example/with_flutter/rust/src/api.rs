@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 
-use flutter_rust_bridge::ZeroCopyBuffer;
+use flutter_rust_bridge::{frb, ZeroCopyBuffer};
 
 //
 // NOTE: Please look at https://github.com/fzyzcjy/flutter_rust_bridge/blob/master/frb_example/simple/rust/src/api.rs
@@ -43,6 +43,17 @@ pub struct TreeNode {
     pub children: Vec<TreeNode>,
 }
 
+/// A Flutter `ChangeNotifier`: setting `count` notifies listeners and updates the Rust copy
+#[frb(on_change)]
+#[derive(Debug, Clone)]
+pub struct Counter {
+    pub count: i32,
+}
+
+pub fn create_counter(count: i32) -> Result<Counter> {
+    Ok(Counter { count })
+}
+
 // following are used only for memory tests. Readers of this example do not need to consider it.
 
 pub fn off_topic_memory_test_input_array(input: Vec<u8>) -> Result<i32> {
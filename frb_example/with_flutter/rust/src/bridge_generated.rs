@@ -1,11 +1,6 @@
-#![allow(
-    non_camel_case_types,
-    unused,
-    clippy::redundant_closure,
-    clippy::useless_conversion
-)]
+#![allow(non_camel_case_types, unused, clippy::all)]
 // AUTO GENERATED FILE, DO NOT EDIT.
-// Generated by `flutter_rust_bridge`.
+// Generated by `flutter_rust_bridge` 1.5.0.
 
 use crate::api::*;
 use flutter_rust_bridge::*;
@@ -33,6 +28,7 @@ pub extern "C" fn wire_draw_mandelbrot(
             let api_num_threads = num_threads.wire2api();
             move |task_callback| {
                 draw_mandelbrot(api_image_size, api_zoom_point, api_scale, api_num_threads)
+                    .map_err(support::string_error)
             }
         },
     )
@@ -48,7 +44,22 @@ pub extern "C" fn wire_passing_complex_structs(port: i64, root: *mut wire_TreeNo
         },
         move || {
             let api_root = root.wire2api();
-            move |task_callback| passing_complex_structs(api_root)
+            move |task_callback| passing_complex_structs(api_root).map_err(support::string_error)
+        },
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn wire_create_counter(port: i64, count: i32) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap(
+        WrapInfo {
+            debug_name: "create_counter",
+            port: Some(port),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_count = count.wire2api();
+            move |task_callback| create_counter(api_count).map_err(support::string_error)
         },
     )
 }
@@ -63,7 +74,9 @@ pub extern "C" fn wire_off_topic_memory_test_input_array(port: i64, input: *mut
         },
         move || {
             let api_input = input.wire2api();
-            move |task_callback| off_topic_memory_test_input_array(api_input)
+            move |task_callback| {
+                off_topic_memory_test_input_array(api_input).map_err(support::string_error)
+            }
         },
     )
 }
@@ -78,7 +91,10 @@ pub extern "C" fn wire_off_topic_memory_test_output_zero_copy_buffer(port: i64,
         },
         move || {
             let api_len = len.wire2api();
-            move |task_callback| off_topic_memory_test_output_zero_copy_buffer(api_len)
+            move |task_callback| {
+                off_topic_memory_test_output_zero_copy_buffer(api_len)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -93,7 +109,11 @@ pub extern "C" fn wire_off_topic_memory_test_output_vec_u8(port: i64, len: i32)
         },
         move || {
             let api_len = len.wire2api();
-            move |task_callback| off_topic_memory_test_output_vec_u8(api_len)
+            move |task_callback| {
+                off_topic_memory_test_output_vec_u8(api_len)
+                    .map(ZeroCopyBuffer)
+                    .map_err(support::string_error)
+            }
         },
     )
 }
@@ -111,7 +131,9 @@ pub extern "C" fn wire_off_topic_memory_test_input_vec_of_object(
         },
         move || {
             let api_input = input.wire2api();
-            move |task_callback| off_topic_memory_test_input_vec_of_object(api_input)
+            move |task_callback| {
+                off_topic_memory_test_input_vec_of_object(api_input).map_err(support::string_error)
+            }
         },
     )
 }
@@ -126,7 +148,9 @@ pub extern "C" fn wire_off_topic_memory_test_output_vec_of_object(port: i64, len
         },
         move || {
             let api_len = len.wire2api();
-            move |task_callback| off_topic_memory_test_output_vec_of_object(api_len)
+            move |task_callback| {
+                off_topic_memory_test_output_vec_of_object(api_len).map_err(support::string_error)
+            }
         },
     )
 }
@@ -144,7 +168,9 @@ pub extern "C" fn wire_off_topic_memory_test_input_complex_struct(
         },
         move || {
             let api_input = input.wire2api();
-            move |task_callback| off_topic_memory_test_input_complex_struct(api_input)
+            move |task_callback| {
+                off_topic_memory_test_input_complex_struct(api_input).map_err(support::string_error)
+            }
         },
     )
 }
@@ -159,7 +185,9 @@ pub extern "C" fn wire_off_topic_memory_test_output_complex_struct(port: i64, le
         },
         move || {
             let api_len = len.wire2api();
-            move |task_callback| off_topic_memory_test_output_complex_struct(api_len)
+            move |task_callback| {
+                off_topic_memory_test_output_complex_struct(api_len).map_err(support::string_error)
+            }
         },
     )
 }
@@ -172,7 +200,11 @@ pub extern "C" fn wire_off_topic_deliberately_return_error(port: i64) {
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| off_topic_deliberately_return_error(),
+        move || {
+            move |task_callback| {
+                off_topic_deliberately_return_error().map_err(support::string_error)
+            }
+        },
     )
 }
 
@@ -184,10 +216,32 @@ pub extern "C" fn wire_off_topic_deliberately_panic(port: i64) {
             port: Some(port),
             mode: FfiCallMode::Normal,
         },
-        move || move |task_callback| off_topic_deliberately_panic(),
+        move || move |task_callback| off_topic_deliberately_panic().map_err(support::string_error),
     )
 }
 
+// Section: weak references
+
+// Section: opaque handles
+
+// Section: change notifiers
+
+support::lazy_static! {
+    static ref ON_CHANGE_COUNTER: support::OpaqueSlab<Counter> = Default::default();
+}
+
+#[no_mangle]
+pub extern "C" fn wire_set_counter_count(handle: i64, value: i32) {
+    ON_CHANGE_COUNTER.with_mut(handle, |s| s.count = value.wire2api());
+}
+
+#[no_mangle]
+pub extern "C" fn drop_on_change_counter(handle: i64) {
+    ON_CHANGE_COUNTER.remove(handle);
+}
+
+// Section: callbacks
+
 // Section: wire structs
 
 #[repr(C)]
@@ -305,21 +359,21 @@ impl Wire2Api<String> for *mut wire_uint_8_list {
 impl Wire2Api<Point> for *mut wire_Point {
     fn wire2api(self) -> Point {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<Point>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<Size> for *mut wire_Size {
     fn wire2api(self) -> Size {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<Size>::wire2api(*wrap).into()
     }
 }
 
 impl Wire2Api<TreeNode> for *mut wire_TreeNode {
     fn wire2api(self) -> TreeNode {
         let wrap = unsafe { support::box_from_leak_ptr(self) };
-        (*wrap).wire2api().into()
+        Wire2Api::<TreeNode>::wire2api(*wrap).into()
     }
 }
 
@@ -438,6 +492,17 @@ impl NewWithNullPtr for wire_TreeNode {
 
 // Section: impl IntoDart
 
+impl support::IntoDart for Counter {
+    fn into_dart(self) -> support::DartCObject {
+        vec![
+            ON_CHANGE_COUNTER.insert(self.clone()).into_dart(),
+            self.count.into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for Counter {}
+
 impl support::IntoDart for Size {
     fn into_dart(self) -> support::DartCObject {
         vec![self.width.into_dart(), self.height.into_dart()].into_dart()
@@ -452,6 +517,8 @@ impl support::IntoDart for TreeNode {
 }
 impl support::IntoDartExceptPrimitive for TreeNode {}
 
+// Section: mirror conversions
+
 // Section: executor
 support::lazy_static! {
     pub static ref FLUTTER_RUST_BRIDGE_HANDLER: support::DefaultHandler = Default::default();
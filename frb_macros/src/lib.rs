@@ -0,0 +1,47 @@
+use proc_macro::TokenStream;
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+use syn::Item;
+
+/// Configures how the code generator bridges an item, e.g. `#[frb(sync)]` on a function or
+/// `#[frb(opaque)]` on a struct. The code generator reads the attributes from the source, so this
+/// macro changes nothing but removing the `#[frb(...)]` attributes inside the item, such as
+/// `#[frb(default = 0)]` on a field or `#[frb(into)]` on a parameter, which Rust would otherwise
+/// reject. A struct or function with such attributes therefore needs one itself, which may be a
+/// bare `#[frb]`.
+#[proc_macro_attribute]
+pub fn frb(_attribute: TokenStream, item: TokenStream) -> TokenStream {
+    let mut item = syn::parse_macro_input!(item as Item);
+    StripFrbAttrs.visit_item_mut(&mut item);
+    item.into_token_stream().into()
+}
+
+struct StripFrbAttrs;
+
+macro_rules! strip_frb_attrs {
+    ($($visit:ident($node:ty)),* $(,)?) => {
+        $(
+            fn $visit(&mut self, node: &mut $node) {
+                node.attrs.retain(|attr| !attr.path.is_ident("frb"));
+                visit_mut::$visit(self, node);
+            }
+        )*
+    };
+}
+
+impl VisitMut for StripFrbAttrs {
+    strip_frb_attrs!(
+        visit_field_mut(syn::Field),
+        visit_variant_mut(syn::Variant),
+        visit_pat_type_mut(syn::PatType),
+        visit_impl_item_const_mut(syn::ImplItemConst),
+        visit_impl_item_method_mut(syn::ImplItemMethod),
+        visit_item_fn_mut(syn::ItemFn),
+        visit_item_struct_mut(syn::ItemStruct),
+        visit_item_enum_mut(syn::ItemEnum),
+        visit_item_impl_mut(syn::ItemImpl),
+        visit_item_type_mut(syn::ItemType),
+        visit_item_const_mut(syn::ItemConst),
+        visit_item_mod_mut(syn::ItemMod),
+    );
+}
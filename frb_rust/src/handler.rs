@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::fmt;
 use std::mem::ManuallyDrop;
 use std::panic;
 use std::panic::{RefUnwindSafe, UnwindSafe};
@@ -10,7 +11,7 @@ use parking_lot::Mutex;
 use threadpool::ThreadPool;
 
 use crate::rust2dart::{Rust2Dart, TaskCallback};
-use crate::support::{into_leak_vec_ptr, WireSyncReturnStruct};
+use crate::support::{into_leak_vec_ptr, DartCObject, WireSyncReturnStruct};
 use crate::SyncReturn;
 
 #[derive(Copy, Clone)]
@@ -116,7 +117,7 @@ impl<E: Executor, EH: ErrorHandler> Handler for SimpleHandler<E, EH> {
                     Ok(data) => (data.0, true),
                     Err(err) => (
                         self.error_handler
-                            .handle_error_sync(Error::ResultError(err)),
+                            .handle_error_sync(Error::from_result_error(err)),
                         false,
                     ),
                 }
@@ -208,7 +209,10 @@ impl<EH: ErrorHandler> Executor for ThreadPoolExecutor<EH> {
                         }
                     }
                     Err(error) => {
-                        eh2.handle_error(wrap_info2.port.unwrap(), Error::ResultError(error));
+                        eh2.handle_error(
+                            wrap_info2.port.unwrap(),
+                            Error::from_result_error(error),
+                        );
                     }
                 };
             });
@@ -234,13 +238,23 @@ impl<EH: ErrorHandler> Executor for ThreadPoolExecutor<EH> {
 #[derive(Debug)]
 pub enum Error {
     ResultError(anyhow::Error),
+    CustomError(CustomError),
     Panic(Box<dyn Any + Send>),
 }
 
 impl Error {
+    /// Tell [CustomError]s apart from other errors returned by the function
+    pub fn from_result_error(error: anyhow::Error) -> Self {
+        match error.downcast::<CustomError>() {
+            Ok(custom_error) => Error::CustomError(custom_error),
+            Err(error) => Error::ResultError(error),
+        }
+    }
+
     pub fn code(&self) -> &'static str {
         match self {
             Error::ResultError(_) => "RESULT_ERROR",
+            Error::CustomError(_) => "CUSTOM_ERROR",
             Error::Panic(_) => "PANIC_ERROR",
         }
     }
@@ -248,6 +262,7 @@ impl Error {
     pub fn message(&self) -> String {
         match self {
             Error::ResultError(e) => format!("{:?}", e),
+            Error::CustomError(e) => format!("{:?}", e),
             Error::Panic(panic_err) => match panic_err.downcast_ref::<&'static str>() {
                 Some(s) => *s,
                 None => match panic_err.downcast_ref::<String>() {
@@ -260,6 +275,41 @@ impl Error {
     }
 }
 
+/// The `E` of a function returning `Result<T, E>` with a user-defined `E`, whose Dart counterpart
+/// is sent as the details of the error for the generated code to throw
+pub struct CustomError {
+    type_name: &'static str,
+    // [Mutex] since [anyhow::Error] needs [Sync], while [IntoDart] types are only [Send]
+    into_dart: Mutex<Box<dyn FnOnce() -> DartCObject + Send>>,
+}
+
+impl CustomError {
+    pub fn new<E: IntoDart + Send + 'static>(error: E) -> Self {
+        CustomError {
+            type_name: std::any::type_name::<E>(),
+            into_dart: Mutex::new(Box::new(move || error.into_dart())),
+        }
+    }
+
+    pub fn into_details(self) -> DartCObject {
+        (self.into_dart.into_inner())()
+    }
+}
+
+impl fmt::Debug for CustomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CustomError({})", self.type_name)
+    }
+}
+
+impl fmt::Display for CustomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "an error of type {}", self.type_name)
+    }
+}
+
+impl std::error::Error for CustomError {}
+
 pub trait ErrorHandler: UnwindSafe + RefUnwindSafe + Copy + Send + 'static {
     fn handle_error(&self, port: i64, error: Error);
 
@@ -271,7 +321,12 @@ pub struct ReportDartErrorHandler;
 
 impl ErrorHandler for ReportDartErrorHandler {
     fn handle_error(&self, port: i64, error: Error) {
-        Rust2Dart::new(port).error(error.code().to_string(), error.message());
+        let rust2dart = Rust2Dart::new(port);
+        let (code, message) = (error.code().to_string(), error.message());
+        match error {
+            Error::CustomError(e) => rust2dart.error_full(code, message, e.into_details()),
+            _ => rust2dart.error(code, message),
+        };
     }
 
     fn handle_error_sync(&self, error: Error) -> Vec<u8> {
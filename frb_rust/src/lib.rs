@@ -1,7 +1,8 @@
 pub use allo_isolate::ZeroCopyBuffer;
+pub use flutter_rust_bridge_macros::frb;
 
 pub use handler::{FfiCallMode, Handler, WrapInfo};
-pub use rust2dart::StreamSink;
+pub use rust2dart::{DartCallback, StreamSink};
 
 pub mod handler;
 pub mod rust2dart;
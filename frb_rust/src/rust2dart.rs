@@ -1,8 +1,19 @@
+use std::any::Any;
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
 
 pub use allo_isolate::ffi::DartCObject;
 pub use allo_isolate::IntoDart;
 use allo_isolate::Isolate;
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use log::debug;
+use parking_lot::Mutex;
 
 #[derive(Copy, Clone)]
 pub struct Rust2Dart {
@@ -13,6 +24,9 @@ const RUST2DART_ACTION_SUCCESS: i32 = 0;
 const RUST2DART_ACTION_ERROR: i32 = 1;
 const RUST2DART_ACTION_CLOSE_STREAM: i32 = 2;
 
+const STREAM_EVENT_OK: i32 = 0;
+const STREAM_EVENT_ERR: i32 = 1;
+
 // api signatures is similar to Flutter Android's callback https://api.flutter.dev/javadoc/io/flutter/plugin/common/MethodChannel.Result.html
 impl Rust2Dart {
     pub fn new(port: i64) -> Self {
@@ -64,27 +78,322 @@ impl TaskCallback {
     pub fn stream_sink<T: IntoDart>(&self) -> StreamSink<T> {
         StreamSink::new(self.rust2dart)
     }
+
+    pub fn stream_sink_rate_limited<T: IntoDart>(&self, events_per_second: u32) -> StreamSink<T> {
+        StreamSink::new_rate_limited(self.rust2dart, events_per_second)
+    }
+
+    pub fn stream_sink_buffered<T: IntoDart + Send + 'static>(
+        &self,
+        buffer_size: usize,
+    ) -> StreamSink<T> {
+        StreamSink::new_buffered(self.rust2dart, buffer_size)
+    }
+
+    pub fn stream_sink_of_results<T: IntoDart, E: IntoDart>(&self) -> StreamSink<Result<T, E>> {
+        StreamSink::new_of_results(self.rust2dart)
+    }
 }
 
 #[derive(Clone)]
-pub struct StreamSink<T: IntoDart> {
+pub struct StreamSink<T> {
     rust2dart: Rust2Dart,
+    encode: fn(T) -> DartCObject,
+    rate_limiter: Option<Arc<Mutex<RateLimiter>>>,
+    buffer: Option<SyncSender<BufferedEvent<T>>>,
     _phantom_data: PhantomData<T>,
 }
 
+enum BufferedEvent<T> {
+    Value(T),
+    Close,
+}
+
 impl<T: IntoDart> StreamSink<T> {
     pub fn new(rust2dart: Rust2Dart) -> Self {
+        Self::with_encoder(rust2dart, T::into_dart)
+    }
+
+    /// Similar to [new], except that events exceeding [events_per_second] are dropped
+    pub fn new_rate_limited(rust2dart: Rust2Dart, events_per_second: u32) -> Self {
+        Self::new(rust2dart).rate_limited(events_per_second)
+    }
+
+    /// Similar to [new], except that events are posted to Dart by a separate thread, and [add]
+    /// blocks when [buffer_size] events are waiting to be posted
+    pub fn new_buffered(rust2dart: Rust2Dart, buffer_size: usize) -> Self
+    where
+        T: Send + 'static,
+    {
+        Self::new(rust2dart).buffered(buffer_size)
+    }
+}
+
+impl<T: IntoDart, E: IntoDart> StreamSink<Result<T, E>> {
+    /// Similar to [new], except that each `Err` is added to the Dart stream as an error instead
+    /// of a value, without closing it
+    pub fn new_of_results(rust2dart: Rust2Dart) -> Self {
+        Self::with_encoder(rust2dart, |event| match event {
+            Ok(value) => vec![STREAM_EVENT_OK.into_dart(), value.into_dart()].into_dart(),
+            Err(error) => vec![STREAM_EVENT_ERR.into_dart(), error.into_dart()].into_dart(),
+        })
+    }
+}
+
+impl<T> StreamSink<T> {
+    fn with_encoder(rust2dart: Rust2Dart, encode: fn(T) -> DartCObject) -> Self {
         Self {
             rust2dart,
+            encode,
+            rate_limiter: None,
+            buffer: None,
             _phantom_data: PhantomData,
         }
     }
 
+    /// Drop events exceeding [events_per_second], see [StreamSink::new_rate_limited]
+    pub fn rate_limited(self, events_per_second: u32) -> Self {
+        Self {
+            rate_limiter: Some(Arc::new(Mutex::new(RateLimiter::new(events_per_second)))),
+            ..self
+        }
+    }
+
+    /// Post events to Dart by a separate thread, see [StreamSink::new_buffered]
+    pub fn buffered(self, buffer_size: usize) -> Self
+    where
+        T: Send + 'static,
+    {
+        let rust2dart = self.rust2dart;
+        let encode = self.encode;
+        self.buffered_with(buffer_size, move |event| {
+            match event {
+                BufferedEvent::Value(value) => rust2dart.success(encode(value)),
+                BufferedEvent::Close => rust2dart.close_stream(),
+            };
+        })
+    }
+
+    /// Like [StreamSink::buffered], with the events handled by [post] instead of posted to Dart
+    fn buffered_with(
+        self,
+        buffer_size: usize,
+        mut post: impl FnMut(BufferedEvent<T>) + Send + 'static,
+    ) -> Self
+    where
+        T: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::sync_channel(buffer_size);
+        thread::Builder::new()
+            .name("frb_stream_buffer".to_string())
+            .spawn(move || receiver.into_iter().for_each(&mut post))
+            .expect("fail to spawn the thread of a buffered StreamSink");
+
+        Self {
+            buffer: Some(sender),
+            ..self
+        }
+    }
+
+    /// NOTE an event dropped because of the rate limit is not considered as a failure
     pub fn add(&self, value: T) -> bool {
-        self.rust2dart.success(value)
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let mut rate_limiter = rate_limiter.lock();
+            if !rate_limiter.try_acquire() {
+                debug!(
+                    "StreamSink drop event because of rate limit (dropped {} events in total)",
+                    rate_limiter.dropped_count
+                );
+                return true;
+            }
+        }
+        if let Some(buffer) = &self.buffer {
+            return buffer.send(BufferedEvent::Value(value)).is_ok();
+        }
+        self.rust2dart.success((self.encode)(value))
     }
 
     pub fn close(&self) -> bool {
+        if let Some(buffer) = &self.buffer {
+            return buffer.send(BufferedEvent::Close).is_ok();
+        }
         self.rust2dart.close_stream()
     }
 }
+
+/// A token bucket refilled at [events_per_second], holding at most one second of events
+struct RateLimiter {
+    events_per_second: u32,
+    tokens: f64,
+    last_refill: Instant,
+    dropped_count: u64,
+}
+
+impl RateLimiter {
+    fn new(events_per_second: u32) -> Self {
+        Self {
+            events_per_second,
+            tokens: events_per_second as f64,
+            last_refill: Instant::now(),
+            dropped_count: 0,
+        }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let capacity = self.events_per_second as f64;
+        self.tokens = (self.tokens + now.duration_since(self.last_refill).as_secs_f64() * capacity)
+            .min(capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            self.dropped_count += 1;
+            false
+        }
+    }
+}
+
+/// A Dart function passed to Rust as a callback, which listens to the calls at a native port.
+/// Each call is posted as `[call_id, args...]`, where `call_id` is 0 if no result is expected, and
+/// dropping the callback posts `[]` for Dart to close the port.
+pub struct DartCallback {
+    isolate: Isolate,
+}
+
+type CallbackResult = Option<Box<dyn Any + Send>>;
+
+lazy_static! {
+    /// Calls waiting for the results of Dart, by the id of the call
+    static ref PENDING_CALLBACK_CALLS: Mutex<HashMap<i64, SyncSender<CallbackResult>>> =
+        Default::default();
+}
+
+static NEXT_CALLBACK_CALL_ID: AtomicI64 = AtomicI64::new(1);
+
+impl DartCallback {
+    pub fn new(port: i64) -> Self {
+        DartCallback {
+            isolate: Isolate::new(port),
+        }
+    }
+
+    /// Call without waiting for Dart to run the function
+    pub fn post(&self, args: Vec<DartCObject>) -> bool {
+        self.isolate.post(Self::message(0, args))
+    }
+
+    /// Call and block until Dart replies with the result by [DartCallback::reply]. Fails if the
+    /// Dart function throws, or if its isolate has exited.
+    ///
+    /// NOTE this must not be called on the thread of the Dart isolate, which would never reply
+    pub fn call<R: Send + 'static>(&self, args: Vec<DartCObject>) -> anyhow::Result<R> {
+        let call_id = NEXT_CALLBACK_CALL_ID.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::sync_channel(1);
+        PENDING_CALLBACK_CALLS.lock().insert(call_id, sender);
+        if !self.isolate.post(Self::message(call_id, args)) {
+            PENDING_CALLBACK_CALLS.lock().remove(&call_id);
+            return Err(anyhow!(
+                "fail to call the Dart callback, whose isolate may have exited"
+            ));
+        }
+        match receiver.recv() {
+            Ok(Some(result)) => Ok(*result
+                .downcast::<R>()
+                .expect("unexpected result type of the Dart callback")),
+            _ => Err(anyhow!("the Dart callback threw")),
+        }
+    }
+
+    /// The result of the call [call_id] of [DartCallback::call]
+    pub fn reply<R: Send + 'static>(call_id: i64, result: R) {
+        Self::complete(call_id, Some(Box::new(result)));
+    }
+
+    /// The Dart function of the call [call_id] of [DartCallback::call] threw
+    pub fn reject(call_id: i64) {
+        Self::complete(call_id, None);
+    }
+
+    fn complete(call_id: i64, result: CallbackResult) {
+        match PENDING_CALLBACK_CALLS.lock().remove(&call_id) {
+            Some(sender) => {
+                let _ = sender.send(result);
+            }
+            None => debug!("DartCallback ignore the result of unknown call {}", call_id),
+        }
+    }
+
+    fn message(call_id: i64, args: Vec<DartCObject>) -> Vec<DartCObject> {
+        let mut message = vec![call_id.into_dart()];
+        message.extend(args);
+        message
+    }
+}
+
+impl Drop for DartCallback {
+    fn drop(&mut self) {
+        self.isolate.post(Vec::<DartCObject>::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn buffered_stream_sink_blocks_when_full_and_keeps_order() {
+        let sent = Arc::new(AtomicUsize::new(0));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (resume, paused) = mpsc::channel::<()>();
+        let (closed, wait_closed) = mpsc::channel();
+
+        let sink = StreamSink::<i32>::new(Rust2Dart::new(0)).buffered_with(16, {
+            let received = received.clone();
+            move |event| match event {
+                BufferedEvent::Value(value) => {
+                    // a slow Dart consumer, stuck on the first event until resumed
+                    if value == 0 {
+                        paused.recv().unwrap();
+                    }
+                    received.lock().push(value);
+                }
+                BufferedEvent::Close => closed.send(()).unwrap(),
+            }
+        });
+        let producer = thread::spawn({
+            let sent = sent.clone();
+            move || {
+                for value in 0..200 {
+                    assert!(sink.add(value));
+                    sent.fetch_add(1, Ordering::SeqCst);
+                }
+                assert!(sink.close());
+            }
+        });
+
+        // the event being handled plus 16 buffered ones, then `add` blocks
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while sent.load(Ordering::SeqCst) < 17 {
+            assert!(
+                Instant::now() < deadline,
+                "producer stuck before filling the buffer"
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(sent.load(Ordering::SeqCst), 17);
+        assert!(received.lock().is_empty());
+
+        resume.send(()).unwrap();
+        wait_closed.recv_timeout(Duration::from_secs(5)).unwrap();
+        producer.join().unwrap();
+        assert_eq!(sent.load(Ordering::SeqCst), 200);
+        assert_eq!(*received.lock(), (0..200).collect::<Vec<_>>());
+    }
+}
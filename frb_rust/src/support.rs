@@ -1,13 +1,21 @@
 //! Functions that support auto-generated Rust code.
 //! These functions are *not* meant to be used by humans directly.
 
+use std::fmt::Display;
+use std::hash::Hash;
 use std::mem;
+use std::panic::{self, UnwindSafe};
+use std::sync::Weak;
+
+use allo_isolate::ZeroCopyBuffer;
+use parking_lot::Mutex;
 
 pub use allo_isolate::ffi::DartCObject;
 pub use allo_isolate::{IntoDart, IntoDartExceptPrimitive};
 pub use lazy_static::lazy_static;
 
-pub use crate::handler::DefaultHandler;
+use crate::handler::Error;
+pub use crate::handler::{CustomError, DefaultHandler};
 
 // ref https://stackoverflow.com/questions/39224904/how-to-expose-a-rust-vect-to-ffi
 pub fn new_leak_vec_ptr<T: Clone>(fill: T, length: i32) -> *mut T {
@@ -41,6 +49,142 @@ pub unsafe fn box_from_leak_ptr<T>(ptr: *mut T) -> Box<T> {
     Box::from_raw(ptr)
 }
 
+/// A clone of the `Weak<T>` in the box leaked by [new_leak_box_ptr], which Dart holds as a
+/// `WeakRef<T>` until it calls the drop function
+///
+/// # Safety
+/// [weak] must be the address of a leaked `Weak<T>` whose box is not dropped yet.
+pub unsafe fn clone_leaked_weak<T>(weak: i64) -> Weak<T> {
+    (*(weak as *const Weak<T>)).clone()
+}
+
+/// For `anyhow::Error` and `String` errors, which are both reported as their message, followed
+/// by those of the causes of `anyhow::Error`s, e.g. `failed to open config: file not found`
+pub fn string_error(error: impl Display) -> anyhow::Error {
+    anyhow::Error::msg(format!("{:#}", error))
+}
+
+/// For functions returning `Result<T, E>` with a user-defined `E`, see [CustomError]
+pub fn custom_error<E: IntoDart + Send + 'static>(error: E) -> anyhow::Error {
+    anyhow::Error::new(CustomError::new(error))
+}
+
+/// Runs [f], appending ` [rust: {location}]` to the message of any panic, which the Dart side
+/// parses into `FfiException.rustLocation`, see `--source-locations` of the code generator
+pub fn with_source_location<T>(location: &str, f: impl FnOnce() -> T + UnwindSafe) -> T {
+    panic::catch_unwind(f).unwrap_or_else(|error| {
+        let message = Error::Panic(error).message();
+        panic::resume_unwind(Box::new(format!("{} [rust: {}]", message, location)))
+    })
+}
+
+/// Only compiles if `T` can be an element of a `HashSet`, which the generated code asserts for
+/// clearer errors
+pub fn assert_hash_set_element<T: Eq + Hash>() {}
+
+/// Only compiles if `T` can be an element of a `BTreeSet`, see [assert_hash_set_element]
+pub fn assert_b_tree_set_element<T: Ord>() {}
+
+/// `ZeroCopyBuffer<Vec<Vec<T>>>` is passed as `[lengths, data]` of the frame lengths and the
+/// concatenated frames, both zero-copy, which Dart splits into views
+pub fn zero_copy_frames<T>(frames: Vec<Vec<T>>) -> DartCObject
+where
+    ZeroCopyBuffer<Vec<T>>: IntoDart,
+{
+    let lengths: Vec<u64> = frames.iter().map(|frame| frame.len() as u64).collect();
+    let data: Vec<T> = frames.into_iter().flatten().collect();
+    vec![
+        ZeroCopyBuffer(lengths).into_dart(),
+        ZeroCopyBuffer(data).into_dart(),
+    ]
+    .into_dart()
+}
+
+/// `ZeroCopyBuffer<Vec<String>>` is passed like `ZeroCopyBuffer<Vec<Vec<u8>>>` of the UTF-8 bytes,
+/// see [zero_copy_frames]
+pub fn zero_copy_strings(strings: Vec<String>) -> DartCObject {
+    zero_copy_frames(strings.into_iter().map(String::into_bytes).collect())
+}
+
+/// `chrono` types are passed as milliseconds since the Unix epoch
+#[cfg(feature = "chrono")]
+pub fn timestamp_from_millis(millis: i64) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .unwrap_or_else(|| panic!("timestamp out of range: {}", millis))
+}
+
+/// Naive types are taken as UTC, see [timestamp_from_millis]
+#[cfg(feature = "chrono")]
+pub fn naive_timestamp_millis(datetime: impl Into<chrono::NaiveDateTime>) -> i64 {
+    datetime.into().and_utc().timestamp_millis()
+}
+
+/// `uuid::Uuid` is passed as its 16 bytes
+#[cfg(feature = "uuid")]
+pub fn uuid_from_bytes(bytes: [u8; 16]) -> uuid::Uuid {
+    uuid::Uuid::from_bytes(bytes)
+}
+
+#[cfg(feature = "uuid")]
+pub fn uuid_to_bytes(uuid: uuid::Uuid) -> Vec<u8> {
+    uuid.as_bytes().to_vec()
+}
+
+/// Objects passed to Dart as opaque handles, which are indices into the slab. Slots of removed
+/// objects are reused.
+pub struct OpaqueSlab<T> {
+    slots: Mutex<OpaqueSlots<T>>,
+}
+
+struct OpaqueSlots<T> {
+    values: Vec<Option<T>>,
+    free: Vec<usize>,
+}
+
+impl<T> Default for OpaqueSlab<T> {
+    fn default() -> Self {
+        Self {
+            slots: Mutex::new(OpaqueSlots {
+                values: Vec::new(),
+                free: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl<T> OpaqueSlab<T> {
+    /// Returns the handle of [value]
+    pub fn insert(&self, value: T) -> i64 {
+        let mut slots = self.slots.lock();
+        let index = match slots.free.pop() {
+            Some(index) => {
+                slots.values[index] = Some(value);
+                index
+            }
+            None => {
+                slots.values.push(Some(value));
+                slots.values.len() - 1
+            }
+        };
+        index as i64
+    }
+
+    /// Takes the object out of the slab, or gives [None] if [handle] is already removed
+    pub fn remove(&self, handle: i64) -> Option<T> {
+        let mut slots = self.slots.lock();
+        let index = handle as usize;
+        let value = slots.values.get_mut(index)?.take()?;
+        slots.free.push(index);
+        Some(value)
+    }
+
+    /// Calls [f] with the object of [handle] in the slab, or gives [None] if it is already removed
+    pub fn with_mut<R>(&self, handle: i64, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut slots = self.slots.lock();
+        slots.values.get_mut(handle as usize)?.as_mut().map(f)
+    }
+}
+
 /// NOTE for maintainer: Please keep this struct in sync with [DUMMY_WIRE_CODE_FOR_BINDGEN]
 /// in the code generator
 #[repr(C)]
@@ -49,3 +193,53 @@ pub struct WireSyncReturnStruct {
     pub len: i32,
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn leaked_weak_upgrades_only_while_strong_ref_is_alive() {
+        let strong = Arc::new("value".to_string());
+        let weak = new_leak_box_ptr(Arc::downgrade(&strong)) as i64;
+
+        let upgraded = unsafe { clone_leaked_weak::<String>(weak) }.upgrade();
+        assert_eq!(upgraded.as_deref().map(String::as_str), Some("value"));
+        drop(upgraded);
+
+        drop(strong);
+        assert!(unsafe { clone_leaked_weak::<String>(weak) }
+            .upgrade()
+            .is_none());
+
+        unsafe { box_from_leak_ptr(weak as *mut Weak<String>) };
+    }
+
+    #[test]
+    fn cloned_weak_outlives_the_leaked_box() {
+        let strong = Arc::new(42);
+        let weak = new_leak_box_ptr(Arc::downgrade(&strong)) as i64;
+
+        // an upgrade is still pending when Dart disposes its `WeakRef<T>`
+        let api_weak = unsafe { clone_leaked_weak::<i32>(weak) };
+        unsafe { box_from_leak_ptr(weak as *mut Weak<i32>) };
+        assert_eq!(api_weak.upgrade().as_deref(), Some(&42));
+
+        drop(strong);
+        assert!(api_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn opaque_slab_reuses_slots_of_removed_objects() {
+        let slab = OpaqueSlab::default();
+        let first = slab.insert("first");
+        let second = slab.insert("second");
+
+        assert_eq!(slab.remove(first), Some("first"));
+        assert_eq!(slab.remove(first), None);
+        assert_eq!(slab.insert("third"), first);
+        assert_eq!(slab.with_mut(second, |value| *value), Some("second"));
+    }
+}